@@ -0,0 +1,226 @@
+use crate::error::Result;
+use crate::macros::decode_err;
+use crate::mp4::{AudioObjectType, SAMPLE_RATES};
+
+use std::io::Read;
+
+// The largest possible ADIF header is far smaller than this, this is just a sanity check
+const MAX_HEADER_SIZE: usize = 2048;
+
+#[derive(Copy, Clone)]
+pub(crate) struct ADIFHeader {
+	pub(crate) audio_object_ty: AudioObjectType,
+	pub(crate) sample_rate: u32,
+	pub(crate) channels: u8,
+	pub(crate) copyright: bool,
+	pub(crate) original: bool,
+	pub(crate) variable_bitrate: bool,
+	/// The bitrate in bits per second, this is the *maximum* bitrate for variable bitrate streams
+	pub(crate) bitrate: u32,
+}
+
+impl ADIFHeader {
+	/// Read an ADIF header, following the "ADIF" magic
+	///
+	/// Returns the header and its size, not including the magic.
+	pub(super) fn read<R>(reader: &mut R) -> Result<(Self, u64)>
+	where
+		R: Read,
+	{
+		// The header isn't byte aligned until the end of the first program config element,
+		// so just grab as much as we could possibly need.
+		let mut header = Vec::new();
+		reader
+			.by_ref()
+			.take(MAX_HEADER_SIZE as u64)
+			.read_to_end(&mut header)?;
+
+		let mut bits = BitReader::new(&header);
+
+		// adif_id (32), already read
+		// copyright_id_present (1)
+		// copyright_id (72), if copyright_id_present
+		// original_copy (1)
+		// home (1)
+		// bitstream_type (1)
+		// bitrate (23)
+		// num_program_config_elements (4)
+		let copyright = bits.read_bool()?;
+		if copyright {
+			bits.skip(72)?;
+		}
+
+		let original = bits.read_bool()?;
+		let _home = bits.read_bool()?;
+		let variable_bitrate = bits.read_bool()?;
+		let bitrate = bits.read(23)?;
+		let num_program_config_elements = bits.read(4)? + 1;
+
+		let mut first_pce = None;
+		for _ in 0..num_program_config_elements {
+			// adif_buffer_fullness (20), only for constant bitrate streams
+			if !variable_bitrate {
+				bits.skip(20)?;
+			}
+
+			let pce = ProgramConfigElement::read(&mut bits)?;
+			first_pce.get_or_insert(pce);
+		}
+
+		let Some(pce) = first_pce else {
+			decode_err!(@BAIL Aac, "ADIF header has no program config elements");
+		};
+
+		let audio_object_ty = match pce.profile + 1 {
+			1 => AudioObjectType::AacMain,
+			2 => AudioObjectType::AacLowComplexity,
+			3 => AudioObjectType::AacScalableSampleRate,
+			4 => AudioObjectType::AacLongTermPrediction,
+			_ => unreachable!(),
+		};
+
+		let Some(&sample_rate) = SAMPLE_RATES.get(pce.sample_rate_idx as usize) else {
+			decode_err!(@BAIL Aac, "File contains an invalid sample frequency index");
+		};
+
+		Ok((
+			Self {
+				audio_object_ty,
+				sample_rate,
+				channels: pce.channels,
+				copyright,
+				original,
+				variable_bitrate,
+				bitrate,
+			},
+			bits.byte_position() as u64,
+		))
+	}
+}
+
+struct ProgramConfigElement {
+	profile: u32,
+	sample_rate_idx: u32,
+	channels: u8,
+}
+
+impl ProgramConfigElement {
+	fn read(bits: &mut BitReader<'_>) -> Result<Self> {
+		// element_instance_tag (4)
+		// object_type (2)
+		// sampling_frequency_index (4)
+		bits.skip(4)?;
+		let profile = bits.read(2)?;
+		let sample_rate_idx = bits.read(4)?;
+
+		// num_front_channel_elements (4)
+		// num_side_channel_elements (4)
+		// num_back_channel_elements (4)
+		// num_lfe_channel_elements (2)
+		// num_assoc_data_elements (3)
+		// num_valid_cc_elements (4)
+		let num_front = bits.read(4)?;
+		let num_side = bits.read(4)?;
+		let num_back = bits.read(4)?;
+		let num_lfe = bits.read(2)?;
+		let num_assoc_data = bits.read(3)?;
+		let num_valid_cc = bits.read(4)?;
+
+		// mono_mixdown_present (1), mono_mixdown_element_number (4)
+		// stereo_mixdown_present (1), stereo_mixdown_element_number (4)
+		if bits.read_bool()? {
+			bits.skip(4)?;
+		}
+		if bits.read_bool()? {
+			bits.skip(4)?;
+		}
+
+		// matrix_mixdown_idx_present (1), matrix_mixdown_idx (2), pseudo_surround_enable (1)
+		if bits.read_bool()? {
+			bits.skip(3)?;
+		}
+
+		// Each front/side/back element is either a channel pair element (2 channels),
+		// or a single channel element.
+		let mut channels = 0;
+		for _ in 0..(num_front + num_side + num_back) {
+			// *_element_is_cpe (1), *_element_tag_select (4)
+			let is_cpe = bits.read_bool()?;
+			bits.skip(4)?;
+
+			channels += if is_cpe { 2 } else { 1 };
+		}
+
+		// lfe_element_tag_select (4)
+		bits.skip(num_lfe * 4)?;
+		channels += num_lfe;
+
+		// assoc_data_element_tag_select (4)
+		bits.skip(num_assoc_data * 4)?;
+
+		// cc_element_is_ind_sw (1), valid_cc_element_tag_select (4)
+		bits.skip(num_valid_cc * 5)?;
+
+		bits.byte_align();
+
+		// comment_field_bytes (8)
+		// comment_field_data (8 * comment_field_bytes)
+		let comment_field_bytes = bits.read(8)?;
+		bits.skip(comment_field_bytes * 8)?;
+
+		Ok(Self {
+			profile,
+			sample_rate_idx,
+			channels: channels as u8,
+		})
+	}
+}
+
+struct BitReader<'a> {
+	bytes: &'a [u8],
+	bit_pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+	fn new(bytes: &'a [u8]) -> Self {
+		Self { bytes, bit_pos: 0 }
+	}
+
+	fn read(&mut self, count: u32) -> Result<u32> {
+		debug_assert!(count <= 32);
+
+		let mut value = 0;
+		for _ in 0..count {
+			let Some(byte) = self.bytes.get(self.bit_pos / 8) else {
+				decode_err!(@BAIL Aac, "ADIF header is too short");
+			};
+
+			let bit = (byte >> (7 - (self.bit_pos % 8))) & 1;
+			value = (value << 1) | u32::from(bit);
+			self.bit_pos += 1;
+		}
+
+		Ok(value)
+	}
+
+	fn read_bool(&mut self) -> Result<bool> {
+		Ok(self.read(1)? == 1)
+	}
+
+	fn skip(&mut self, count: u32) -> Result<()> {
+		self.bit_pos += count as usize;
+		if self.bit_pos > self.bytes.len() * 8 {
+			decode_err!(@BAIL Aac, "ADIF header is too short");
+		}
+
+		Ok(())
+	}
+
+	fn byte_align(&mut self) {
+		self.bit_pos = self.bit_pos.next_multiple_of(8);
+	}
+
+	fn byte_position(&self) -> usize {
+		self.bit_pos.div_ceil(8)
+	}
+}
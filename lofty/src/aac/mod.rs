@@ -1,21 +1,22 @@
-//! AAC (ADTS) specific items
-
-// TODO: Currently we only support ADTS, might want to look into ADIF in the future.
+//! AAC (ADTS/ADIF) specific items
 
+mod adif;
 mod header;
 mod properties;
 mod read;
 
 use crate::id3::v1::tag::Id3v1Tag;
 use crate::id3::v2::tag::Id3v2Tag;
+use crate::macros::impl_chapters;
+use crate::xmp::XmpTag;
 
 use lofty_attr::LoftyFile;
 
 // Exports
 
-pub use properties::AACProperties;
+pub use properties::{AACProperties, AacFormat};
 
-/// An AAC (ADTS) file
+/// An AAC (ADTS/ADIF) file
 #[derive(LoftyFile, Default)]
 #[lofty(read_fn = "read::read_from")]
 #[lofty(internal_write_module_do_not_use_anywhere_else)]
@@ -24,5 +25,9 @@ pub struct AacFile {
 	pub(crate) id3v2_tag: Option<Id3v2Tag>,
 	#[lofty(tag_type = "Id3v1")]
 	pub(crate) id3v1_tag: Option<Id3v1Tag>,
+	#[lofty(tag_type = "Xmp")]
+	pub(crate) xmp_tag: Option<XmpTag>,
 	pub(crate) properties: AACProperties,
 }
+
+impl_chapters!(AacFile, id3v2_tag, Id3v2Tag);
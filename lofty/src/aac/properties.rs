@@ -1,13 +1,32 @@
+use crate::aac::adif::ADIFHeader;
 use crate::aac::header::ADTSHeader;
 use crate::mp4::AudioObjectType;
 use crate::mpeg::header::MpegVersion;
 use crate::properties::{ChannelMask, FileProperties};
+use crate::util::math::RoundedDivision;
 
 use std::time::Duration;
 
+/// The format of an AAC stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AacFormat {
+	/// Audio Data Transport Stream, where each frame has its own header
+	#[default]
+	Adts,
+	/// Audio Data Interchange Format, where a single header precedes the raw stream
+	Adif {
+		/// Whether the stream is variable bitrate
+		///
+		/// The header of a variable bitrate stream only stores its *maximum* bitrate,
+		/// so the duration and bitrate will be overestimated.
+		variable_bitrate: bool,
+	},
+}
+
 /// An AAC file's audio properties
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct AACProperties {
+	pub(crate) format: AacFormat,
 	pub(crate) version: MpegVersion,
 	pub(crate) audio_object_type: AudioObjectType,
 	pub(crate) duration: Duration,
@@ -21,12 +40,19 @@ pub struct AACProperties {
 }
 
 impl AACProperties {
+	/// The format of the stream, ADTS or ADIF
+	pub fn format(&self) -> AacFormat {
+		self.format
+	}
+
 	/// MPEG version
 	///
 	/// The only possible variants are:
 	///
 	/// * [MpegVersion::V2]
 	/// * [MpegVersion::V4]
+	///
+	/// ADIF headers do not specify a version, so [`MpegVersion::V2`] is always used for them.
 	pub fn version(&self) -> MpegVersion {
 		self.version
 	}
@@ -103,20 +129,12 @@ pub(super) fn read_properties(
 	first_frame: ADTSHeader,
 	stream_len: u64,
 ) {
+	properties.format = AacFormat::Adts;
 	properties.version = first_frame.version;
 	properties.audio_object_type = first_frame.audio_object_ty;
 	properties.sample_rate = first_frame.sample_rate;
 	properties.channels = first_frame.channels;
-
-	match ChannelMask::from_mp4_channels(properties.channels) {
-		Some(mask) => properties.channel_mask = Some(mask),
-		None => {
-			log::warn!(
-				"Unable to create channel mask, invalid channel count: {}",
-				properties.channels
-			);
-		},
-	}
+	properties.channel_mask = channel_mask(properties.channels);
 
 	properties.copyright = first_frame.copyright;
 	properties.original = first_frame.original;
@@ -129,3 +147,42 @@ pub(super) fn read_properties(
 		properties.duration = Duration::from_millis((stream_len * 8) / u64::from(bitrate));
 	}
 }
+
+pub(super) fn read_adif_properties(
+	properties: &mut AACProperties,
+	header: ADIFHeader,
+	stream_len: u64,
+) {
+	properties.format = AacFormat::Adif {
+		variable_bitrate: header.variable_bitrate,
+	};
+	properties.version = MpegVersion::V2;
+	properties.audio_object_type = header.audio_object_ty;
+	properties.sample_rate = header.sample_rate;
+	properties.channels = header.channels;
+	properties.channel_mask = channel_mask(properties.channels);
+
+	properties.copyright = header.copyright;
+	properties.original = header.original;
+
+	// There are no frame headers to go off of, the only option is to trust the bitrate in the header
+	if header.bitrate > 0 {
+		let bitrate = u64::from(header.bitrate);
+
+		properties.audio_bitrate = bitrate.div_round(1000) as u32;
+		properties.overall_bitrate = properties.audio_bitrate;
+		properties.duration = Duration::from_millis((stream_len * 8 * 1000) / bitrate);
+	}
+}
+
+fn channel_mask(channels: u8) -> Option<ChannelMask> {
+	let mask = ChannelMask::from_mp4_channels(channels);
+	if mask.is_none() {
+		log::warn!(
+			"Unable to create channel mask, invalid channel count: {}",
+			channels
+		);
+	}
+
+	mask
+}
@@ -1,3 +1,4 @@
+use super::adif::ADIFHeader;
 use super::header::{ADTSHeader, HEADER_MASK};
 use super::AacFile;
 use crate::config::{ParseOptions, ParsingMode};
@@ -7,6 +8,7 @@ use crate::id3::v2::read::parse_id3v2;
 use crate::id3::{find_id3v1, ID3FindResults};
 use crate::macros::{decode_err, err, parse_mode_choice};
 use crate::mpeg::header::{cmp_header, search_for_frame_sync, HeaderCmpResult};
+use crate::xmp::read::read_from_id3v2;
 
 use std::io::{Read, Seek, SeekFrom};
 
@@ -23,6 +25,7 @@ where
 
 	let mut first_frame_header = None;
 	let mut first_frame_end = 0;
+	let mut adif_header = None;
 
 	// Skip any invalid padding
 	while reader.read_u8()? == 0 {}
@@ -81,6 +84,20 @@ where
 
 				continue;
 			},
+			// ADIF has a single header at the start of the stream, with no frames to search for
+			[b'A', b'D', b'I', b'F'] => {
+				log::debug!("Found ADIF header");
+
+				let (header, header_len) = ADIFHeader::read(reader)?;
+
+				let Some(new_stream_len) = stream_len.checked_sub(4 + header_len) else {
+					err!(SizeMismatch);
+				};
+
+				stream_len = new_stream_len;
+				adif_header = Some(header);
+				break;
+			},
 			// Tags might be followed by junk bytes before the first ADTS frame begins
 			_ => {
 				log::debug!("Searching for first ADTS frame");
@@ -103,6 +120,10 @@ where
 		}
 	}
 
+	if let Some(id3v2) = &file.id3v2_tag {
+		file.xmp_tag = read_from_id3v2(id3v2, parse_options.parsing_mode)?;
+	}
+
 	#[allow(unused_variables)]
 	let ID3FindResults(header, id3v1) = find_id3v1(reader, parse_options.read_tags)?;
 
@@ -116,6 +137,15 @@ where
 	}
 
 	if parse_options.read_properties {
+		if let Some(adif_header) = adif_header {
+			if adif_header.bitrate == 0 {
+				parse_mode_choice!(parse_mode, STRICT: decode_err!(@BAIL Aac, "Bitrate is 0"),);
+			}
+
+			super::properties::read_adif_properties(&mut file.properties, adif_header, stream_len);
+			return Ok(file);
+		}
+
 		let Some(mut first_frame_header) = first_frame_header else {
 			// The search for sync bits was unsuccessful
 			decode_err!(@BAIL Mpeg, "File contains an invalid frame");
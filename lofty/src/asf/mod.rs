@@ -0,0 +1,30 @@
+//! ASF specific items
+//!
+//! ## File notes
+//!
+//! ASF is the container used by Windows Media Audio (`.wma`) and Video (`.wmv`, `.asf`) files.
+//!
+//! The only supported tag format is [`AsfTag`], which is read from the Content Description and
+//! Extended Content Description Objects.
+pub(crate) mod object;
+mod properties;
+mod read;
+pub(crate) mod tag;
+
+use lofty_attr::LoftyFile;
+
+// Exports
+
+pub use properties::{AsfCodec, AsfProperties};
+pub use tag::{AsfAttribute, AsfAttributeValue, AsfTag};
+
+/// An ASF file
+#[derive(LoftyFile)]
+#[lofty(read_fn = "read::read_from")]
+pub struct AsfFile {
+	/// The file's attributes
+	#[lofty(tag_type = "Asf")]
+	pub(crate) asf_tag: Option<AsfTag>,
+	/// The file's audio properties
+	pub(crate) properties: AsfProperties,
+}
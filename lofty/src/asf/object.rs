@@ -0,0 +1,170 @@
+//! ASF object primitives
+//!
+//! See <https://learn.microsoft.com/en-us/windows/win32/wmformat/overview-of-the-asf-format>
+
+use crate::error::Result;
+use crate::macros::{decode_err, try_vec};
+use crate::util::text::utf16_decode_bytes;
+
+use std::io::{Read, Seek, SeekFrom};
+
+use byteorder::{LittleEndian, ReadBytesExt};
+
+/// A GUID, in the byte order it is stored in the file
+pub(crate) type Guid = [u8; 16];
+
+// GUIDs are written in their canonical form, the first three fields are stored little-endian
+const fn guid(data1: u32, data2: u16, data3: u16, data4: [u8; 8]) -> Guid {
+	let data1 = data1.to_le_bytes();
+	let data2 = data2.to_le_bytes();
+	let data3 = data3.to_le_bytes();
+
+	[
+		data1[0], data1[1], data1[2], data1[3], data2[0], data2[1], data3[0], data3[1], data4[0],
+		data4[1], data4[2], data4[3], data4[4], data4[5], data4[6], data4[7],
+	]
+}
+
+// Top-level objects
+pub(crate) const HEADER_OBJECT: Guid = guid(
+	0x75B2_2630,
+	0x668E,
+	0x11CF,
+	[0xA6, 0xD9, 0x00, 0xAA, 0x00, 0x62, 0xCE, 0x6C],
+);
+
+// Header objects
+pub(crate) const FILE_PROPERTIES_OBJECT: Guid = guid(
+	0x8CAB_DCA1,
+	0xA947,
+	0x11CF,
+	[0x8E, 0xE4, 0x00, 0xC0, 0x0C, 0x20, 0x53, 0x65],
+);
+pub(crate) const STREAM_PROPERTIES_OBJECT: Guid = guid(
+	0xB7DC_0791,
+	0xA9B7,
+	0x11CF,
+	[0x8E, 0xE6, 0x00, 0xC0, 0x0C, 0x20, 0x53, 0x65],
+);
+pub(crate) const CONTENT_DESCRIPTION_OBJECT: Guid = guid(
+	0x75B2_2633,
+	0x668E,
+	0x11CF,
+	[0xA6, 0xD9, 0x00, 0xAA, 0x00, 0x62, 0xCE, 0x6C],
+);
+pub(crate) const EXTENDED_CONTENT_DESCRIPTION_OBJECT: Guid = guid(
+	0xD2D0_A440,
+	0xE307,
+	0x11D2,
+	[0x97, 0xF0, 0x00, 0xA0, 0xC9, 0x5E, 0xA8, 0x50],
+);
+
+// Stream types
+pub(crate) const AUDIO_MEDIA: Guid = guid(
+	0xF869_9E40,
+	0x5B4D,
+	0x11CF,
+	[0xA8, 0xFD, 0x00, 0x80, 0x5F, 0x5C, 0x44, 0x2B],
+);
+
+/// The size of an object's GUID and size
+pub(crate) const OBJECT_HEADER_SIZE: u64 = 24;
+
+/// The size of the Header Object, before its children
+///
+/// This is the object header, followed by the number of child objects (4 bytes), and two reserved bytes.
+pub(crate) const HEADER_OBJECT_HEADER_SIZE: u64 = OBJECT_HEADER_SIZE + 6;
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct ObjectHeader {
+	pub(crate) guid: Guid,
+	/// The size of the entire object, including the header
+	pub(crate) size: u64,
+}
+
+impl ObjectHeader {
+	pub(crate) fn read<R>(reader: &mut R) -> Result<Self>
+	where
+		R: Read,
+	{
+		let mut guid = [0; 16];
+		reader.read_exact(&mut guid)?;
+
+		let size = reader.read_u64::<LittleEndian>()?;
+		if size < OBJECT_HEADER_SIZE {
+			decode_err!(@BAIL Asf, "Object has an invalid size (< 24)");
+		}
+
+		Ok(Self { guid, size })
+	}
+
+	pub(crate) fn content_len(&self) -> u64 {
+		self.size - OBJECT_HEADER_SIZE
+	}
+
+	pub(crate) fn read_content<R>(&self, reader: &mut R) -> Result<Vec<u8>>
+	where
+		R: Read,
+	{
+		let mut content = try_vec![0; self.content_len() as usize];
+		reader.read_exact(&mut content)?;
+
+		Ok(content)
+	}
+
+	pub(crate) fn skip<R>(&self, reader: &mut R) -> Result<()>
+	where
+		R: Seek,
+	{
+		let Ok(content_len) = i64::try_from(self.content_len()) else {
+			decode_err!(@BAIL Asf, "Object is too large");
+		};
+
+		reader.seek(SeekFrom::Current(content_len))?;
+		Ok(())
+	}
+}
+
+/// Read a UTF-16LE string of `len` bytes, trimming any null terminator
+pub(crate) fn read_utf16<R>(reader: &mut R, len: usize) -> Result<String>
+where
+	R: Read,
+{
+	let mut bytes = try_vec![0; len];
+	reader.read_exact(&mut bytes)?;
+
+	utf16_decode_bytes(&bytes, u16::from_le_bytes)
+}
+
+/// Encode a null terminated UTF-16LE string
+pub(crate) fn encode_utf16(text: &str) -> Vec<u8> {
+	text.encode_utf16()
+		.chain(std::iter::once(0))
+		.flat_map(u16::to_le_bytes)
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{encode_utf16, read_utf16, HEADER_OBJECT};
+
+	#[test_log::test]
+	fn guid_byte_order() {
+		assert_eq!(
+			HEADER_OBJECT,
+			[
+				0x30, 0x26, 0xB2, 0x75, 0x8E, 0x66, 0xCF, 0x11, 0xA6, 0xD9, 0x00, 0xAA, 0x00, 0x62,
+				0xCE, 0x6C
+			]
+		);
+	}
+
+	#[test_log::test]
+	fn utf16_round_trip() {
+		let encoded = encode_utf16("Foo title");
+		assert_eq!(encoded.len(), 20);
+
+		let decoded = read_utf16(&mut &encoded[..], encoded.len()).unwrap();
+		assert_eq!(decoded, "Foo title");
+	}
+}
@@ -0,0 +1,239 @@
+use super::object::{Guid, AUDIO_MEDIA};
+use crate::error::Result;
+use crate::macros::decode_err;
+use crate::properties::FileProperties;
+use crate::util::math::RoundedDivision;
+
+use std::time::Duration;
+
+use byteorder::{LittleEndian, ReadBytesExt};
+
+const WMA_V1: u16 = 0x0160;
+const WMA_V2: u16 = 0x0161;
+const WMA_PRO: u16 = 0x0162;
+const WMA_LOSSLESS: u16 = 0x0163;
+
+// Set when the file is being broadcast, and its durations are not yet known
+const BROADCAST_FLAG: u32 = 0x01;
+
+/// An ASF file's audio codec
+#[allow(missing_docs)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AsfCodec {
+	WmaV1,
+	WmaV2,
+	WmaPro,
+	WmaLossless,
+	/// Any other codec, with its format tag
+	Other(u16),
+}
+
+impl Default for AsfCodec {
+	fn default() -> Self {
+		Self::Other(0)
+	}
+}
+
+impl From<u16> for AsfCodec {
+	fn from(format_tag: u16) -> Self {
+		match format_tag {
+			WMA_V1 => Self::WmaV1,
+			WMA_V2 => Self::WmaV2,
+			WMA_PRO => Self::WmaPro,
+			WMA_LOSSLESS => Self::WmaLossless,
+			_ => Self::Other(format_tag),
+		}
+	}
+}
+
+/// An ASF file's audio properties
+///
+/// These describe the first audio stream in the file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub struct AsfProperties {
+	pub(crate) codec: AsfCodec,
+	pub(crate) duration: Duration,
+	pub(crate) overall_bitrate: u32,
+	pub(crate) audio_bitrate: u32,
+	pub(crate) sample_rate: u32,
+	pub(crate) bit_depth: u8,
+	pub(crate) channels: u8,
+}
+
+impl From<AsfProperties> for FileProperties {
+	fn from(input: AsfProperties) -> Self {
+		Self {
+			duration: input.duration,
+			overall_bitrate: Some(input.overall_bitrate),
+			audio_bitrate: Some(input.audio_bitrate),
+			sample_rate: Some(input.sample_rate),
+			bit_depth: Some(input.bit_depth),
+			channels: Some(input.channels),
+			channel_mask: None,
+		}
+	}
+}
+
+impl AsfProperties {
+	/// Duration of the audio
+	pub fn duration(&self) -> Duration {
+		self.duration
+	}
+
+	/// Overall bitrate (kbps)
+	pub fn overall_bitrate(&self) -> u32 {
+		self.overall_bitrate
+	}
+
+	/// Audio bitrate (kbps)
+	pub fn audio_bitrate(&self) -> u32 {
+		self.audio_bitrate
+	}
+
+	/// Sample rate (Hz)
+	pub fn sample_rate(&self) -> u32 {
+		self.sample_rate
+	}
+
+	/// Bits per sample
+	pub fn bit_depth(&self) -> u8 {
+		self.bit_depth
+	}
+
+	/// Channel count
+	pub fn channels(&self) -> u8 {
+		self.channels
+	}
+
+	/// The audio codec
+	pub fn codec(&self) -> AsfCodec {
+		self.codec
+	}
+}
+
+/// The relevant parts of the File Properties Object
+#[derive(Copy, Clone, Debug)]
+pub(super) struct FilePropertiesObject {
+	/// The play duration, in 100-nanosecond units
+	play_duration: u64,
+	/// The preroll, in milliseconds
+	preroll: u64,
+	flags: u32,
+}
+
+impl FilePropertiesObject {
+	pub(super) fn parse(mut content: &[u8]) -> Result<Self> {
+		if content.len() < 80 {
+			decode_err!(@BAIL Asf, "File Properties Object is too small");
+		}
+
+		// File ID (16), File size (8), Creation date (8), Data packets count (8)
+		content = &content[40..];
+
+		let play_duration = content.read_u64::<LittleEndian>()?;
+		let _send_duration = content.read_u64::<LittleEndian>()?;
+		let preroll = content.read_u64::<LittleEndian>()?;
+		let flags = content.read_u32::<LittleEndian>()?;
+
+		Ok(Self {
+			play_duration,
+			preroll,
+			flags,
+		})
+	}
+
+	fn duration(self) -> Duration {
+		if self.flags & BROADCAST_FLAG != 0 {
+			return Duration::ZERO;
+		}
+
+		// The play duration includes the preroll
+		Duration::from_millis((self.play_duration / 10_000).saturating_sub(self.preroll))
+	}
+}
+
+/// The relevant parts of an audio stream's Stream Properties Object
+#[derive(Copy, Clone, Debug)]
+pub(super) struct AudioStream {
+	format_tag: u16,
+	channels: u16,
+	sample_rate: u32,
+	bytes_per_second: u32,
+	bits_per_sample: u16,
+}
+
+impl AudioStream {
+	/// Parse a Stream Properties Object, returning `None` if it isn't an audio stream
+	pub(super) fn parse(mut content: &[u8]) -> Result<Option<Self>> {
+		if content.len() < 54 {
+			decode_err!(@BAIL Asf, "Stream Properties Object is too small");
+		}
+
+		let mut stream_type = Guid::default();
+		stream_type.copy_from_slice(&content[..16]);
+		if stream_type != AUDIO_MEDIA {
+			return Ok(None);
+		}
+
+		// Stream type (16), Error correction type (16), Time offset (8)
+		content = &content[40..];
+
+		let type_specific_data_len = content.read_u32::<LittleEndian>()?;
+		let _error_correction_data_len = content.read_u32::<LittleEndian>()?;
+		let _flags = content.read_u16::<LittleEndian>()?;
+		let _reserved = content.read_u32::<LittleEndian>()?;
+
+		// The type-specific data is a WAVEFORMATEX structure
+		if type_specific_data_len < 16 || content.len() < 16 {
+			decode_err!(@BAIL Asf, "Audio stream has an invalid format structure");
+		}
+
+		let format_tag = content.read_u16::<LittleEndian>()?;
+		let channels = content.read_u16::<LittleEndian>()?;
+		let sample_rate = content.read_u32::<LittleEndian>()?;
+		let bytes_per_second = content.read_u32::<LittleEndian>()?;
+		let _block_align = content.read_u16::<LittleEndian>()?;
+		let bits_per_sample = content.read_u16::<LittleEndian>()?;
+
+		Ok(Some(Self {
+			format_tag,
+			channels,
+			sample_rate,
+			bytes_per_second,
+			bits_per_sample,
+		}))
+	}
+}
+
+pub(super) fn read_properties(
+	file_properties: Option<FilePropertiesObject>,
+	stream: Option<AudioStream>,
+	file_length: u64,
+) -> AsfProperties {
+	let mut properties = AsfProperties::default();
+
+	if let Some(file_properties) = file_properties {
+		properties.duration = file_properties.duration();
+	}
+
+	if let Some(stream) = stream {
+		properties.codec = AsfCodec::from(stream.format_tag);
+		properties.channels = stream.channels.min(u16::from(u8::MAX)) as u8;
+		properties.sample_rate = stream.sample_rate;
+		properties.bit_depth = stream.bits_per_sample.min(u16::from(u8::MAX)) as u8;
+
+		if stream.bytes_per_second > 0 {
+			properties.audio_bitrate =
+				(u64::from(stream.bytes_per_second) * 8).div_round(1000) as u32;
+		}
+	}
+
+	let length = properties.duration.as_millis() as u64;
+	if length > 0 {
+		properties.overall_bitrate = (file_length * 8).div_round(length) as u32;
+	}
+
+	properties
+}
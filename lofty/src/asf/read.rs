@@ -0,0 +1,86 @@
+use super::object::{
+	ObjectHeader, CONTENT_DESCRIPTION_OBJECT, EXTENDED_CONTENT_DESCRIPTION_OBJECT,
+	FILE_PROPERTIES_OBJECT, HEADER_OBJECT, HEADER_OBJECT_HEADER_SIZE, OBJECT_HEADER_SIZE,
+	STREAM_PROPERTIES_OBJECT,
+};
+use super::properties::{read_properties, AsfProperties, AudioStream, FilePropertiesObject};
+use super::tag::read::{read_content_description, read_extended_content_description};
+use super::tag::AsfTag;
+use super::AsfFile;
+use crate::config::ParseOptions;
+use crate::error::Result;
+use crate::macros::decode_err;
+use crate::util::io::SeekStreamLen;
+
+use std::io::{Read, Seek};
+
+use byteorder::{LittleEndian, ReadBytesExt};
+
+pub(super) fn read_from<R>(reader: &mut R, parse_options: ParseOptions) -> Result<AsfFile>
+where
+	R: Read + Seek,
+{
+	let file_length = reader.stream_len_hack()?;
+
+	let header = ObjectHeader::read(reader)?;
+	if header.guid != HEADER_OBJECT {
+		decode_err!(@BAIL Asf, "File missing Header Object");
+	}
+
+	if header.size < HEADER_OBJECT_HEADER_SIZE || header.size > file_length {
+		decode_err!(@BAIL Asf, "Header Object has an invalid size");
+	}
+
+	let object_count = reader.read_u32::<LittleEndian>()?;
+	let _reserved = reader.read_u16::<LittleEndian>()?;
+
+	let mut tag = None;
+	let mut file_properties = None;
+	let mut audio_stream = None;
+
+	let header_end = header.size;
+	for _ in 0..object_count {
+		if reader.stream_position()? + OBJECT_HEADER_SIZE > header_end {
+			break;
+		}
+
+		let object = ObjectHeader::read(reader)?;
+		if reader.stream_position()? + object.content_len() > header_end {
+			decode_err!(@BAIL Asf, "Header Object child has an invalid size");
+		}
+
+		match object.guid {
+			FILE_PROPERTIES_OBJECT if parse_options.read_properties => {
+				file_properties = Some(FilePropertiesObject::parse(&object.read_content(reader)?)?);
+			},
+			// Only the first audio stream is used
+			STREAM_PROPERTIES_OBJECT if parse_options.read_properties && audio_stream.is_none() => {
+				audio_stream = AudioStream::parse(&object.read_content(reader)?)?;
+			},
+			CONTENT_DESCRIPTION_OBJECT if parse_options.read_tags => {
+				let tag = tag.get_or_insert_with(AsfTag::default);
+				read_content_description(&object.read_content(reader)?, tag)?;
+			},
+			EXTENDED_CONTENT_DESCRIPTION_OBJECT if parse_options.read_tags => {
+				let tag = tag.get_or_insert_with(AsfTag::default);
+				read_extended_content_description(
+					&object.read_content(reader)?,
+					tag,
+					parse_options,
+				)?;
+			},
+			_ => object.skip(reader)?,
+		}
+	}
+
+	let properties = if parse_options.read_properties {
+		read_properties(file_properties, audio_stream, file_length)
+	} else {
+		AsfProperties::default()
+	};
+
+	Ok(AsfFile {
+		asf_tag: tag,
+		properties,
+	})
+}
@@ -0,0 +1,89 @@
+use std::borrow::Cow;
+
+/// The value of an [`AsfAttribute`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AsfAttributeValue {
+	/// A UTF-16 string
+	String(String),
+	/// Binary data
+	Binary(Vec<u8>),
+	/// A boolean, stored as a 32-bit integer
+	Bool(bool),
+	/// A 32-bit unsigned integer
+	DWord(u32),
+	/// A 64-bit unsigned integer
+	QWord(u64),
+	/// A 16-bit unsigned integer
+	Word(u16),
+}
+
+impl AsfAttributeValue {
+	/// The value's data type, as stored in the Extended Content Description Object
+	pub(crate) fn data_type(&self) -> u16 {
+		match self {
+			Self::String(_) => 0,
+			Self::Binary(_) => 1,
+			Self::Bool(_) => 2,
+			Self::DWord(_) => 3,
+			Self::QWord(_) => 4,
+			Self::Word(_) => 5,
+		}
+	}
+
+	/// Returns the value as text, if it isn't [`AsfAttributeValue::Binary`]
+	///
+	/// Numeric values are formatted as decimal, and booleans as "1" or "0".
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::asf::AsfAttributeValue;
+	///
+	/// let track_number = AsfAttributeValue::DWord(5);
+	/// assert_eq!(track_number.to_text().as_deref(), Some("5"));
+	///
+	/// let binary = AsfAttributeValue::Binary(vec![1, 2, 3]);
+	/// assert!(binary.to_text().is_none());
+	/// ```
+	pub fn to_text(&self) -> Option<Cow<'_, str>> {
+		match self {
+			Self::String(value) => Some(Cow::Borrowed(value)),
+			Self::Binary(_) => None,
+			Self::Bool(value) => Some(Cow::Owned(u8::from(*value).to_string())),
+			Self::DWord(value) => Some(Cow::Owned(value.to_string())),
+			Self::QWord(value) => Some(Cow::Owned(value.to_string())),
+			Self::Word(value) => Some(Cow::Owned(value.to_string())),
+		}
+	}
+}
+
+/// A single ASF attribute
+///
+/// This covers both the fields of the Content Description Object (Ex. "Title"), and the
+/// descriptors of the Extended Content Description Object (Ex. "WM/AlbumTitle").
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AsfAttribute {
+	/// The name of the attribute (Ex. "WM/AlbumTitle")
+	///
+	/// See <https://learn.microsoft.com/en-us/windows/win32/wmformat/attribute-list> for a list of common names.
+	pub name: String,
+	/// The value of the attribute
+	pub value: AsfAttributeValue,
+}
+
+impl AsfAttribute {
+	/// Create a new [`AsfAttribute`]
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::asf::{AsfAttribute, AsfAttributeValue};
+	///
+	/// let track_number =
+	/// 	AsfAttribute::new(String::from("WM/TrackNumber"), AsfAttributeValue::DWord(1));
+	/// assert_eq!(track_number.name, "WM/TrackNumber");
+	/// ```
+	pub fn new(name: String, value: AsfAttributeValue) -> Self {
+		Self { name, value }
+	}
+}
@@ -0,0 +1,623 @@
+mod attribute;
+pub(super) mod read;
+pub(crate) mod write;
+
+use crate::config::WriteOptions;
+use crate::error::LoftyError;
+use crate::id3::v2::util::pairs::{format_number_pair, set_number};
+use crate::picture::{Picture, PictureType};
+use crate::tag::{
+	try_parse_year, Accessor, ItemKey, ItemValue, MergeTag, SplitTag, Tag, TagExt, TagItem, TagType,
+};
+use crate::util::flag_item;
+use crate::util::io::{FileLike, Length, Truncate};
+
+use std::borrow::Cow;
+use std::io::Write;
+use std::ops::Deref;
+
+use lofty_attr::tag;
+
+pub use attribute::{AsfAttribute, AsfAttributeValue};
+
+/// The attributes stored in the Content Description Object, in the order they are stored
+pub(crate) const CONTENT_DESCRIPTION_FIELDS: [&str; 5] =
+	["Title", "Author", "Copyright", "Description", "Rating"];
+
+pub(crate) const PICTURE_NAME: &str = "WM/Picture";
+
+const TRACK_NUMBER_NAME: &str = "WM/TrackNumber";
+const PART_OF_SET_NAME: &str = "WM/PartOfSet";
+
+macro_rules! impl_accessor {
+	($($name:ident => $key:literal;)+) => {
+		paste::paste! {
+			$(
+				fn $name(&self) -> Option<Cow<'_, str>> {
+					self.get_string($key).map(Cow::Borrowed)
+				}
+
+				fn [<set_ $name>](&mut self, value: String) {
+					self.insert(AsfAttribute::new(
+						String::from($key),
+						AsfAttributeValue::String(value),
+					))
+				}
+
+				fn [<remove_ $name>](&mut self) {
+					let _ = self.remove($key);
+				}
+			)+
+		}
+	}
+}
+
+/// ## Attribute storage
+///
+/// ASF files store their metadata in two objects:
+///
+/// * The Content Description Object, which holds the title, author, copyright, description, and rating
+/// * The Extended Content Description Object, which holds any number of named, typed attributes (Ex. "WM/AlbumTitle")
+///
+/// `AsfTag` treats both the same, the Content Description fields are available as attributes named
+/// "Title", "Author", "Copyright", "Description", and "Rating", the same names used by the Windows Media Format SDK.
+///
+/// Attributes in the Metadata and Metadata Library Objects are not read, and are left untouched when writing.
+/// Since both objects above can only store values up to 65535 bytes, any larger attributes (including pictures)
+/// are skipped when writing.
+///
+/// ## Pictures
+///
+/// Pictures are stored in `WM/Picture` attributes, which are converted to [`Picture`]s when reading.
+///
+/// ## Conversions
+///
+/// ### To `Tag`
+///
+/// String and binary attributes are converted to [`TagItem`]s, with unknown names stored as [`ItemKey::Unknown`].
+/// Numeric and boolean attributes are only converted if their name maps to an [`ItemKey`].
+///
+/// `WM/PartOfSet` is split into [`ItemKey::DiscNumber`] and [`ItemKey::DiscTotal`].
+///
+/// ### From `Tag`
+///
+/// All text and binary items are converted to attributes of the same type, with the exception of
+/// [`ItemKey::TrackNumber`] and [`ItemKey::FlagCompilation`], which will be stored as a `DWORD` and
+/// `BOOL` respectively.
+#[derive(Default, PartialEq, Eq, Debug, Clone)]
+#[tag(description = "ASF attributes", supported_formats(Asf))]
+pub struct AsfTag {
+	pub(crate) attributes: Vec<AsfAttribute>,
+	pub(crate) pictures: Vec<Picture>,
+}
+
+impl AsfTag {
+	/// Create a new empty `AsfTag`
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::asf::AsfTag;
+	/// use lofty::tag::TagExt;
+	///
+	/// let asf_tag = AsfTag::new();
+	/// assert!(asf_tag.is_empty());
+	/// ```
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Get all attributes
+	pub fn attributes(&self) -> impl ExactSizeIterator<Item = &AsfAttribute> + Clone {
+		self.attributes.iter()
+	}
+
+	/// Get the first attribute with `name`
+	///
+	/// NOTE: This is case-insensitive
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::asf::{AsfAttributeValue, AsfTag};
+	/// use lofty::tag::Accessor;
+	///
+	/// let mut tag = AsfTag::new();
+	/// tag.set_title(String::from("Foo title"));
+	///
+	/// let title = tag.get("Title").unwrap();
+	/// assert_eq!(
+	/// 	title.value,
+	/// 	AsfAttributeValue::String(String::from("Foo title"))
+	/// );
+	/// ```
+	pub fn get(&self, name: &str) -> Option<&AsfAttribute> {
+		self.attributes
+			.iter()
+			.find(|attribute| attribute.name.eq_ignore_ascii_case(name))
+	}
+
+	/// Get the value of the first attribute with `name`, if it is a string
+	///
+	/// See [`AsfTag::get`]
+	pub fn get_string(&self, name: &str) -> Option<&str> {
+		match self.get(name) {
+			Some(AsfAttribute {
+				value: AsfAttributeValue::String(value),
+				..
+			}) => Some(value),
+			_ => None,
+		}
+	}
+
+	/// Insert an attribute
+	///
+	/// This is the same as [`AsfTag::push`], except it will remove any attributes with the same name.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::asf::{AsfAttribute, AsfAttributeValue, AsfTag};
+	/// use lofty::tag::TagExt;
+	///
+	/// let mut tag = AsfTag::new();
+	/// for genre in ["Rock", "Jazz"] {
+	/// 	tag.insert(AsfAttribute::new(
+	/// 		String::from("WM/Genre"),
+	/// 		AsfAttributeValue::String(String::from(genre)),
+	/// 	));
+	/// }
+	///
+	/// // We only retain the last genre inserted
+	/// assert_eq!(tag.len(), 1);
+	/// assert_eq!(tag.get_string("WM/Genre"), Some("Jazz"));
+	/// ```
+	pub fn insert(&mut self, attribute: AsfAttribute) {
+		self.attributes
+			.retain(|a| !a.name.eq_ignore_ascii_case(&attribute.name));
+		self.attributes.push(attribute);
+	}
+
+	/// Append an attribute
+	///
+	/// Unlike [`AsfTag::insert`], this allows for multiple attributes with the same name.
+	pub fn push(&mut self, attribute: AsfAttribute) {
+		self.attributes.push(attribute);
+	}
+
+	/// Remove all attributes with `name`, returning an iterator
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::asf::AsfTag;
+	/// use lofty::tag::{Accessor, TagExt};
+	///
+	/// let mut tag = AsfTag::new();
+	/// tag.set_artist(String::from("Foo artist"));
+	///
+	/// assert_eq!(tag.remove("Author").count(), 1);
+	/// assert!(tag.is_empty());
+	/// ```
+	pub fn remove(&mut self, name: &str) -> impl Iterator<Item = AsfAttribute> + '_ {
+		// TODO: drain_filter
+		let mut split_idx = 0_usize;
+
+		for read_idx in 0..self.attributes.len() {
+			if self.attributes[read_idx].name.eq_ignore_ascii_case(name) {
+				self.attributes.swap(split_idx, read_idx);
+				split_idx += 1;
+			}
+		}
+
+		self.attributes.drain(..split_idx)
+	}
+
+	/// Get all pictures
+	pub fn pictures(&self) -> &[Picture] {
+		&self.pictures
+	}
+
+	/// Append a picture
+	pub fn push_picture(&mut self, picture: Picture) {
+		self.pictures.push(picture);
+	}
+
+	/// Remove all pictures of type `picture_type`
+	pub fn remove_picture_type(&mut self, picture_type: PictureType) {
+		self.pictures.retain(|p| p.pic_type() != picture_type);
+	}
+
+	fn get_number(&self, name: &str) -> Option<u32> {
+		match &self.get(name)?.value {
+			AsfAttributeValue::String(value) => value.trim().parse().ok(),
+			AsfAttributeValue::DWord(value) => Some(*value),
+			AsfAttributeValue::QWord(value) => u32::try_from(*value).ok(),
+			AsfAttributeValue::Word(value) => Some(u32::from(*value)),
+			_ => None,
+		}
+	}
+
+	fn split_num_pair(&self, name: &str) -> (Option<u32>, Option<u32>) {
+		if let Some(text) = self.get_string(name) {
+			let mut split = text.split('/').map(|n| n.trim().parse::<u32>().ok());
+			return (split.next().flatten(), split.next().flatten());
+		}
+
+		(self.get_number(name), None)
+	}
+
+	fn insert_number_pair(&mut self, name: &'static str, number: Option<u32>, total: Option<u32>) {
+		if let Some(value) = format_number_pair(number, total) {
+			self.insert(AsfAttribute::new(
+				String::from(name),
+				AsfAttributeValue::String(value),
+			));
+		} else {
+			log::warn!("{name} is not set. number: {number:?}, total: {total:?}");
+		}
+	}
+}
+
+impl Accessor for AsfTag {
+	impl_accessor!(
+		artist  => "Author";
+		title   => "Title";
+		album   => "WM/AlbumTitle";
+		genre   => "WM/Genre";
+		comment => "Description";
+	);
+
+	fn track(&self) -> Option<u32> {
+		self.get_number(TRACK_NUMBER_NAME)
+	}
+
+	fn set_track(&mut self, value: u32) {
+		self.insert(AsfAttribute::new(
+			String::from(TRACK_NUMBER_NAME),
+			AsfAttributeValue::DWord(value),
+		));
+	}
+
+	fn remove_track(&mut self) {
+		let _ = self.remove(TRACK_NUMBER_NAME);
+	}
+
+	fn disk(&self) -> Option<u32> {
+		self.split_num_pair(PART_OF_SET_NAME).0
+	}
+
+	fn set_disk(&mut self, value: u32) {
+		self.insert_number_pair(PART_OF_SET_NAME, Some(value), self.disk_total());
+	}
+
+	fn remove_disk(&mut self) {
+		let _ = self.remove(PART_OF_SET_NAME);
+	}
+
+	fn disk_total(&self) -> Option<u32> {
+		self.split_num_pair(PART_OF_SET_NAME).1
+	}
+
+	fn set_disk_total(&mut self, value: u32) {
+		self.insert_number_pair(PART_OF_SET_NAME, self.disk(), Some(value));
+	}
+
+	fn remove_disk_total(&mut self) {
+		let existing_disk_number = self.disk();
+		let _ = self.remove(PART_OF_SET_NAME);
+
+		if let Some(disk) = existing_disk_number {
+			self.insert_number_pair(PART_OF_SET_NAME, Some(disk), None);
+		}
+	}
+
+	fn year(&self) -> Option<u32> {
+		self.get_string("WM/Year").and_then(try_parse_year)
+	}
+
+	fn set_year(&mut self, value: u32) {
+		self.insert(AsfAttribute::new(
+			String::from("WM/Year"),
+			AsfAttributeValue::String(value.to_string()),
+		));
+	}
+
+	fn remove_year(&mut self) {
+		let _ = self.remove("WM/Year");
+	}
+}
+
+impl TagExt for AsfTag {
+	type Err = LoftyError;
+	type RefKey<'a> = &'a str;
+
+	#[inline]
+	fn tag_type(&self) -> TagType {
+		TagType::Asf
+	}
+
+	fn len(&self) -> usize {
+		self.attributes.len() + self.pictures.len()
+	}
+
+	fn contains<'a>(&'a self, key: Self::RefKey<'a>) -> bool {
+		self.get(key).is_some()
+	}
+
+	fn is_empty(&self) -> bool {
+		self.attributes.is_empty() && self.pictures.is_empty()
+	}
+
+	/// Writes the tag to a file
+	///
+	/// # Errors
+	///
+	/// * Attempting to write the tag to a format that does not support it
+	/// * An attribute or picture is too large to be stored (> 65535 bytes)
+	/// * [`std::io::Error`]
+	fn save_to<F>(
+		&self,
+		file: &mut F,
+		write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err>
+	where
+		F: FileLike,
+		LoftyError: From<<F as Truncate>::Error>,
+		LoftyError: From<<F as Length>::Error>,
+	{
+		write::write_to(file, self, write_options)
+	}
+
+	/// Dumps the tag to a writer
+	///
+	/// This will write a Content Description Object and/or an Extended Content Description Object.
+	///
+	/// # Errors
+	///
+	/// * An attribute or picture is too large to be stored (> 65535 bytes)
+	/// * [`std::io::Error`]
+	fn dump_to<W: Write>(
+		&self,
+		writer: &mut W,
+		_write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err> {
+		let (objects, _) = write::create_objects(self)?;
+		writer.write_all(&objects)?;
+		Ok(())
+	}
+
+	fn clear(&mut self) {
+		self.attributes.clear();
+		self.pictures.clear();
+	}
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SplitTagRemainder(AsfTag);
+
+impl From<SplitTagRemainder> for AsfTag {
+	fn from(from: SplitTagRemainder) -> Self {
+		from.0
+	}
+}
+
+impl Deref for SplitTagRemainder {
+	type Target = AsfTag;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl SplitTag for AsfTag {
+	type Remainder = SplitTagRemainder;
+
+	fn split_tag(mut self) -> (Self::Remainder, Tag) {
+		let mut tag = Tag::new(TagType::Asf);
+
+		for attribute in std::mem::take(&mut self.attributes) {
+			let item_key = ItemKey::from_key(TagType::Asf, &attribute.name);
+
+			match (item_key, attribute.value) {
+				(ItemKey::DiscNumber | ItemKey::DiscTotal, AsfAttributeValue::String(value)) => {
+					let mut split = value.splitn(2, '/');
+					if let Some(number) = split.next() {
+						tag.items.push(TagItem::new(
+							ItemKey::DiscNumber,
+							ItemValue::Text(number.trim().to_string()),
+						));
+					}
+
+					if let Some(total) = split.next() {
+						tag.items.push(TagItem::new(
+							ItemKey::DiscTotal,
+							ItemValue::Text(total.trim().to_string()),
+						));
+					}
+				},
+				// A numeric `WM/PartOfSet` can only hold the disc number
+				(
+					ItemKey::DiscNumber | ItemKey::DiscTotal,
+					value @ (AsfAttributeValue::DWord(_)
+					| AsfAttributeValue::QWord(_)
+					| AsfAttributeValue::Word(_)),
+				) => {
+					let text = value.to_text().unwrap_or_default().into_owned();
+					tag.items
+						.push(TagItem::new(ItemKey::DiscNumber, ItemValue::Text(text)));
+				},
+				(item_key, AsfAttributeValue::String(value)) => {
+					tag.items
+						.push(TagItem::new(item_key, ItemValue::Text(value)));
+				},
+				(item_key, AsfAttributeValue::Binary(value)) => {
+					tag.items
+						.push(TagItem::new(item_key, ItemValue::Binary(value)));
+				},
+				// Numeric values can't be converted back to their original type without a known name
+				(ItemKey::Unknown(_), value) => {
+					self.attributes.push(AsfAttribute { value, ..attribute })
+				},
+				(item_key, value) => {
+					let text = value.to_text().unwrap_or_default().into_owned();
+					tag.items
+						.push(TagItem::new(item_key, ItemValue::Text(text)));
+				},
+			}
+		}
+
+		for picture in std::mem::take(&mut self.pictures) {
+			tag.push_picture(picture);
+		}
+
+		(SplitTagRemainder(self), tag)
+	}
+}
+
+impl MergeTag for SplitTagRemainder {
+	type Merged = AsfTag;
+
+	fn merge_tag(self, tag: Tag) -> Self::Merged {
+		let Self(mut merged) = self;
+
+		for item in tag.items {
+			match item.key() {
+				ItemKey::TrackNumber => set_number(&item, |number| merged.set_track(number)),
+				ItemKey::DiscNumber => set_number(&item, |number| merged.set_disk(number)),
+				ItemKey::DiscTotal => set_number(&item, |number| merged.set_disk_total(number)),
+				ItemKey::FlagCompilation => {
+					if let Some(flag) = item.value().text().and_then(flag_item) {
+						merged.insert(AsfAttribute::new(
+							String::from("WM/IsCompilation"),
+							AsfAttributeValue::Bool(flag),
+						));
+					}
+				},
+				item_key => {
+					let Some(name) = item_key.map_key(TagType::Asf, true) else {
+						continue;
+					};
+
+					if name.is_empty() {
+						continue;
+					}
+
+					let name = name.to_string();
+					let value = match item.item_value {
+						ItemValue::Text(value) | ItemValue::Locator(value) => {
+							AsfAttributeValue::String(value)
+						},
+						ItemValue::Binary(value) => AsfAttributeValue::Binary(value),
+					};
+
+					merged.push(AsfAttribute::new(name, value));
+				},
+			}
+		}
+
+		merged.pictures.extend(tag.pictures);
+
+		merged
+	}
+}
+
+impl From<AsfTag> for Tag {
+	fn from(input: AsfTag) -> Self {
+		input.split_tag().1
+	}
+}
+
+impl From<Tag> for AsfTag {
+	fn from(input: Tag) -> Self {
+		SplitTagRemainder::default().merge_tag(input)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::asf::{AsfAttribute, AsfAttributeValue, AsfTag};
+	use crate::prelude::*;
+	use crate::tag::{ItemKey, Tag, TagType};
+
+	fn attribute(name: &str, value: AsfAttributeValue) -> AsfAttribute {
+		AsfAttribute::new(String::from(name), value)
+	}
+
+	#[test_log::test]
+	fn asf_to_tag() {
+		let mut asf_tag = AsfTag::new();
+		asf_tag.push(attribute(
+			"Title",
+			AsfAttributeValue::String(String::from("Foo title")),
+		));
+		asf_tag.push(attribute(
+			"Author",
+			AsfAttributeValue::String(String::from("Bar artist")),
+		));
+		asf_tag.push(attribute(
+			"WM/AlbumTitle",
+			AsfAttributeValue::String(String::from("Baz album")),
+		));
+		asf_tag.push(attribute("WM/TrackNumber", AsfAttributeValue::DWord(1)));
+		asf_tag.push(attribute(
+			"WM/PartOfSet",
+			AsfAttributeValue::String(String::from("2/3")),
+		));
+		asf_tag.push(attribute("IsVBR", AsfAttributeValue::Bool(true)));
+
+		let (remainder, tag) = asf_tag.split_tag();
+
+		assert_eq!(tag.title().as_deref(), Some("Foo title"));
+		assert_eq!(tag.artist().as_deref(), Some("Bar artist"));
+		assert_eq!(tag.album().as_deref(), Some("Baz album"));
+		assert_eq!(tag.track(), Some(1));
+		assert_eq!(tag.disk(), Some(2));
+		assert_eq!(tag.disk_total(), Some(3));
+
+		// Unknown numeric attributes are left alone
+		assert_eq!(
+			remainder.attributes().collect::<Vec<_>>(),
+			[&attribute("IsVBR", AsfAttributeValue::Bool(true))]
+		);
+	}
+
+	#[test_log::test]
+	fn tag_to_asf() {
+		let mut tag = Tag::new(TagType::Asf);
+		tag.set_title(String::from("Foo title"));
+		tag.set_album(String::from("Bar album"));
+		tag.set_track(5);
+		tag.set_disk(1);
+		tag.set_disk_total(2);
+		tag.insert_text(ItemKey::FlagCompilation, String::from("1"));
+
+		let asf_tag: AsfTag = tag.into();
+
+		assert_eq!(asf_tag.get_string("Title"), Some("Foo title"));
+		assert_eq!(asf_tag.get_string("WM/AlbumTitle"), Some("Bar album"));
+		assert_eq!(
+			asf_tag.get("WM/TrackNumber").unwrap().value,
+			AsfAttributeValue::DWord(5)
+		);
+		assert_eq!(asf_tag.get_string("WM/PartOfSet"), Some("1/2"));
+		assert_eq!(
+			asf_tag.get("WM/IsCompilation").unwrap().value,
+			AsfAttributeValue::Bool(true)
+		);
+	}
+
+	#[test_log::test]
+	fn disk_pair() {
+		let mut asf_tag = AsfTag::new();
+		asf_tag.set_disk_total(4);
+		assert_eq!(asf_tag.get_string("WM/PartOfSet"), Some("0/4"));
+
+		asf_tag.set_disk(2);
+		assert_eq!(asf_tag.disk(), Some(2));
+		assert_eq!(asf_tag.disk_total(), Some(4));
+
+		asf_tag.remove_disk_total();
+		assert_eq!(asf_tag.get_string("WM/PartOfSet"), Some("2"));
+	}
+}
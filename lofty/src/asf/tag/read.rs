@@ -0,0 +1,153 @@
+use super::{AsfAttribute, AsfAttributeValue, AsfTag, CONTENT_DESCRIPTION_FIELDS, PICTURE_NAME};
+use crate::asf::object::read_utf16;
+use crate::config::{ParseOptions, ParsingMode};
+use crate::error::Result;
+use crate::macros::{decode_err, parse_mode_choice, try_vec};
+use crate::picture::{MimeType, Picture, PictureType};
+use crate::util::text::{read_to_terminator, utf16_decode_bytes, TextEncoding};
+
+use std::io::Read;
+
+use byteorder::{LittleEndian, ReadBytesExt};
+
+/// Read the fields of a Content Description Object
+pub(in crate::asf) fn read_content_description(mut content: &[u8], tag: &mut AsfTag) -> Result<()> {
+	let mut lengths = [0; CONTENT_DESCRIPTION_FIELDS.len()];
+	for len in &mut lengths {
+		*len = content.read_u16::<LittleEndian>()?;
+	}
+
+	for (name, len) in CONTENT_DESCRIPTION_FIELDS.into_iter().zip(lengths) {
+		let value = read_utf16(&mut content, usize::from(len))?;
+		if value.is_empty() {
+			continue;
+		}
+
+		tag.attributes.push(AsfAttribute::new(
+			String::from(name),
+			AsfAttributeValue::String(value),
+		));
+	}
+
+	Ok(())
+}
+
+/// Read the descriptors of an Extended Content Description Object
+pub(in crate::asf) fn read_extended_content_description(
+	mut content: &[u8],
+	tag: &mut AsfTag,
+	parse_options: ParseOptions,
+) -> Result<()> {
+	let parse_mode = parse_options.parsing_mode;
+
+	let descriptor_count = content.read_u16::<LittleEndian>()?;
+	for _ in 0..descriptor_count {
+		let name_len = content.read_u16::<LittleEndian>()?;
+		let name = read_utf16(&mut content, usize::from(name_len))?;
+
+		let data_type = content.read_u16::<LittleEndian>()?;
+		let value_len = content.read_u16::<LittleEndian>()?;
+
+		let mut value = try_vec![0; usize::from(value_len)];
+		content.read_exact(&mut value)?;
+
+		if name == PICTURE_NAME && data_type == 1 {
+			if !parse_options.read_cover_art {
+				continue;
+			}
+
+			match read_picture(&value) {
+				Ok(picture) => tag.pictures.push(picture),
+				Err(e) => {
+					parse_mode_choice!(
+						parse_mode,
+						STRICT: return Err(e),
+						DEFAULT: log::warn!("Unable to read \"{PICTURE_NAME}\", discarding")
+					);
+				},
+			}
+
+			continue;
+		}
+
+		let Some(value) = read_value(data_type, value, parse_mode)? else {
+			continue;
+		};
+
+		tag.attributes.push(AsfAttribute::new(name, value));
+	}
+
+	Ok(())
+}
+
+fn read_value(
+	data_type: u16,
+	value: Vec<u8>,
+	parse_mode: ParsingMode,
+) -> Result<Option<AsfAttributeValue>> {
+	let mut reader = &value[..];
+
+	let value = match data_type {
+		0 => AsfAttributeValue::String(utf16_decode_bytes(&value, u16::from_le_bytes)?),
+		1 => AsfAttributeValue::Binary(value),
+		// Booleans are supposed to be 32 bits, but 16-bit values are also seen in the wild
+		2 if value.len() == 2 => AsfAttributeValue::Bool(reader.read_u16::<LittleEndian>()? != 0),
+		2 => AsfAttributeValue::Bool(reader.read_u32::<LittleEndian>()? != 0),
+		3 => AsfAttributeValue::DWord(reader.read_u32::<LittleEndian>()?),
+		4 => AsfAttributeValue::QWord(reader.read_u64::<LittleEndian>()?),
+		5 => AsfAttributeValue::Word(reader.read_u16::<LittleEndian>()?),
+		_ => {
+			parse_mode_choice!(
+				parse_mode,
+				STRICT: decode_err!(@BAIL Asf, "Encountered an attribute with an unknown data type"),
+				DEFAULT: {
+					log::warn!("Encountered an attribute with an unknown data type ({data_type}), discarding");
+					return Ok(None);
+				}
+			);
+		},
+	};
+
+	Ok(Some(value))
+}
+
+// The `WM/Picture` structure:
+//
+// Picture type (1)
+// Data length (4)
+// MIME type (null terminated UTF-16)
+// Description (null terminated UTF-16)
+// Data
+fn read_picture(mut content: &[u8]) -> Result<Picture> {
+	let pic_type = PictureType::from_u8(content.read_u8()?);
+	let data_len = content.read_u32::<LittleEndian>()? as usize;
+
+	let (mime_type, _) = read_to_terminator(&mut content, TextEncoding::UTF16);
+	let mime_type = utf16_decode_bytes(&mime_type, u16::from_le_bytes)?;
+
+	let (description, _) = read_to_terminator(&mut content, TextEncoding::UTF16);
+	let description = utf16_decode_bytes(&description, u16::from_le_bytes)?;
+
+	if content.len() < data_len {
+		decode_err!(@BAIL Asf, "\"WM/Picture\" has an invalid data length");
+	}
+
+	let mime_type = if mime_type.is_empty() {
+		None
+	} else {
+		Some(MimeType::from_str(&mime_type))
+	};
+
+	let description = if description.is_empty() {
+		None
+	} else {
+		Some(description)
+	};
+
+	Ok(Picture::new_unchecked(
+		pic_type,
+		mime_type,
+		description,
+		content[..data_len].to_vec(),
+	))
+}
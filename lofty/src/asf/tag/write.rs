@@ -0,0 +1,343 @@
+use super::{AsfAttribute, AsfAttributeValue, AsfTag, CONTENT_DESCRIPTION_FIELDS, PICTURE_NAME};
+use crate::asf::object::{
+	encode_utf16, Guid, ObjectHeader, CONTENT_DESCRIPTION_OBJECT,
+	EXTENDED_CONTENT_DESCRIPTION_OBJECT, FILE_PROPERTIES_OBJECT, HEADER_OBJECT,
+	HEADER_OBJECT_HEADER_SIZE, OBJECT_HEADER_SIZE,
+};
+use crate::config::WriteOptions;
+use crate::error::{LoftyError, Result};
+use crate::macros::{decode_err, err, try_vec};
+use crate::picture::Picture;
+use crate::util::io::{splice_file, FileLike, Length, Truncate};
+
+use std::io::{Read, Write};
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+
+// The offset of the file size within the File Properties Object
+const FILE_SIZE_OFFSET: usize = OBJECT_HEADER_SIZE as usize + 16;
+
+pub(crate) fn write_to<F>(file: &mut F, tag: &AsfTag, _write_options: WriteOptions) -> Result<()>
+where
+	F: FileLike,
+	LoftyError: From<<F as Truncate>::Error>,
+	LoftyError: From<<F as Length>::Error>,
+{
+	let header = ObjectHeader::read(file)?;
+	if header.guid != HEADER_OBJECT {
+		decode_err!(@BAIL Asf, "File missing Header Object");
+	}
+
+	if header.size < HEADER_OBJECT_HEADER_SIZE {
+		decode_err!(@BAIL Asf, "Header Object has an invalid size");
+	}
+
+	let _object_count = file.read_u32::<LittleEndian>()?;
+	let mut reserved = [0; 2];
+	file.read_exact(&mut reserved)?;
+
+	let mut children = try_vec![0; (header.size - HEADER_OBJECT_HEADER_SIZE) as usize];
+	file.read_exact(&mut children)?;
+
+	// Everything following the Header Object is left as-is
+	let remaining_len = file.len()?.saturating_sub(header.size);
+
+	let (new_objects, new_object_count) = create_objects(tag)?;
+
+	let mut new_children = Vec::with_capacity(children.len() + new_objects.len());
+	let mut object_count = new_object_count;
+	let mut file_properties_offset = None;
+
+	let mut content = &children[..];
+	while !content.is_empty() {
+		let child = ObjectHeader::read(&mut &content[..])?;
+		if child.size > content.len() as u64 {
+			decode_err!(@BAIL Asf, "Header Object child has an invalid size");
+		}
+
+		let (object, rest) = content.split_at(child.size as usize);
+		content = rest;
+
+		match child.guid {
+			CONTENT_DESCRIPTION_OBJECT | EXTENDED_CONTENT_DESCRIPTION_OBJECT => continue,
+			FILE_PROPERTIES_OBJECT => file_properties_offset = Some(new_children.len()),
+			_ => {},
+		}
+
+		new_children.extend_from_slice(object);
+		object_count += 1;
+	}
+
+	new_children.extend(new_objects);
+
+	let new_header_size = HEADER_OBJECT_HEADER_SIZE + new_children.len() as u64;
+	let Ok(object_count) = u32::try_from(object_count) else {
+		err!(TooMuchData);
+	};
+
+	// The File Properties Object stores the size of the entire file
+	if let Some(offset) = file_properties_offset {
+		let file_size = new_header_size + remaining_len;
+		let file_size_offset = offset + FILE_SIZE_OFFSET;
+		if let Some(size) = new_children.get_mut(file_size_offset..file_size_offset + 8) {
+			size.copy_from_slice(&file_size.to_le_bytes());
+		}
+	}
+
+	let mut new_header = Vec::with_capacity(new_header_size as usize);
+	new_header.write_all(&HEADER_OBJECT)?;
+	new_header.write_u64::<LittleEndian>(new_header_size)?;
+	new_header.write_u32::<LittleEndian>(object_count)?;
+	new_header.write_all(&reserved)?;
+	new_header.write_all(&new_children)?;
+
+	// Only the Header Object changes, the rest of the file is shifted in place
+	splice_file(file, vec![(0..header.size, new_header)])?;
+
+	Ok(())
+}
+
+/// Create the Content Description and Extended Content Description Objects, returning the number of objects created
+pub(crate) fn create_objects(tag: &AsfTag) -> Result<(Vec<u8>, u32)> {
+	let mut content_description = [None; CONTENT_DESCRIPTION_FIELDS.len()];
+	let mut extended_attributes = Vec::new();
+
+	// Only the first string value for each field fits in the Content Description Object, anything
+	// else has to go in the Extended Content Description Object.
+	for attribute in &tag.attributes {
+		let field = CONTENT_DESCRIPTION_FIELDS
+			.iter()
+			.position(|field| field.eq_ignore_ascii_case(&attribute.name));
+
+		match (field, &attribute.value) {
+			(Some(idx), AsfAttributeValue::String(value)) if content_description[idx].is_none() => {
+				content_description[idx] = Some(value.as_str());
+			},
+			_ => extended_attributes.push(attribute),
+		}
+	}
+
+	let mut objects = Vec::new();
+	let mut object_count = 0;
+
+	if content_description.iter().any(Option::is_some) {
+		let content = create_content_description(&content_description)?;
+		write_object(&mut objects, CONTENT_DESCRIPTION_OBJECT, &content);
+		object_count += 1;
+	}
+
+	if !extended_attributes.is_empty() || !tag.pictures.is_empty() {
+		let content = create_extended_content_description(&extended_attributes, &tag.pictures)?;
+		write_object(&mut objects, EXTENDED_CONTENT_DESCRIPTION_OBJECT, &content);
+		object_count += 1;
+	}
+
+	Ok((objects, object_count))
+}
+
+fn write_object(writer: &mut Vec<u8>, guid: Guid, content: &[u8]) {
+	writer.extend(guid);
+	writer.extend((OBJECT_HEADER_SIZE + content.len() as u64).to_le_bytes());
+	writer.extend(content);
+}
+
+fn create_content_description(fields: &[Option<&str>]) -> Result<Vec<u8>> {
+	let encoded = fields
+		.iter()
+		.map(|field| field.map(encode_utf16).unwrap_or_default())
+		.collect::<Vec<_>>();
+
+	let mut content = Vec::new();
+	for field in &encoded {
+		content.write_u16::<LittleEndian>(u16_len(field).unwrap_or(0))?;
+	}
+
+	for field in encoded {
+		// Too large, skipped, see `u16_len`
+		if u16_len(&field).is_none() {
+			log::warn!("Content Description field is too large, skipping");
+			continue;
+		}
+
+		content.extend(field);
+	}
+
+	Ok(content)
+}
+
+fn create_extended_content_description(
+	attributes: &[&AsfAttribute],
+	pictures: &[Picture],
+) -> Result<Vec<u8>> {
+	let mut descriptors = Vec::new();
+	let mut descriptor_count = 0_usize;
+
+	for attribute in attributes {
+		let value = match &attribute.value {
+			AsfAttributeValue::String(value) => encode_utf16(value),
+			AsfAttributeValue::Binary(value) => value.clone(),
+			AsfAttributeValue::Bool(value) => u32::from(*value).to_le_bytes().to_vec(),
+			AsfAttributeValue::DWord(value) => value.to_le_bytes().to_vec(),
+			AsfAttributeValue::QWord(value) => value.to_le_bytes().to_vec(),
+			AsfAttributeValue::Word(value) => value.to_le_bytes().to_vec(),
+		};
+
+		if write_descriptor(
+			&mut descriptors,
+			&attribute.name,
+			attribute.value.data_type(),
+			&value,
+		)? {
+			descriptor_count += 1;
+		}
+	}
+
+	for picture in pictures {
+		if write_descriptor(&mut descriptors, PICTURE_NAME, 1, &create_picture(picture)?)? {
+			descriptor_count += 1;
+		}
+	}
+
+	let Ok(descriptor_count) = u16::try_from(descriptor_count) else {
+		err!(TooMuchData);
+	};
+
+	let mut content = Vec::with_capacity(2 + descriptors.len());
+	content.write_u16::<LittleEndian>(descriptor_count)?;
+	content.extend(descriptors);
+
+	Ok(content)
+}
+
+// Returns `false` if the descriptor was too large to write, see `u16_len`
+fn write_descriptor(
+	writer: &mut Vec<u8>,
+	name: &str,
+	data_type: u16,
+	value: &[u8],
+) -> Result<bool> {
+	let name = encode_utf16(name);
+
+	let (Some(name_len), Some(value_len)) = (u16_len(&name), u16_len(value)) else {
+		log::warn!("Attribute is too large for the Extended Content Description Object, skipping");
+		return Ok(false);
+	};
+
+	writer.write_u16::<LittleEndian>(name_len)?;
+	writer.extend(name);
+	writer.write_u16::<LittleEndian>(data_type)?;
+	writer.write_u16::<LittleEndian>(value_len)?;
+	writer.extend(value);
+
+	Ok(true)
+}
+
+fn create_picture(picture: &Picture) -> Result<Vec<u8>> {
+	let Ok(data_len) = u32::try_from(picture.data().len()) else {
+		err!(TooMuchData);
+	};
+
+	let mut content = Vec::new();
+	content.write_u8(picture.pic_type().as_u8())?;
+	content.write_u32::<LittleEndian>(data_len)?;
+	content.extend(encode_utf16(picture.mime_str()));
+	content.extend(encode_utf16(picture.description().unwrap_or_default()));
+	content.extend(picture.data());
+
+	Ok(content)
+}
+
+// The Content Description and Extended Content Description Objects can only store 16-bit lengths
+//
+// Anything larger would have to go in the Metadata Library Object, which isn't supported.
+fn u16_len(bytes: &[u8]) -> Option<u16> {
+	u16::try_from(bytes.len()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::asf::tag::read::{read_content_description, read_extended_content_description};
+	use crate::asf::{AsfAttribute, AsfAttributeValue, AsfTag};
+	use crate::config::ParseOptions;
+	use crate::picture::{MimeType, Picture, PictureType};
+
+	use std::io::Read;
+
+	#[test_log::test]
+	fn objects_round_trip() {
+		let mut tag = AsfTag::new();
+		tag.push(AsfAttribute::new(
+			String::from("Title"),
+			AsfAttributeValue::String(String::from("Foo title")),
+		));
+		tag.push(AsfAttribute::new(
+			String::from("WM/TrackNumber"),
+			AsfAttributeValue::DWord(3),
+		));
+		tag.push(AsfAttribute::new(
+			String::from("WM/IsCompilation"),
+			AsfAttributeValue::Bool(true),
+		));
+		tag.push_picture(Picture::new_unchecked(
+			PictureType::CoverFront,
+			Some(MimeType::Png),
+			Some(String::from("Foo description")),
+			vec![1, 2, 3, 4],
+		));
+
+		let (objects, count) = super::create_objects(&tag).unwrap();
+		assert_eq!(count, 2);
+
+		let mut reader = &objects[..];
+		let mut parsed = AsfTag::new();
+
+		let content_description = super::ObjectHeader::read(&mut reader).unwrap();
+		let mut content = vec![0; content_description.content_len() as usize];
+		reader.read_exact(&mut content).unwrap();
+		read_content_description(&content, &mut parsed).unwrap();
+
+		let extended_content_description = super::ObjectHeader::read(&mut reader).unwrap();
+		let mut content = vec![0; extended_content_description.content_len() as usize];
+		reader.read_exact(&mut content).unwrap();
+		read_extended_content_description(&content, &mut parsed, ParseOptions::new()).unwrap();
+
+		assert_eq!(parsed, tag);
+	}
+
+	#[test_log::test]
+	fn oversized_attributes_skipped() {
+		let mut tag = AsfTag::new();
+		tag.push(AsfAttribute::new(
+			String::from("WM/AlbumTitle"),
+			AsfAttributeValue::String(String::from("Foo album")),
+		));
+		tag.push(AsfAttribute::new(
+			String::from("WM/Lyrics"),
+			AsfAttributeValue::String("a".repeat(usize::from(u16::MAX))),
+		));
+		tag.push_picture(Picture::new_unchecked(
+			PictureType::CoverFront,
+			Some(MimeType::Png),
+			None,
+			vec![0; usize::from(u16::MAX) + 1],
+		));
+
+		let (objects, count) = super::create_objects(&tag).unwrap();
+		assert_eq!(count, 1);
+
+		let mut reader = &objects[..];
+		let extended_content_description = super::ObjectHeader::read(&mut reader).unwrap();
+		let mut content = vec![0; extended_content_description.content_len() as usize];
+		reader.read_exact(&mut content).unwrap();
+
+		let mut parsed = AsfTag::new();
+		read_extended_content_description(&content, &mut parsed, ParseOptions::new()).unwrap();
+
+		let mut expected = AsfTag::new();
+		expected.push(AsfAttribute::new(
+			String::from("WM/AlbumTitle"),
+			AsfAttributeValue::String(String::from("Foo album")),
+		));
+		assert_eq!(parsed, expected);
+	}
+}
@@ -10,6 +10,8 @@ pub struct WriteOptions {
 	pub(crate) respect_read_only: bool,
 	pub(crate) uppercase_id3v2_chunk: bool,
 	pub(crate) use_id3v23: bool,
+	pub(crate) moov_position: MoovPosition,
+	pub(crate) migrate_quicktime_text: bool,
 }
 
 impl WriteOptions {
@@ -34,6 +36,8 @@ impl WriteOptions {
 			respect_read_only: true,
 			uppercase_id3v2_chunk: true,
 			use_id3v23: false,
+			moov_position: MoovPosition::Preserve,
+			migrate_quicktime_text: false,
 		}
 	}
 
@@ -46,6 +50,8 @@ impl WriteOptions {
 	///
 	/// * Not all tag formats support padding
 	/// * The actual padding size may be different from this value, depending on tag size limitations
+	/// * FLAC files are only rewritten when the new metadata doesn't fit in the existing padding. Otherwise,
+	///   the metadata is written in place, and the padding shrinks or grows to fill the remaining space.
 	///
 	/// # Examples
 	///
@@ -177,6 +183,61 @@ impl WriteOptions {
 		self.use_id3v23 = use_id3v23;
 		*self
 	}
+
+	/// Where to place the `moov` atom when saving MP4 files, see [`MoovPosition`] for details
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// use lofty::config::{MoovPosition, WriteOptions};
+	/// use lofty::prelude::*;
+	/// use lofty::tag::{Tag, TagType};
+	///
+	/// # fn main() -> lofty::error::Result<()> {
+	/// let mut ilst = Tag::new(TagType::Mp4Ilst);
+	///
+	/// // ...
+	///
+	/// // My files are streamed, so they need to be playable before they're fully downloaded
+	/// let options = WriteOptions::new().moov_position(MoovPosition::Start);
+	/// ilst.save_to_path("test.m4a", options)?;
+	/// # Ok(()) }
+	/// ```
+	pub fn moov_position(mut self, moov_position: MoovPosition) -> Self {
+		self.moov_position = moov_position;
+		self
+	}
+
+	/// Whether to remove the QuickTime text atoms (Ex. `©nam`) from `moov.udta` when saving MP4 files
+	///
+	/// Older QuickTime software stores its metadata in these atoms, rather than in an `ilst` atom.
+	/// When reading, they're merged into the [`Ilst`](crate::mp4::Ilst), so saving it with this
+	/// enabled will move them into the `ilst` atom. Otherwise, only the items that were changed are
+	/// written to the `ilst` atom.
+	///
+	/// NOTE: If the `Ilst` wasn't read from the file, or items were removed from it, the text atoms will
+	/// be lost. When disabled, a removed item will reappear the next time the file is read.
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// use lofty::config::{ParseOptions, WriteOptions};
+	/// use lofty::file::AudioFile;
+	/// use lofty::mp4::Mp4File;
+	///
+	/// # fn main() -> lofty::error::Result<()> {
+	/// # let path = "foo.mov";
+	/// let mp4_file = Mp4File::read_from(&mut std::fs::File::open(path)?, ParseOptions::new())?;
+	///
+	/// // I only want my metadata in the `ilst` atom
+	/// let options = WriteOptions::new().migrate_quicktime_text(true);
+	/// mp4_file.save_to_path(path, options)?;
+	/// # Ok(()) }
+	/// ```
+	pub fn migrate_quicktime_text(mut self, migrate_quicktime_text: bool) -> Self {
+		self.migrate_quicktime_text = migrate_quicktime_text;
+		self
+	}
 }
 
 impl Default for WriteOptions {
@@ -191,9 +252,40 @@ impl Default for WriteOptions {
 	///     respect_read_only: true,
 	///     uppercase_id3v2_chunk: true,
 	///     use_id3v23: false,
+	///     moov_position: MoovPosition::Preserve,
+	///     migrate_quicktime_text: false,
 	/// }
 	/// ```
 	fn default() -> Self {
 		Self::new()
 	}
 }
+
+/// Where to place the `moov` atom when saving MP4 files
+///
+/// The `moov` atom holds the information needed to play the file, including the offsets of
+/// the audio in the `mdat` atom. Moving it requires updating all of those offsets.
+///
+/// NOTE: Fragmented files are always left as-is, since their `moov` atom has to come before the
+/// `moof` atoms.
+///
+/// This can be set with [`WriteOptions::moov_position`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+#[non_exhaustive]
+pub enum MoovPosition {
+	/// Leave the `moov` atom where it is
+	#[default]
+	Preserve,
+	/// Place the `moov` atom before the `mdat` atom (commonly known as "faststart")
+	///
+	/// This allows the file to be played before it is fully downloaded.
+	///
+	/// A `free` atom of [`WriteOptions::preferred_padding`] bytes is placed between the `moov` and
+	/// `mdat` atoms, so that future edits can be made without moving the audio. If the `moov` atom
+	/// is already at the start, this padding will be used to keep the `mdat` atom in place.
+	Start,
+	/// Place the `moov` atom at the end of the file, after the `mdat` atom
+	///
+	/// The `moov` atom can then grow without moving the audio, at the expense of streaming.
+	End,
+}
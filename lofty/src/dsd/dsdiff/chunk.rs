@@ -0,0 +1,89 @@
+use crate::error::Result;
+use crate::macros::{decode_err, err};
+
+use std::io::{Read, Seek, SeekFrom};
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+// The size of a chunk's ID and size fields
+pub(crate) const CHUNK_HEADER_SIZE: u64 = 12;
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct ChunkHeader {
+	pub(crate) id: [u8; 4],
+	/// The size of the chunk's data, *excluding* the header and padding byte
+	pub(crate) size: u64,
+}
+
+impl ChunkHeader {
+	pub(crate) fn read<R>(reader: &mut R) -> Result<Self>
+	where
+		R: Read,
+	{
+		let mut id = [0; 4];
+		reader.read_exact(&mut id)?;
+
+		let size = reader.read_u64::<BigEndian>()?;
+
+		Ok(Self { id, size })
+	}
+
+	/// The size of the chunk's data, including the padding byte if there is one
+	///
+	/// Chunks are expected to start on even boundaries, so odd sized chunks are padded with a 0.
+	pub(crate) fn padded_size(&self) -> u64 {
+		self.size.saturating_add(self.size % 2)
+	}
+
+	pub(crate) fn skip<R>(&self, reader: &mut R) -> Result<()>
+	where
+		R: Seek,
+	{
+		let Ok(size) = i64::try_from(self.padded_size()) else {
+			err!(SizeMismatch);
+		};
+
+		reader.seek(SeekFrom::Current(size))?;
+		Ok(())
+	}
+}
+
+/// Verifies the "FRM8" chunk, returning its header
+pub(crate) fn verify_dsdiff<R>(reader: &mut R) -> Result<ChunkHeader>
+where
+	R: Read,
+{
+	let header = ChunkHeader::read(reader)?;
+	if &header.id != b"FRM8" {
+		err!(UnknownFormat);
+	}
+
+	let mut form_type = [0; 4];
+	reader.read_exact(&mut form_type)?;
+	if &form_type != b"DSD " {
+		err!(UnknownFormat);
+	}
+
+	log::debug!("File verified to be DSDIFF");
+	Ok(header)
+}
+
+/// Calls `f` with the header and content of each sub-chunk within `content`
+pub(crate) fn for_each_sub_chunk<F>(mut content: &[u8], mut f: F) -> Result<()>
+where
+	F: FnMut(ChunkHeader, &[u8]) -> Result<()>,
+{
+	while content.len() as u64 >= CHUNK_HEADER_SIZE {
+		let header = ChunkHeader::read(&mut content)?;
+		if header.size > content.len() as u64 {
+			decode_err!(@BAIL Dsdiff, "Encountered a sub-chunk with an invalid size");
+		}
+
+		let (chunk_content, rest) = content.split_at(header.size as usize);
+		f(header, chunk_content)?;
+
+		content = rest.get((header.size % 2) as usize..).unwrap_or_default();
+	}
+
+	Ok(())
+}
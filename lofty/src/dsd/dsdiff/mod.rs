@@ -0,0 +1,68 @@
+//! DSDIFF specific items
+//!
+//! ## File notes
+//!
+//! DSDIFF has no official tagging support, though an ID3v2 tag stored in an "ID3 " chunk is widely supported.
+//!
+//! The "DIIN" (Edited Master Information) chunk can also hold an artist and title. This is
+//! available through [`DsdiffFile::edited_master_info`], and is never written.
+pub(crate) mod chunk;
+mod read;
+
+use crate::dsd::DsdProperties;
+use crate::id3::v2::tag::Id3v2Tag;
+use crate::macros::impl_chapters;
+
+use lofty_attr::LoftyFile;
+
+/// The contents of a DSDIFF "DIIN" (Edited Master Information) chunk
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub struct EditedMasterInfo {
+	/// The edited master ID ("EMID")
+	pub id: Option<String>,
+	/// The artist ("DIAR")
+	pub artist: Option<String>,
+	/// The title ("DITI")
+	pub title: Option<String>,
+}
+
+/// A DSDIFF file
+#[derive(LoftyFile)]
+#[lofty(read_fn = "read::read_from")]
+#[lofty(internal_write_module_do_not_use_anywhere_else)]
+pub struct DsdiffFile {
+	/// An ID3v2 tag
+	#[lofty(tag_type = "Id3v2")]
+	pub(crate) id3v2_tag: Option<Id3v2Tag>,
+	/// The contents of the "DIIN" chunk
+	pub(crate) edited_master_info: Option<EditedMasterInfo>,
+	/// The file's audio properties
+	pub(crate) properties: DsdProperties,
+}
+
+impl_chapters!(DsdiffFile, id3v2_tag, Id3v2Tag);
+
+impl DsdiffFile {
+	/// Returns a reference to the file's "DIIN" (Edited Master Information) chunk, if it exists
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// use lofty::config::ParseOptions;
+	/// use lofty::dsd::dsdiff::DsdiffFile;
+	/// use lofty::file::AudioFile;
+	///
+	/// # fn main() -> lofty::error::Result<()> {
+	/// # let mut reader = std::io::Cursor::new(Vec::new());
+	/// let dsdiff_file = DsdiffFile::read_from(&mut reader, ParseOptions::new())?;
+	///
+	/// if let Some(info) = dsdiff_file.edited_master_info() {
+	/// 	println!("Artist: {:?}", info.artist);
+	/// }
+	/// # Ok(()) }
+	/// ```
+	pub fn edited_master_info(&self) -> Option<&EditedMasterInfo> {
+		self.edited_master_info.as_ref()
+	}
+}
@@ -0,0 +1,205 @@
+use super::chunk::{for_each_sub_chunk, verify_dsdiff, ChunkHeader, CHUNK_HEADER_SIZE};
+use super::{DsdiffFile, EditedMasterInfo};
+use crate::config::ParseOptions;
+use crate::dsd::properties::read_properties;
+use crate::dsd::DsdProperties;
+use crate::error::Result;
+use crate::id3::v2::header::Id3v2Header;
+use crate::id3::v2::read::parse_id3v2;
+use crate::id3::v2::tag::Id3v2Tag;
+use crate::macros::{decode_err, err, try_vec};
+use crate::util::io::SeekStreamLen;
+use crate::util::text::utf8_decode;
+
+use std::io::{Read, Seek, SeekFrom};
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+/// The audio data, either raw DSD or DST compressed
+enum SoundData {
+	Dsd {
+		size: u64,
+	},
+	Dst {
+		size: u64,
+		frame_count: u32,
+		frame_rate: u16,
+	},
+}
+
+pub(super) fn read_from<R>(reader: &mut R, parse_options: ParseOptions) -> Result<DsdiffFile>
+where
+	R: Read + Seek,
+{
+	let file_length = reader.stream_len_hack()?;
+
+	let form = verify_dsdiff(reader)?;
+	let form_end = form.size.saturating_add(CHUNK_HEADER_SIZE).min(file_length);
+
+	let mut sample_rate = None;
+	let mut channels = None;
+	let mut sound_data = None;
+
+	let mut id3v2_tag: Option<Id3v2Tag> = None;
+	let mut edited_master_info = None;
+
+	while reader.stream_position()? + CHUNK_HEADER_SIZE <= form_end {
+		let chunk = ChunkHeader::read(reader)?;
+		if reader.stream_position()?.saturating_add(chunk.size) > form_end {
+			decode_err!(@BAIL Dsdiff, "Encountered a chunk with an invalid size");
+		}
+
+		match &chunk.id {
+			b"ID3 " | b"id3 " if parse_options.read_tags => {
+				let content = read_content(reader, chunk)?;
+				let reader = &mut &*content;
+
+				let header = Id3v2Header::parse(reader)?;
+				let tag = parse_id3v2(reader, header, parse_options)?;
+				if let Some(existing_tag) = id3v2_tag.as_mut() {
+					log::warn!("Duplicate ID3v2 tag found, appending frames to previous tag");
+
+					for frame in tag.frames {
+						existing_tag.insert(frame);
+					}
+					continue;
+				}
+				id3v2_tag = Some(tag);
+			},
+			b"DIIN" if parse_options.read_tags && edited_master_info.is_none() => {
+				let content = read_content(reader, chunk)?;
+				edited_master_info = Some(read_edited_master_info(&content)?);
+			},
+			b"PROP" if parse_options.read_properties && sample_rate.is_none() => {
+				let content = read_content(reader, chunk)?;
+				if !content.starts_with(b"SND ") {
+					continue;
+				}
+
+				for_each_sub_chunk(&content[4..], |header, mut content| {
+					match &header.id {
+						b"FS  " => sample_rate = Some(content.read_u32::<BigEndian>()?),
+						b"CHNL" => channels = Some(content.read_u16::<BigEndian>()?),
+						_ => {},
+					}
+
+					Ok(())
+				})?;
+			},
+			b"DSD " if parse_options.read_properties => {
+				sound_data = Some(SoundData::Dsd { size: chunk.size });
+				chunk.skip(reader)?;
+			},
+			b"DST " if parse_options.read_properties => {
+				let content_start = reader.stream_position()?;
+
+				// The "FRTE" chunk is required to be the first chunk within "DST "
+				let frte = ChunkHeader::read(reader)?;
+				if &frte.id != b"FRTE" || frte.size < 6 {
+					decode_err!(@BAIL Dsdiff, "\"DST \" chunk is missing a valid \"FRTE\" chunk");
+				}
+
+				let frame_count = reader.read_u32::<BigEndian>()?;
+				let frame_rate = reader.read_u16::<BigEndian>()?;
+
+				sound_data = Some(SoundData::Dst {
+					size: chunk.size,
+					frame_count,
+					frame_rate,
+				});
+
+				// Skip the DST frames
+				reader.seek(SeekFrom::Start(content_start + chunk.padded_size()))?;
+			},
+			_ => chunk.skip(reader)?,
+		}
+	}
+
+	let properties = if parse_options.read_properties {
+		let (Some(sample_rate), Some(channels)) = (sample_rate, channels) else {
+			decode_err!(@BAIL Dsdiff, "File is missing a valid \"PROP\" chunk");
+		};
+
+		if channels == 0 || channels > u16::from(u8::MAX) {
+			decode_err!(@BAIL Dsdiff, "File has an invalid channel count");
+		}
+
+		let (stream_len, sample_count) = match sound_data {
+			// Every byte holds 8 samples of a single channel
+			Some(SoundData::Dsd { size }) => (size, size * 8 / u64::from(channels)),
+			Some(SoundData::Dst {
+				size,
+				frame_count,
+				frame_rate,
+			}) => {
+				let sample_count = if frame_rate == 0 {
+					0
+				} else {
+					u64::from(frame_count) * u64::from(sample_rate) / u64::from(frame_rate)
+				};
+
+				(size, sample_count)
+			},
+			None => decode_err!(@BAIL Dsdiff, "File does not contain a \"DSD \" or \"DST \" chunk"),
+		};
+
+		read_properties(
+			sample_rate,
+			channels as u8,
+			sample_count,
+			stream_len,
+			file_length,
+		)
+	} else {
+		DsdProperties::default()
+	};
+
+	Ok(DsdiffFile {
+		id3v2_tag,
+		edited_master_info,
+		properties,
+	})
+}
+
+fn read_content<R>(reader: &mut R, chunk: ChunkHeader) -> Result<Vec<u8>>
+where
+	R: Read + Seek,
+{
+	let mut content = try_vec![0; chunk.size as usize];
+	reader.read_exact(&mut content)?;
+
+	if chunk.size % 2 != 0 {
+		reader.seek(SeekFrom::Current(1))?;
+	}
+
+	Ok(content)
+}
+
+fn read_edited_master_info(content: &[u8]) -> Result<EditedMasterInfo> {
+	let mut info = EditedMasterInfo::default();
+
+	for_each_sub_chunk(content, |header, mut content| {
+		match &header.id {
+			b"EMID" => info.id = Some(utf8_decode(content.to_vec())?),
+			// Both are prefixed with a 32-bit count
+			b"DIAR" | b"DITI" => {
+				let count = content.read_u32::<BigEndian>()? as usize;
+				if count > content.len() {
+					err!(SizeMismatch);
+				}
+
+				let text = utf8_decode(content[..count].to_vec())?;
+				if &header.id == b"DIAR" {
+					info.artist = Some(text);
+				} else {
+					info.title = Some(text);
+				}
+			},
+			_ => {},
+		}
+
+		Ok(())
+	})?;
+
+	Ok(info)
+}
@@ -0,0 +1,127 @@
+use crate::error::Result;
+use crate::macros::{decode_err, err};
+
+use std::io::{Read, Seek, SeekFrom};
+
+use byteorder::{LittleEndian, ReadBytesExt};
+
+// The size of a chunk's ID and size fields
+const CHUNK_HEADER_SIZE: u64 = 12;
+// The size of the "DSD " chunk, including its header
+const DSD_CHUNK_SIZE: u64 = 28;
+// The smallest possible "fmt " chunk, including its header
+const FMT_CHUNK_MIN_SIZE: u64 = 52;
+
+/// The offset of the total file size within the "DSD " chunk, followed by the metadata offset
+pub(crate) const FILE_SIZE_OFFSET: u64 = 12;
+
+/// The "DSD " chunk
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct DsdChunk {
+	/// The offset of the ID3v2 tag, 0 if there is no tag
+	pub(crate) metadata_offset: u64,
+}
+
+impl DsdChunk {
+	pub(crate) fn read<R>(reader: &mut R) -> Result<Self>
+	where
+		R: Read + Seek,
+	{
+		let header = ChunkHeader::read(reader)?;
+		if &header.id != b"DSD " {
+			err!(UnknownFormat);
+		}
+
+		if header.size < DSD_CHUNK_SIZE {
+			decode_err!(@BAIL Dsf, "File has an invalid \"DSD \" chunk size (< 28)");
+		}
+
+		let _file_size = reader.read_u64::<LittleEndian>()?;
+		let metadata_offset = reader.read_u64::<LittleEndian>()?;
+
+		// Skip the rest of the chunk
+		let Ok(remaining) = i64::try_from(header.size - DSD_CHUNK_SIZE) else {
+			err!(SizeMismatch);
+		};
+
+		reader.seek(SeekFrom::Current(remaining))?;
+
+		Ok(Self { metadata_offset })
+	}
+}
+
+/// The "fmt " chunk
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct FmtChunk {
+	pub(crate) channels: u32,
+	pub(crate) sample_rate: u32,
+	/// The number of samples per channel
+	pub(crate) sample_count: u64,
+}
+
+impl FmtChunk {
+	pub(crate) fn read<R>(reader: &mut R) -> Result<Self>
+	where
+		R: Read + Seek,
+	{
+		let header = ChunkHeader::read(reader)?;
+		if &header.id != b"fmt " {
+			decode_err!(@BAIL Dsf, "File missing \"fmt \" chunk");
+		}
+
+		if header.size < FMT_CHUNK_MIN_SIZE {
+			decode_err!(@BAIL Dsf, "File has an invalid \"fmt \" chunk size (< 52)");
+		}
+
+		let _format_version = reader.read_u32::<LittleEndian>()?;
+		let format_id = reader.read_u32::<LittleEndian>()?;
+		if format_id != 0 {
+			log::warn!("Encountered an unknown DSF format ID ({format_id})");
+		}
+
+		let _channel_type = reader.read_u32::<LittleEndian>()?;
+		let channels = reader.read_u32::<LittleEndian>()?;
+		let sample_rate = reader.read_u32::<LittleEndian>()?;
+		let _bits_per_sample = reader.read_u32::<LittleEndian>()?;
+		let sample_count = reader.read_u64::<LittleEndian>()?;
+		let _block_size_per_channel = reader.read_u32::<LittleEndian>()?;
+		let _reserved = reader.read_u32::<LittleEndian>()?;
+
+		// Skip the rest of the chunk
+		let Ok(remaining) = i64::try_from(header.size - FMT_CHUNK_MIN_SIZE) else {
+			err!(SizeMismatch);
+		};
+
+		reader.seek(SeekFrom::Current(remaining))?;
+
+		Ok(Self {
+			channels,
+			sample_rate,
+			sample_count,
+		})
+	}
+}
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct ChunkHeader {
+	pub(crate) id: [u8; 4],
+	/// The size of the chunk, *including* the header
+	pub(crate) size: u64,
+}
+
+impl ChunkHeader {
+	pub(crate) fn read<R>(reader: &mut R) -> Result<Self>
+	where
+		R: Read,
+	{
+		let mut id = [0; 4];
+		reader.read_exact(&mut id)?;
+
+		let size = reader.read_u64::<LittleEndian>()?;
+		if size < CHUNK_HEADER_SIZE {
+			decode_err!(@BAIL Dsf, "Encountered a chunk with an invalid size (< 12)");
+		}
+
+		Ok(Self { id, size })
+	}
+}
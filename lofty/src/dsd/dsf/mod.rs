@@ -0,0 +1,28 @@
+//! DSF specific items
+//!
+//! ## File notes
+//!
+//! DSF files store their ID3v2 tag at the very end of the file, after the audio data. Its location
+//! is recorded in the "DSD " chunk, which will be updated when writing.
+pub(crate) mod chunk;
+mod read;
+
+use crate::dsd::DsdProperties;
+use crate::id3::v2::tag::Id3v2Tag;
+use crate::macros::impl_chapters;
+
+use lofty_attr::LoftyFile;
+
+/// A DSF file
+#[derive(LoftyFile)]
+#[lofty(read_fn = "read::read_from")]
+#[lofty(internal_write_module_do_not_use_anywhere_else)]
+pub struct DsfFile {
+	/// An ID3v2 tag
+	#[lofty(tag_type = "Id3v2")]
+	pub(crate) id3v2_tag: Option<Id3v2Tag>,
+	/// The file's audio properties
+	pub(crate) properties: DsdProperties,
+}
+
+impl_chapters!(DsfFile, id3v2_tag, Id3v2Tag);
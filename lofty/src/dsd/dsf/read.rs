@@ -0,0 +1,71 @@
+use super::chunk::{ChunkHeader, DsdChunk, FmtChunk};
+use super::DsfFile;
+use crate::config::ParseOptions;
+use crate::dsd::properties::read_properties;
+use crate::dsd::DsdProperties;
+use crate::error::Result;
+use crate::id3::v2::header::Id3v2Header;
+use crate::id3::v2::read::parse_id3v2;
+use crate::macros::{decode_err, parse_mode_choice};
+use crate::util::io::SeekStreamLen;
+
+use std::io::{Read, Seek, SeekFrom};
+
+pub(super) fn read_from<R>(reader: &mut R, parse_options: ParseOptions) -> Result<DsfFile>
+where
+	R: Read + Seek,
+{
+	let file_length = reader.stream_len_hack()?;
+
+	let dsd_chunk = DsdChunk::read(reader)?;
+	let fmt_chunk = FmtChunk::read(reader)?;
+
+	let data_chunk = ChunkHeader::read(reader)?;
+	if &data_chunk.id != b"data" {
+		decode_err!(@BAIL Dsf, "File missing \"data\" chunk");
+	}
+
+	let mut id3v2_tag = None;
+	if parse_options.read_tags && dsd_chunk.metadata_offset != 0 {
+		if dsd_chunk.metadata_offset >= file_length {
+			parse_mode_choice!(
+				parse_options.parsing_mode,
+				STRICT: decode_err!(@BAIL Dsf, "Metadata offset points past the end of the file"),
+				DEFAULT: log::warn!("Metadata offset points past the end of the file, ignoring")
+			);
+		} else {
+			reader.seek(SeekFrom::Start(dsd_chunk.metadata_offset))?;
+
+			let header = Id3v2Header::parse(reader)?;
+			let tag = parse_id3v2(reader, header, parse_options)?;
+			id3v2_tag = Some(tag);
+		}
+	}
+
+	let properties = if parse_options.read_properties {
+		if fmt_chunk.channels == 0 || fmt_chunk.channels > u32::from(u8::MAX) {
+			decode_err!(@BAIL Dsf, "File has an invalid channel count");
+		}
+
+		// The "data" chunk is padded out to a whole block for each channel, so its size can't be used
+		let stream_len = fmt_chunk
+			.sample_count
+			.saturating_mul(u64::from(fmt_chunk.channels))
+			/ 8;
+
+		read_properties(
+			fmt_chunk.sample_rate,
+			fmt_chunk.channels as u8,
+			fmt_chunk.sample_count,
+			stream_len,
+			file_length,
+		)
+	} else {
+		DsdProperties::default()
+	};
+
+	Ok(DsfFile {
+		id3v2_tag,
+		properties,
+	})
+}
@@ -0,0 +1,8 @@
+//! DSF/DSDIFF specific items
+pub mod dsdiff;
+pub mod dsf;
+mod properties;
+
+// Exports
+
+pub use properties::DsdProperties;
@@ -0,0 +1,92 @@
+use crate::properties::FileProperties;
+use crate::util::math::RoundedDivision;
+
+use std::time::Duration;
+
+/// A DSF or DSDIFF file's audio properties
+///
+/// DSD audio is always 1-bit, so the [`FileProperties`] created from these will have
+/// a `bit_depth` of 1.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub struct DsdProperties {
+	pub(crate) duration: Duration,
+	pub(crate) overall_bitrate: u32,
+	pub(crate) audio_bitrate: u32,
+	pub(crate) sample_rate: u32,
+	pub(crate) channels: u8,
+}
+
+impl From<DsdProperties> for FileProperties {
+	fn from(input: DsdProperties) -> Self {
+		Self {
+			duration: input.duration,
+			overall_bitrate: Some(input.overall_bitrate),
+			audio_bitrate: Some(input.audio_bitrate),
+			sample_rate: Some(input.sample_rate),
+			bit_depth: Some(1),
+			channels: Some(input.channels),
+			channel_mask: None,
+		}
+	}
+}
+
+impl DsdProperties {
+	/// Duration of the audio
+	pub fn duration(&self) -> Duration {
+		self.duration
+	}
+
+	/// Overall bitrate (kbps)
+	pub fn overall_bitrate(&self) -> u32 {
+		self.overall_bitrate
+	}
+
+	/// Audio bitrate (kbps)
+	pub fn audio_bitrate(&self) -> u32 {
+		self.audio_bitrate
+	}
+
+	/// Sample rate (Hz)
+	///
+	/// This is the DSD sample rate, for example 2822400 Hz for DSD64.
+	pub fn sample_rate(&self) -> u32 {
+		self.sample_rate
+	}
+
+	/// Channel count
+	pub fn channels(&self) -> u8 {
+		self.channels
+	}
+}
+
+pub(super) fn read_properties(
+	sample_rate: u32,
+	channels: u8,
+	sample_count: u64,
+	stream_len: u64,
+	file_length: u64,
+) -> DsdProperties {
+	let mut properties = DsdProperties {
+		sample_rate,
+		channels,
+		..DsdProperties::default()
+	};
+
+	if sample_rate == 0 {
+		log::warn!("DSD file has a sample rate of 0, unable to calculate duration");
+		return properties;
+	}
+
+	// The sizes come straight from the file, so these are done in u128 to avoid overflowing
+	let length = (u128::from(sample_count) * 1000).div_round(u128::from(sample_rate)) as u64;
+	properties.duration = Duration::from_millis(length);
+
+	if length > 0 {
+		let length = u128::from(length);
+		properties.overall_bitrate = (u128::from(file_length) * 8).div_round(length) as u32;
+		properties.audio_bitrate = (u128::from(stream_len) * 8).div_round(length) as u32;
+	}
+
+	properties
+}
@@ -13,13 +13,18 @@ pub enum FileType {
 	Aac,
 	Aiff,
 	Ape,
+	Asf,
+	Dsdiff,
+	Dsf,
 	Flac,
+	Matroska,
 	Mpeg,
 	Mp4,
 	Mpc,
 	Opus,
 	Vorbis,
 	Speex,
+	OggFlac,
 	Wav,
 	WavPack,
 	Custom(&'static str),
@@ -31,9 +36,13 @@ impl FileType {
 	/// | [`FileType`]                      | [`TagType`]      |
 	/// |-----------------------------------|------------------|
 	/// | `Aac`, `Aiff`, `Mp3`, `Wav`       | `Id3v2`          |
+	/// | `Dsf`, `Dsdiff`                   | `Id3v2`          |
 	/// | `Ape` , `Mpc`, `WavPack`          | `Ape`            |
 	/// | `Flac`, `Opus`, `Vorbis`, `Speex` | `VorbisComments` |
+	/// | `OggFlac`                         | `VorbisComments` |
 	/// | `Mp4`                             | `Mp4Ilst`        |
+	/// | `Matroska`                        | `Matroska`       |
+	/// | `Asf`                             | `Asf`            |
 	///
 	/// # Panics
 	///
@@ -50,12 +59,21 @@ impl FileType {
 	/// ```
 	pub fn primary_tag_type(&self) -> TagType {
 		match self {
-			FileType::Aac | FileType::Aiff | FileType::Mpeg | FileType::Wav => TagType::Id3v2,
+			FileType::Aac
+			| FileType::Aiff
+			| FileType::Dsdiff
+			| FileType::Dsf
+			| FileType::Mpeg
+			| FileType::Wav => TagType::Id3v2,
 			FileType::Ape | FileType::Mpc | FileType::WavPack => TagType::Ape,
-			FileType::Flac | FileType::Opus | FileType::Vorbis | FileType::Speex => {
-				TagType::VorbisComments
-			},
+			FileType::Flac
+			| FileType::Opus
+			| FileType::Vorbis
+			| FileType::Speex
+			| FileType::OggFlac => TagType::VorbisComments,
 			FileType::Mp4 => TagType::Mp4Ilst,
+			FileType::Matroska => TagType::Matroska,
+			FileType::Asf => TagType::Asf,
 			FileType::Custom(c) => {
 				let resolver = crate::resolve::lookup_resolver(c);
 				resolver.primary_tag_type()
@@ -96,11 +114,17 @@ impl FileType {
 			TagType::VorbisComments => crate::ogg::VorbisComments::SUPPORTED_FORMATS.contains(self),
 			TagType::RiffInfo => crate::iff::wav::RiffInfoList::SUPPORTED_FORMATS.contains(self),
 			TagType::AiffText => crate::iff::aiff::AiffTextChunks::SUPPORTED_FORMATS.contains(self),
+			TagType::Matroska => crate::matroska::MatroskaTag::SUPPORTED_FORMATS.contains(self),
+			TagType::Asf => crate::asf::AsfTag::SUPPORTED_FORMATS.contains(self),
+			TagType::Xmp => crate::xmp::XmpTag::SUPPORTED_FORMATS.contains(self),
 		}
 	}
 
 	/// Attempts to extract a [`FileType`] from an extension
 	///
+	/// NOTE: `.oga` is used for Ogg streams of any codec, so it can't be mapped to a single [`FileType`].
+	///       Use [`Probe::guess_file_type`](crate::probe::Probe::guess_file_type) for those files instead.
+	///
 	/// # Examples
 	///
 	/// ```rust
@@ -132,7 +156,7 @@ impl FileType {
 			"ape" => Some(Self::Ape),
 			"aiff" | "aif" | "afc" | "aifc" => Some(Self::Aiff),
 			"mp3" | "mp2" | "mp1" => Some(Self::Mpeg),
-			"wav" | "wave" => Some(Self::Wav),
+			"wav" | "wave" | "w64" | "rf64" | "bw64" => Some(Self::Wav),
 			"wv" => Some(Self::WavPack),
 			"opus" => Some(Self::Opus),
 			"flac" => Some(Self::Flac),
@@ -140,6 +164,10 @@ impl FileType {
 			"mp4" | "m4a" | "m4b" | "m4p" | "m4r" | "m4v" | "3gp" => Some(Self::Mp4),
 			"mpc" | "mp+" | "mpp" => Some(Self::Mpc),
 			"spx" => Some(Self::Speex),
+			"mkv" | "mka" | "mks" | "webm" => Some(Self::Matroska),
+			"wma" | "wmv" | "asf" => Some(Self::Asf),
+			"dsf" => Some(Self::Dsf),
+			"dff" => Some(Self::Dsdiff),
 			_ => None,
 		}
 	}
@@ -237,6 +265,7 @@ impl FileType {
 		// Safe to index, since we return early on an empty buffer
 		match buf[0] {
 			77 if buf.starts_with(b"MAC") => Some(Self::Ape),
+			65 if buf.starts_with(b"ADIF") => Some(Self::Aac),
 			255 if buf.len() >= 2 && verify_frame_sync([buf[0], buf[1]]) => {
 				// ADTS and MPEG frame headers are way too similar
 
@@ -271,6 +300,10 @@ impl FileType {
 
 				Some(Self::Mpeg)
 			},
+			68 if buf.starts_with(b"DSD ") => Some(Self::Dsf),
+			70 if buf.len() >= 16 && &buf[..4] == b"FRM8" && &buf[12..16] == b"DSD " => {
+				Some(Self::Dsdiff)
+			},
 			70 if buf.len() >= 12 && &buf[..4] == b"FORM" => {
 				let id = &buf[8..12];
 
@@ -287,19 +320,27 @@ impl FileType {
 					return Some(Self::Opus);
 				} else if &buf[28..36] == b"Speex   " {
 					return Some(Self::Speex);
+				} else if &buf[28..33] == b"\x7FFLAC" {
+					return Some(Self::OggFlac);
 				}
 
 				None
 			},
 			102 if buf.starts_with(b"fLaC") => Some(Self::Flac),
-			82 if buf.len() >= 12 && &buf[..4] == b"RIFF" => {
+			82 if buf.len() >= 12 && (&buf[..4] == b"RIFF" || &buf[..4] == b"RF64") => {
 				if &buf[8..12] == b"WAVE" {
 					return Some(Self::Wav);
 				}
 
 				None
 			},
+			66 if buf.len() >= 12 && &buf[..4] == b"BW64" && &buf[8..12] == b"WAVE" => {
+				Some(Self::Wav)
+			},
+			114 if buf.starts_with(&crate::iff::chunk::WAVE64_RIFF_GUID) => Some(Self::Wav),
 			119 if buf.len() >= 4 && &buf[..4] == b"wvpk" => Some(Self::WavPack),
+			26 if buf.starts_with(&[0x1A, 0x45, 0xDF, 0xA3]) => Some(Self::Matroska),
+			48 if buf.starts_with(&crate::asf::object::HEADER_OBJECT) => Some(Self::Asf),
 			_ if buf.len() >= 8 && &buf[4..8] == b"ftyp" => Some(Self::Mp4),
 			_ if buf.starts_with(b"MPCK") || buf.starts_with(b"MP+") => Some(Self::Mpc),
 			_ => None,
@@ -3,6 +3,7 @@ use super::file_type::FileType;
 use crate::config::{ParseOptions, WriteOptions};
 use crate::error::{LoftyError, Result};
 use crate::properties::FileProperties;
+use crate::tag::items::Chapter;
 use crate::tag::{Tag, TagExt, TagType};
 
 use crate::util::io::{FileLike, Length, Truncate};
@@ -186,6 +187,35 @@ pub trait TaggedFileExt {
 		self.tag_mut(self.primary_tag_type())
 	}
 
+	/// Returns the file's [`Chapter`]s
+	///
+	/// This will use the chapters of the [primary tag](Self::primary_tag), falling back to
+	/// the first tag that has any.
+	///
+	/// To change the chapters, use [`Tag::set_chapters`] on the tag that should store them.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::file::TaggedFileExt;
+	///
+	/// # fn main() -> lofty::error::Result<()> {
+	/// # let path_to_mp3 = "tests/files/assets/minimal/full_test.mp3";
+	/// let tagged_file = lofty::read_from_path(path_to_mp3)?;
+	///
+	/// for chapter in tagged_file.chapters() {
+	/// 	println!("{:?}: {:?}", chapter.start, chapter.title);
+	/// }
+	/// # Ok(()) }
+	/// ```
+	fn chapters(&self) -> Vec<Chapter> {
+		self.primary_tag()
+			.filter(|tag| !tag.chapters.is_empty())
+			.or_else(|| self.tags().iter().find(|tag| !tag.chapters.is_empty()))
+			.map(Tag::chapters)
+			.unwrap_or_default()
+	}
+
 	/// Gets the first tag, if there are any
 	///
 	/// NOTE: This will grab the first available tag, you cannot rely on the result being
@@ -1,19 +1,26 @@
 #![allow(dead_code)]
 
-use crate::error::Result;
-use crate::macros::try_vec;
+use crate::error::{FileEncodingError, Result};
+use crate::file::FileType;
+use crate::id3::{find_id3v2, FindId3v2Config};
+use crate::macros::{decode_err, err, try_vec};
 
 use std::io::{Read, Seek, SeekFrom};
 
 use byteorder::{BigEndian, ReadBytesExt};
 
-pub(in crate::flac) const BLOCK_ID_STREAMINFO: u8 = 0;
-pub(in crate::flac) const BLOCK_ID_PADDING: u8 = 1;
-pub(in crate::flac) const BLOCK_ID_SEEKTABLE: u8 = 3;
-pub(in crate::flac) const BLOCK_ID_VORBIS_COMMENTS: u8 = 4;
-pub(in crate::flac) const BLOCK_ID_PICTURE: u8 = 6;
+pub(crate) const BLOCK_ID_STREAMINFO: u8 = 0;
+pub(crate) const BLOCK_ID_PADDING: u8 = 1;
+pub(crate) const BLOCK_ID_APPLICATION: u8 = 2;
+pub(crate) const BLOCK_ID_SEEKTABLE: u8 = 3;
+pub(crate) const BLOCK_ID_VORBIS_COMMENTS: u8 = 4;
+pub(crate) const BLOCK_ID_CUESHEET: u8 = 5;
+pub(crate) const BLOCK_ID_PICTURE: u8 = 6;
+// Block type 127 is forbidden, to avoid confusion with a frame sync code
+const BLOCK_ID_INVALID: u8 = 127;
 
 const BLOCK_HEADER_SIZE: u64 = 4;
+pub(crate) const MAX_BLOCK_SIZE: u32 = 16_777_215;
 
 pub(crate) struct Block {
 	pub(super) byte: u8,
@@ -60,3 +67,283 @@ impl Block {
 		})
 	}
 }
+
+/// The type of a [`MetadataBlock`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlockType {
+	/// STREAMINFO, which is always the first block
+	StreamInfo,
+	/// PADDING
+	Padding,
+	/// APPLICATION, holding data for third-party applications
+	Application,
+	/// SEEKTABLE
+	SeekTable,
+	/// VORBIS_COMMENT
+	VorbisComment,
+	/// CUESHEET
+	CueSheet,
+	/// PICTURE
+	Picture,
+	/// A reserved block type
+	Unknown(u8),
+}
+
+impl BlockType {
+	/// Get a `BlockType` from a `u8`
+	///
+	/// This will only consider the lower 7 bits, the high bit is the "last block" flag.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::flac::BlockType;
+	///
+	/// assert_eq!(BlockType::from_u8(2), BlockType::Application);
+	/// assert_eq!(BlockType::from_u8(0x80 | 4), BlockType::VorbisComment);
+	/// assert_eq!(BlockType::from_u8(10), BlockType::Unknown(10));
+	/// ```
+	pub fn from_u8(byte: u8) -> Self {
+		match byte & 0x7F {
+			BLOCK_ID_STREAMINFO => Self::StreamInfo,
+			BLOCK_ID_PADDING => Self::Padding,
+			BLOCK_ID_APPLICATION => Self::Application,
+			BLOCK_ID_SEEKTABLE => Self::SeekTable,
+			BLOCK_ID_VORBIS_COMMENTS => Self::VorbisComment,
+			BLOCK_ID_CUESHEET => Self::CueSheet,
+			BLOCK_ID_PICTURE => Self::Picture,
+			ty => Self::Unknown(ty),
+		}
+	}
+
+	/// Get a `u8` from a `BlockType`
+	pub fn as_u8(&self) -> u8 {
+		match self {
+			Self::StreamInfo => BLOCK_ID_STREAMINFO,
+			Self::Padding => BLOCK_ID_PADDING,
+			Self::Application => BLOCK_ID_APPLICATION,
+			Self::SeekTable => BLOCK_ID_SEEKTABLE,
+			Self::VorbisComment => BLOCK_ID_VORBIS_COMMENTS,
+			Self::CueSheet => BLOCK_ID_CUESHEET,
+			Self::Picture => BLOCK_ID_PICTURE,
+			Self::Unknown(ty) => *ty & 0x7F,
+		}
+	}
+}
+
+/// A FLAC metadata block
+///
+/// This is the raw content of a block, without its header. The "last block" flag is not
+/// stored, as it is determined by the block's position when writing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetadataBlock {
+	pub(crate) ty: BlockType,
+	pub(crate) content: Vec<u8>,
+}
+
+impl MetadataBlock {
+	/// Create a new `MetadataBlock`
+	///
+	/// A [`BlockType::Unknown`] holding the value of a known block type is converted to that type.
+	///
+	/// # Errors
+	///
+	/// * `content` is larger than 16 MiB (the maximum size of a block)
+	/// * `ty` is [`BlockType::Unknown`] with the forbidden value of 127
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::flac::{BlockType, MetadataBlock};
+	///
+	/// # fn main() -> lofty::error::Result<()> {
+	/// let block = MetadataBlock::new(BlockType::Unknown(10), vec![1, 2, 3])?;
+	/// assert_eq!(block.content(), &[1, 2, 3]);
+	///
+	/// let block = MetadataBlock::new(BlockType::Unknown(2), b"riffFoo data".to_vec())?;
+	/// assert_eq!(block.block_type(), BlockType::Application);
+	/// # Ok(()) }
+	/// ```
+	pub fn new(ty: BlockType, content: Vec<u8>) -> Result<Self> {
+		if content.len() > MAX_BLOCK_SIZE as usize {
+			err!(TooMuchData);
+		}
+
+		if ty.as_u8() == BLOCK_ID_INVALID {
+			return Err(FileEncodingError::new(FileType::Flac, "Block type 127 is invalid").into());
+		}
+
+		// `Unknown` can hold any value, so `Unknown(2)` needs to become `Application`
+		let ty = BlockType::from_u8(ty.as_u8());
+
+		Ok(Self { ty, content })
+	}
+
+	/// Create a new APPLICATION block
+	///
+	/// The `id` is the application ID, as registered with the FLAC project (Ex. `riff` for foreign
+	/// RIFF metadata).
+	///
+	/// # Errors
+	///
+	/// See [`MetadataBlock::new`]
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::flac::MetadataBlock;
+	///
+	/// # fn main() -> lofty::error::Result<()> {
+	/// let block = MetadataBlock::application(*b"riff", b"Foo data")?;
+	///
+	/// assert_eq!(block.application_id(), Some(*b"riff"));
+	/// assert_eq!(block.application_data(), Some(&b"Foo data"[..]));
+	/// # Ok(()) }
+	/// ```
+	pub fn application(id: [u8; 4], data: &[u8]) -> Result<Self> {
+		let mut content = Vec::with_capacity(data.len() + 4);
+		content.extend(id);
+		content.extend(data);
+
+		Self::new(BlockType::Application, content)
+	}
+
+	/// The type of the block
+	pub fn block_type(&self) -> BlockType {
+		self.ty
+	}
+
+	/// The content of the block, not including the header
+	pub fn content(&self) -> &[u8] {
+		&self.content
+	}
+
+	/// Consume the block, returning its content
+	pub fn into_content(self) -> Vec<u8> {
+		self.content
+	}
+
+	/// The application ID, if this is an APPLICATION block
+	pub fn application_id(&self) -> Option<[u8; 4]> {
+		if self.ty != BlockType::Application || self.content.len() < 4 {
+			return None;
+		}
+
+		let mut id = [0; 4];
+		id.copy_from_slice(&self.content[..4]);
+		Some(id)
+	}
+
+	/// The application data, if this is an APPLICATION block
+	pub fn application_data(&self) -> Option<&[u8]> {
+		self.application_id()?;
+		Some(&self.content[4..])
+	}
+
+	/// Whether the block is handled by [`FlacFile`](super::FlacFile) itself
+	///
+	/// These are not available through [`FlacFile::metadata_blocks`](super::FlacFile::metadata_blocks).
+	pub(crate) fn is_managed(&self) -> bool {
+		matches!(
+			self.ty.as_u8(),
+			BLOCK_ID_STREAMINFO | BLOCK_ID_PADDING | BLOCK_ID_VORBIS_COMMENTS | BLOCK_ID_PICTURE
+		) || self.application_id() == Some(crate::xmp::FLAC_APPLICATION_ID)
+	}
+
+	pub(crate) fn write_to(&self, writer: &mut Vec<u8>, last: bool) {
+		let mut byte = self.ty.as_u8();
+		if last {
+			byte |= 0x80;
+		}
+
+		writer.push(byte);
+		writer.extend(&(self.content.len() as u32).to_be_bytes()[1..]);
+		writer.extend(&self.content);
+	}
+}
+
+/// An iterator over the metadata blocks of a FLAC stream
+///
+/// This reads every block, including the STREAMINFO block, in the order they appear in the stream.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use lofty::flac::{BlockType, MetadataBlocks};
+///
+/// # fn main() -> lofty::error::Result<()> {
+/// let mut file = std::fs::File::open("foo.flac")?;
+///
+/// for block in MetadataBlocks::new(&mut file)? {
+/// 	let block = block?;
+/// 	if let Some(id) = block.application_id() {
+/// 		println!("Found an APPLICATION block: {:?}", id);
+/// 	}
+/// }
+/// # Ok(()) }
+/// ```
+pub struct MetadataBlocks<'a, R> {
+	reader: &'a mut R,
+	done: bool,
+}
+
+impl<'a, R> MetadataBlocks<'a, R>
+where
+	R: Read + Seek,
+{
+	/// Create a new `MetadataBlocks` iterator
+	///
+	/// This will skip any ID3v2 tag at the start of the stream.
+	///
+	/// # Errors
+	///
+	/// * The reader does not contain a FLAC stream
+	pub fn new(reader: &'a mut R) -> Result<Self> {
+		find_id3v2(reader, FindId3v2Config::NO_READ_TAG)?;
+
+		let mut marker = [0; 4];
+		reader.read_exact(&mut marker)?;
+
+		if &marker != b"fLaC" {
+			decode_err!(@BAIL Flac, "File missing \"fLaC\" stream marker");
+		}
+
+		Ok(Self {
+			reader,
+			done: false,
+		})
+	}
+}
+
+impl<R> Iterator for MetadataBlocks<'_, R>
+where
+	R: Read + Seek,
+{
+	type Item = Result<MetadataBlock>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.done {
+			return None;
+		}
+
+		match Block::read(self.reader, |_| true) {
+			Ok(block) => {
+				self.done = block.last;
+				Some(Ok(MetadataBlock::from(block)))
+			},
+			Err(e) => {
+				self.done = true;
+				Some(Err(e))
+			},
+		}
+	}
+}
+
+impl From<Block> for MetadataBlock {
+	fn from(block: Block) -> Self {
+		Self {
+			ty: BlockType::from_u8(block.ty),
+			content: block.content,
+		}
+	}
+}
@@ -0,0 +1,540 @@
+use crate::error::{FileEncodingError, Result};
+use crate::file::FileType;
+use crate::macros::{decode_err, err};
+use crate::util::text::{latin1_decode, trim_end_nulls};
+
+use std::io::Read;
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+
+const MEDIA_CATALOG_NUMBER_SIZE: usize = 128;
+const ISRC_SIZE: usize = 12;
+
+// Media catalog number (128) + lead-in (8) + flags/reserved (259) + track count (1)
+const CUESHEET_HEADER_SIZE: usize = 396;
+const CUESHEET_RESERVED_SIZE: usize = 258;
+// Offset (8) + number (1) + ISRC (12) + flags/reserved (14) + index count (1)
+const TRACK_SIZE: usize = 36;
+const TRACK_RESERVED_SIZE: usize = 13;
+// Offset (8) + number (1) + reserved (3)
+const INDEX_SIZE: usize = 12;
+
+const CD_LEAD_OUT_TRACK: u8 = 170;
+const LEAD_OUT_TRACK: u8 = 255;
+
+// CD-DA timestamps are in frames, of which there are 75 per second
+const CD_FRAMES_PER_SECOND: u64 = 75;
+
+/// An index point of a [`CueSheetTrack`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CueSheetIndex {
+	/// The offset in samples, relative to the offset of the track
+	pub offset: u64,
+	/// The index point number
+	///
+	/// For CD-DA, an index of 0 marks the pregap of the track, and 1 marks its start.
+	pub number: u8,
+}
+
+/// A track of a [`CueSheet`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CueSheetTrack {
+	/// The offset in samples, relative to the start of the audio
+	pub offset: u64,
+	/// The track number
+	///
+	/// For CD-DA, this is 1-99, or 170 for the lead-out track. Otherwise, the lead-out track is 255.
+	pub number: u8,
+	/// The track's ISRC, or an empty string if there is none
+	///
+	/// This must be 12 characters long.
+	pub isrc: String,
+	/// Whether the track contains audio (`false` for data tracks)
+	pub is_audio: bool,
+	/// Whether the track was recorded with pre-emphasis
+	pub pre_emphasis: bool,
+	/// The track's index points, which are empty for the lead-out track
+	pub indices: Vec<CueSheetIndex>,
+}
+
+impl Default for CueSheetTrack {
+	fn default() -> Self {
+		Self {
+			offset: 0,
+			number: 0,
+			isrc: String::new(),
+			is_audio: true,
+			pre_emphasis: false,
+			indices: Vec::new(),
+		}
+	}
+}
+
+impl CueSheetTrack {
+	fn is_lead_out(&self, is_cd: bool) -> bool {
+		self.number
+			== if is_cd {
+				CD_LEAD_OUT_TRACK
+			} else {
+				LEAD_OUT_TRACK
+			}
+	}
+}
+
+/// A FLAC CUESHEET block
+///
+/// This stores the track layout of a CD (or any other media), so that a single file can hold
+/// an image of an entire disc.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use lofty::config::ParseOptions;
+/// use lofty::file::AudioFile;
+/// use lofty::flac::FlacFile;
+///
+/// # fn main() -> lofty::error::Result<()> {
+/// # let mut file = std::fs::File::open("foo.flac")?;
+/// let flac_file = FlacFile::read_from(&mut file, ParseOptions::new())?;
+///
+/// if let Some(cue_sheet) = flac_file.cue_sheet()? {
+/// 	for track in cue_sheet.audio_tracks() {
+/// 		println!("Track {} starts at sample {}", track.number, track.offset);
+/// 	}
+/// }
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CueSheet {
+	/// The media catalog number (Ex. the UPC/EAN of a CD), up to 128 characters
+	pub media_catalog_number: String,
+	/// The number of lead-in samples, only meaningful for CD-DA
+	pub lead_in: u64,
+	/// Whether the cue sheet corresponds to a Compact Disc
+	pub is_cd: bool,
+	/// The tracks, including the lead-out track
+	pub tracks: Vec<CueSheetTrack>,
+}
+
+impl CueSheet {
+	/// The lead-out track, if there is one
+	///
+	/// Its offset is the length of the audio, in samples.
+	pub fn lead_out(&self) -> Option<&CueSheetTrack> {
+		self.tracks
+			.last()
+			.filter(|track| track.is_lead_out(self.is_cd))
+	}
+
+	/// An iterator over the tracks, excluding the lead-out and any data tracks
+	pub fn audio_tracks(&self) -> impl Iterator<Item = &CueSheetTrack> + '_ {
+		self.tracks
+			.iter()
+			.filter(|track| track.is_audio && !track.is_lead_out(self.is_cd))
+	}
+
+	/// Parse a `CueSheet` from the content of a CUESHEET block
+	///
+	/// # Errors
+	///
+	/// * `bytes` is too short to contain the tracks and index points it declares
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::flac::{CueSheet, CueSheetTrack};
+	///
+	/// # fn main() -> lofty::error::Result<()> {
+	/// let cue_sheet = CueSheet {
+	/// 	tracks: vec![CueSheetTrack {
+	/// 		number: 255,
+	/// 		..CueSheetTrack::default()
+	/// 	}],
+	/// 	..CueSheet::default()
+	/// };
+	///
+	/// let bytes = cue_sheet.as_flac_bytes()?;
+	/// assert_eq!(CueSheet::from_flac_bytes(&bytes)?, cue_sheet);
+	/// # Ok(()) }
+	/// ```
+	pub fn from_flac_bytes(mut bytes: &[u8]) -> Result<Self> {
+		if bytes.len() < CUESHEET_HEADER_SIZE {
+			decode_err!(@BAIL Flac, "CUESHEET block is too small");
+		}
+
+		let reader = &mut bytes;
+
+		let mut media_catalog_number = [0; MEDIA_CATALOG_NUMBER_SIZE];
+		reader.read_exact(&mut media_catalog_number)?;
+
+		let mut media_catalog_number = latin1_decode(&media_catalog_number);
+		trim_end_nulls(&mut media_catalog_number);
+
+		let lead_in = reader.read_u64::<BigEndian>()?;
+		let is_cd = (reader.read_u8()? & 0x80) != 0;
+
+		*reader = &reader[CUESHEET_RESERVED_SIZE..];
+
+		let track_count = reader.read_u8()?;
+
+		let mut tracks =
+			Vec::with_capacity(usize::from(track_count).min(reader.len() / TRACK_SIZE));
+		for _ in 0..track_count {
+			if reader.len() < TRACK_SIZE {
+				decode_err!(@BAIL Flac, "CUESHEET block has an invalid track count");
+			}
+
+			let offset = reader.read_u64::<BigEndian>()?;
+			let number = reader.read_u8()?;
+
+			let mut isrc = [0; ISRC_SIZE];
+			reader.read_exact(&mut isrc)?;
+
+			let mut isrc = latin1_decode(&isrc);
+			trim_end_nulls(&mut isrc);
+
+			let flags = reader.read_u8()?;
+			*reader = &reader[TRACK_RESERVED_SIZE..];
+
+			let index_count = reader.read_u8()?;
+			if reader.len() < usize::from(index_count) * INDEX_SIZE {
+				decode_err!(@BAIL Flac, "CUESHEET block has an invalid index point count");
+			}
+
+			let mut indices = Vec::with_capacity(usize::from(index_count));
+			for _ in 0..index_count {
+				indices.push(CueSheetIndex {
+					offset: reader.read_u64::<BigEndian>()?,
+					number: reader.read_u8()?,
+				});
+
+				// Reserved
+				*reader = &reader[3..];
+			}
+
+			tracks.push(CueSheetTrack {
+				offset,
+				number,
+				isrc,
+				is_audio: (flags & 0x80) == 0,
+				pre_emphasis: (flags & 0x40) != 0,
+				indices,
+			});
+		}
+
+		Ok(Self {
+			media_catalog_number,
+			lead_in,
+			is_cd,
+			tracks,
+		})
+	}
+
+	/// Convert the `CueSheet` into the content of a CUESHEET block
+	///
+	/// # Errors
+	///
+	/// * The media catalog number is longer than 128 bytes
+	/// * A track's ISRC is not 12 bytes long (or empty)
+	/// * There are more than 255 tracks, or more than 255 index points in a track
+	pub fn as_flac_bytes(&self) -> Result<Vec<u8>> {
+		if self.media_catalog_number.len() > MEDIA_CATALOG_NUMBER_SIZE {
+			err!(TooMuchData);
+		}
+
+		let Ok(track_count) = u8::try_from(self.tracks.len()) else {
+			err!(TooMuchData);
+		};
+
+		let mut bytes = Vec::with_capacity(CUESHEET_HEADER_SIZE + self.tracks.len() * TRACK_SIZE);
+
+		bytes.extend(self.media_catalog_number.as_bytes());
+		bytes.resize(MEDIA_CATALOG_NUMBER_SIZE, 0);
+
+		bytes.write_u64::<BigEndian>(self.lead_in)?;
+		bytes.write_u8(if self.is_cd { 0x80 } else { 0 })?;
+		bytes.resize(bytes.len() + CUESHEET_RESERVED_SIZE, 0);
+		bytes.write_u8(track_count)?;
+
+		for track in &self.tracks {
+			if !track.isrc.is_empty() && track.isrc.len() != ISRC_SIZE {
+				return Err(FileEncodingError::new(
+					FileType::Flac,
+					"CUESHEET track ISRCs must be 12 characters long",
+				)
+				.into());
+			}
+
+			let Ok(index_count) = u8::try_from(track.indices.len()) else {
+				err!(TooMuchData);
+			};
+
+			let mut flags = 0;
+			if !track.is_audio {
+				flags |= 0x80;
+			}
+			if track.pre_emphasis {
+				flags |= 0x40;
+			}
+
+			bytes.write_u64::<BigEndian>(track.offset)?;
+			bytes.write_u8(track.number)?;
+			bytes.extend(track.isrc.as_bytes());
+			bytes.resize(bytes.len() + ISRC_SIZE - track.isrc.len(), 0);
+			bytes.write_u8(flags)?;
+			bytes.resize(bytes.len() + TRACK_RESERVED_SIZE, 0);
+			bytes.write_u8(index_count)?;
+
+			for index in &track.indices {
+				bytes.write_u64::<BigEndian>(index.offset)?;
+				bytes.write_u8(index.number)?;
+				bytes.extend([0; 3]);
+			}
+		}
+
+		Ok(bytes)
+	}
+
+	/// Parse a `CueSheet` from a text cue sheet (a `.cue` file)
+	///
+	/// This is the format used in the `CUESHEET` Vorbis comment. Only the `CATALOG`, `TRACK`, `ISRC`,
+	/// `FLAGS`, and `INDEX` commands are used, everything else is ignored.
+	///
+	/// Cue sheet timestamps are in CD frames, so `sample_rate` is needed to convert them to sample offsets.
+	///
+	/// NOTE: A text cue sheet doesn't store the length of the audio, so there will be no lead-out track.
+	///       `is_cd` is set if the sample rate is 44.1 kHz.
+	///
+	/// # Errors
+	///
+	/// * The cue sheet references more than one file
+	/// * A track or index point number is invalid
+	/// * An index point has an invalid timestamp
+	/// * An index point or ISRC appears before the first track
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::flac::CueSheet;
+	///
+	/// # fn main() -> lofty::error::Result<()> {
+	/// let cue_text = r#"
+	/// FILE "Foo.wav" WAVE
+	///   TRACK 01 AUDIO
+	///     INDEX 01 00:00:00
+	///   TRACK 02 AUDIO
+	///     ISRC USFOO2400001
+	///     INDEX 00 03:20:00
+	///     INDEX 01 03:22:00
+	/// "#;
+	///
+	/// let cue_sheet = CueSheet::from_cue_text(cue_text, 44100)?;
+	///
+	/// let second_track = &cue_sheet.tracks[1];
+	/// assert_eq!(second_track.isrc, "USFOO2400001");
+	/// assert_eq!(second_track.offset, 200 * 44100);
+	/// assert_eq!(second_track.indices[1].offset, 2 * 44100);
+	/// # Ok(()) }
+	/// ```
+	pub fn from_cue_text(text: &str, sample_rate: u32) -> Result<Self> {
+		let mut cue_sheet = Self {
+			is_cd: sample_rate == 44100,
+			..Self::default()
+		};
+
+		let mut seen_file = false;
+		for line in text.lines() {
+			let line = line.trim();
+			let (command, args) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+			let args = args.trim();
+
+			match command {
+				"CATALOG" => cue_sheet.media_catalog_number = args.to_string(),
+				"FILE" => {
+					if seen_file {
+						decode_err!(@BAIL Flac, "Cue sheets referencing multiple files are not supported");
+					}
+
+					seen_file = true;
+				},
+				"TRACK" => {
+					let (number, ty) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+					let Ok(number) = number.parse::<u8>() else {
+						decode_err!(@BAIL Flac, "Cue sheet has an invalid track number");
+					};
+
+					cue_sheet.tracks.push(CueSheetTrack {
+						number,
+						is_audio: ty.trim() == "AUDIO",
+						..CueSheetTrack::default()
+					});
+				},
+				"ISRC" => {
+					let Some(track) = cue_sheet.tracks.last_mut() else {
+						decode_err!(@BAIL Flac, "Cue sheet has an ISRC outside of a track");
+					};
+
+					track.isrc = args.to_string();
+				},
+				"FLAGS" => {
+					if let Some(track) = cue_sheet.tracks.last_mut() {
+						track.pre_emphasis = args.split_whitespace().any(|flag| flag == "PRE");
+					}
+				},
+				"INDEX" => {
+					let Some(track) = cue_sheet.tracks.last_mut() else {
+						decode_err!(@BAIL Flac, "Cue sheet has an index point outside of a track");
+					};
+
+					let (number, timestamp) =
+						args.split_once(char::is_whitespace).unwrap_or((args, ""));
+					let Ok(number) = number.parse::<u8>() else {
+						decode_err!(@BAIL Flac, "Cue sheet has an invalid index point number");
+					};
+
+					let Some(frames) = parse_cue_timestamp(timestamp.trim()) else {
+						decode_err!(@BAIL Flac, "Cue sheet has an invalid index point timestamp");
+					};
+
+					let Some(offset) = frames.checked_mul(u64::from(sample_rate)) else {
+						decode_err!(@BAIL Flac, "Cue sheet has an index point timestamp that is too large");
+					};
+					let offset = offset / CD_FRAMES_PER_SECOND;
+
+					// The first index point is the start of the track, the rest are relative to it
+					if track.indices.is_empty() {
+						track.offset = offset;
+					}
+
+					track.indices.push(CueSheetIndex {
+						offset: offset.saturating_sub(track.offset),
+						number,
+					});
+				},
+				_ => {},
+			}
+		}
+
+		Ok(cue_sheet)
+	}
+}
+
+// MM:SS:FF, where the minutes can exceed 99
+fn parse_cue_timestamp(timestamp: &str) -> Option<u64> {
+	let mut parts = timestamp.splitn(3, ':');
+
+	let minutes = parts.next()?.parse::<u64>().ok()?;
+	let seconds = parts.next()?.parse::<u64>().ok()?;
+	let frames = parts.next()?.parse::<u64>().ok()?;
+
+	if seconds >= 60 || frames >= CD_FRAMES_PER_SECOND {
+		return None;
+	}
+
+	minutes
+		.checked_mul(60)?
+		.checked_add(seconds)?
+		.checked_mul(CD_FRAMES_PER_SECOND)?
+		.checked_add(frames)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{CueSheet, CueSheetIndex, CueSheetTrack};
+
+	fn test_cue_sheet() -> CueSheet {
+		CueSheet {
+			media_catalog_number: String::from("1234567890123"),
+			lead_in: 88200,
+			is_cd: true,
+			tracks: vec![
+				CueSheetTrack {
+					offset: 0,
+					number: 1,
+					isrc: String::from("USFOO2400001"),
+					indices: vec![CueSheetIndex {
+						offset: 0,
+						number: 1,
+					}],
+					..CueSheetTrack::default()
+				},
+				CueSheetTrack {
+					offset: 588 * 100,
+					number: 2,
+					pre_emphasis: true,
+					indices: vec![
+						CueSheetIndex {
+							offset: 0,
+							number: 0,
+						},
+						CueSheetIndex {
+							offset: 588 * 75,
+							number: 1,
+						},
+					],
+					..CueSheetTrack::default()
+				},
+				CueSheetTrack {
+					offset: 588 * 400,
+					number: 170,
+					..CueSheetTrack::default()
+				},
+			],
+		}
+	}
+
+	#[test_log::test]
+	fn cue_sheet_round_trip() {
+		let cue_sheet = test_cue_sheet();
+
+		let bytes = cue_sheet.as_flac_bytes().unwrap();
+		assert_eq!(bytes.len(), 396 + (3 * 36) + (3 * 12));
+
+		let parsed = CueSheet::from_flac_bytes(&bytes).unwrap();
+		assert_eq!(parsed, cue_sheet);
+
+		assert_eq!(parsed.lead_out().map(|track| track.number), Some(170));
+		assert_eq!(parsed.audio_tracks().count(), 2);
+	}
+
+	#[test_log::test]
+	fn cue_sheet_invalid_isrc() {
+		let mut cue_sheet = test_cue_sheet();
+		cue_sheet.tracks[0].isrc = String::from("USFOO");
+
+		assert!(cue_sheet.as_flac_bytes().is_err());
+	}
+
+	#[test_log::test]
+	fn cue_sheet_truncated() {
+		let bytes = test_cue_sheet().as_flac_bytes().unwrap();
+		assert!(CueSheet::from_flac_bytes(&bytes[..bytes.len() - 1]).is_err());
+	}
+
+	#[test_log::test]
+	fn cue_sheet_from_text() {
+		let text = "REM GENRE Foo\r\nCATALOG 1234567890123\r\nPERFORMER \"Foo artist\"\r\nFILE \
+		            \"Foo.wav\" WAVE\r\nTRACK 01 AUDIO\r\nTITLE \"Foo title\"\r\nISRC \
+		            USFOO2400001\r\nINDEX 01 00:00:00\r\nTRACK 02 AUDIO\r\nFLAGS DCP PRE\r\nINDEX \
+		            00 00:01:25\r\nINDEX 01 00:02:25\r\n";
+
+		let cue_sheet = CueSheet::from_cue_text(text, 44100).unwrap();
+
+		let mut expected = test_cue_sheet();
+		expected.lead_in = 0;
+		expected.tracks.pop();
+
+		assert_eq!(cue_sheet, expected);
+		assert!(cue_sheet.lead_out().is_none());
+	}
+
+	#[test_log::test]
+	fn cue_sheet_from_text_overflow() {
+		let text = "TRACK 01 AUDIO\nINDEX 01 999999999999999999:00:00\n";
+		assert!(CueSheet::from_cue_text(text, 44100).is_err());
+
+		// Fits in the frame count, but not once converted to samples
+		let text = "TRACK 01 AUDIO\nINDEX 01 4000000000000000:00:00\n";
+		assert!(CueSheet::from_cue_text(text, 44100).is_err());
+	}
+}
@@ -0,0 +1,399 @@
+use super::block::Block;
+use super::read::verify_flac;
+use crate::error::Result;
+use crate::id3::{find_id3v2, FindId3v2Config};
+
+use std::io::{Read, Seek, SeekFrom};
+
+// Sync code (2) + block size/sample rate (1) + channels/sample size (1) + frame number (1) + CRC-8 (1)
+const MIN_FRAME_HEADER_SIZE: usize = 6;
+// The frame number can be up to 7 bytes, followed by up to 2 bytes each for the block size and sample rate
+const MAX_FRAME_HEADER_SIZE: usize = 16;
+
+const READ_CHUNK_SIZE: u64 = 64 * 1024;
+const ID3V1_TAG_SIZE: u64 = 128;
+
+const CRC8_TABLE: [u8; 256] = crc8_table();
+const CRC16_TABLE: [u16; 256] = crc16_table();
+
+// CRC-8, polynomial x^8 + x^2 + x^1 + x^0
+const fn crc8_table() -> [u8; 256] {
+	let mut table = [0; 256];
+
+	let mut i = 0;
+	while i < 256 {
+		let mut crc = i as u8;
+
+		let mut bit = 0;
+		while bit < 8 {
+			crc = if crc & 0x80 == 0 {
+				crc << 1
+			} else {
+				(crc << 1) ^ 0x07
+			};
+			bit += 1;
+		}
+
+		table[i] = crc;
+		i += 1;
+	}
+
+	table
+}
+
+// CRC-16, polynomial x^16 + x^15 + x^2 + x^0
+const fn crc16_table() -> [u16; 256] {
+	let mut table = [0; 256];
+
+	let mut i = 0;
+	while i < 256 {
+		let mut crc = (i as u16) << 8;
+
+		let mut bit = 0;
+		while bit < 8 {
+			crc = if crc & 0x8000 == 0 {
+				crc << 1
+			} else {
+				(crc << 1) ^ 0x8005
+			};
+			bit += 1;
+		}
+
+		table[i] = crc;
+		i += 1;
+	}
+
+	table
+}
+
+fn crc8(bytes: &[u8]) -> u8 {
+	bytes
+		.iter()
+		.fold(0, |crc, byte| CRC8_TABLE[usize::from(crc ^ byte)])
+}
+
+fn crc16_update(crc: u16, byte: u8) -> u16 {
+	(crc << 8) ^ CRC16_TABLE[usize::from((crc >> 8) as u8 ^ byte)]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FrameHeader {
+	variable_block_size: bool,
+	/// The frame number for fixed block size streams, otherwise the number of the first sample
+	number: u64,
+	block_size: u32,
+}
+
+impl FrameHeader {
+	/// Parse a frame header, returning `None` if it is invalid or its CRC-8 doesn't match
+	fn parse(bytes: &[u8]) -> Option<Self> {
+		if bytes.len() < MIN_FRAME_HEADER_SIZE || bytes[0] != 0xFF || (bytes[1] & 0xFE) != 0xF8 {
+			return None;
+		}
+
+		let variable_block_size = (bytes[1] & 0x01) != 0;
+
+		let block_size_code = bytes[2] >> 4;
+		let sample_rate_code = bytes[2] & 0x0F;
+		let channel_assignment = bytes[3] >> 4;
+
+		// Reserved values
+		if block_size_code == 0
+			|| sample_rate_code == 0x0F
+			|| channel_assignment > 10
+			|| (bytes[3] & 0x01) != 0
+		{
+			return None;
+		}
+
+		// The number is encoded the same way as UTF-8 characters, extended to 7 bytes
+		let first = bytes[4];
+		let (mut number, number_len) = match first.leading_ones() {
+			0 => (u64::from(first), 1),
+			len @ 2..=7 => (u64::from(first & (0x7F >> len)), len as usize),
+			_ => return None,
+		};
+
+		for &byte in bytes.get(5..4 + number_len)? {
+			if (byte & 0xC0) != 0x80 {
+				return None;
+			}
+
+			number = (number << 6) | u64::from(byte & 0x3F);
+		}
+
+		let mut pos = 4 + number_len;
+
+		let block_size = match block_size_code {
+			1 => 192,
+			2..=5 => 576 << (block_size_code - 2),
+			6 => {
+				pos += 1;
+				u32::from(*bytes.get(pos - 1)?) + 1
+			},
+			7 => {
+				pos += 2;
+				u32::from(u16::from_be_bytes([
+					*bytes.get(pos - 2)?,
+					*bytes.get(pos - 1)?,
+				])) + 1
+			},
+			_ => 256 << (block_size_code - 8),
+		};
+
+		// The sample rate itself isn't needed, just skip it
+		match sample_rate_code {
+			12 => pos += 1,
+			13 | 14 => pos += 2,
+			_ => {},
+		}
+
+		let crc = *bytes.get(pos)?;
+		if crc8(&bytes[..pos]) != crc {
+			return None;
+		}
+
+		Some(Self {
+			variable_block_size,
+			number,
+			block_size,
+		})
+	}
+
+	/// Whether this frame directly follows `previous`
+	fn follows(&self, previous: &Self) -> bool {
+		if self.variable_block_size != previous.variable_block_size {
+			return false;
+		}
+
+		let expected = if self.variable_block_size {
+			previous.number + u64::from(previous.block_size)
+		} else {
+			previous.number + 1
+		};
+
+		self.number == expected
+	}
+}
+
+/// The result of [`verify_frames`]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FrameVerification {
+	frame_count: u64,
+	sample_count: u64,
+	expected_sample_count: u64,
+	corrupt_frames: Vec<u64>,
+}
+
+impl FrameVerification {
+	/// The number of frames in the stream
+	pub fn frame_count(&self) -> u64 {
+		self.frame_count
+	}
+
+	/// The total number of samples (per channel) in the frames
+	pub fn sample_count(&self) -> u64 {
+		self.sample_count
+	}
+
+	/// The total number of samples (per channel) according to STREAMINFO, or 0 if unknown
+	pub fn expected_sample_count(&self) -> u64 {
+		self.expected_sample_count
+	}
+
+	/// The offsets of every frame with a mismatched CRC-16
+	///
+	/// These are relative to the start of the reader.
+	pub fn corrupt_frames(&self) -> &[u64] {
+		&self.corrupt_frames
+	}
+
+	/// Whether the stream is intact
+	///
+	/// This means that no frames are corrupt, and that the sample count matches the one in
+	/// STREAMINFO (if it is known).
+	pub fn is_valid(&self) -> bool {
+		self.corrupt_frames.is_empty()
+			&& (self.expected_sample_count == 0 || self.expected_sample_count == self.sample_count)
+	}
+
+	fn push_frame(&mut self, offset: u64, header: &FrameHeader, crc: u16) {
+		self.frame_count += 1;
+		self.sample_count += u64::from(header.block_size);
+
+		if crc != 0 {
+			log::warn!("FLAC frame at offset {offset} has a mismatched CRC-16");
+			self.corrupt_frames.push(offset);
+		}
+	}
+}
+
+/// Check the integrity of the audio frames in a FLAC stream
+///
+/// This walks every frame, checking their CRC-16 against their contents, without decoding them.
+/// Since Lofty doesn't decode audio, the MD5 signature in STREAMINFO ([`FlacProperties::signature`](super::FlacProperties::signature))
+/// can't be checked.
+///
+/// NOTE: This reads the entire file, so it will be **much** slower than reading its metadata.
+///
+/// # Errors
+///
+/// * The reader does not contain a FLAC stream
+/// * The metadata blocks are invalid
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use lofty::flac::verify_frames;
+///
+/// # fn main() -> lofty::error::Result<()> {
+/// let mut file = std::fs::File::open("foo.flac")?;
+///
+/// let verification = verify_frames(&mut file)?;
+/// if !verification.is_valid() {
+/// 	println!(
+/// 		"Found {} corrupt frames",
+/// 		verification.corrupt_frames().len()
+/// 	);
+/// }
+/// # Ok(()) }
+/// ```
+pub fn verify_frames<R>(reader: &mut R) -> Result<FrameVerification>
+where
+	R: Read + Seek,
+{
+	find_id3v2(reader, FindId3v2Config::NO_READ_TAG)?;
+
+	let stream_info = verify_flac(reader)?;
+	let properties = super::properties::read_properties(&mut &*stream_info.content, 0, 0)?;
+
+	let mut last_block = stream_info.last;
+	while !last_block {
+		last_block = Block::read(reader, |_| false)?.last;
+	}
+
+	let audio_start = reader.stream_position()?;
+	let mut audio_end = reader.seek(SeekFrom::End(0))?;
+
+	// An ID3v1 tag isn't part of the stream, but may still be present
+	if audio_end >= audio_start + ID3V1_TAG_SIZE {
+		reader.seek(SeekFrom::End(-(ID3V1_TAG_SIZE as i64)))?;
+
+		let mut id = [0; 3];
+		reader.read_exact(&mut id)?;
+
+		if &id == b"TAG" {
+			audio_end -= ID3V1_TAG_SIZE;
+		}
+	}
+
+	reader.seek(SeekFrom::Start(audio_start))?;
+
+	let mut verification = FrameVerification {
+		expected_sample_count: properties.total_samples,
+		..FrameVerification::default()
+	};
+
+	let mut audio = reader.take(audio_end - audio_start);
+
+	// The audio is read in chunks, only keeping enough to parse the next frame header
+	let mut buf = Vec::new();
+	let mut buf_offset = audio_start;
+	let mut pos = 0;
+	let mut eof = false;
+
+	// The start of the current frame, and the CRC-16 of its contents so far
+	let mut current_frame: Option<(u64, FrameHeader)> = None;
+	let mut crc = 0;
+
+	loop {
+		if !eof && buf.len() - pos < MAX_FRAME_HEADER_SIZE {
+			buf.drain(..pos);
+			buf_offset += pos as u64;
+			pos = 0;
+
+			eof = (&mut audio).take(READ_CHUNK_SIZE).read_to_end(&mut buf)? == 0;
+			continue;
+		}
+
+		let Some(&byte) = buf.get(pos) else {
+			break;
+		};
+
+		if byte == 0xFF {
+			if let Some(header) = FrameHeader::parse(&buf[pos..]) {
+				// The sync code can appear in the frame data, so we need to make sure this is
+				// actually the next frame. A corrupt frame won't end with a CRC-16 of 0, so the
+				// number in its header is checked as well.
+				let is_next_frame = match current_frame {
+					Some((_, ref previous)) => crc == 0 || header.follows(previous),
+					None => true,
+				};
+
+				if is_next_frame {
+					let offset = buf_offset + pos as u64;
+					match current_frame.replace((offset, header)) {
+						Some((previous_offset, previous)) => {
+							verification.push_frame(previous_offset, &previous, crc);
+						},
+						None if offset != audio_start => {
+							log::warn!("Found junk before the first FLAC frame");
+						},
+						None => {},
+					}
+
+					crc = 0;
+				}
+			}
+		}
+
+		crc = crc16_update(crc, byte);
+		pos += 1;
+	}
+
+	if let Some((offset, header)) = current_frame {
+		verification.push_frame(offset, &header, crc);
+	}
+
+	Ok(verification)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::FrameHeader;
+
+	#[test_log::test]
+	fn crc_check_values() {
+		assert_eq!(super::crc8(b"123456789"), 0xF4);
+
+		let crc = b"123456789"
+			.iter()
+			.fold(0, |crc, byte| super::crc16_update(crc, *byte));
+		assert_eq!(crc, 0xFEE8);
+	}
+
+	#[test_log::test]
+	fn frame_header() {
+		// Fixed block size of 4608, 48 kHz, stereo, 16-bit, frame 1
+		let mut header = vec![0xFF, 0xF8, 0x5A, 0x18, 0x01];
+		header.push(super::crc8(&header));
+
+		let parsed = FrameHeader::parse(&header).unwrap();
+		assert!(!parsed.variable_block_size);
+		assert_eq!(parsed.number, 1);
+		assert_eq!(parsed.block_size, 4608);
+
+		// A bad CRC-8
+		*header.last_mut().unwrap() ^= 0xFF;
+		assert!(FrameHeader::parse(&header).is_none());
+
+		// Variable block size, with an 8-bit block size at the end of the header, and a 2 byte number
+		let mut header = vec![0xFF, 0xF9, 0x69, 0x18, 0xC2, 0x80, 0xFF];
+		header.push(super::crc8(&header));
+
+		let parsed = FrameHeader::parse(&header).unwrap();
+		assert!(parsed.variable_block_size);
+		assert_eq!(parsed.number, 128);
+		assert_eq!(parsed.block_size, 256);
+	}
+}
@@ -5,6 +5,8 @@
 //! * See [`FlacFile`]
 
 pub(crate) mod block;
+mod cuesheet;
+mod frame;
 pub(crate) mod properties;
 mod read;
 pub(crate) mod write;
@@ -13,17 +15,22 @@ use crate::config::WriteOptions;
 use crate::error::{LoftyError, Result};
 use crate::file::{FileType, TaggedFile};
 use crate::id3::v2::tag::Id3v2Tag;
+use crate::macros::impl_chapters;
 use crate::ogg::tag::VorbisCommentsRef;
 use crate::ogg::{OggPictureStorage, VorbisComments};
 use crate::picture::{Picture, PictureInformation};
 use crate::tag::TagExt;
 use crate::util::io::{FileLike, Length, Truncate};
+use crate::xmp::XmpTag;
 
 use std::borrow::Cow;
 
 use lofty_attr::LoftyFile;
 
 // Exports
+pub use block::{BlockType, MetadataBlock, MetadataBlocks};
+pub use cuesheet::{CueSheet, CueSheetIndex, CueSheetTrack};
+pub use frame::{verify_frames, FrameVerification};
 pub use properties::FlacProperties;
 
 /// A FLAC file
@@ -37,6 +44,9 @@ pub use properties::FlacProperties;
 ///   methods on `FlacFile` ([`FlacFile::pictures`], [`FlacFile::remove_picture_type`], etc.)
 /// * When converting to [`TaggedFile`], all pictures will be put inside of a [`VorbisComments`] tag, even if the
 ///   file did not originally contain one.
+/// * Any other metadata blocks (APPLICATION, SEEKTABLE, CUESHEET, etc.) are available through
+///   [`FlacFile::metadata_blocks`]. They are written if they were read, or modified.
+/// * The XMP APPLICATION block is the exception, its packet is available through [`FlacFile::xmp`].
 #[derive(LoftyFile)]
 #[lofty(read_fn = "read::read_from")]
 #[lofty(write_fn = "Self::write_to")]
@@ -49,11 +59,130 @@ pub struct FlacFile {
 	#[lofty(tag_type = "VorbisComments")]
 	pub(crate) vorbis_comments_tag: Option<VorbisComments>,
 	pub(crate) pictures: Vec<(Picture, PictureInformation)>,
+	// `None` if the blocks were never read or set, to avoid removing them on save
+	pub(crate) metadata_blocks: Option<Vec<MetadataBlock>>,
+	/// An XMP packet, stored in an APPLICATION block
+	#[lofty(tag_type = "Xmp")]
+	pub(crate) xmp_tag: Option<XmpTag>,
 	/// The file's audio properties
 	pub(crate) properties: FlacProperties,
 }
 
+impl_chapters!(FlacFile, vorbis_comments_tag, VorbisComments);
+
 impl FlacFile {
+	/// Returns the metadata blocks that aren't handled by `FlacFile` itself
+	///
+	/// This does **not** include the STREAMINFO, PADDING, VORBIS_COMMENT, or PICTURE blocks. Those
+	/// are available through [`FlacFile::properties`], [`FlacFile::vorbis_comments`], and [`FlacFile::pictures`].
+	///
+	/// The blocks are in the order they appear in the file.
+	pub fn metadata_blocks(&self) -> &[MetadataBlock] {
+		self.metadata_blocks.as_deref().unwrap_or_default()
+	}
+
+	/// Returns a mutable reference to the metadata blocks
+	///
+	/// Blocks can be inserted, removed, and reordered. When saving, they will replace all
+	/// of the existing blocks, keeping the order of this list.
+	///
+	/// Any STREAMINFO, PADDING, VORBIS_COMMENT, or PICTURE blocks in this list will be ignored.
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// use lofty::config::ParseOptions;
+	/// use lofty::file::AudioFile;
+	/// use lofty::flac::{BlockType, FlacFile, MetadataBlock};
+	///
+	/// # fn main() -> lofty::error::Result<()> {
+	/// # let mut file = std::fs::File::open("foo.flac")?;
+	/// let mut flac_file = FlacFile::read_from(&mut file, ParseOptions::new())?;
+	///
+	/// // Remove the seek table, and add an APPLICATION block
+	/// let blocks = flac_file.metadata_blocks_mut();
+	/// blocks.retain(|block| block.block_type() != BlockType::SeekTable);
+	/// blocks.push(MetadataBlock::application(*b"Foo ", b"Bar data")?);
+	/// # Ok(()) }
+	/// ```
+	pub fn metadata_blocks_mut(&mut self) -> &mut Vec<MetadataBlock> {
+		self.metadata_blocks.get_or_insert_with(Vec::new)
+	}
+
+	/// Removes all metadata blocks, returning them
+	///
+	/// This **will** remove the blocks from the file it is saved to.
+	pub fn remove_metadata_blocks(&mut self) -> Vec<MetadataBlock> {
+		self.metadata_blocks.replace(Vec::new()).unwrap_or_default()
+	}
+
+	/// Returns the file's cue sheet
+	///
+	/// This is read from the CUESHEET block, falling back to the `CUESHEET` Vorbis comment
+	/// (see [`CueSheet::from_cue_text`]). The comment is only used if the file's properties were read,
+	/// as its timestamps are converted using the sample rate.
+	///
+	/// # Errors
+	///
+	/// * The CUESHEET block or `CUESHEET` comment is invalid
+	pub fn cue_sheet(&self) -> Result<Option<CueSheet>> {
+		if let Some(block) = self
+			.metadata_blocks()
+			.iter()
+			.find(|block| block.block_type() == BlockType::CueSheet)
+		{
+			return CueSheet::from_flac_bytes(block.content()).map(Some);
+		}
+
+		let sample_rate = self.properties.sample_rate;
+		match self
+			.vorbis_comments_tag
+			.as_ref()
+			.and_then(|vorbis_comments| vorbis_comments.get("CUESHEET"))
+		{
+			Some(cue_text) if sample_rate > 0 => {
+				CueSheet::from_cue_text(cue_text, sample_rate).map(Some)
+			},
+			_ => Ok(None),
+		}
+	}
+
+	/// Sets the CUESHEET block, replacing any existing one
+	///
+	/// The `CUESHEET` Vorbis comment, if any, is left untouched.
+	///
+	/// NOTE: Like [`FlacFile::metadata_blocks_mut`], if the file was read without tags, this will
+	///       replace all of the blocks in the file it is saved to.
+	///
+	/// # Errors
+	///
+	/// See [`CueSheet::as_flac_bytes`]
+	pub fn set_cue_sheet(&mut self, cue_sheet: &CueSheet) -> Result<()> {
+		let block = MetadataBlock::new(BlockType::CueSheet, cue_sheet.as_flac_bytes()?)?;
+
+		let blocks = self.metadata_blocks_mut();
+
+		// Keep the position of the existing block, if there is one
+		let position = blocks
+			.iter()
+			.position(|block| block.block_type() == BlockType::CueSheet);
+		blocks.retain(|block| block.block_type() != BlockType::CueSheet);
+
+		let position = position.unwrap_or(blocks.len());
+		blocks.insert(position, block);
+
+		Ok(())
+	}
+
+	/// Removes the CUESHEET block
+	///
+	/// The `CUESHEET` Vorbis comment, if any, is left untouched.
+	pub fn remove_cue_sheet(&mut self) {
+		if let Some(ref mut blocks) = self.metadata_blocks {
+			blocks.retain(|block| block.block_type() != BlockType::CueSheet);
+		}
+	}
+
 	// We need a special write fn to append our pictures into a `VorbisComments` tag
 	fn write_to<F>(&self, file: &mut F, write_options: WriteOptions) -> Result<()>
 	where
@@ -68,7 +197,7 @@ impl FlacFile {
 
 		// We have an existing vorbis comments tag, we can just append our pictures to it
 		if let Some(ref vorbis_comments) = self.vorbis_comments_tag {
-			return VorbisCommentsRef {
+			VorbisCommentsRef {
 				vendor: Cow::from(vorbis_comments.vendor.as_str()),
 				items: vorbis_comments
 					.items
@@ -80,17 +209,25 @@ impl FlacFile {
 					.map(|(p, i)| (p, *i))
 					.chain(self.pictures.iter().map(|(p, i)| (p, *i))),
 			}
-			.write_to(file, write_options);
-		}
-
-		// We have pictures, but no vorbis comments tag, we'll need to create a dummy one
-		if !self.pictures.is_empty() {
-			return VorbisCommentsRef {
+			.write_to(file, write_options)?;
+		} else if !self.pictures.is_empty() {
+			// We have pictures, but no vorbis comments tag, we'll need to create a dummy one
+			VorbisCommentsRef {
 				vendor: Cow::from(""),
 				items: std::iter::empty(),
 				pictures: self.pictures.iter().map(|(p, i)| (p, *i)),
 			}
-			.write_to(file, write_options);
+			.write_to(file, write_options)?;
+		}
+
+		if let Some(ref metadata_blocks) = self.metadata_blocks {
+			file.rewind()?;
+			write::write_metadata_blocks(file, metadata_blocks, write_options)?;
+		}
+
+		if let Some(ref xmp) = self.xmp_tag {
+			file.rewind()?;
+			xmp.save_to(file, write_options)?;
 		}
 
 		Ok(())
@@ -109,7 +246,7 @@ impl From<FlacFile> for TaggedFile {
 			ty: FileType::Flac,
 			properties: value.properties.into(),
 			tags: {
-				let mut tags = Vec::with_capacity(2);
+				let mut tags = Vec::with_capacity(3);
 
 				if let Some(id3v2) = value.id3v2_tag {
 					tags.push(id3v2.into());
@@ -132,6 +269,10 @@ impl From<FlacFile> for TaggedFile {
 					_ => {},
 				}
 
+				if let Some(xmp) = value.xmp_tag {
+					tags.push(xmp.into());
+				}
+
 				tags
 			},
 		}
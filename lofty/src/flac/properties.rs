@@ -16,6 +16,11 @@ pub struct FlacProperties {
 	pub(crate) sample_rate: u32,
 	pub(crate) bit_depth: u8,
 	pub(crate) channels: u8,
+	pub(crate) min_block_size: u16,
+	pub(crate) max_block_size: u16,
+	pub(crate) min_frame_size: u32,
+	pub(crate) max_frame_size: u32,
+	pub(crate) total_samples: u64,
 	pub(crate) signature: u128,
 }
 
@@ -64,7 +69,37 @@ impl FlacProperties {
 		self.channels
 	}
 
+	/// Minimum block size, in samples
+	pub fn min_block_size(&self) -> u16 {
+		self.min_block_size
+	}
+
+	/// Maximum block size, in samples
+	///
+	/// If this is equal to [`FlacProperties::min_block_size`], the stream uses a fixed block size.
+	pub fn max_block_size(&self) -> u16 {
+		self.max_block_size
+	}
+
+	/// Minimum frame size, in bytes (0 if unknown)
+	pub fn min_frame_size(&self) -> u32 {
+		self.min_frame_size
+	}
+
+	/// Maximum frame size, in bytes (0 if unknown)
+	pub fn max_frame_size(&self) -> u32 {
+		self.max_frame_size
+	}
+
+	/// Total number of samples (per channel), or 0 if unknown
+	pub fn total_samples(&self) -> u64 {
+		self.total_samples
+	}
+
 	/// MD5 signature of the unencoded audio data
+	///
+	/// NOTE: Lofty doesn't decode audio, so this can't be checked. [`verify_frames`](super::verify_frames)
+	///       can be used to check the integrity of the frames instead.
 	pub fn signature(&self) -> u128 {
 		self.signature
 	}
@@ -78,15 +113,11 @@ pub(crate) fn read_properties<R>(
 where
 	R: Read,
 {
-	// Skip 4 bytes
-	// Minimum block size (2)
-	// Maximum block size (2)
-	stream_info.read_u32::<BigEndian>()?;
+	let min_block_size = stream_info.read_u16::<BigEndian>()?;
+	let max_block_size = stream_info.read_u16::<BigEndian>()?;
 
-	// Skip 6 bytes
-	// Minimum frame size (3)
-	// Maximum frame size (3)
-	stream_info.read_uint::<BigEndian>(6)?;
+	let min_frame_size = stream_info.read_u24::<BigEndian>()?;
+	let max_frame_size = stream_info.read_u24::<BigEndian>()?;
 
 	// Read 4 bytes
 	// Sample rate (20 bits)
@@ -100,7 +131,8 @@ where
 	let channels = ((info >> 9) & 7) + 1;
 
 	// Read the remaining 32 bits of the total samples
-	let total_samples = stream_info.read_u32::<BigEndian>()? | (info << 28);
+	let total_samples =
+		(u64::from(info & 0b1111) << 32) | u64::from(stream_info.read_u32::<BigEndian>()?);
 
 	let signature = stream_info.read_u128::<BigEndian>()?;
 
@@ -108,12 +140,17 @@ where
 		sample_rate,
 		bit_depth: bits_per_sample as u8,
 		channels: channels as u8,
+		min_block_size,
+		max_block_size,
+		min_frame_size,
+		max_frame_size,
+		total_samples,
 		signature,
 		..FlacProperties::default()
 	};
 
 	if sample_rate > 0 && total_samples > 0 {
-		let length = (u64::from(total_samples) * 1000) / u64::from(sample_rate);
+		let length = (total_samples * 1000) / u64::from(sample_rate);
 		properties.duration = Duration::from_millis(length);
 
 		if length > 0 && file_length > 0 && stream_length > 0 {
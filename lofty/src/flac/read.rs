@@ -1,14 +1,18 @@
-use super::block::Block;
+use super::block::{Block, MetadataBlock};
 use super::properties::FlacProperties;
 use super::FlacFile;
 use crate::config::{ParseOptions, ParsingMode};
 use crate::error::Result;
-use crate::flac::block::{BLOCK_ID_PICTURE, BLOCK_ID_STREAMINFO, BLOCK_ID_VORBIS_COMMENTS};
+use crate::flac::block::{
+	BLOCK_ID_PADDING, BLOCK_ID_PICTURE, BLOCK_ID_STREAMINFO, BLOCK_ID_VORBIS_COMMENTS,
+};
 use crate::id3::v2::read::parse_id3v2;
 use crate::id3::{find_id3v2, FindId3v2Config, ID3FindResults};
 use crate::macros::{decode_err, err};
 use crate::ogg::read::read_comments;
 use crate::picture::Picture;
+use crate::xmp::read::read_xmp;
+use crate::xmp::FLAC_APPLICATION_ID;
 
 use std::io::{Read, Seek, SeekFrom};
 
@@ -41,6 +45,8 @@ where
 		id3v2_tag: None,
 		vorbis_comments_tag: None,
 		pictures: Vec::new(),
+		metadata_blocks: None,
+		xmp_tag: None,
 		properties: FlacProperties::default(),
 	};
 
@@ -69,14 +75,39 @@ where
 
 	let mut last_block = stream_info.last;
 
+	let mut metadata_blocks = Vec::new();
 	while !last_block {
-		let block = Block::read(data, |block_type| {
-			(block_type == BLOCK_ID_VORBIS_COMMENTS && parse_options.read_tags)
-				|| (block_type == BLOCK_ID_PICTURE && parse_options.read_cover_art)
+		let block = Block::read(data, |block_type| match block_type {
+			BLOCK_ID_VORBIS_COMMENTS => parse_options.read_tags,
+			BLOCK_ID_PICTURE => parse_options.read_cover_art,
+			BLOCK_ID_STREAMINFO | BLOCK_ID_PADDING => false,
+			// Everything else is kept as-is, to be written back later
+			_ => parse_options.read_tags,
 		})?;
 
 		last_block = block.last;
 
+		if !matches!(
+			block.ty,
+			BLOCK_ID_STREAMINFO | BLOCK_ID_VORBIS_COMMENTS | BLOCK_ID_PICTURE | BLOCK_ID_PADDING
+		) {
+			if !parse_options.read_tags {
+				continue;
+			}
+
+			let block = MetadataBlock::from(block);
+			if block.application_id() == Some(FLAC_APPLICATION_ID) {
+				log::debug!("Encountered an XMP APPLICATION block, parsing");
+
+				let data = block.application_data().unwrap_or_default();
+				flac_file.xmp_tag = read_xmp(data, parse_options.parsing_mode)?;
+				continue;
+			}
+
+			metadata_blocks.push(block);
+			continue;
+		}
+
 		if block.content.is_empty() {
 			continue;
 		}
@@ -126,6 +157,10 @@ where
 		}
 	}
 
+	if parse_options.read_tags {
+		flac_file.metadata_blocks = Some(metadata_blocks);
+	}
+
 	if !parse_options.read_properties {
 		return Ok(flac_file);
 	}
@@ -1,4 +1,4 @@
-use super::block::{Block, BLOCK_ID_PADDING, BLOCK_ID_PICTURE, BLOCK_ID_VORBIS_COMMENTS};
+use super::block::{Block, BlockType, MetadataBlock, BLOCK_ID_PADDING, MAX_BLOCK_SIZE};
 use super::read::verify_flac;
 use crate::config::WriteOptions;
 use crate::error::{LoftyError, Result};
@@ -7,7 +7,7 @@ use crate::ogg::tag::VorbisCommentsRef;
 use crate::ogg::write::create_comments;
 use crate::picture::{Picture, PictureInformation};
 use crate::tag::{Tag, TagType};
-use crate::util::io::{FileLike, Length, Truncate};
+use crate::util::io::{splice_file, FileLike, Length, Truncate};
 
 use std::borrow::Cow;
 use std::io::{Cursor, Read, Seek, SeekFrom, Write};
@@ -15,7 +15,6 @@ use std::io::{Cursor, Read, Seek, SeekFrom, Write};
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 
 const BLOCK_HEADER_SIZE: usize = 4;
-const MAX_BLOCK_SIZE: u32 = 16_777_215;
 
 pub(crate) fn write_to<F>(file: &mut F, tag: &Tag, write_options: WriteOptions) -> Result<()>
 where
@@ -25,7 +24,9 @@ where
 {
 	match tag.tag_type() {
 		TagType::VorbisComments => {
-			let (vendor, items, pictures) = crate::ogg::tag::create_vorbis_comments_ref(tag);
+			let chapter_comments = crate::ogg::tag::tag_chapter_comments(tag);
+			let (vendor, items, pictures) =
+				crate::ogg::tag::create_vorbis_comments_ref(tag, &chapter_comments);
 
 			let mut comments_ref = VorbisCommentsRef {
 				vendor: Cow::from(vendor),
@@ -35,6 +36,11 @@ where
 
 			write_to_inner(file, &mut comments_ref, write_options)
 		},
+		TagType::Xmp => crate::xmp::write::write_to(
+			file,
+			&Into::<crate::xmp::XmpTag>::into(tag.clone()),
+			write_options,
+		),
 		// This tag can *only* be removed in this format
 		TagType::Id3v2 => crate::id3::v2::tag::Id3v2TagRef::empty().write_to(file, write_options),
 		_ => err!(UnsupportedTag),
@@ -49,197 +55,258 @@ pub(crate) fn write_to_inner<'a, F, II, IP>(
 where
 	F: FileLike,
 	LoftyError: From<<F as Truncate>::Error>,
+	LoftyError: From<<F as Length>::Error>,
 	II: Iterator<Item = (&'a str, &'a str)>,
 	IP: Iterator<Item = (&'a Picture, PictureInformation)>,
 {
-	let stream_info = verify_flac(file)?;
-
-	let mut last_block = stream_info.last;
-
-	let mut file_bytes = Vec::new();
-	file.read_to_end(&mut file_bytes)?;
-
-	let mut cursor = Cursor::new(file_bytes);
-
-	// TODO: We need to actually use padding (https://github.com/Serial-ATA/lofty-rs/issues/445)
-	let mut end_padding_exists = false;
-	let mut last_block_info = (
-		stream_info.byte,
-		stream_info.start as usize,
-		stream_info.end as usize,
-	);
-
-	let mut blocks_to_remove = Vec::new();
-
-	while !last_block {
-		let block = Block::read(&mut cursor, |block_ty| block_ty == BLOCK_ID_VORBIS_COMMENTS)?;
-		let start = block.start;
-		let end = block.end;
-
-		let block_type = block.ty;
-		last_block = block.last;
-
-		if last_block {
-			last_block_info = (block.byte, (end - start) as usize, end as usize)
+	write_blocks(file, write_options, |blocks| {
+		if let Some(vorbis_comments) = blocks
+			.iter()
+			.find(|block| block.block_type() == BlockType::VorbisComment)
+		{
+			// Retain the original vendor string
+			let reader = &mut &vorbis_comments.content[..];
+
+			let vendor_len = reader.read_u32::<LittleEndian>()?;
+			let mut vendor = try_vec![0; vendor_len as usize];
+			reader.read_exact(&mut vendor)?;
+
+			// TODO: Error on strict?
+			match String::from_utf8(vendor) {
+				Ok(vendor_str) => tag.vendor = Cow::Owned(vendor_str),
+				Err(_) => {
+					log::warn!("FLAC vendor string is not valid UTF-8, not re-using");
+					tag.vendor = Cow::Borrowed("");
+				},
+			}
 		}
 
-		match block_type {
-			BLOCK_ID_VORBIS_COMMENTS => {
-				blocks_to_remove.push((start, end));
+		let mut new_blocks = Vec::new();
+		new_blocks.extend(create_comment_block(&tag.vendor, &mut tag.items)?);
+		create_picture_blocks(&mut new_blocks, &mut tag.pictures)?;
 
-				// Retain the original vendor string
-				let reader = &mut &block.content[..];
+		// The new blocks take the place of the first old one, or go directly after STREAMINFO
+		let is_replaced = |block: &MetadataBlock| {
+			matches!(
+				block.block_type(),
+				BlockType::VorbisComment | BlockType::Picture
+			)
+		};
 
-				let vendor_len = reader.read_u32::<LittleEndian>()?;
-				let mut vendor = try_vec![0; vendor_len as usize];
-				reader.read_exact(&mut vendor)?;
+		let position = blocks.iter().position(is_replaced).unwrap_or(1);
+		blocks.retain(|block| !is_replaced(block));
+		blocks.splice(position..position, new_blocks);
 
-				// TODO: Error on strict?
-				let Ok(vendor_str) = String::from_utf8(vendor) else {
-					log::warn!("FLAC vendor string is not valid UTF-8, not re-using");
-					tag.vendor = Cow::Borrowed("");
-					continue;
-				};
-
-				tag.vendor = Cow::Owned(vendor_str);
-			},
-			BLOCK_ID_PICTURE => blocks_to_remove.push((start, end)),
-			BLOCK_ID_PADDING => {
-				if last_block {
-					end_padding_exists = true
-				} else {
-					blocks_to_remove.push((start, end))
-				}
-			},
-			_ => {},
+		Ok(())
+	})
+}
+
+/// Replace every block not handled by `FlacFile` itself (APPLICATION, SEEKTABLE, CUESHEET, etc.)
+///
+/// The new blocks are written where the first replaced block was, or directly after STREAMINFO.
+pub(crate) fn write_metadata_blocks<F>(
+	file: &mut F,
+	blocks: &[MetadataBlock],
+	write_options: WriteOptions,
+) -> Result<()>
+where
+	F: FileLike,
+	LoftyError: From<<F as Truncate>::Error>,
+	LoftyError: From<<F as Length>::Error>,
+{
+	let new_blocks = blocks.iter().filter(|block| {
+		if block.is_managed() {
+			log::warn!(
+				"Attempted to write a {:?} block through `FlacFile::metadata_blocks`, ignoring",
+				block.block_type()
+			);
+			return false;
 		}
-	}
 
-	let mut file_bytes = cursor.into_inner();
+		true
+	});
 
-	if !end_padding_exists {
-		if let Some(preferred_padding) = write_options.preferred_padding {
-			log::warn!("File is missing a PADDING block. Adding one");
+	write_blocks(file, write_options, |existing| {
+		let position = existing
+			.iter()
+			.position(|block| !block.is_managed())
+			.unwrap_or(1);
+		existing.retain(MetadataBlock::is_managed);
+		existing.splice(position..position, new_blocks.cloned());
 
-			let mut first_byte = 0_u8;
-			first_byte |= last_block_info.0 & 0x7F;
+		Ok(())
+	})
+}
 
-			file_bytes[last_block_info.1] = first_byte;
+/// Replace the APPLICATION block with the given `id`, or remove it if `data` is `None`
+///
+/// A new block is written after the last existing block.
+pub(crate) fn write_application_block<F>(
+	file: &mut F,
+	id: [u8; 4],
+	data: Option<&[u8]>,
+	write_options: WriteOptions,
+) -> Result<()>
+where
+	F: FileLike,
+	LoftyError: From<<F as Truncate>::Error>,
+	LoftyError: From<<F as Length>::Error>,
+{
+	let new_block = data
+		.map(|data| MetadataBlock::application(id, data))
+		.transpose()?;
+
+	write_blocks(file, write_options, |existing| {
+		let is_replaced = |block: &MetadataBlock| block.application_id() == Some(id);
+
+		let position = existing
+			.iter()
+			.position(is_replaced)
+			.unwrap_or(existing.len());
+		existing.retain(|block| !is_replaced(block));
+		existing.splice(position..position, new_block);
+
+		Ok(())
+	})
+}
 
-			let block_size = core::cmp::min(preferred_padding, MAX_BLOCK_SIZE);
-			let mut padding_block = try_vec![0; BLOCK_HEADER_SIZE + block_size as usize];
+/// Rewrite the metadata blocks of a FLAC stream
+///
+/// `update` is given every existing block except PADDING, in their original order, starting with STREAMINFO.
+///
+/// If the updated blocks fit in the space of the old ones, they are written in place, with the remaining
+/// space filled with padding. Otherwise, the audio is shifted to make room, with
+/// [`WriteOptions::preferred_padding`] bytes of padding after the blocks.
+fn write_blocks<F, U>(file: &mut F, write_options: WriteOptions, update: U) -> Result<()>
+where
+	F: FileLike,
+	LoftyError: From<<F as Truncate>::Error>,
+	LoftyError: From<<F as Length>::Error>,
+	U: FnOnce(&mut Vec<MetadataBlock>) -> Result<()>,
+{
+	let stream_info = verify_flac(file)?;
+	let metadata_start = stream_info.start;
+	let mut metadata_end = stream_info.end;
 
-			let mut padding_byte = 0;
-			padding_byte |= 0x80;
-			padding_byte |= 1 & 0x7F;
+	let mut last_block = stream_info.last;
+	let mut blocks = vec![MetadataBlock::from(stream_info)];
 
-			padding_block[0] = padding_byte;
-			padding_block[1..4].copy_from_slice(&block_size.to_be_bytes()[1..]);
+	while !last_block {
+		let block = Block::read(file, |block_ty| block_ty != BLOCK_ID_PADDING)?;
+		last_block = block.last;
+		metadata_end = block.end;
 
-			file_bytes.splice(last_block_info.2..last_block_info.2, padding_block);
+		if block.ty != BLOCK_ID_PADDING {
+			blocks.push(MetadataBlock::from(block));
 		}
 	}
 
-	let mut comment_blocks = Cursor::new(Vec::new());
-
-	create_comment_block(&mut comment_blocks, &tag.vendor, &mut tag.items)?;
-
-	let mut comment_blocks = comment_blocks.into_inner();
-
-	create_picture_blocks(&mut comment_blocks, &mut tag.pictures)?;
+	update(&mut blocks)?;
+
+	let blocks_size = blocks
+		.iter()
+		.map(|block| (BLOCK_HEADER_SIZE + block.content().len()) as u64)
+		.sum::<u64>();
+
+	let max_padding_block_size = (BLOCK_HEADER_SIZE as u64) + u64::from(MAX_BLOCK_SIZE);
+	let (padding, in_place) = match (metadata_end - metadata_start).checked_sub(blocks_size) {
+		// The blocks fit exactly, no padding needed
+		Some(0) => (None, true),
+		// The rest of the space is filled with padding, as long as it fits in a single PADDING block
+		Some(space)
+			if write_options.preferred_padding.is_some()
+				&& (BLOCK_HEADER_SIZE as u64..=max_padding_block_size).contains(&space) =>
+		{
+			(Some(space - BLOCK_HEADER_SIZE as u64), true)
+		},
+		_ => (
+			write_options
+				.preferred_padding
+				.map(|padding| u64::from(padding.min(MAX_BLOCK_SIZE))),
+			false,
+		),
+	};
+
+	let mut file_bytes = Vec::with_capacity(blocks_size as usize);
+	for (idx, block) in blocks.iter().enumerate() {
+		block.write_to(
+			&mut file_bytes,
+			padding.is_none() && idx == blocks.len() - 1,
+		);
+	}
 
-	if blocks_to_remove.is_empty() {
-		file_bytes.splice(0..0, comment_blocks);
-	} else {
-		blocks_to_remove.sort_unstable();
-		blocks_to_remove.reverse();
+	if let Some(padding) = padding {
+		let padding_block = MetadataBlock {
+			ty: BlockType::Padding,
+			content: try_vec![0; padding as usize],
+		};
 
-		let first = blocks_to_remove.pop().unwrap(); // Infallible
+		padding_block.write_to(&mut file_bytes, true);
+	}
 
-		for (s, e) in &blocks_to_remove {
-			file_bytes.drain(*s as usize..*e as usize);
-		}
+	if in_place {
+		log::debug!("FLAC metadata blocks fit in the existing space, writing in place");
 
-		file_bytes.splice(first.0 as usize..first.1 as usize, comment_blocks);
+		file.seek(SeekFrom::Start(metadata_start))?;
+		file.write_all(&file_bytes)?;
+		return Ok(());
 	}
 
-	file.seek(SeekFrom::Start(stream_info.end))?;
-	file.truncate(stream_info.end)?;
-	file.write_all(&file_bytes)?;
+	log::debug!("FLAC metadata blocks don't fit in the existing space, shifting the audio");
 
+	splice_file(file, vec![(metadata_start..metadata_end, file_bytes)])?;
 	Ok(())
 }
 
 fn create_comment_block(
-	writer: &mut Cursor<Vec<u8>>,
 	vendor: &str,
 	items: &mut dyn Iterator<Item = (&str, &str)>,
-) -> Result<()> {
+) -> Result<Option<MetadataBlock>> {
 	let mut peek = items.peekable();
 
-	if peek.peek().is_some() {
-		let mut byte = 0_u8;
-		byte |= 4 & 0x7F;
-
-		writer.write_u8(byte)?;
-		writer.write_u32::<LittleEndian>(vendor.len() as u32)?;
-		writer.write_all(vendor.as_bytes())?;
-
-		let item_count_pos = writer.stream_position()?;
-		let mut count = 0;
-
-		writer.write_u32::<LittleEndian>(count)?;
+	if peek.peek().is_none() {
+		return Ok(None);
+	}
 
-		create_comments(writer, &mut count, &mut peek)?;
+	let mut content = Cursor::new(Vec::new());
+	content.write_u32::<LittleEndian>(vendor.len() as u32)?;
+	content.write_all(vendor.as_bytes())?;
 
-		let len = (writer.get_ref().len() - 1) as u32;
+	let item_count_pos = content.stream_position()?;
+	let mut count = 0;
 
-		if len > MAX_BLOCK_SIZE {
-			err!(TooMuchData);
-		}
+	content.write_u32::<LittleEndian>(count)?;
 
-		let comment_end = writer.stream_position()?;
+	create_comments(&mut content, &mut count, &mut peek)?;
 
-		writer.seek(SeekFrom::Start(item_count_pos))?;
-		writer.write_u32::<LittleEndian>(count)?;
+	content.seek(SeekFrom::Start(item_count_pos))?;
+	content.write_u32::<LittleEndian>(count)?;
 
-		writer.seek(SeekFrom::Start(comment_end))?;
-		writer
-			.get_mut()
-			.splice(1..1, len.to_be_bytes()[1..].to_vec());
+	let content = content.into_inner();
 
-		// size = block type + vendor length + vendor + item count + items
-		log::trace!(
-			"Wrote a comment block, size: {}",
-			1 + 4 + vendor.len() + 4 + (len as usize)
-		);
-	}
+	// size = block header + vendor length + vendor + item count + items
+	log::trace!(
+		"Wrote a comment block, size: {}",
+		BLOCK_HEADER_SIZE + content.len()
+	);
 
-	Ok(())
+	MetadataBlock::new(BlockType::VorbisComment, content).map(Some)
 }
 
 fn create_picture_blocks(
-	writer: &mut Vec<u8>,
+	blocks: &mut Vec<MetadataBlock>,
 	pictures: &mut dyn Iterator<Item = (&Picture, PictureInformation)>,
 ) -> Result<()> {
-	let mut byte = 0_u8;
-	byte |= 6 & 0x7F;
-
 	for (pic, info) in pictures {
-		writer.write_u8(byte)?;
-
 		let pic_bytes = pic.as_flac_bytes(info, false);
-		let pic_len = pic_bytes.len() as u32;
 
-		if pic_len > MAX_BLOCK_SIZE {
-			err!(TooMuchData);
-		}
-
-		writer.write_all(&pic_len.to_be_bytes()[1..])?;
-		writer.write_all(pic_bytes.as_slice())?;
+		// size = block header + data
+		log::trace!(
+			"Wrote a picture block, size: {}",
+			BLOCK_HEADER_SIZE + pic_bytes.len()
+		);
 
-		// size = block type + block length + data
-		log::trace!("Wrote a picture block, size: {}", 1 + 3 + pic_len);
+		blocks.push(MetadataBlock::new(BlockType::Picture, pic_bytes)?);
 	}
 
 	Ok(())
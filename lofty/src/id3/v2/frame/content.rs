@@ -2,10 +2,10 @@ use crate::config::ParsingMode;
 use crate::error::{Id3v2Error, Id3v2ErrorKind, Result};
 use crate::id3::v2::header::Id3v2Version;
 use crate::id3::v2::items::{
-	AttachedPictureFrame, CommentFrame, EventTimingCodesFrame, ExtendedTextFrame, ExtendedUrlFrame,
-	KeyValueFrame, OwnershipFrame, PopularimeterFrame, PrivateFrame, RelativeVolumeAdjustmentFrame,
-	TextInformationFrame, TimestampFrame, UniqueFileIdentifierFrame, UnsynchronizedTextFrame,
-	UrlLinkFrame,
+	AttachedPictureFrame, ChapterFrame, CommentFrame, EventTimingCodesFrame, ExtendedTextFrame,
+	ExtendedUrlFrame, KeyValueFrame, OwnershipFrame, PopularimeterFrame, PrivateFrame,
+	RelativeVolumeAdjustmentFrame, TableOfContentsFrame, TextInformationFrame, TimestampFrame,
+	UniqueFileIdentifierFrame, UnsynchronizedTextFrame, UrlLinkFrame,
 };
 use crate::id3::v2::{BinaryFrame, Frame, FrameFlags, FrameId};
 use crate::macros::err;
@@ -38,6 +38,9 @@ pub(super) fn parse_content<R: Read>(
 		"OWNE" => OwnershipFrame::parse(reader, flags)?.map(Frame::Ownership),
 		"ETCO" => EventTimingCodesFrame::parse(reader, flags)?.map(Frame::EventTimingCodes),
 		"PRIV" => PrivateFrame::parse(reader, flags)?.map(Frame::Private),
+		// Both contain embedded frames, which are read with the same version
+		"CHAP" => ChapterFrame::parse(reader, flags, version, parse_mode)?.map(Frame::Chapter),
+		"CTOC" => TableOfContentsFrame::parse(reader, flags, version, parse_mode)?.map(Frame::TableOfContents),
 		"TDEN" | "TDOR" | "TDRC" | "TDRL" | "TDTG" => TimestampFrame::parse(reader, id, flags, parse_mode)?.map(Frame::Timestamp),
 		i if i.starts_with('T') => TextInformationFrame::parse(reader, id, flags, version)?.map(Frame::Text),
 		// Apple proprietary frames
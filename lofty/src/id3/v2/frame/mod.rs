@@ -5,10 +5,10 @@ pub(super) mod read;
 
 use super::header::Id3v2Version;
 use super::items::{
-	AttachedPictureFrame, BinaryFrame, CommentFrame, EventTimingCodesFrame, ExtendedTextFrame,
-	ExtendedUrlFrame, KeyValueFrame, OwnershipFrame, PopularimeterFrame, PrivateFrame,
-	RelativeVolumeAdjustmentFrame, TextInformationFrame, TimestampFrame, UniqueFileIdentifierFrame,
-	UnsynchronizedTextFrame, UrlLinkFrame,
+	AttachedPictureFrame, BinaryFrame, ChapterFrame, CommentFrame, EventTimingCodesFrame,
+	ExtendedTextFrame, ExtendedUrlFrame, KeyValueFrame, OwnershipFrame, PopularimeterFrame,
+	PrivateFrame, RelativeVolumeAdjustmentFrame, TableOfContentsFrame, TextInformationFrame,
+	TimestampFrame, UniqueFileIdentifierFrame, UnsynchronizedTextFrame, UrlLinkFrame,
 };
 use crate::error::Result;
 use crate::id3::v2::FrameHeader;
@@ -136,6 +136,10 @@ define_frames! {
 		Private(PrivateFrame<'a>),
 		/// Represents a timestamp for the "TDEN", "TDOR", "TDRC", "TDRL", and "TDTG" frames
 		Timestamp(TimestampFrame<'a>),
+		/// Represents a "CHAP" frame
+		Chapter(ChapterFrame<'a>),
+		/// Represents a "CTOC" frame
+		TableOfContents(TableOfContentsFrame<'a>),
 		/// Binary data
 		///
 		/// NOTES:
@@ -182,7 +186,9 @@ impl Frame<'_> {
 			Frame::EventTimingCodes(event_timing) => event_timing.events.is_empty(),
 			Frame::Private(private) => private.private_data.is_empty(),
 			Frame::Binary(binary) => binary.data.is_empty(),
+			Frame::TableOfContents(toc) => toc.child_element_ids.is_empty(),
 			Frame::Popularimeter(_)
+			| Frame::Chapter(_)
 			| Frame::RelativeVolumeAdjustment(_)
 			| Frame::Ownership(_)
 			| Frame::Timestamp(_) => {
@@ -220,6 +226,8 @@ impl Frame<'_> {
 			Frame::EventTimingCodes(frame) => frame.as_bytes(),
 			Frame::Private(frame) => frame.as_bytes()?,
 			Frame::Timestamp(frame) => frame.as_bytes(is_id3v23)?,
+			Frame::Chapter(frame) => frame.as_bytes(is_id3v23)?,
+			Frame::TableOfContents(frame) => frame.as_bytes(is_id3v23)?,
 			Frame::Binary(frame) => frame.as_bytes(),
 		})
 	}
@@ -242,6 +250,8 @@ impl Frame<'_> {
 			Frame::EventTimingCodes(_) => "EventTimingCodes",
 			Frame::Private(_) => "Private",
 			Frame::Timestamp(_) => "Timestamp",
+			Frame::Chapter(_) => "Chapter",
+			Frame::TableOfContents(_) => "TableOfContents",
 			Frame::Binary(_) => "Binary",
 		}
 	}
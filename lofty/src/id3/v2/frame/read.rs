@@ -213,6 +213,60 @@ impl ParsedFrame<'_> {
 	}
 }
 
+/// Read the frames embedded in a `CHAP` or `CTOC` frame
+///
+/// Nested `CHAP` and `CTOC` frames are skipped, as the specification doesn't allow them.
+///
+/// NOTE: This expects `reader` to be restricted to the remaining content of the parent frame
+pub(in crate::id3::v2) fn read_embedded_frames<R>(
+	reader: &mut R,
+	version: Id3v2Version,
+	parse_mode: ParsingMode,
+) -> Result<Vec<Frame<'static>>>
+where
+	R: Read,
+{
+	// The embedded frames are read from a buffer, rather than directly from `reader`, as
+	// they may themselves contain embedded frames
+	let mut content = Vec::new();
+	reader.read_to_end(&mut content)?;
+
+	let mut content = &content[..];
+	let parse_options = ParseOptions::new().parsing_mode(parse_mode);
+
+	let mut frames = Vec::new();
+	loop {
+		// Reading these would recurse again, with no limit on how deep a crafted tag could go
+		if content.len() >= 10 && matches!(&content[..4], b"CHAP" | b"CTOC") {
+			log::warn!("Encountered a nested chapter frame, skipping");
+
+			let mut size = u32::from_be_bytes([content[4], content[5], content[6], content[7]]);
+			if version == Id3v2Version::V4 {
+				size = size.unsynch();
+			}
+
+			content = content.get(10 + size as usize..).unwrap_or_default();
+			continue;
+		}
+
+		match ParsedFrame::read(&mut content, version, parse_options) {
+			Ok(ParsedFrame::Next(frame)) => frames.push(frame),
+			Ok(ParsedFrame::Skip) => {},
+			Ok(ParsedFrame::Eof) => break,
+			Err(e) => {
+				if parse_mode == ParsingMode::Strict {
+					return Err(e);
+				}
+
+				log::warn!("Failed to read an embedded frame, skipping the rest: {e}");
+				break;
+			},
+		}
+	}
+
+	Ok(frames)
+}
+
 #[cfg(feature = "id3v2_compression_support")]
 #[allow(clippy::unnecessary_wraps)]
 fn handle_compression<R: Read>(reader: R) -> Result<flate2::read::ZlibDecoder<R>> {
@@ -0,0 +1,230 @@
+use crate::config::ParsingMode;
+use crate::error::Result;
+use crate::id3::v2::frame::read::read_embedded_frames;
+use crate::id3::v2::header::Id3v2Version;
+use crate::id3::v2::write::frame::create_embedded_items;
+use crate::id3::v2::{Frame, FrameFlags, FrameHeader, FrameId};
+use crate::util::text::{decode_text, encode_text, TextDecodeOptions, TextEncoding};
+
+use std::borrow::Cow;
+use std::hash::{Hash, Hasher};
+use std::io::Read;
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+
+const FRAME_ID: FrameId<'static> = FrameId::Valid(Cow::Borrowed("CHAP"));
+
+/// An `ID3v2` chapter frame
+///
+/// This describes a single chapter of the audio, and may contain any number of embedded frames,
+/// most commonly a `TIT2` frame for the title of the chapter.
+///
+/// See <https://mutagen-specs.readthedocs.io/en/latest/id3/id3v2-chapters-1.0.html#chapter-frame>
+///
+/// NOTE: For a simpler, format-agnostic way of working with chapters, see [`Chapter`](crate::tag::items::Chapter).
+#[derive(Clone, Debug, Eq)]
+pub struct ChapterFrame<'a> {
+	pub(crate) header: FrameHeader<'a>,
+	/// A unique identifier for the chapter, used to reference it in a [`TableOfContentsFrame`](crate::id3::v2::TableOfContentsFrame)
+	pub element_id: String,
+	/// The start of the chapter, in milliseconds
+	pub start_time: u32,
+	/// The end of the chapter, in milliseconds
+	pub end_time: u32,
+	/// The byte offset of the first audio frame in the chapter, from the start of the file
+	///
+	/// If this is `0xFFFFFFFF`, the offset should be ignored and `start_time` used instead.
+	pub start_offset: u32,
+	/// The byte offset of the first audio frame *following* the chapter, from the start of the file
+	///
+	/// If this is `0xFFFFFFFF`, the offset should be ignored and `end_time` used instead.
+	pub end_offset: u32,
+	/// Frames describing the chapter
+	pub embedded_frames: Vec<Frame<'a>>,
+}
+
+// Element IDs must be unique within a tag
+impl PartialEq for ChapterFrame<'_> {
+	fn eq(&self, other: &Self) -> bool {
+		self.element_id == other.element_id
+	}
+}
+
+impl Hash for ChapterFrame<'_> {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.element_id.hash(state);
+	}
+}
+
+impl ChapterFrame<'_> {
+	/// Create a new [`ChapterFrame`]
+	///
+	/// The byte offsets will be set to `0xFFFFFFFF` (unused), and there will be no embedded frames.
+	pub fn new(element_id: String, start_time: u32, end_time: u32) -> Self {
+		let header = FrameHeader::new(FRAME_ID, FrameFlags::default());
+		Self {
+			header,
+			element_id,
+			start_time,
+			end_time,
+			start_offset: u32::MAX,
+			end_offset: u32::MAX,
+			embedded_frames: Vec::new(),
+		}
+	}
+
+	/// Get the ID for the frame
+	pub fn id(&self) -> FrameId<'_> {
+		FRAME_ID
+	}
+
+	/// Get the flags for the frame
+	pub fn flags(&self) -> FrameFlags {
+		self.header.flags
+	}
+
+	/// Set the flags for the frame
+	pub fn set_flags(&mut self, flags: FrameFlags) {
+		self.header.flags = flags;
+	}
+
+	/// Read a [`ChapterFrame`]
+	///
+	/// NOTE: This expects the frame header to have already been skipped
+	///
+	/// # Errors
+	///
+	/// * Failure to read from `reader`
+	/// * An embedded frame is invalid, when using [`ParsingMode::Strict`]
+	pub fn parse<R>(
+		reader: &mut R,
+		frame_flags: FrameFlags,
+		version: Id3v2Version,
+		parse_mode: ParsingMode,
+	) -> Result<Option<Self>>
+	where
+		R: Read,
+	{
+		let Ok(element_id) = decode_text(
+			reader,
+			TextDecodeOptions::new()
+				.encoding(TextEncoding::Latin1)
+				.terminated(true),
+		) else {
+			return Ok(None);
+		};
+
+		let start_time = reader.read_u32::<BigEndian>()?;
+		let end_time = reader.read_u32::<BigEndian>()?;
+		let start_offset = reader.read_u32::<BigEndian>()?;
+		let end_offset = reader.read_u32::<BigEndian>()?;
+
+		let embedded_frames = read_embedded_frames(reader, version, parse_mode)?;
+
+		let header = FrameHeader::new(FRAME_ID, frame_flags);
+		Ok(Some(ChapterFrame {
+			header,
+			element_id: element_id.content,
+			start_time,
+			end_time,
+			start_offset,
+			end_offset,
+			embedded_frames,
+		}))
+	}
+
+	/// Convert a [`ChapterFrame`] to a byte vec
+	///
+	/// The embedded frames are written in the same version as the parent tag.
+	///
+	/// # Errors
+	///
+	/// * An embedded frame's content does not match its ID
+	pub fn as_bytes(&self, is_id3v23: bool) -> Result<Vec<u8>> {
+		let mut content = encode_text(&self.element_id, TextEncoding::Latin1, true);
+
+		content.write_u32::<BigEndian>(self.start_time)?;
+		content.write_u32::<BigEndian>(self.end_time)?;
+		content.write_u32::<BigEndian>(self.start_offset)?;
+		content.write_u32::<BigEndian>(self.end_offset)?;
+
+		create_embedded_items(&mut content, &self.embedded_frames, is_id3v23)?;
+
+		Ok(content)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::config::ParsingMode;
+	use crate::id3::v2::{
+		ChapterFrame, Frame, FrameFlags, FrameId, Id3v2Version, TextInformationFrame,
+	};
+	use crate::util::text::TextEncoding;
+
+	use std::borrow::Cow;
+
+	fn expected() -> ChapterFrame<'static> {
+		let mut chapter = ChapterFrame::new(String::from("chp0"), 0, 30_000);
+		chapter
+			.embedded_frames
+			.push(Frame::Text(TextInformationFrame::new(
+				FrameId::Valid(Cow::Borrowed("TIT2")),
+				TextEncoding::UTF8,
+				String::from("Intro"),
+			)));
+
+		chapter
+	}
+
+	#[test_log::test]
+	fn chap_round_trip() {
+		for (is_id3v23, version) in [(false, Id3v2Version::V4), (true, Id3v2Version::V3)] {
+			let encoded = expected().as_bytes(is_id3v23).unwrap();
+
+			let parsed = ChapterFrame::parse(
+				&mut &encoded[..],
+				FrameFlags::default(),
+				version,
+				ParsingMode::Strict,
+			)
+			.unwrap()
+			.unwrap();
+
+			// `ChapterFrame`s are only compared by their element IDs
+			assert_eq!(parsed, expected());
+			assert_eq!(parsed.start_time, 0);
+			assert_eq!(parsed.end_time, 30_000);
+			assert_eq!(parsed.start_offset, u32::MAX);
+			assert_eq!(parsed.end_offset, u32::MAX);
+
+			let [Frame::Text(title)] = &parsed.embedded_frames[..] else {
+				panic!("Expected a single text frame");
+			};
+			assert_eq!(title.value, "Intro");
+		}
+	}
+
+	#[test_log::test]
+	fn nested_chap_skipped() {
+		let mut nested = expected();
+		nested.embedded_frames.insert(0, Frame::Chapter(expected()));
+
+		let encoded = nested.as_bytes(false).unwrap();
+
+		let parsed = ChapterFrame::parse(
+			&mut &encoded[..],
+			FrameFlags::default(),
+			Id3v2Version::V4,
+			ParsingMode::Strict,
+		)
+		.unwrap()
+		.unwrap();
+
+		// Only the title should remain
+		let [Frame::Text(title)] = &parsed.embedded_frames[..] else {
+			panic!("Expected a single text frame");
+		};
+		assert_eq!(title.value, "Intro");
+	}
+}
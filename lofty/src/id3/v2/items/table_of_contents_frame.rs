@@ -0,0 +1,218 @@
+use crate::config::ParsingMode;
+use crate::error::Result;
+use crate::id3::v2::frame::read::read_embedded_frames;
+use crate::id3::v2::header::Id3v2Version;
+use crate::id3::v2::write::frame::create_embedded_items;
+use crate::id3::v2::{Frame, FrameFlags, FrameHeader, FrameId};
+use crate::macros::err;
+use crate::util::text::{decode_text, encode_text, TextDecodeOptions, TextEncoding};
+
+use std::borrow::Cow;
+use std::hash::{Hash, Hasher};
+use std::io::Read;
+
+use byteorder::ReadBytesExt;
+
+const FRAME_ID: FrameId<'static> = FrameId::Valid(Cow::Borrowed("CTOC"));
+
+const TOP_LEVEL_FLAG: u8 = 0x02;
+const ORDERED_FLAG: u8 = 0x01;
+
+/// An `ID3v2` table of contents frame
+///
+/// This lists the element IDs of [`ChapterFrame`](crate::id3::v2::ChapterFrame)s, or other
+/// [`TableOfContentsFrame`]s, allowing for a hierarchy of chapters.
+///
+/// See <https://mutagen-specs.readthedocs.io/en/latest/id3/id3v2-chapters-1.0.html#table-of-contents-frame>
+#[derive(Clone, Debug, Eq)]
+pub struct TableOfContentsFrame<'a> {
+	pub(crate) header: FrameHeader<'a>,
+	/// A unique identifier for the table of contents
+	pub element_id: String,
+	/// Whether this is the root of the hierarchy
+	///
+	/// A tag should only contain a single top-level table of contents.
+	pub top_level: bool,
+	/// Whether the entries in `child_element_ids` are in playback order
+	pub ordered: bool,
+	/// The element IDs of the chapters and tables of contents this one references
+	///
+	/// NOTE: This can hold at most 255 entries.
+	pub child_element_ids: Vec<String>,
+	/// Frames describing the table of contents
+	pub embedded_frames: Vec<Frame<'a>>,
+}
+
+// Element IDs must be unique within a tag
+impl PartialEq for TableOfContentsFrame<'_> {
+	fn eq(&self, other: &Self) -> bool {
+		self.element_id == other.element_id
+	}
+}
+
+impl Hash for TableOfContentsFrame<'_> {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.element_id.hash(state);
+	}
+}
+
+impl TableOfContentsFrame<'_> {
+	/// Create a new [`TableOfContentsFrame`]
+	///
+	/// There will be no embedded frames.
+	pub fn new(
+		element_id: String,
+		top_level: bool,
+		ordered: bool,
+		child_element_ids: Vec<String>,
+	) -> Self {
+		let header = FrameHeader::new(FRAME_ID, FrameFlags::default());
+		Self {
+			header,
+			element_id,
+			top_level,
+			ordered,
+			child_element_ids,
+			embedded_frames: Vec::new(),
+		}
+	}
+
+	/// Get the ID for the frame
+	pub fn id(&self) -> FrameId<'_> {
+		FRAME_ID
+	}
+
+	/// Get the flags for the frame
+	pub fn flags(&self) -> FrameFlags {
+		self.header.flags
+	}
+
+	/// Set the flags for the frame
+	pub fn set_flags(&mut self, flags: FrameFlags) {
+		self.header.flags = flags;
+	}
+
+	/// Read a [`TableOfContentsFrame`]
+	///
+	/// NOTE: This expects the frame header to have already been skipped
+	///
+	/// # Errors
+	///
+	/// * Failure to read from `reader`
+	/// * An embedded frame is invalid, when using [`ParsingMode::Strict`]
+	pub fn parse<R>(
+		reader: &mut R,
+		frame_flags: FrameFlags,
+		version: Id3v2Version,
+		parse_mode: ParsingMode,
+	) -> Result<Option<Self>>
+	where
+		R: Read,
+	{
+		let latin1 = TextDecodeOptions::new()
+			.encoding(TextEncoding::Latin1)
+			.terminated(true);
+
+		let Ok(element_id) = decode_text(reader, latin1) else {
+			return Ok(None);
+		};
+
+		let flags = reader.read_u8()?;
+		let entry_count = reader.read_u8()?;
+
+		let mut child_element_ids = Vec::with_capacity(usize::from(entry_count));
+		for _ in 0..entry_count {
+			child_element_ids.push(decode_text(reader, latin1)?.content);
+		}
+
+		let embedded_frames = read_embedded_frames(reader, version, parse_mode)?;
+
+		let header = FrameHeader::new(FRAME_ID, frame_flags);
+		Ok(Some(TableOfContentsFrame {
+			header,
+			element_id: element_id.content,
+			top_level: flags & TOP_LEVEL_FLAG == TOP_LEVEL_FLAG,
+			ordered: flags & ORDERED_FLAG == ORDERED_FLAG,
+			child_element_ids,
+			embedded_frames,
+		}))
+	}
+
+	/// Convert a [`TableOfContentsFrame`] to a byte vec
+	///
+	/// The embedded frames are written in the same version as the parent tag.
+	///
+	/// # Errors
+	///
+	/// * `child_element_ids` has more than 255 entries
+	/// * An embedded frame's content does not match its ID
+	pub fn as_bytes(&self, is_id3v23: bool) -> Result<Vec<u8>> {
+		let Ok(entry_count) = u8::try_from(self.child_element_ids.len()) else {
+			err!(TooMuchData);
+		};
+
+		let mut content = encode_text(&self.element_id, TextEncoding::Latin1, true);
+
+		let mut flags = 0;
+		if self.top_level {
+			flags |= TOP_LEVEL_FLAG;
+		}
+
+		if self.ordered {
+			flags |= ORDERED_FLAG;
+		}
+
+		content.push(flags);
+		content.push(entry_count);
+
+		for child_element_id in &self.child_element_ids {
+			content.extend(encode_text(child_element_id, TextEncoding::Latin1, true));
+		}
+
+		create_embedded_items(&mut content, &self.embedded_frames, is_id3v23)?;
+
+		Ok(content)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::config::ParsingMode;
+	use crate::id3::v2::{FrameFlags, Id3v2Version, TableOfContentsFrame};
+
+	fn expected() -> TableOfContentsFrame<'static> {
+		TableOfContentsFrame::new(
+			String::from("toc"),
+			true,
+			true,
+			vec![String::from("chp0"), String::from("chp1")],
+		)
+	}
+
+	#[test_log::test]
+	fn ctoc_round_trip() {
+		let encoded = expected().as_bytes(false).unwrap();
+
+		assert_eq!(
+			encoded,
+			b"toc\0\x03\x02chp0\0chp1\0".to_vec(),
+			"unexpected encoding"
+		);
+
+		let parsed = TableOfContentsFrame::parse(
+			&mut &encoded[..],
+			FrameFlags::default(),
+			Id3v2Version::V4,
+			ParsingMode::Strict,
+		)
+		.unwrap()
+		.unwrap();
+
+		// `TableOfContentsFrame`s are only compared by their element IDs
+		assert_eq!(parsed, expected());
+		assert!(parsed.top_level);
+		assert!(parsed.ordered);
+		assert_eq!(parsed.child_element_ids, expected().child_element_ids);
+		assert!(parsed.embedded_frames.is_empty());
+	}
+}
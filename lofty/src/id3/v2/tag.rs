@@ -11,6 +11,9 @@ use crate::id3::v2::items::{
 	AttachedPictureFrame, CommentFrame, ExtendedTextFrame, ExtendedUrlFrame, TextInformationFrame,
 	UniqueFileIdentifierFrame, UnsynchronizedTextFrame, UrlLinkFrame,
 };
+use crate::id3::v2::util::chapters::{
+	chapters_from_frames, frames_from_chapters, CHAPTER_ID, TABLE_OF_CONTENTS_ID,
+};
 use crate::id3::v2::util::mappings::TIPL_MAPPINGS;
 use crate::id3::v2::util::pairs::{
 	format_number_pair, set_number, NUMBER_PAIR_KEYS, NUMBER_PAIR_SEPARATOR,
@@ -19,7 +22,7 @@ use crate::id3::v2::{BinaryFrame, FrameHeader, FrameId, KeyValueFrame, Timestamp
 use crate::mp4::AdvisoryRating;
 use crate::picture::{Picture, PictureType, TOMBSTONE_PICTURE};
 use crate::tag::companion_tag::CompanionTag;
-use crate::tag::items::{Lang, Timestamp, UNKNOWN_LANGUAGE};
+use crate::tag::items::{Chapter, Lang, Timestamp, UNKNOWN_LANGUAGE};
 use crate::tag::{Accessor, ItemKey, ItemValue, MergeTag, SplitTag, Tag, TagExt, TagItem, TagType};
 use crate::util::flag_item;
 use crate::util::io::{FileLike, Length, Truncate};
@@ -112,7 +115,7 @@ macro_rules! impl_accessor {
 #[derive(PartialEq, Eq, Debug, Clone)]
 #[tag(
 	description = "An `ID3v2` tag",
-	supported_formats(Aac, Aiff, Mpeg, Wav, read_only(Ape, Flac, Mpc))
+	supported_formats(Aac, Aiff, Dsdiff, Dsf, Mp4, Mpeg, Wav, read_only(Ape, Flac, Mpc))
 )]
 pub struct Id3v2Tag {
 	flags: Id3v2TagFlags,
@@ -514,6 +517,46 @@ impl Id3v2Tag {
 		})
 	}
 
+	/// Returns all chapters stored in `CHAP` frames
+	///
+	/// If the tag has a top-level `CTOC` frame, the chapters will be in the order it specifies.
+	/// Any chapters it doesn't reference follow, sorted by their start times.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::id3::v2::Id3v2Tag;
+	/// use lofty::tag::items::Chapter;
+	/// use std::time::Duration;
+	///
+	/// let mut tag = Id3v2Tag::new();
+	/// assert!(tag.chapters().is_empty());
+	///
+	/// let mut chapter = Chapter::new(Duration::ZERO);
+	/// chapter.end = Some(Duration::from_secs(30));
+	/// chapter.title = Some(String::from("Intro"));
+	///
+	/// tag.set_chapters(vec![chapter.clone()]);
+	/// assert_eq!(tag.chapters(), vec![chapter]);
+	/// ```
+	pub fn chapters(&self) -> Vec<Chapter> {
+		chapters_from_frames(&self.frames)
+	}
+
+	/// Replaces all `CHAP` and `CTOC` frames with `chapters`
+	///
+	/// NOTE: A `CTOC` frame can only reference 255 chapters, anything past that will be discarded.
+	pub fn set_chapters(&mut self, chapters: Vec<Chapter>) {
+		self.remove_chapters();
+		self.frames.extend(frames_from_chapters(&chapters));
+	}
+
+	/// Removes all `CHAP` and `CTOC` frames
+	pub fn remove_chapters(&mut self) {
+		self.frames
+			.retain(|f| !matches!(f.id_str(), CHAPTER_ID | TABLE_OF_CONTENTS_ID));
+	}
+
 	fn split_num_pair(&self, id: &FrameId<'_>) -> (Option<u32>, Option<u32>) {
 		if let Some(Frame::Text(TextInformationFrame { ref value, .. })) = self.get(id) {
 			let mut split = value
@@ -1235,7 +1278,9 @@ fn handle_tag_split(tag: &mut Tag, frame: &mut Frame<'_>) -> bool {
 		| Frame::Ownership(_)
 		| Frame::EventTimingCodes(_)
 		| Frame::Popularimeter(_)
-		| Frame::Private(_) => {
+		| Frame::Private(_)
+		| Frame::Chapter(_)
+		| Frame::TableOfContents(_) => {
 			return FRAME_RETAINED; // Keep unsupported frame
 		},
 	}
@@ -1247,6 +1292,12 @@ impl SplitTag for Id3v2Tag {
 	fn split_tag(mut self) -> (Self::Remainder, Tag) {
 		let mut tag = Tag::new(TagType::Id3v2);
 
+		let chapters = self.chapters();
+		if !chapters.is_empty() {
+			self.remove_chapters();
+			tag.chapters = chapters;
+		}
+
 		self.frames
 			.retain_mut(|frame| handle_tag_split(&mut tag, frame));
 
@@ -1582,6 +1633,10 @@ impl MergeTag for SplitTagRemainder {
 			}
 		}
 
+		if !tag.chapters.is_empty() {
+			merged.set_chapters(tag.chapters);
+		}
+
 		merged
 	}
 }
@@ -1692,7 +1747,11 @@ pub(crate) fn tag_frames(tag: &Tag) -> impl Iterator<Item = FrameRef<'_>> {
 		))))
 	});
 
-	items.chain(pictures)
+	let chapters = frames_from_chapters(&tag.chapters)
+		.into_iter()
+		.map(|frame| FrameRef(Cow::Owned(frame)));
+
+	items.chain(pictures).chain(chapters)
 }
 
 impl<'a, I: Iterator<Item = FrameRef<'a>> + 'a> Id3v2TagRef<'a, I> {
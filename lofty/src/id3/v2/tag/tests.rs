@@ -1583,3 +1583,66 @@ fn ensure_frame_skipping_within_bounds() {
 		)))
 	);
 }
+
+#[test_log::test]
+fn chapters_round_trip() {
+	use crate::tag::items::Chapter;
+	use std::time::Duration;
+
+	let mut intro = Chapter::new(Duration::ZERO);
+	intro.title = Some(String::from("Intro"));
+	intro.url = Some(String::from("https://example.com"));
+
+	let mut outro = Chapter::new(Duration::from_secs(90));
+	outro.end = Some(Duration::from_secs(120));
+	outro.title = Some(String::from("Outro"));
+
+	let mut tag = Id3v2Tag::new();
+	tag.set_title(String::from("Foo title"));
+	tag.set_chapters(vec![intro.clone(), outro.clone()]);
+
+	let tag_re_read = dump_and_re_read(&tag, WriteOptions::default());
+
+	// The end of the first chapter is filled in with the start of the second
+	intro.end = Some(Duration::from_secs(90));
+	assert_eq!(tag_re_read.chapters(), vec![intro.clone(), outro.clone()]);
+
+	// Chapters should survive a conversion to and from `Tag`
+	let tag: Tag = tag_re_read.into();
+	assert_eq!(tag.chapters(), vec![intro.clone(), outro.clone()]);
+
+	let id3v2: Id3v2Tag = tag.into();
+	assert_eq!(id3v2.chapters(), vec![intro, outro]);
+	assert_eq!(id3v2.title().as_deref(), Some("Foo title"));
+}
+
+#[test_log::test]
+fn chapters_round_trip_id3v23() {
+	use crate::tag::items::Chapter;
+	use std::time::Duration;
+
+	let mut chapter = Chapter::new(Duration::ZERO);
+	chapter.end = Some(Duration::from_secs(30));
+	chapter.title = Some(String::from("Intro"));
+	chapter.picture = Some(Picture::new_unchecked(
+		PictureType::CoverFront,
+		Some(MimeType::Png),
+		None,
+		read_path("tests/tags/assets/id3v2/test_full_cover.png"),
+	));
+
+	let mut tag = Id3v2Tag::new();
+	tag.set_chapters(vec![chapter.clone()]);
+
+	let tag_re_read = dump_and_re_read(&tag, WriteOptions::default().use_id3v23(true));
+	assert_eq!(tag_re_read.original_version(), Id3v2Version::V3);
+	assert!(tag_re_read
+		.frames
+		.iter()
+		.any(|frame| matches!(frame, Frame::Chapter(_))));
+	assert_eq!(tag_re_read.chapters(), vec![chapter.clone()]);
+
+	// The embedded frames were upgraded when read, so they can be written as ID3v2.4
+	let tag_re_read = dump_and_re_read(&tag_re_read, WriteOptions::default());
+	assert_eq!(tag_re_read.chapters(), vec![chapter]);
+}
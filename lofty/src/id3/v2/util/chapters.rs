@@ -0,0 +1,164 @@
+//! Conversions between [`Chapter`]s and `CHAP`/`CTOC` frames
+//!
+//! See <https://mutagen-specs.readthedocs.io/en/latest/id3/id3v2-chapters-1.0.html>
+
+use crate::id3::v2::{
+	AttachedPictureFrame, ChapterFrame, ExtendedUrlFrame, Frame, FrameHeader, FrameId,
+	TableOfContentsFrame, TextInformationFrame,
+};
+use crate::tag::items::{chapter_end, Chapter};
+use crate::util::text::TextEncoding;
+
+use std::borrow::Cow;
+use std::time::Duration;
+
+pub(crate) const CHAPTER_ID: &str = "CHAP";
+pub(crate) const TABLE_OF_CONTENTS_ID: &str = "CTOC";
+
+const TITLE_ID: &str = "TIT2";
+
+// The element ID given to the table of contents we create
+const TABLE_OF_CONTENTS_ELEMENT_ID: &str = "toc";
+
+/// Collect all chapters from `CHAP` frames
+///
+/// Chapters referenced by the first top-level `CTOC` frame are returned in its order, and all others follow,
+/// sorted by their start times.
+pub(crate) fn chapters_from_frames<'a>(
+	frames: impl IntoIterator<Item = &'a Frame<'static>>,
+) -> Vec<Chapter> {
+	let mut chapters = Vec::new();
+	let mut toc_order = None;
+
+	for frame in frames {
+		match frame {
+			Frame::Chapter(chapter_frame) => {
+				chapters.push((chapter_frame.element_id.clone(), chapter(chapter_frame)));
+			},
+			Frame::TableOfContents(toc) if toc.top_level && toc_order.is_none() => {
+				toc_order = Some(&toc.child_element_ids);
+			},
+			_ => {},
+		}
+	}
+
+	let mut ordered = Vec::with_capacity(chapters.len());
+	for element_id in toc_order.into_iter().flatten() {
+		if let Some(pos) = chapters.iter().position(|(id, _)| id == element_id) {
+			ordered.push(chapters.remove(pos).1);
+		}
+	}
+
+	chapters.sort_by_key(|(_, chapter)| chapter.start);
+	ordered.extend(chapters.into_iter().map(|(_, chapter)| chapter));
+	ordered
+}
+
+/// Create a top-level `CTOC` frame, followed by a `CHAP` frame for each chapter
+///
+/// NOTE: A `CTOC` frame can only reference 255 chapters, anything past that will be discarded.
+pub(crate) fn frames_from_chapters(chapters: &[Chapter]) -> Vec<Frame<'static>> {
+	if chapters.is_empty() {
+		return Vec::new();
+	}
+
+	let mut chapters = chapters;
+	if chapters.len() > usize::from(u8::MAX) {
+		log::warn!(
+			"Discarding {} chapters, a table of contents can only hold {}",
+			chapters.len() - usize::from(u8::MAX),
+			u8::MAX
+		);
+		chapters = &chapters[..usize::from(u8::MAX)];
+	}
+
+	let element_ids = (0..chapters.len())
+		.map(|index| format!("chp{index}"))
+		.collect::<Vec<_>>();
+
+	let mut frames = Vec::with_capacity(chapters.len() + 1);
+	frames.push(Frame::TableOfContents(TableOfContentsFrame::new(
+		String::from(TABLE_OF_CONTENTS_ELEMENT_ID),
+		true,
+		true,
+		element_ids.clone(),
+	)));
+
+	for (index, (chapter, element_id)) in chapters.iter().zip(element_ids).enumerate() {
+		let end = chapter_end(chapters, index).unwrap_or(chapter.start);
+		frames.push(Frame::Chapter(chapter_frame(element_id, chapter, end)));
+	}
+
+	frames
+}
+
+fn chapter(chapter_frame: &ChapterFrame<'_>) -> Chapter {
+	let ChapterFrame {
+		start_time,
+		end_time,
+		embedded_frames,
+		..
+	} = chapter_frame;
+
+	let mut chapter = Chapter::new(Duration::from_millis(u64::from(*start_time)));
+	if end_time > start_time {
+		chapter.end = Some(Duration::from_millis(u64::from(*end_time)));
+	}
+
+	for frame in embedded_frames {
+		match frame {
+			Frame::Text(TextInformationFrame {
+				header: FrameHeader { id, .. },
+				value,
+				..
+			}) if id.as_str() == TITLE_ID => {
+				chapter.title = Some(value.clone());
+			},
+			Frame::UserUrl(ExtendedUrlFrame { content, .. }) => chapter.url = Some(content.clone()),
+			Frame::Picture(AttachedPictureFrame { picture, .. }) => {
+				chapter.picture = Some(picture.clone());
+			},
+			_ => {},
+		}
+	}
+
+	chapter
+}
+
+fn chapter_frame(element_id: String, chapter: &Chapter, end: Duration) -> ChapterFrame<'static> {
+	let mut chapter_frame = ChapterFrame::new(
+		element_id,
+		duration_to_millis(chapter.start),
+		duration_to_millis(end),
+	);
+
+	let embedded_frames = &mut chapter_frame.embedded_frames;
+	if let Some(title) = &chapter.title {
+		embedded_frames.push(Frame::Text(TextInformationFrame::new(
+			FrameId::Valid(Cow::Borrowed(TITLE_ID)),
+			TextEncoding::UTF8,
+			title.clone(),
+		)));
+	}
+
+	if let Some(url) = &chapter.url {
+		embedded_frames.push(Frame::UserUrl(ExtendedUrlFrame::new(
+			TextEncoding::UTF8,
+			String::new(),
+			url.clone(),
+		)));
+	}
+
+	if let Some(picture) = &chapter.picture {
+		embedded_frames.push(Frame::Picture(AttachedPictureFrame::new(
+			TextEncoding::UTF8,
+			picture.clone(),
+		)));
+	}
+
+	chapter_frame
+}
+
+fn duration_to_millis(duration: Duration) -> u32 {
+	u32::try_from(duration.as_millis()).unwrap_or(u32::MAX)
+}
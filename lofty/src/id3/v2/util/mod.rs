@@ -1,5 +1,6 @@
 //! Utilities for working with ID3v2 tags
 
+pub(crate) mod chapters;
 pub(crate) mod mappings;
 pub(crate) mod pairs;
 pub mod synchsafe;
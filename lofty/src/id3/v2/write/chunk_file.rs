@@ -1,7 +1,9 @@
 use crate::config::WriteOptions;
 use crate::error::{LoftyError, Result};
-use crate::iff::chunk::Chunks;
-use crate::util::io::{FileLike, Length, Truncate};
+use crate::iff::chunk::{ChunkContainer, Chunks};
+use crate::iff::wav::container::RiffContainer;
+use crate::macros::err;
+use crate::util::io::{splice_file, spliced_len, FileLike, Length, Truncate};
 
 use std::io::SeekFrom;
 
@@ -36,56 +38,112 @@ where
 			break;
 		}
 
-		file.seek(SeekFrom::Current(i64::from(chunks.size)))?;
+		file.seek(SeekFrom::Current(chunks.size as i64))?;
 
 		chunks.correct_position(file)?;
 	}
 
-	if let (Some(chunk_start), Some(mut chunk_size)) = id3v2_chunk {
-		file.rewind()?;
+	let end = file.len()?;
+	let mut edits = Vec::with_capacity(2);
 
+	if let (Some(chunk_start), Some(mut chunk_size)) = id3v2_chunk {
 		// We need to remove the padding byte if it exists
 		if chunk_size % 2 != 0 {
 			chunk_size += 1;
 		}
 
-		let mut file_bytes = Vec::new();
-		file.read_to_end(&mut file_bytes)?;
-
-		file_bytes.splice(
-			chunk_start as usize..(chunk_start + u64::from(chunk_size) + 8) as usize,
-			[],
-		);
-
-		file.rewind()?;
-		file.truncate(0)?;
-		file.write_all(&file_bytes)?;
+		edits.push((chunk_start..chunk_start + chunk_size + 8, Vec::new()));
 	}
 
 	if !tag.is_empty() {
-		file.seek(SeekFrom::End(0))?;
+		let Ok(tag_size) = u32::try_from(tag.len()) else {
+			err!(TooMuchData);
+		};
+
+		let mut chunk = Vec::with_capacity(tag.len() + 9);
 
 		if write_options.uppercase_id3v2_chunk {
-			file.write_all(&CHUNK_NAME_UPPER)?;
+			chunk.extend(CHUNK_NAME_UPPER);
 		} else {
-			file.write_all(&CHUNK_NAME_LOWER)?;
+			chunk.extend(CHUNK_NAME_LOWER);
 		}
 
-		file.write_u32::<B>(tag.len() as u32)?;
-		file.write_all(tag)?;
+		chunk.write_u32::<B>(tag_size)?;
+		chunk.extend(tag);
 
 		// It is required an odd length chunk be padded with a 0
 		// The 0 isn't included in the chunk size, however
 		if tag.len() % 2 != 0 {
-			file.write_u8(0)?;
+			chunk.push(0);
+		}
+
+		edits.push((end..end, chunk));
+	}
+
+	// Check the new size before anything is written
+	let Ok(total_size) = u32::try_from(spliced_len(end, &edits) - 8) else {
+		err!(TooMuchData);
+	};
+
+	splice_file(file, edits)?;
+
+	file.seek(SeekFrom::Start(4))?;
+	file.write_u32::<B>(total_size)?;
+
+	Ok(())
+}
+
+/// Write an ID3v2 chunk to a WAV file, in any of the supported containers (RIFF, RF64/BW64, Wave64)
+pub(in crate::id3::v2) fn write_to_wav<F>(
+	file: &mut F,
+	tag: &[u8],
+	write_options: WriteOptions,
+) -> Result<()>
+where
+	F: FileLike,
+	LoftyError: From<<F as Truncate>::Error>,
+	LoftyError: From<<F as Length>::Error>,
+{
+	let container = RiffContainer::read(file)?;
+	let file_len = file.len()?;
+
+	let mut id3v2_chunk = None;
+
+	let mut chunks = container.chunks(file_len);
+
+	let mut start = file.stream_position()?;
+	while chunks.next(file).is_ok() {
+		let is_id3v2 = chunks.fourcc == CHUNK_NAME_UPPER || chunks.fourcc == CHUNK_NAME_LOWER;
+
+		chunks.skip(file)?;
+
+		let end = file.stream_position()?.min(file_len);
+		if is_id3v2 {
+			id3v2_chunk = Some(start..end);
+			break;
 		}
 
-		let total_size = file.stream_position()? - 8;
+		start = end;
+	}
 
-		file.seek(SeekFrom::Start(4))?;
+	let mut edits = Vec::with_capacity(2);
 
-		file.write_u32::<B>(total_size as u32)?;
+	if let Some(id3v2_chunk) = id3v2_chunk {
+		edits.push((id3v2_chunk, Vec::new()));
 	}
 
+	if !tag.is_empty() {
+		let chunk_name = if write_options.uppercase_id3v2_chunk {
+			CHUNK_NAME_UPPER
+		} else {
+			CHUNK_NAME_LOWER
+		};
+
+		edits.push((file_len..file_len, container.create_chunk(chunk_name, tag)?));
+	}
+
+	container.update_file_size(file, spliced_len(file_len, &edits))?;
+	splice_file(file, edits)?;
+
 	Ok(())
 }
@@ -0,0 +1,116 @@
+use crate::config::WriteOptions;
+use crate::dsd::dsdiff::chunk::{self as dsdiff_chunk, CHUNK_HEADER_SIZE};
+use crate::dsd::dsf::chunk::{self as dsf_chunk, DsdChunk, FmtChunk};
+use crate::error::{LoftyError, Result};
+use crate::macros::{decode_err, err};
+use crate::util::io::{splice_file, FileLike, Length, Truncate};
+
+use std::io::SeekFrom;
+
+use byteorder::{BigEndian, LittleEndian, WriteBytesExt};
+
+const CHUNK_NAME_UPPER: [u8; 4] = [b'I', b'D', b'3', b' '];
+const CHUNK_NAME_LOWER: [u8; 4] = [b'i', b'd', b'3', b' '];
+
+// DSF stores the tag at the end of the file, following the "data" chunk
+pub(in crate::id3::v2) fn write_to_dsf<F>(file: &mut F, tag: &[u8]) -> Result<()>
+where
+	F: FileLike,
+	LoftyError: From<<F as Truncate>::Error>,
+	LoftyError: From<<F as Length>::Error>,
+{
+	let _dsd_chunk = DsdChunk::read(file)?;
+	let _fmt_chunk = FmtChunk::read(file)?;
+
+	let data_start = file.stream_position()?;
+	let data_chunk = dsf_chunk::ChunkHeader::read(file)?;
+	if &data_chunk.id != b"data" {
+		decode_err!(@BAIL Dsf, "File missing \"data\" chunk");
+	}
+
+	// Anything after the audio data is either the old tag or junk
+	let Some(data_end) = data_start.checked_add(data_chunk.size) else {
+		err!(SizeMismatch);
+	};
+
+	if data_end > file.len()? {
+		err!(SizeMismatch);
+	}
+
+	file.truncate(data_end)?;
+
+	file.seek(SeekFrom::Start(data_end))?;
+	file.write_all(tag)?;
+
+	let metadata_offset = if tag.is_empty() { 0 } else { data_end };
+
+	file.seek(SeekFrom::Start(dsf_chunk::FILE_SIZE_OFFSET))?;
+	file.write_u64::<LittleEndian>(data_end + tag.len() as u64)?;
+	file.write_u64::<LittleEndian>(metadata_offset)?;
+
+	Ok(())
+}
+
+// DSDIFF has no official tagging support, the tag goes in an "ID3 " chunk at the end of the "FRM8" chunk
+pub(in crate::id3::v2) fn write_to_dsdiff<F>(
+	file: &mut F,
+	tag: &[u8],
+	write_options: WriteOptions,
+) -> Result<()>
+where
+	F: FileLike,
+	LoftyError: From<<F as Truncate>::Error>,
+	LoftyError: From<<F as Length>::Error>,
+{
+	let form = dsdiff_chunk::verify_dsdiff(file)?;
+	let form_end = form.size.saturating_add(CHUNK_HEADER_SIZE).min(file.len()?);
+
+	// FRM8....DSD
+	let mut pos = 16;
+	let mut edits = Vec::new();
+	let mut removed = 0;
+	while pos + CHUNK_HEADER_SIZE <= form_end {
+		file.seek(SeekFrom::Start(pos))?;
+		let chunk = dsdiff_chunk::ChunkHeader::read(file)?;
+		let chunk_end = pos
+			.saturating_add(CHUNK_HEADER_SIZE)
+			.saturating_add(chunk.padded_size())
+			.min(form_end);
+
+		if chunk.id == CHUNK_NAME_UPPER || chunk.id == CHUNK_NAME_LOWER {
+			removed += chunk_end - pos;
+			edits.push((pos..chunk_end, Vec::new()));
+		}
+
+		pos = chunk_end;
+	}
+
+	let mut new_form_end = form_end - removed;
+	if !tag.is_empty() {
+		let mut id3v2_chunk = Vec::with_capacity(tag.len() + CHUNK_HEADER_SIZE as usize + 1);
+		if write_options.uppercase_id3v2_chunk {
+			id3v2_chunk.extend(CHUNK_NAME_UPPER);
+		} else {
+			id3v2_chunk.extend(CHUNK_NAME_LOWER);
+		}
+
+		id3v2_chunk.write_u64::<BigEndian>(tag.len() as u64)?;
+		id3v2_chunk.extend(tag);
+
+		// It is required an odd length chunk be padded with a 0
+		// The 0 isn't included in the chunk size, however
+		if tag.len() % 2 != 0 {
+			id3v2_chunk.push(0);
+		}
+
+		new_form_end += id3v2_chunk.len() as u64;
+		edits.push((form_end..form_end, id3v2_chunk));
+	}
+
+	let form_size = new_form_end.saturating_sub(CHUNK_HEADER_SIZE);
+	edits.push((4..12, form_size.to_be_bytes().to_vec()));
+
+	splice_file(file, edits)?;
+
+	Ok(())
+}
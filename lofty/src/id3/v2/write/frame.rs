@@ -225,6 +225,23 @@ where
 	Ok(())
 }
 
+/// Write the frames embedded in a `CHAP` or `CTOC` frame, using the same version as the parent
+pub(in crate::id3::v2) fn create_embedded_items<W>(
+	writer: &mut W,
+	frames: &[Frame<'_>],
+	is_id3v23: bool,
+) -> Result<()>
+where
+	W: Write,
+{
+	let mut frames = frames.iter().filter_map(Frame::as_opt_ref);
+	if is_id3v23 {
+		create_items_v3(writer, &mut frames)
+	} else {
+		create_items(writer, &mut frames)
+	}
+}
+
 fn verify_frame(frame: &FrameRef<'_>) -> Result<()> {
 	match (frame.id().as_str(), &**frame) {
 		("APIC", Frame::Picture { .. })
@@ -239,7 +256,9 @@ fn verify_frame(frame: &FrameRef<'_>) -> Result<()> {
 		| ("WFED" | "GRP1" | "MVNM" | "MVIN", Frame::Text { .. })
 		| ("TDEN" | "TDOR" | "TDRC" | "TDRL" | "TDTG", Frame::Timestamp(_))
 		| ("RVA2", Frame::RelativeVolumeAdjustment(_))
-		| ("PRIV", Frame::Private(_)) => Ok(()),
+		| ("PRIV", Frame::Private(_))
+		| ("CHAP", Frame::Chapter(_))
+		| ("CTOC", Frame::TableOfContents(_)) => Ok(()),
 		(id, Frame::Text { .. }) if id.starts_with('T') => Ok(()),
 		(id, Frame::Url(_)) if id.starts_with('W') => Ok(()),
 		(id, frame_value) => Err(Id3v2Error::new(Id3v2ErrorKind::BadFrame(
@@ -1,5 +1,6 @@
 mod chunk_file;
-mod frame;
+mod dsd;
+pub(super) mod frame;
 
 use super::Id3v2TagFlags;
 use crate::config::WriteOptions;
@@ -18,7 +19,7 @@ use std::io::{Cursor, Read, Seek, SeekFrom, Write};
 use std::ops::Not;
 use std::sync::OnceLock;
 
-use byteorder::{BigEndian, LittleEndian, WriteBytesExt};
+use byteorder::{BigEndian, WriteBytesExt};
 
 // In the very rare chance someone wants to write a CRC in their extended header
 fn crc_32_table() -> &'static [u32; 256] {
@@ -77,12 +78,22 @@ where
 		// Formats such as WAV and AIFF store the ID3v2 tag in an 'ID3 ' chunk rather than at the beginning of the file
 		FileType::Wav => {
 			tag.flags.footer = false;
-			return chunk_file::write_to_chunk_file::<F, LittleEndian>(file, &id3v2, write_options);
+			return chunk_file::write_to_wav(file, &id3v2, write_options);
 		},
 		FileType::Aiff => {
 			tag.flags.footer = false;
 			return chunk_file::write_to_chunk_file::<F, BigEndian>(file, &id3v2, write_options);
 		},
+		FileType::Dsf => return dsd::write_to_dsf(file, &id3v2),
+		// MP4 files store the ID3v2 tag in an `ID32` atom
+		FileType::Mp4 => {
+			tag.flags.footer = false;
+			return crate::mp4::ilst::write::write_id3v2_to(file, &id3v2, write_options);
+		},
+		FileType::Dsdiff => {
+			tag.flags.footer = false;
+			return dsd::write_to_dsdiff(file, &id3v2, write_options);
+		},
 		_ => {},
 	}
 
@@ -0,0 +1,53 @@
+use crate::error::Result;
+use crate::macros::decode_err;
+
+/// An `APPL` chunk
+///
+/// These hold application specific data, which is identified by the application's signature.
+/// A file can contain any number of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ApplicationChunk {
+	/// The signature of the application (Ex. `pdos` for Apple II applications)
+	pub signature: [u8; 4],
+	/// The application specific data
+	pub data: Vec<u8>,
+}
+
+impl ApplicationChunk {
+	/// Create a new [`ApplicationChunk`]
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::iff::aiff::ApplicationChunk;
+	///
+	/// let appl = ApplicationChunk::new(*b"pdos", vec![1, 2, 3]);
+	/// assert_eq!(&appl.signature, b"pdos");
+	/// ```
+	pub fn new(signature: [u8; 4], data: Vec<u8>) -> Self {
+		Self { signature, data }
+	}
+
+	pub(super) fn parse(content: Vec<u8>) -> Result<Self> {
+		if content.len() < 4 {
+			decode_err!(@BAIL Aiff, "\"APPL\" chunk is too small");
+		}
+
+		let mut signature = [0; 4];
+		signature.copy_from_slice(&content[..4]);
+
+		Ok(Self {
+			signature,
+			data: content[4..].to_vec(),
+		})
+	}
+
+	pub(super) fn as_bytes(&self) -> Vec<u8> {
+		let mut bytes = Vec::with_capacity(self.data.len() + 4);
+		bytes.extend(self.signature);
+		bytes.extend(&self.data);
+
+		bytes
+	}
+}
@@ -0,0 +1,63 @@
+use crate::error::{LoftyError, Result};
+use crate::iff::chunk::{self, ChunkContainer, ChunkUpdate, Chunks};
+use crate::macros::err;
+use crate::util::io::{FileLike, Length, Truncate};
+
+use std::io::{Seek, SeekFrom, Write};
+
+use byteorder::{BigEndian, WriteBytesExt};
+
+/// An AIFF/AIFC file, which only has 32-bit chunk sizes
+pub(crate) struct AiffContainer;
+
+impl ChunkContainer for AiffContainer {
+	type Endianness = BigEndian;
+
+	const INSERT_BEFORE: [u8; 4] = *b"SSND";
+
+	fn chunks(&self, file_size: u64) -> Chunks<BigEndian> {
+		Chunks::new(file_size)
+	}
+
+	fn create_chunk(&self, fourcc: [u8; 4], content: &[u8]) -> Result<Vec<u8>> {
+		let Ok(size) = u32::try_from(content.len()) else {
+			err!(TooMuchData);
+		};
+
+		let mut chunk = Vec::with_capacity(content.len() + 9);
+		chunk.extend(fourcc);
+		chunk.extend(size.to_be_bytes());
+		chunk.extend(content);
+
+		if size % 2 != 0 {
+			chunk.push(0);
+		}
+
+		Ok(chunk)
+	}
+
+	fn update_file_size<W>(&self, file: &mut W, len: u64) -> Result<()>
+	where
+		W: Write + Seek,
+	{
+		let Ok(size) = u32::try_from(len - 8) else {
+			err!(TooMuchData);
+		};
+
+		file.seek(SeekFrom::Start(4))?;
+		file.write_u32::<BigEndian>(size)?;
+
+		Ok(())
+	}
+}
+
+/// Replace, insert, or remove chunks in an AIFF file, see [`chunk::write_chunks`]
+pub(crate) fn write_chunks<F>(file: &mut F, updates: &[ChunkUpdate]) -> Result<()>
+where
+	F: FileLike,
+	LoftyError: From<<F as Truncate>::Error>,
+	LoftyError: From<<F as Length>::Error>,
+{
+	super::read::verify_aiff(file)?;
+	chunk::write_chunks(file, &AiffContainer, updates)
+}
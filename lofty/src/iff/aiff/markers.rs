@@ -0,0 +1,344 @@
+use crate::error::Result;
+use crate::macros::{decode_err, err};
+use crate::util::text::{latin1_decode, utf8_decode};
+
+use std::io::Read;
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+
+// Base note (1) + detune (1) + low note (1) + high note (1) + low velocity (1) + high velocity (1)
+// + gain (2) + sustain loop (6) + release loop (6)
+const INST_SIZE: usize = 20;
+
+/// A marker, from the `MARK` chunk
+///
+/// Markers point to a position in the sound data, and are referenced by the loops of an
+/// [`Instrument`], and by [`Comment`](super::Comment)s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Marker {
+	/// A unique identifier for the marker, greater than 0
+	pub id: u16,
+	/// The position of the marker, in sample frames
+	///
+	/// A position of 0 is before the first sample frame.
+	pub position: u32,
+	/// The name of the marker
+	///
+	/// The size of the name is restricted to 255 bytes.
+	///
+	/// Names that aren't valid UTF-8 (Ex. Mac OS Roman) are read as Latin-1, so some characters
+	/// may differ from the original.
+	pub name: String,
+}
+
+impl Marker {
+	/// Create a new [`Marker`]
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::iff::aiff::Marker;
+	///
+	/// let marker = Marker::new(1, 1024, String::from("Loop start"));
+	/// assert_eq!(marker.position, 1024);
+	/// ```
+	pub fn new(id: u16, position: u32, name: String) -> Self {
+		Self { id, position, name }
+	}
+}
+
+/// How a [`AiffLoop`] is played
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AiffLoopPlayMode {
+	/// The loop is not played
+	NoLooping,
+	/// Play forward, from the start to the end
+	Forward,
+	/// Alternate between playing forward and backward
+	ForwardBackward,
+	/// An unknown play mode
+	Other(u16),
+}
+
+impl AiffLoopPlayMode {
+	/// Get an `AiffLoopPlayMode` from a `u16`
+	pub fn from_u16(value: u16) -> Self {
+		match value {
+			0 => Self::NoLooping,
+			1 => Self::Forward,
+			2 => Self::ForwardBackward,
+			_ => Self::Other(value),
+		}
+	}
+
+	/// Get a `u16` from an `AiffLoopPlayMode`
+	pub fn as_u16(&self) -> u16 {
+		match self {
+			Self::NoLooping => 0,
+			Self::Forward => 1,
+			Self::ForwardBackward => 2,
+			Self::Other(value) => *value,
+		}
+	}
+}
+
+/// A loop in an [`Instrument`]
+///
+/// The start and end of the loop are the IDs of [`Marker`]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AiffLoop {
+	/// How the loop is played
+	pub play_mode: AiffLoopPlayMode,
+	/// The ID of the marker at the start of the loop
+	pub begin_marker: u16,
+	/// The ID of the marker at the end of the loop
+	pub end_marker: u16,
+}
+
+impl AiffLoop {
+	/// Create a new [`AiffLoop`] between two [`Marker`]s
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::iff::aiff::{AiffLoop, AiffLoopPlayMode};
+	///
+	/// let aiff_loop = AiffLoop::new(AiffLoopPlayMode::Forward, 1, 2);
+	/// assert_eq!(aiff_loop.end_marker, 2);
+	/// ```
+	pub fn new(play_mode: AiffLoopPlayMode, begin_marker: u16, end_marker: u16) -> Self {
+		Self {
+			play_mode,
+			begin_marker,
+			end_marker,
+		}
+	}
+}
+
+impl Default for AiffLoop {
+	fn default() -> Self {
+		Self {
+			play_mode: AiffLoopPlayMode::NoLooping,
+			begin_marker: 0,
+			end_marker: 0,
+		}
+	}
+}
+
+/// The contents of an `INST` chunk
+///
+/// This describes how a sampler should play the sound data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Instrument {
+	/// The MIDI note that will play the sound at its original pitch (Ex. 60 for middle C)
+	pub base_note: u8,
+	/// The detuning of the base note in cents, from -50 to 50
+	pub detune: i8,
+	/// The lowest MIDI note the sound should be played at
+	pub low_note: u8,
+	/// The highest MIDI note the sound should be played at
+	pub high_note: u8,
+	/// The lowest MIDI velocity the sound should be played at
+	pub low_velocity: u8,
+	/// The highest MIDI velocity the sound should be played at
+	pub high_velocity: u8,
+	/// The gain in decibels
+	pub gain: i16,
+	/// The loop played while the note is held
+	pub sustain_loop: AiffLoop,
+	/// The loop played after the note is released
+	pub release_loop: AiffLoop,
+}
+
+impl Default for Instrument {
+	fn default() -> Self {
+		Self {
+			base_note: 60,
+			detune: 0,
+			low_note: 0,
+			high_note: 127,
+			low_velocity: 1,
+			high_velocity: 127,
+			gain: 0,
+			sustain_loop: AiffLoop::default(),
+			release_loop: AiffLoop::default(),
+		}
+	}
+}
+
+impl Instrument {
+	pub(super) fn parse(mut content: &[u8]) -> Result<Self> {
+		if content.len() < INST_SIZE {
+			decode_err!(@BAIL Aiff, "\"INST\" chunk is too small");
+		}
+
+		let reader = &mut content;
+
+		Ok(Self {
+			base_note: reader.read_u8()?,
+			detune: reader.read_i8()?,
+			low_note: reader.read_u8()?,
+			high_note: reader.read_u8()?,
+			low_velocity: reader.read_u8()?,
+			high_velocity: reader.read_u8()?,
+			gain: reader.read_i16::<BigEndian>()?,
+			sustain_loop: read_loop(reader)?,
+			release_loop: read_loop(reader)?,
+		})
+	}
+
+	pub(super) fn as_bytes(&self) -> Result<Vec<u8>> {
+		let mut bytes = Vec::with_capacity(INST_SIZE);
+
+		bytes.write_u8(self.base_note)?;
+		bytes.write_i8(self.detune)?;
+		bytes.write_u8(self.low_note)?;
+		bytes.write_u8(self.high_note)?;
+		bytes.write_u8(self.low_velocity)?;
+		bytes.write_u8(self.high_velocity)?;
+		bytes.write_i16::<BigEndian>(self.gain)?;
+
+		for aiff_loop in [self.sustain_loop, self.release_loop] {
+			bytes.write_u16::<BigEndian>(aiff_loop.play_mode.as_u16())?;
+			bytes.write_u16::<BigEndian>(aiff_loop.begin_marker)?;
+			bytes.write_u16::<BigEndian>(aiff_loop.end_marker)?;
+		}
+
+		Ok(bytes)
+	}
+}
+
+fn read_loop(reader: &mut &[u8]) -> Result<AiffLoop> {
+	Ok(AiffLoop {
+		play_mode: AiffLoopPlayMode::from_u16(reader.read_u16::<BigEndian>()?),
+		begin_marker: reader.read_u16::<BigEndian>()?,
+		end_marker: reader.read_u16::<BigEndian>()?,
+	})
+}
+
+// The `MARK` chunk:
+//
+// Marker count (2)
+// Markers:
+//     ID (2)
+//     Position (4)
+//     Name (Pascal string, padded to an even length)
+pub(super) fn parse_markers(mut content: &[u8]) -> Result<Vec<Marker>> {
+	let reader = &mut content;
+
+	let count = reader.read_u16::<BigEndian>()?;
+
+	// Every marker is at least 8 bytes
+	let mut markers = Vec::with_capacity(usize::from(count).min(reader.len() / 8));
+	for _ in 0..count {
+		let id = reader.read_u16::<BigEndian>()?;
+		let position = reader.read_u32::<BigEndian>()?;
+
+		let name_len = usize::from(reader.read_u8()?);
+		if name_len > reader.len() {
+			decode_err!(@BAIL Aiff, "\"MARK\" chunk has a marker name with an invalid size");
+		}
+
+		let (name, remaining) = reader.split_at(name_len);
+		let name = utf8_decode(name.to_vec()).unwrap_or_else(|_| latin1_decode(name));
+		*reader = remaining;
+
+		// The count byte and name are padded to an even length
+		if name_len % 2 == 0 {
+			let mut padding = [0; 1];
+			reader.read_exact(&mut padding)?;
+		}
+
+		markers.push(Marker { id, position, name });
+	}
+
+	Ok(markers)
+}
+
+pub(super) fn create_mark_chunk(markers: &[Marker]) -> Result<Vec<u8>> {
+	let Ok(count) = u16::try_from(markers.len()) else {
+		err!(TooMuchData);
+	};
+
+	let mut bytes = Vec::new();
+	bytes.write_u16::<BigEndian>(count)?;
+
+	for marker in markers {
+		let Ok(name_len) = u8::try_from(marker.name.len()) else {
+			err!(TooMuchData);
+		};
+
+		bytes.write_u16::<BigEndian>(marker.id)?;
+		bytes.write_u32::<BigEndian>(marker.position)?;
+		bytes.write_u8(name_len)?;
+		bytes.extend(marker.name.as_bytes());
+
+		if name_len % 2 == 0 {
+			bytes.push(0);
+		}
+	}
+
+	Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{AiffLoop, AiffLoopPlayMode, Instrument, Marker};
+
+	#[test_log::test]
+	fn markers_round_trip() {
+		let markers = vec![
+			Marker {
+				id: 1,
+				position: 0,
+				name: String::from("Foo start"),
+			},
+			Marker {
+				id: 2,
+				position: 4096,
+				name: String::from("Bar end"),
+			},
+			Marker {
+				id: 3,
+				position: 8192,
+				name: String::new(),
+			},
+		];
+
+		let bytes = super::create_mark_chunk(&markers).unwrap();
+		assert_eq!(bytes.len() % 2, 0);
+
+		assert_eq!(super::parse_markers(&bytes).unwrap(), markers);
+	}
+
+	#[test_log::test]
+	fn mac_roman_marker_name() {
+		// "Café" in Mac OS Roman, which isn't valid UTF-8
+		let bytes = [0, 1, 0, 1, 0, 0, 0, 0, 4, b'C', b'a', b'f', 0x8E, 0];
+
+		let markers = super::parse_markers(&bytes).unwrap();
+		assert_eq!(markers.len(), 1);
+		assert_eq!(markers[0].name, "Caf\u{8E}");
+	}
+
+	#[test_log::test]
+	fn instrument_round_trip() {
+		let instrument = Instrument {
+			detune: -12,
+			gain: -6,
+			sustain_loop: AiffLoop {
+				play_mode: AiffLoopPlayMode::ForwardBackward,
+				begin_marker: 1,
+				end_marker: 2,
+			},
+			..Instrument::default()
+		};
+
+		let bytes = instrument.as_bytes().unwrap();
+		assert_eq!(Instrument::parse(&bytes).unwrap(), instrument);
+	}
+}
@@ -1,21 +1,46 @@
 //! AIFF specific items
+//!
+//! ## File notes
+//!
+//! The `MARK`, `INST`, and `APPL` chunks are available on [`AiffFile`]. These are not tags, and are not
+//! included when converting to a [`TaggedFile`](crate::file::TaggedFile).
 
+mod application;
+pub(crate) mod chunks;
+mod markers;
 mod properties;
 mod read;
 pub(crate) mod tag;
 
+use crate::config::WriteOptions;
+use crate::error::{LoftyError, Result};
 use crate::id3::v2::tag::Id3v2Tag;
+use crate::iff::chunk::ChunkUpdate;
+use crate::macros::impl_chapters;
+use crate::tag::TagExt;
+use crate::util::io::{FileLike, Length, Truncate};
+use crate::xmp::XmpTag;
 
 use lofty_attr::LoftyFile;
 
 // Exports
 
+pub use application::ApplicationChunk;
+pub use markers::{AiffLoop, AiffLoopPlayMode, Instrument, Marker};
 pub use properties::{AiffCompressionType, AiffProperties};
 pub use tag::{AiffTextChunks, Comment};
 
 /// An AIFF file
+///
+/// ## Notes
+///
+/// * The markers and application chunks are only written if they were modified. Removing all of them
+///   will remove the chunks from the file it is saved to.
+/// * The `INST` chunk is only written if it is set. Removing it from an `AiffFile` will **not** remove it
+///   from the file it is saved to.
 #[derive(LoftyFile)]
 #[lofty(read_fn = "read::read_from")]
+#[lofty(write_fn = "Self::write_to")]
 #[lofty(internal_write_module_do_not_use_anywhere_else)]
 pub struct AiffFile {
 	/// Any text chunks included in the file
@@ -24,6 +49,164 @@ pub struct AiffFile {
 	/// An ID3v2 tag
 	#[lofty(tag_type = "Id3v2")]
 	pub(crate) id3v2_tag: Option<Id3v2Tag>,
+	// `None` if the chunks were never read or set, to avoid removing them on save
+	pub(crate) markers: Option<Vec<Marker>>,
+	pub(crate) application_chunks: Option<Vec<ApplicationChunk>>,
+	// The chunks as they were read, they're only written if they differ
+	pub(crate) original_markers: Option<Vec<Marker>>,
+	pub(crate) original_application_chunks: Option<Vec<ApplicationChunk>>,
+	pub(crate) instrument: Option<Instrument>,
+	/// An XMP packet, stored in a `_PMX` chunk
+	#[lofty(tag_type = "Xmp")]
+	pub(crate) xmp_tag: Option<XmpTag>,
 	/// The file's audio properties
 	pub(crate) properties: AiffProperties,
 }
+
+impl_chapters!(AiffFile, id3v2_tag, Id3v2Tag);
+
+impl AiffFile {
+	/// Returns the markers
+	pub fn markers(&self) -> &[Marker] {
+		self.markers.as_deref().unwrap_or_default()
+	}
+
+	/// Returns a mutable reference to the markers
+	pub fn markers_mut(&mut self) -> &mut Vec<Marker> {
+		self.markers.get_or_insert_with(Vec::new)
+	}
+
+	/// Removes all markers, returning them
+	///
+	/// Unlike the `INST` chunk, this **will** remove the markers from the file it is saved to.
+	pub fn remove_markers(&mut self) -> Vec<Marker> {
+		self.markers.replace(Vec::new()).unwrap_or_default()
+	}
+
+	/// Returns the marker with the given ID, if it exists
+	pub fn marker(&self, id: u16) -> Option<&Marker> {
+		self.markers().iter().find(|marker| marker.id == id)
+	}
+
+	/// Returns the marker a [`Comment`] is linked to, if it exists
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// use lofty::config::ParseOptions;
+	/// use lofty::file::AudioFile;
+	/// use lofty::iff::aiff::AiffFile;
+	///
+	/// # fn main() -> lofty::error::Result<()> {
+	/// # let mut file = std::fs::File::open("foo.aiff")?;
+	/// let aiff_file = AiffFile::read_from(&mut file, ParseOptions::new())?;
+	///
+	/// if let Some(comments) = aiff_file.text_chunks().and_then(|t| t.comments.as_ref()) {
+	/// 	for comment in comments {
+	/// 		if let Some(marker) = aiff_file.comment_marker(comment) {
+	/// 			println!("{} @ {}: {}", marker.name, marker.position, comment.text);
+	/// 		}
+	/// 	}
+	/// }
+	/// # Ok(()) }
+	/// ```
+	pub fn comment_marker(&self, comment: &Comment) -> Option<&Marker> {
+		if comment.marker_id == 0 {
+			return None;
+		}
+
+		self.marker(comment.marker_id)
+	}
+
+	/// Returns a reference to the [`Instrument`], if it exists
+	pub fn instrument(&self) -> Option<&Instrument> {
+		self.instrument.as_ref()
+	}
+
+	/// Returns a mutable reference to the [`Instrument`], if it exists
+	pub fn instrument_mut(&mut self) -> Option<&mut Instrument> {
+		self.instrument.as_mut()
+	}
+
+	/// Sets the [`Instrument`], returning the old one if it exists
+	pub fn set_instrument(&mut self, instrument: Instrument) -> Option<Instrument> {
+		self.instrument.replace(instrument)
+	}
+
+	/// Removes the [`Instrument`], returning it if it exists
+	pub fn remove_instrument(&mut self) -> Option<Instrument> {
+		self.instrument.take()
+	}
+
+	/// Returns the application chunks
+	pub fn application_chunks(&self) -> &[ApplicationChunk] {
+		self.application_chunks.as_deref().unwrap_or_default()
+	}
+
+	/// Returns a mutable reference to the application chunks
+	pub fn application_chunks_mut(&mut self) -> &mut Vec<ApplicationChunk> {
+		self.application_chunks.get_or_insert_with(Vec::new)
+	}
+
+	/// Removes all application chunks, returning them
+	pub fn remove_application_chunks(&mut self) -> Vec<ApplicationChunk> {
+		self.application_chunks
+			.replace(Vec::new())
+			.unwrap_or_default()
+	}
+
+	fn write_to<F>(&self, file: &mut F, write_options: WriteOptions) -> Result<()>
+	where
+		F: FileLike,
+		LoftyError: From<<F as Truncate>::Error>,
+		LoftyError: From<<F as Length>::Error>,
+	{
+		if let Some(ref text_chunks) = self.text_chunks_tag {
+			file.rewind()?;
+			text_chunks.save_to(file, write_options)?;
+		}
+
+		if let Some(ref id3v2) = self.id3v2_tag {
+			file.rewind()?;
+			id3v2.save_to(file, write_options)?;
+		}
+
+		if let Some(ref xmp) = self.xmp_tag {
+			file.rewind()?;
+			xmp.save_to(file, write_options)?;
+		}
+
+		let mut updates = Vec::new();
+		if self.markers != self.original_markers {
+			if let Some(ref marker_list) = self.markers {
+				let mark_chunk = if marker_list.is_empty() {
+					None
+				} else {
+					Some(markers::create_mark_chunk(marker_list)?)
+				};
+
+				updates.push(ChunkUpdate::new(*b"MARK", mark_chunk));
+			}
+		}
+
+		if let Some(ref instrument) = self.instrument {
+			updates.push(ChunkUpdate::new(*b"INST", Some(instrument.as_bytes()?)));
+		}
+
+		if self.application_chunks != self.original_application_chunks {
+			if let Some(ref application_chunks) = self.application_chunks {
+				updates.push(ChunkUpdate::new(
+					*b"APPL",
+					application_chunks.iter().map(ApplicationChunk::as_bytes),
+				));
+			}
+		}
+
+		if updates.is_empty() {
+			return Ok(());
+		}
+
+		file.rewind()?;
+		chunks::write_chunks(file, &updates)
+	}
+}
@@ -16,6 +16,7 @@ use byteorder::{BigEndian, ReadBytesExt};
 /// This contains a non-exhaustive list of compression types
 #[allow(non_camel_case_types)]
 #[derive(Clone, Eq, PartialEq, Default, Debug)]
+#[non_exhaustive]
 pub enum AiffCompressionType {
 	#[default]
 	/// PCM
@@ -44,6 +45,16 @@ pub enum AiffCompressionType {
 	ALAW,
 	/// IEEE 32-bit float (From SoundHack & Csound)
 	FL32,
+	/// 4:1 IMA ADPCM, as written by QuickTime
+	ima4,
+	/// PCM (big-endian, two's complement)
+	twos,
+	/// PCM (unsigned 8-bit)
+	raw,
+	/// PCM (big-endian, 24-bit)
+	in24,
+	/// PCM (big-endian, 32-bit)
+	in32,
 	/// Catch-all for unknown compression algorithms
 	Other {
 		/// Identifier from the compression algorithm
@@ -81,11 +92,86 @@ impl AiffCompressionType {
 			AiffCompressionType::ULAW => Cow::Borrowed("CCITT G.711 u-law"),
 			AiffCompressionType::ALAW => Cow::Borrowed("CCITT G.711 A-law"),
 			AiffCompressionType::FL32 => Cow::Borrowed("Float 32"),
+			AiffCompressionType::ima4 => Cow::Borrowed("IMA 4:1"),
+			AiffCompressionType::twos => Cow::Borrowed(""), // Has no compression name
+			AiffCompressionType::raw => Cow::Borrowed(""),  // Has no compression name
+			AiffCompressionType::in24 => Cow::Borrowed("24-bit integer"),
+			AiffCompressionType::in32 => Cow::Borrowed("32-bit integer"),
 			AiffCompressionType::Other {
 				compression_name, ..
 			} => Cow::from(compression_name),
 		}
 	}
+
+	/// Get the identifier of the compression type, as stored in the `COMM` chunk
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::iff::aiff::AiffCompressionType;
+	///
+	/// let compression_type = AiffCompressionType::ima4;
+	/// assert_eq!(&compression_type.compression_type(), b"ima4");
+	/// ```
+	pub fn compression_type(&self) -> [u8; 4] {
+		match self {
+			AiffCompressionType::None => *b"NONE",
+			AiffCompressionType::ACE2 => *b"ACE2",
+			AiffCompressionType::ACE8 => *b"ACE8",
+			AiffCompressionType::MAC3 => *b"MAC3",
+			AiffCompressionType::MAC6 => *b"MAC6",
+			AiffCompressionType::sowt => *b"sowt",
+			AiffCompressionType::fl32 => *b"fl32",
+			AiffCompressionType::fl64 => *b"fl64",
+			AiffCompressionType::alaw => *b"alaw",
+			AiffCompressionType::ulaw => *b"ulaw",
+			AiffCompressionType::ULAW => *b"ULAW",
+			AiffCompressionType::ALAW => *b"ALAW",
+			AiffCompressionType::FL32 => *b"FL32",
+			AiffCompressionType::ima4 => *b"ima4",
+			AiffCompressionType::twos => *b"twos",
+			AiffCompressionType::raw => *b"raw ",
+			AiffCompressionType::in24 => *b"in24",
+			AiffCompressionType::in32 => *b"in32",
+			AiffCompressionType::Other {
+				compression_type, ..
+			} => *compression_type,
+		}
+	}
+
+	/// Whether the audio is stored as floating point samples
+	pub fn is_float(&self) -> bool {
+		matches!(
+			self,
+			AiffCompressionType::fl32 | AiffCompressionType::fl64 | AiffCompressionType::FL32
+		)
+	}
+
+	// The sample size in the `COMM` chunk is the size of the *decompressed* samples for
+	// some compression types, and is sometimes 0 for floating point audio
+	fn stored_sample_size(&self, sample_size: u16) -> u16 {
+		match self {
+			AiffCompressionType::alaw
+			| AiffCompressionType::ulaw
+			| AiffCompressionType::ALAW
+			| AiffCompressionType::ULAW
+			| AiffCompressionType::raw => 8,
+			AiffCompressionType::ima4 => 4,
+			AiffCompressionType::in24 => 24,
+			AiffCompressionType::fl32 | AiffCompressionType::FL32 | AiffCompressionType::in32 => 32,
+			AiffCompressionType::fl64 => 64,
+			_ => sample_size,
+		}
+	}
+
+	// The number of sample frames in each packet, since the `COMM` chunk stores the
+	// packet count rather than the sample frame count for IMA ADPCM
+	fn frames_per_packet(&self) -> u32 {
+		match self {
+			AiffCompressionType::ima4 => 64,
+			_ => 1,
+		}
+	}
 }
 
 /// A AIFF file's audio properties
@@ -164,8 +250,8 @@ pub(super) fn read_properties(
 		decode_err!(@BAIL Aiff, "File contains 0 channels");
 	}
 
-	let sample_frames = comm.read_u32::<BigEndian>()?;
-	let sample_size = comm.read_u16::<BigEndian>()?;
+	let mut sample_frames = comm.read_u32::<BigEndian>()?;
+	let mut sample_size = comm.read_u16::<BigEndian>()?;
 
 	let sample_rate_extended = comm.read_f80()?;
 	let sample_rate_64 = sample_rate_extended.as_f64();
@@ -175,6 +261,16 @@ pub(super) fn read_properties(
 
 	let sample_rate = sample_rate_64.round() as u32;
 
+	let mut compression = None;
+	if comm.len() >= 4 && compression_present == CompressionPresent::Yes {
+		let compression_type = read_compression_type(comm)?;
+
+		sample_frames = sample_frames.saturating_mul(compression_type.frames_per_packet());
+		sample_size = compression_type.stored_sample_size(sample_size);
+
+		compression = Some(compression_type);
+	}
+
 	let (duration, overall_bitrate, audio_bitrate) = if sample_rate > 0 && sample_frames > 0 {
 		let length = (f64::from(sample_frames) * 1000.0) / f64::from(sample_rate);
 
@@ -187,23 +283,26 @@ pub(super) fn read_properties(
 		(Duration::ZERO, 0, 0)
 	};
 
-	let is_compressed = comm.len() >= 5 && compression_present == CompressionPresent::Yes;
-	if !is_compressed {
-		return Ok(AiffProperties {
-			duration,
-			overall_bitrate,
-			audio_bitrate,
-			sample_rate,
-			sample_size,
-			channels,
-			compression_type: None,
-		});
-	}
+	Ok(AiffProperties {
+		duration,
+		overall_bitrate,
+		audio_bitrate,
+		sample_rate,
+		sample_size,
+		channels,
+		compression_type: compression,
+	})
+}
 
+// The extended `COMM` chunk:
+//
+// Compression type (4)
+// Compression name (Pascal string, padded to an even length)
+fn read_compression_type(comm: &mut &[u8]) -> Result<AiffCompressionType> {
 	let mut compression_type = [0u8; 4];
 	comm.read_exact(&mut compression_type)?;
 
-	let compression = Some(match &compression_type {
+	let compression = match &compression_type {
 		b"NONE" => AiffCompressionType::None,
 		b"ACE2" => AiffCompressionType::ACE2,
 		b"ACE8" => AiffCompressionType::ACE8,
@@ -217,6 +316,11 @@ pub(super) fn read_properties(
 		b"ULAW" => AiffCompressionType::ULAW,
 		b"ALAW" => AiffCompressionType::ALAW,
 		b"FL32" => AiffCompressionType::FL32,
+		b"ima4" => AiffCompressionType::ima4,
+		b"twos" => AiffCompressionType::twos,
+		b"raw " => AiffCompressionType::raw,
+		b"in24" => AiffCompressionType::in24,
+		b"in32" => AiffCompressionType::in32,
 		_ => {
 			log::debug!(
 				"Encountered unknown compression type: {:?}",
@@ -239,15 +343,56 @@ pub(super) fn read_properties(
 				compression_name,
 			}
 		},
-	});
+	};
 
-	Ok(AiffProperties {
-		duration,
-		overall_bitrate,
-		audio_bitrate,
-		sample_rate,
-		sample_size,
-		channels,
-		compression_type: compression,
-	})
+	Ok(compression)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{read_properties, AiffCompressionType};
+	use crate::iff::aiff::read::CompressionPresent;
+
+	fn comm(sample_frames: u32, sample_size: u16, compression: &[u8]) -> Vec<u8> {
+		let mut comm = Vec::new();
+		comm.extend(2_u16.to_be_bytes());
+		comm.extend(sample_frames.to_be_bytes());
+		comm.extend(sample_size.to_be_bytes());
+		// 44100 as an 80-bit float
+		comm.extend([0x40, 0x0E, 0xAC, 0x44, 0, 0, 0, 0, 0, 0]);
+		comm.extend(compression);
+		comm
+	}
+
+	#[test_log::test]
+	fn ima4_duration() {
+		// 689 packets of 64 sample frames, 34 bytes per channel
+		let comm = comm(689, 16, b"ima4\x07IMA 4:1");
+		let stream_len = 689 * 34 * 2;
+
+		let properties =
+			read_properties(&mut &comm[..], CompressionPresent::Yes, stream_len, 0).unwrap();
+
+		assert_eq!(
+			properties.compression_type(),
+			Some(&AiffCompressionType::ima4)
+		);
+		assert_eq!(properties.duration().as_millis(), 999);
+		assert_eq!(properties.audio_bitrate(), 375);
+		assert_eq!(properties.sample_size(), 4);
+	}
+
+	#[test_log::test]
+	fn float_sample_size() {
+		let comm = comm(44100, 0, b"fl32\x1532-bit floating point");
+		let stream_len = 44100 * 4 * 2;
+
+		let properties =
+			read_properties(&mut &comm[..], CompressionPresent::Yes, stream_len, 0).unwrap();
+
+		assert!(properties.compression_type().unwrap().is_float());
+		assert_eq!(properties.duration().as_millis(), 1000);
+		assert_eq!(properties.audio_bitrate(), 2822);
+		assert_eq!(properties.sample_size(), 32);
+	}
 }
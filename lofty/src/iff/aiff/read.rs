@@ -1,3 +1,5 @@
+use super::application::ApplicationChunk;
+use super::markers::{self, Instrument};
 use super::properties::AiffProperties;
 use super::tag::{AiffTextChunks, Comment};
 use super::AiffFile;
@@ -5,7 +7,8 @@ use crate::config::ParseOptions;
 use crate::error::Result;
 use crate::id3::v2::tag::Id3v2Tag;
 use crate::iff::chunk::Chunks;
-use crate::macros::{decode_err, err};
+use crate::macros::{decode_err, err, parse_mode_choice};
+use crate::xmp::read::read_xmp;
 
 use std::io::{Read, Seek, SeekFrom};
 
@@ -47,6 +50,8 @@ pub(crate) fn read_from<R>(data: &mut R, parse_options: ParseOptions) -> Result<
 where
 	R: Read + Seek,
 {
+	let parse_mode = parse_options.parsing_mode;
+
 	// TODO: Maybe one day the `Seek` bound can be removed?
 	// let file_size = verify_aiff(data)?;
 	let compression_present = verify_aiff(data)?;
@@ -65,6 +70,13 @@ where
 
 	let mut id3v2_tag: Option<Id3v2Tag> = None;
 
+	let mut marker_list = None;
+	let mut instrument = None;
+	// Left as `None` if any of them can't be read, so they aren't removed on save
+	let mut application_chunks = parse_options.read_tags.then(Vec::new);
+
+	let mut xmp_tag = None;
+
 	let mut chunks = Chunks::<BigEndian>::new(file_len);
 
 	while chunks.next(data).is_ok() {
@@ -92,7 +104,11 @@ where
 				chunks.correct_position(data)?;
 			},
 			b"SSND" if parse_options.read_properties => {
-				stream_len = chunks.size;
+				let Ok(size) = u32::try_from(chunks.size) else {
+					err!(TooMuchData);
+				};
+
+				stream_len = size;
 				chunks.skip(data)?;
 			},
 			b"ANNO" if parse_options.read_tags => {
@@ -132,10 +148,80 @@ where
 			b"(c) " if text_chunks.copyright.is_none() && parse_options.read_tags => {
 				text_chunks.copyright = Some(chunks.read_pstring(data, None)?);
 			},
+			b"MARK" if marker_list.is_none() && parse_options.read_tags => {
+				let content = chunks.content(data)?;
+				chunks.correct_position(data)?;
+
+				match markers::parse_markers(&content) {
+					Ok(list) => marker_list = Some(list),
+					Err(e) => {
+						parse_mode_choice!(
+							parse_mode,
+							STRICT: return Err(e),
+							DEFAULT: log::warn!("Unable to read \"MARK\" chunk, discarding")
+						);
+					},
+				}
+			},
+			b"INST" if instrument.is_none() && parse_options.read_tags => {
+				let content = chunks.content(data)?;
+				chunks.correct_position(data)?;
+
+				match Instrument::parse(&content) {
+					Ok(inst) => instrument = Some(inst),
+					Err(e) => {
+						parse_mode_choice!(
+							parse_mode,
+							STRICT: return Err(e),
+							DEFAULT: log::warn!("Unable to read \"INST\" chunk, discarding")
+						);
+					},
+				}
+			},
+			b"APPL" if parse_options.read_tags => {
+				let content = chunks.content(data)?;
+				chunks.correct_position(data)?;
+
+				match ApplicationChunk::parse(content) {
+					Ok(appl) => {
+						if let Some(application_chunks) = application_chunks.as_mut() {
+							application_chunks.push(appl);
+						}
+					},
+					Err(e) => {
+						parse_mode_choice!(
+							parse_mode,
+							STRICT: return Err(e),
+							DEFAULT: {
+								log::warn!("Unable to read \"APPL\" chunk, discarding all application chunks");
+								application_chunks = None;
+							}
+						);
+					},
+				}
+			},
+			b"_PMX" if xmp_tag.is_none() && parse_options.read_tags => {
+				let content = chunks.content(data)?;
+				chunks.correct_position(data)?;
+
+				xmp_tag = read_xmp(&content, parse_mode)?;
+			},
 			_ => chunks.skip(data)?,
 		}
 	}
 
+	if parse_options.read_tags {
+		let known_markers = marker_list.as_deref().unwrap_or_default();
+		for comment in &comments {
+			if comment.marker_id != 0 && !known_markers.iter().any(|m| m.id == comment.marker_id) {
+				log::warn!(
+					"Comment is linked to a marker that doesn't exist (ID: {})",
+					comment.marker_id
+				);
+			}
+		}
+	}
+
 	if !annotations.is_empty() {
 		text_chunks.annotations = Some(annotations);
 	}
@@ -178,5 +264,11 @@ where
 			_ => Some(text_chunks),
 		},
 		id3v2_tag,
+		original_markers: marker_list.clone(),
+		markers: marker_list,
+		original_application_chunks: application_chunks.clone(),
+		application_chunks,
+		instrument,
+		xmp_tag,
 	})
 }
@@ -25,7 +25,7 @@ pub struct Comment {
 	///
 	/// This is for storing descriptions of markers as a comment.
 	/// An id of 0 means the comment is not linked to a marker,
-	/// otherwise it should be the ID of a marker.
+	/// otherwise it should be the ID of a marker. See [`AiffFile::comment_marker`](super::AiffFile::comment_marker).
 	pub marker_id: u16,
 	/// The comment itself
 	///
@@ -480,8 +480,11 @@ where
 			file_bytes.splice(first.0..first.1, text_chunks);
 		}
 
-		let total_size = ((file_bytes.len() - 8) as u32).to_be_bytes();
-		file_bytes.splice(4..8, total_size.to_vec());
+		let Ok(total_size) = u32::try_from(file_bytes.len() - 8) else {
+			err!(TooMuchData);
+		};
+
+		file_bytes.splice(4..8, total_size.to_be_bytes());
 
 		file.rewind()?;
 		file.truncate(0)?;
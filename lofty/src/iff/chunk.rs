@@ -1,11 +1,13 @@
 use crate::config::ParseOptions;
-use crate::error::Result;
+use crate::error::{LoftyError, Result};
 use crate::id3::v2::tag::Id3v2Tag;
 use crate::macros::{err, try_vec};
+use crate::util::io::{splice_file, spliced_len, FileLike, Length, Truncate};
 use crate::util::text::utf8_decode;
 
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::marker::PhantomData;
+use std::ops::Range;
 
 use byteorder::{ByteOrder, ReadBytesExt};
 
@@ -14,8 +16,10 @@ where
 	B: ByteOrder,
 {
 	pub fourcc: [u8; 4],
-	pub size: u32,
+	pub size: u64,
 	remaining_size: u64,
+	wave64: bool,
+	large_sizes: Vec<([u8; 4], u64)>,
 	_phantom: PhantomData<B>,
 }
 
@@ -26,16 +30,65 @@ impl<B: ByteOrder> Chunks<B> {
 			fourcc: [0; 4],
 			size: 0,
 			remaining_size: file_size,
+			wave64: false,
+			large_sizes: Vec::new(),
 			_phantom: PhantomData,
 		}
 	}
 
+	/// Chunks identified by GUIDs, with 64-bit sizes that include the chunk header
+	///
+	/// Known GUIDs are mapped back to their RIFF equivalent, anything else will have a FOURCC of zeros.
+	#[must_use]
+	pub const fn new_wave64(file_size: u64) -> Self {
+		let mut chunks = Self::new(file_size);
+		chunks.wave64 = true;
+		chunks
+	}
+
+	/// Chunks with a size of `0xFFFFFFFF` will use the size from this table (RF64 `ds64` chunk)
+	pub fn set_large_sizes(&mut self, large_sizes: Vec<([u8; 4], u64)>) {
+		self.large_sizes = large_sizes;
+	}
+
 	pub fn next<R>(&mut self, data: &mut R) -> Result<()>
 	where
 		R: Read,
 	{
+		// Nothing left to read, which ends the loops over the chunks
+		if self.remaining_size == 0 {
+			err!(SizeMismatch);
+		}
+
+		if self.wave64 {
+			let mut guid = [0; 16];
+			data.read_exact(&mut guid)?;
+
+			let size = data.read_u64::<B>()?;
+			if size < WAVE64_CHUNK_HEADER_SIZE {
+				err!(SizeMismatch);
+			}
+
+			self.fourcc = wave64_guid_to_fourcc(guid);
+			self.size = size - WAVE64_CHUNK_HEADER_SIZE;
+			self.remaining_size = self.remaining_size.saturating_sub(WAVE64_CHUNK_HEADER_SIZE);
+
+			return Ok(());
+		}
+
 		data.read_exact(&mut self.fourcc)?;
-		self.size = data.read_u32::<B>()?;
+		let size = data.read_u32::<B>()?;
+
+		self.size = u64::from(size);
+		if size == u32::MAX {
+			if let Some((_, large_size)) = self
+				.large_sizes
+				.iter()
+				.find(|(fourcc, _)| *fourcc == self.fourcc)
+			{
+				self.size = *large_size;
+			}
+		}
 
 		self.remaining_size = self.remaining_size.saturating_sub(8);
 
@@ -73,7 +126,7 @@ impl<B: ByteOrder> Chunks<B> {
 	where
 		R: Read,
 	{
-		self.read(data, u64::from(self.size))
+		self.read(data, self.size)
 	}
 
 	fn read<R>(&mut self, data: &mut R, size: u64) -> Result<Vec<u8>>
@@ -119,10 +172,20 @@ impl<B: ByteOrder> Chunks<B> {
 	where
 		R: Read + Seek,
 	{
-		data.seek(SeekFrom::Current(i64::from(self.size)))?;
+		// A chunk can't extend past the end of the file, and skipping it would otherwise be able to
+		// seek backwards
+		if self.size > self.remaining_size {
+			err!(SizeMismatch);
+		}
+
+		let Ok(size) = i64::try_from(self.size) else {
+			err!(SizeMismatch);
+		};
+
+		data.seek(SeekFrom::Current(size))?;
 		self.correct_position(data)?;
 
-		self.remaining_size = self.remaining_size.saturating_sub(u64::from(self.size));
+		self.remaining_size = self.remaining_size.saturating_sub(self.size);
 
 		Ok(())
 	}
@@ -134,11 +197,201 @@ impl<B: ByteOrder> Chunks<B> {
 		// Chunks are expected to start on even boundaries, and are padded
 		// with a 0 if necessary. This is NOT the null terminator of the value,
 		// and it is NOT included in the chunk's size
-		if self.size % 2 != 0 {
-			data.seek(SeekFrom::Current(1))?;
-			self.remaining_size = self.remaining_size.saturating_sub(1);
+		let padding = self.padding();
+		if padding > 0 {
+			data.seek(SeekFrom::Current(padding as i64))?;
+			self.remaining_size = self.remaining_size.saturating_sub(padding);
 		}
 
 		Ok(())
 	}
+
+	/// The number of padding bytes following the current chunk
+	pub fn padding(&self) -> u64 {
+		// Wave64 chunks are aligned to 8 bytes
+		if self.wave64 {
+			return (8 - self.size % 8) % 8;
+		}
+
+		self.size % 2
+	}
+}
+
+/// The parts of writing chunks that differ between formats, see [`write_chunks`]
+pub(crate) trait ChunkContainer {
+	type Endianness: ByteOrder;
+
+	/// The chunk that new chunks are inserted before, this is the audio data
+	const INSERT_BEFORE: [u8; 4];
+
+	fn chunks(&self, file_size: u64) -> Chunks<Self::Endianness>;
+
+	/// Create a chunk, including its header and padding
+	fn create_chunk(&self, fourcc: [u8; 4], content: &[u8]) -> Result<Vec<u8>>;
+
+	/// Update the file size stored in the header
+	///
+	/// This only touches the header, so it can be done before the rest of the file is modified.
+	fn update_file_size<W>(&self, file: &mut W, len: u64) -> Result<()>
+	where
+		W: Write + Seek;
+}
+
+/// A chunk to be written with [`write_chunks`]
+pub(crate) struct ChunkUpdate {
+	pub(crate) fourcc: [u8; 4],
+	/// The list type, for `LIST` chunks
+	///
+	/// This is written before the content.
+	pub(crate) list_type: Option<[u8; 4]>,
+	/// The content of each chunk to write, or nothing to remove them
+	pub(crate) contents: Vec<Vec<u8>>,
+}
+
+impl ChunkUpdate {
+	pub(crate) fn new(fourcc: [u8; 4], contents: impl IntoIterator<Item = Vec<u8>>) -> Self {
+		Self {
+			fourcc,
+			list_type: None,
+			contents: contents.into_iter().collect(),
+		}
+	}
+
+	pub(crate) fn list(list_type: [u8; 4], contents: impl IntoIterator<Item = Vec<u8>>) -> Self {
+		Self {
+			fourcc: *b"LIST",
+			list_type: Some(list_type),
+			contents: contents.into_iter().collect(),
+		}
+	}
+}
+
+/// Replace, insert, or remove chunks, keeping the rest in their original position
+///
+/// The reader is expected to be at the first chunk. Every existing chunk matching an update is
+/// replaced, with the new chunks written where the first one was. Chunks that don't exist yet are
+/// inserted before [`ChunkContainer::INSERT_BEFORE`].
+pub(crate) fn write_chunks<F, C>(file: &mut F, container: &C, updates: &[ChunkUpdate]) -> Result<()>
+where
+	F: FileLike,
+	LoftyError: From<<F as Truncate>::Error>,
+	LoftyError: From<<F as Length>::Error>,
+	C: ChunkContainer,
+{
+	let file_len = file.len()?;
+
+	let mut existing: Vec<Vec<Range<u64>>> = vec![Vec::new(); updates.len()];
+	let mut insert_pos = None;
+
+	let mut chunks = container.chunks(file_len);
+
+	let mut start = file.stream_position()?;
+	while chunks.next(file).is_ok() {
+		let fourcc = chunks.fourcc;
+
+		let mut list_type = None;
+		if &fourcc == b"LIST" && chunks.size >= 4 {
+			let mut ty = [0; 4];
+			file.read_exact(&mut ty)?;
+			file.seek(SeekFrom::Current(-4))?;
+
+			list_type = Some(ty);
+		}
+
+		chunks.skip(file)?;
+
+		// The file may have been truncated
+		let end = file.stream_position()?.min(file_len);
+
+		if fourcc == C::INSERT_BEFORE && insert_pos.is_none() {
+			insert_pos = Some(start);
+		}
+
+		if let Some(idx) = updates
+			.iter()
+			.position(|u| u.fourcc == fourcc && u.list_type == list_type)
+		{
+			existing[idx].push(start..end);
+		}
+
+		start = end;
+	}
+
+	let insert_pos = insert_pos.unwrap_or(file_len);
+
+	let mut edits = Vec::with_capacity(updates.len());
+	let mut inserted = Vec::new();
+	for (update, mut ranges) in updates.iter().zip(existing) {
+		let mut new_chunks = Vec::new();
+		for content in &update.contents {
+			match update.list_type {
+				Some(list_type) => {
+					let mut list = Vec::with_capacity(content.len() + 4);
+					list.extend(list_type);
+					list.extend(content);
+
+					new_chunks.extend(container.create_chunk(update.fourcc, &list)?);
+				},
+				None => new_chunks.extend(container.create_chunk(update.fourcc, content)?),
+			}
+		}
+
+		if ranges.is_empty() {
+			inserted.extend(new_chunks);
+			continue;
+		}
+
+		let first = ranges.remove(0);
+		edits.push((first, new_chunks));
+		edits.extend(ranges.into_iter().map(|range| (range, Vec::new())));
+	}
+
+	if !inserted.is_empty() {
+		edits.push((insert_pos..insert_pos, inserted));
+	}
+
+	// Done first, so a file that would outgrow its size field is left untouched
+	container.update_file_size(file, spliced_len(file_len, &edits))?;
+	splice_file(file, edits)?;
+
+	Ok(())
+}
+
+pub(crate) const WAVE64_CHUNK_HEADER_SIZE: u64 = 24;
+
+// Every RIFF chunk with a FOURCC has a matching Wave64 GUID of the form
+// `XXXXXXXX-ACF3-11D3-8CD1-00C04F8EDB8A`, with the FOURCC in the first 4 bytes
+const WAVE64_GUID_SUFFIX: [u8; 12] = [
+	0xF3, 0xAC, 0xD3, 0x11, 0x8C, 0xD1, 0x00, 0xC0, 0x4F, 0x8E, 0xDB, 0x8A,
+];
+
+// The exceptions are the container GUIDs, which are derived from `riff` and `list` instead
+pub(crate) const WAVE64_RIFF_GUID: [u8; 16] = [
+	b'r', b'i', b'f', b'f', 0x2E, 0x91, 0xCF, 0x11, 0xA5, 0xD6, 0x28, 0xDB, 0x04, 0xC1, 0x00, 0x00,
+];
+const WAVE64_LIST_GUID: [u8; 16] = [
+	b'l', b'i', b's', b't', 0x2F, 0x91, 0xCF, 0x11, 0xA5, 0xD6, 0x28, 0xDB, 0x04, 0xC1, 0x00, 0x00,
+];
+
+fn wave64_guid_to_fourcc(guid: [u8; 16]) -> [u8; 4] {
+	if guid == WAVE64_LIST_GUID {
+		return *b"LIST";
+	}
+
+	if guid[4..] == WAVE64_GUID_SUFFIX {
+		return [guid[0], guid[1], guid[2], guid[3]];
+	}
+
+	[0; 4]
+}
+
+pub(crate) fn wave64_fourcc_to_guid(fourcc: [u8; 4]) -> [u8; 16] {
+	if &fourcc == b"LIST" {
+		return WAVE64_LIST_GUID;
+	}
+
+	let mut guid = [0; 16];
+	guid[..4].copy_from_slice(&fourcc);
+	guid[4..].copy_from_slice(&WAVE64_GUID_SUFFIX);
+	guid
 }
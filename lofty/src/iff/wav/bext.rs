@@ -0,0 +1,240 @@
+use crate::error::Result;
+use crate::macros::{decode_err, err};
+use crate::util::text::{encode_text, latin1_decode, TextEncoding};
+
+use std::io::Read;
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+
+const DESCRIPTION_SIZE: usize = 256;
+const ORIGINATOR_SIZE: usize = 32;
+const ORIGINATOR_REFERENCE_SIZE: usize = 32;
+const ORIGINATION_DATE_SIZE: usize = 10;
+const ORIGINATION_TIME_SIZE: usize = 8;
+const UMID_SIZE: usize = 64;
+const RESERVED_SIZE: usize = 180;
+
+// Everything up to the coding history
+const BEXT_MIN_SIZE: usize = 602;
+
+// Used by version 2 for loudness values that were not calculated
+const LOUDNESS_UNSET: i16 = 0x7FFF;
+
+/// The contents of a Broadcast Wave Format "bext" (Broadcast Audio Extension) chunk
+///
+/// See [EBU Tech 3285](https://tech.ebu.ch/publications/tech3285) for the meaning of each field.
+///
+/// The text fields are ASCII, and limited to the sizes noted below. Anything longer will fail to write.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BroadcastExtension {
+	/// A description of the sound sequence (256 characters)
+	pub description: String,
+	/// The name of the originator (32 characters)
+	pub originator: String,
+	/// The originator's reference (32 characters)
+	pub originator_reference: String,
+	/// The date of creation, formatted as `yyyy:mm:dd` (10 characters)
+	pub origination_date: String,
+	/// The time of creation, formatted as `hh:mm:ss` (8 characters)
+	pub origination_time: String,
+	/// The first sample count since midnight
+	pub time_reference: u64,
+	/// The version of the chunk
+	pub version: u16,
+	/// The SMPTE UMID, the last 32 bytes are zeroed if it is a basic UMID
+	pub umid: [u8; 64],
+	/// The integrated loudness in LUFS, multiplied by 100 (version 2)
+	pub loudness_value: Option<i16>,
+	/// The loudness range in LU, multiplied by 100 (version 2)
+	pub loudness_range: Option<i16>,
+	/// The maximum true peak level in dBTP, multiplied by 100 (version 2)
+	pub max_true_peak_level: Option<i16>,
+	/// The highest momentary loudness in LUFS, multiplied by 100 (version 2)
+	pub max_momentary_loudness: Option<i16>,
+	/// The highest short-term loudness in LUFS, multiplied by 100 (version 2)
+	pub max_short_term_loudness: Option<i16>,
+	/// The coding history, with each line terminated by CR/LF
+	pub coding_history: String,
+}
+
+impl Default for BroadcastExtension {
+	fn default() -> Self {
+		Self {
+			description: String::new(),
+			originator: String::new(),
+			originator_reference: String::new(),
+			origination_date: String::new(),
+			origination_time: String::new(),
+			time_reference: 0,
+			version: 2,
+			umid: [0; UMID_SIZE],
+			loudness_value: None,
+			loudness_range: None,
+			max_true_peak_level: None,
+			max_momentary_loudness: None,
+			max_short_term_loudness: None,
+			coding_history: String::new(),
+		}
+	}
+}
+
+impl BroadcastExtension {
+	pub(super) fn parse(mut content: &[u8]) -> Result<Self> {
+		if content.len() < BEXT_MIN_SIZE {
+			decode_err!(@BAIL Wav, "\"bext\" chunk is too small");
+		}
+
+		let reader = &mut content;
+
+		let description = read_text(reader, DESCRIPTION_SIZE)?;
+		let originator = read_text(reader, ORIGINATOR_SIZE)?;
+		let originator_reference = read_text(reader, ORIGINATOR_REFERENCE_SIZE)?;
+		let origination_date = read_text(reader, ORIGINATION_DATE_SIZE)?;
+		let origination_time = read_text(reader, ORIGINATION_TIME_SIZE)?;
+
+		let time_reference_low = reader.read_u32::<LittleEndian>()?;
+		let time_reference_high = reader.read_u32::<LittleEndian>()?;
+		let time_reference = (u64::from(time_reference_high) << 32) | u64::from(time_reference_low);
+
+		let version = reader.read_u16::<LittleEndian>()?;
+
+		let mut umid = [0; UMID_SIZE];
+		reader.read_exact(&mut umid)?;
+
+		// Version 1 and earlier have these as part of the reserved space
+		let mut loudness = [None; 5];
+		for value in &mut loudness {
+			let raw = reader.read_i16::<LittleEndian>()?;
+			if version >= 2 && raw != LOUDNESS_UNSET {
+				*value = Some(raw);
+			}
+		}
+
+		let [loudness_value, loudness_range, max_true_peak_level, max_momentary_loudness, max_short_term_loudness] =
+			loudness;
+
+		let coding_history = latin1_decode(&reader[RESERVED_SIZE..]);
+
+		Ok(Self {
+			description,
+			originator,
+			originator_reference,
+			origination_date,
+			origination_time,
+			time_reference,
+			version,
+			umid,
+			loudness_value,
+			loudness_range,
+			max_true_peak_level,
+			max_momentary_loudness,
+			max_short_term_loudness,
+			coding_history,
+		})
+	}
+
+	pub(super) fn as_bytes(&self) -> Result<Vec<u8>> {
+		let mut bytes = Vec::with_capacity(BEXT_MIN_SIZE + self.coding_history.len());
+
+		write_text(&mut bytes, &self.description, DESCRIPTION_SIZE)?;
+		write_text(&mut bytes, &self.originator, ORIGINATOR_SIZE)?;
+		write_text(
+			&mut bytes,
+			&self.originator_reference,
+			ORIGINATOR_REFERENCE_SIZE,
+		)?;
+		write_text(&mut bytes, &self.origination_date, ORIGINATION_DATE_SIZE)?;
+		write_text(&mut bytes, &self.origination_time, ORIGINATION_TIME_SIZE)?;
+
+		bytes.write_u32::<LittleEndian>(self.time_reference as u32)?;
+		bytes.write_u32::<LittleEndian>((self.time_reference >> 32) as u32)?;
+		bytes.write_u16::<LittleEndian>(self.version)?;
+		bytes.extend(self.umid);
+
+		for value in [
+			self.loudness_value,
+			self.loudness_range,
+			self.max_true_peak_level,
+			self.max_momentary_loudness,
+			self.max_short_term_loudness,
+		] {
+			let value = match value {
+				Some(value) => value,
+				None if self.version >= 2 => LOUDNESS_UNSET,
+				None => 0,
+			};
+
+			bytes.write_i16::<LittleEndian>(value)?;
+		}
+
+		bytes.resize(BEXT_MIN_SIZE, 0);
+		bytes.extend(encode_text(
+			&self.coding_history,
+			TextEncoding::Latin1,
+			false,
+		));
+
+		Ok(bytes)
+	}
+}
+
+fn read_text(reader: &mut &[u8], size: usize) -> Result<String> {
+	let mut text = [0; DESCRIPTION_SIZE];
+	reader.read_exact(&mut text[..size])?;
+
+	let len = text[..size].iter().position(|&c| c == 0).unwrap_or(size);
+	Ok(latin1_decode(&text[..len]))
+}
+
+fn write_text(bytes: &mut Vec<u8>, text: &str, size: usize) -> Result<()> {
+	let encoded = encode_text(text, TextEncoding::Latin1, false);
+	if encoded.len() > size {
+		err!(TooMuchData);
+	}
+
+	let padding = size - encoded.len();
+	bytes.extend(encoded);
+	bytes.resize(bytes.len() + padding, 0);
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::BroadcastExtension;
+
+	#[test_log::test]
+	fn bext_round_trip() {
+		let mut umid = [0; 64];
+		umid[..4].copy_from_slice(&[6, 10, 43, 52]);
+
+		let bext = BroadcastExtension {
+			description: String::from("Foo description"),
+			originator: String::from("Bar originator"),
+			origination_date: String::from("2024:01:01"),
+			origination_time: String::from("12:34:56"),
+			time_reference: u64::from(u32::MAX) + 48000,
+			umid,
+			loudness_value: Some(-2300),
+			max_true_peak_level: Some(-100),
+			coding_history: String::from("A=PCM,F=48000,W=16,M=stereo\r\n"),
+			..BroadcastExtension::default()
+		};
+
+		let bytes = bext.as_bytes().unwrap();
+		assert_eq!(bytes.len(), 602 + bext.coding_history.len());
+
+		assert_eq!(BroadcastExtension::parse(&bytes).unwrap(), bext);
+	}
+
+	#[test_log::test]
+	fn bext_text_too_long() {
+		let bext = BroadcastExtension {
+			origination_date: String::from("2024-01-01T00:00:00"),
+			..BroadcastExtension::default()
+		};
+
+		assert!(bext.as_bytes().is_err());
+	}
+}
@@ -0,0 +1,237 @@
+use crate::error::{LoftyError, Result};
+use crate::iff::chunk::{
+	self, wave64_fourcc_to_guid, ChunkContainer, ChunkUpdate, Chunks, WAVE64_CHUNK_HEADER_SIZE,
+	WAVE64_RIFF_GUID,
+};
+use crate::macros::{decode_err, err, try_vec};
+use crate::util::io::{FileLike, Length, Truncate};
+
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+
+// "RF64" + size (always 0xFFFFFFFF) + "WAVE" + "ds64" + size
+const RF64_RIFF_SIZE_OFFSET: u64 = 20;
+// Riff size (8) + data size (8) + sample count (8) + table length (4)
+const DS64_MIN_SIZE: u32 = 28;
+// Chunk ID (4) + chunk size (8)
+const DS64_TABLE_ENTRY_SIZE: u32 = 12;
+
+// GUID (16) + size (8) + GUID (16)
+const WAVE64_HEADER_SIZE: usize = 40;
+const WAVE64_RIFF_SIZE_OFFSET: u64 = 16;
+
+/// The container a WAV file is stored in
+///
+/// * RIFF: The standard container, limited to 4 GiB
+/// * RF64/BW64: RIFF with an additional `ds64` chunk, holding 64-bit sizes for any chunk
+///   with a size of `0xFFFFFFFF`
+/// * Sony Wave64: Chunks are identified by GUIDs rather than FOURCCs, and have 64-bit sizes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum RiffContainer {
+	Riff,
+	Rf64 {
+		sample_count: u64,
+		large_sizes: Vec<([u8; 4], u64)>,
+	},
+	Wave64,
+}
+
+impl RiffContainer {
+	/// Verify the file header, leaving the reader at the start of the first chunk
+	///
+	/// For RF64 files, this will also read the `ds64` chunk.
+	pub(crate) fn read<R>(data: &mut R) -> Result<Self>
+	where
+		R: Read + Seek,
+	{
+		let mut id = [0; 12];
+		data.read_exact(&mut id)?;
+
+		match &id[..4] {
+			b"RIFF" | b"RF64" | b"BW64" => {
+				if &id[8..] != b"WAVE" {
+					decode_err!(@BAIL Wav, "Found RIFF file, format is not WAVE");
+				}
+
+				if &id[..4] == b"RIFF" {
+					log::debug!("File verified to be WAV");
+					return Ok(Self::Riff);
+				}
+
+				log::debug!("File verified to be WAV (RF64)");
+				Self::read_ds64(data)
+			},
+			b"riff" => {
+				let mut header = [0; WAVE64_HEADER_SIZE];
+				header[..12].copy_from_slice(&id);
+				data.read_exact(&mut header[12..])?;
+
+				if header[..16] != WAVE64_RIFF_GUID {
+					decode_err!(@BAIL Wav, "Wave64 file has an invalid RIFF GUID");
+				}
+
+				if header[24..] != wave64_fourcc_to_guid(*b"wave") {
+					decode_err!(@BAIL Wav, "Found Wave64 file, format is not WAVE");
+				}
+
+				log::debug!("File verified to be WAV (Wave64)");
+				Ok(Self::Wave64)
+			},
+			_ => decode_err!(@BAIL Wav, "WAV file doesn't contain a RIFF chunk"),
+		}
+	}
+
+	// The `ds64` chunk:
+	//
+	// RIFF size (8)
+	// data chunk size (8)
+	// Sample count (8)
+	// Table length (4)
+	// Table (12 * table length), chunk ID and size pairs for any other large chunks
+	fn read_ds64<R>(data: &mut R) -> Result<Self>
+	where
+		R: Read + Seek,
+	{
+		let mut id = [0; 4];
+		data.read_exact(&mut id)?;
+
+		let size = data.read_u32::<LittleEndian>()?;
+		if &id != b"ds64" || size < DS64_MIN_SIZE {
+			decode_err!(@BAIL Wav, "RF64 file doesn't start with a valid \"ds64\" chunk");
+		}
+
+		let mut content = try_vec![0; size as usize];
+		data.read_exact(&mut content)?;
+
+		if size % 2 != 0 {
+			data.seek(SeekFrom::Current(1))?;
+		}
+
+		let reader = &mut &content[..];
+
+		let _riff_size = reader.read_u64::<LittleEndian>()?;
+		let data_size = reader.read_u64::<LittleEndian>()?;
+		let sample_count = reader.read_u64::<LittleEndian>()?;
+		let table_length = reader.read_u32::<LittleEndian>()?;
+
+		if table_length > (size - DS64_MIN_SIZE) / DS64_TABLE_ENTRY_SIZE {
+			decode_err!(@BAIL Wav, "\"ds64\" chunk has an invalid table length");
+		}
+
+		let mut large_sizes = Vec::with_capacity(table_length as usize + 1);
+		large_sizes.push((*b"data", data_size));
+
+		for _ in 0..table_length {
+			let mut id = [0; 4];
+			reader.read_exact(&mut id)?;
+
+			large_sizes.push((id, reader.read_u64::<LittleEndian>()?));
+		}
+
+		Ok(Self::Rf64 {
+			sample_count,
+			large_sizes,
+		})
+	}
+
+	/// The sample count from the `ds64` chunk, if this is an RF64 file
+	pub(crate) fn sample_count(&self) -> Option<u64> {
+		match self {
+			Self::Rf64 { sample_count, .. } => Some(*sample_count),
+			_ => None,
+		}
+	}
+
+	pub(crate) fn is_wave64(&self) -> bool {
+		matches!(self, Self::Wave64)
+	}
+}
+
+impl ChunkContainer for RiffContainer {
+	type Endianness = LittleEndian;
+
+	const INSERT_BEFORE: [u8; 4] = *b"data";
+
+	fn chunks(&self, file_size: u64) -> Chunks<LittleEndian> {
+		match self {
+			Self::Riff => Chunks::new(file_size),
+			Self::Rf64 { large_sizes, .. } => {
+				let mut chunks = Chunks::new(file_size);
+				chunks.set_large_sizes(large_sizes.clone());
+				chunks
+			},
+			Self::Wave64 => Chunks::new_wave64(file_size),
+		}
+	}
+
+	fn create_chunk(&self, fourcc: [u8; 4], content: &[u8]) -> Result<Vec<u8>> {
+		let mut chunk;
+		let padding;
+
+		if self.is_wave64() {
+			chunk = Vec::with_capacity(content.len() + WAVE64_CHUNK_HEADER_SIZE as usize + 7);
+			chunk.extend(wave64_fourcc_to_guid(fourcc));
+			chunk.extend((content.len() as u64 + WAVE64_CHUNK_HEADER_SIZE).to_le_bytes());
+			padding = (8 - content.len() % 8) % 8;
+		} else {
+			// Large chunks would need an entry in the `ds64` table, we have no reason to write any
+			let Ok(size) = u32::try_from(content.len()) else {
+				err!(TooMuchData);
+			};
+
+			if size == u32::MAX {
+				err!(TooMuchData);
+			}
+
+			chunk = Vec::with_capacity(content.len() + 9);
+			chunk.extend(fourcc);
+			chunk.extend(size.to_le_bytes());
+			padding = content.len() % 2;
+		}
+
+		chunk.extend(content);
+		chunk.resize(chunk.len() + padding, 0);
+
+		Ok(chunk)
+	}
+
+	fn update_file_size<W>(&self, file: &mut W, len: u64) -> Result<()>
+	where
+		W: Write + Seek,
+	{
+		match self {
+			Self::Riff => {
+				let Ok(size) = u32::try_from(len - 8) else {
+					err!(TooMuchData);
+				};
+
+				file.seek(SeekFrom::Start(4))?;
+				file.write_u32::<LittleEndian>(size)?;
+			},
+			// The size in the header is always 0xFFFFFFFF, the real size is in the `ds64` chunk
+			Self::Rf64 { .. } => {
+				file.seek(SeekFrom::Start(RF64_RIFF_SIZE_OFFSET))?;
+				file.write_u64::<LittleEndian>(len - 8)?;
+			},
+			// Wave64 includes the header in the size
+			Self::Wave64 => {
+				file.seek(SeekFrom::Start(WAVE64_RIFF_SIZE_OFFSET))?;
+				file.write_u64::<LittleEndian>(len)?;
+			},
+		}
+
+		Ok(())
+	}
+}
+
+/// Replace, insert, or remove chunks in a WAV file, see [`chunk::write_chunks`]
+pub(crate) fn write_chunks<F>(file: &mut F, updates: &[ChunkUpdate]) -> Result<()>
+where
+	F: FileLike,
+	LoftyError: From<<F as Truncate>::Error>,
+	LoftyError: From<<F as Length>::Error>,
+{
+	let container = RiffContainer::read(file)?;
+	chunk::write_chunks(file, &container, updates)
+}
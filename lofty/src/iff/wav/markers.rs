@@ -0,0 +1,713 @@
+use crate::error::Result;
+use crate::macros::{decode_err, err};
+use crate::util::text::utf8_decode;
+
+use std::io::Read;
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+
+// ID (4) + position (4) + chunk ID (4) + chunk start (4) + block start (4) + sample offset (4)
+const CUE_POINT_SIZE: usize = 24;
+// Cue point ID (4) + type (4) + start (4) + end (4) + fraction (4) + play count (4)
+const SAMPLE_LOOP_SIZE: usize = 24;
+// Everything up to the loops
+const SMPL_HEADER_SIZE: usize = 36;
+// Cue point ID (4) + sample length (4) + purpose (4) + country (2) + language (2) + dialect (2) + code page (2)
+const LTXT_HEADER_SIZE: usize = 20;
+
+/// A cue point, from the `cue ` chunk
+///
+/// A cue point marks a single position in the audio data. Any text associated with it
+/// is stored in the `LIST` chunk of type `adtl`, and is available through [`CuePoint::label`],
+/// [`CuePoint::note`], and [`CuePoint::region`].
+///
+/// A cue point with a [`CueRegion`] covers a range of samples, rather than a single position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CuePoint {
+	/// A unique identifier for the cue point
+	pub id: u32,
+	/// The sample position of the cue point in the play order
+	pub position: u32,
+	/// The ID of the chunk containing the cue point, this will almost always be `data`
+	pub chunk_id: [u8; 4],
+	/// The position of the chunk containing the cue point, only used with playlists
+	pub chunk_start: u32,
+	/// The byte offset of the block containing the cue point, only used with compressed data
+	pub block_start: u32,
+	/// The sample offset of the cue point, relative to the start of the block
+	pub sample_offset: u32,
+	/// The text of the `labl` chunk
+	pub label: Option<String>,
+	/// The text of the `note` chunk
+	pub note: Option<String>,
+	/// The contents of the `ltxt` chunk
+	pub region: Option<CueRegion>,
+}
+
+impl CuePoint {
+	/// Create a new [`CuePoint`] at a sample position
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::iff::wav::CuePoint;
+	///
+	/// let mut marker = CuePoint::new(1, 48000);
+	/// marker.label = Some(String::from("Chorus"));
+	///
+	/// assert_eq!(marker.position, 48000);
+	/// assert_eq!(marker.sample_offset, 48000);
+	/// ```
+	pub fn new(id: u32, sample: u32) -> Self {
+		Self {
+			id,
+			position: sample,
+			chunk_id: *b"data",
+			chunk_start: 0,
+			block_start: 0,
+			sample_offset: sample,
+			label: None,
+			note: None,
+			region: None,
+		}
+	}
+}
+
+/// The contents of an `ltxt` chunk, which turns a [`CuePoint`] into a region
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CueRegion {
+	/// The number of samples in the region, starting at the cue point
+	pub sample_length: u32,
+	/// The purpose of the region (Ex. `rgn `)
+	pub purpose: [u8; 4],
+	/// The country code
+	pub country: u16,
+	/// The language code
+	pub language: u16,
+	/// The dialect code
+	pub dialect: u16,
+	/// The code page of the text
+	pub code_page: u16,
+	/// A description of the region
+	pub text: Option<String>,
+}
+
+impl CueRegion {
+	/// Create a new [`CueRegion`] with a purpose of `rgn `
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::iff::wav::CueRegion;
+	///
+	/// let region = CueRegion::new(1024);
+	/// assert_eq!(&region.purpose, b"rgn ");
+	/// ```
+	pub fn new(sample_length: u32) -> Self {
+		Self {
+			sample_length,
+			purpose: *b"rgn ",
+			country: 0,
+			language: 0,
+			dialect: 0,
+			code_page: 0,
+			text: None,
+		}
+	}
+}
+
+/// The type of a [`SampleLoop`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SampleLoopType {
+	/// Play forward, from the start to the end
+	Forward,
+	/// Alternate between playing forward and backward
+	Alternating,
+	/// Play backward, from the end to the start
+	Backward,
+	/// An unknown or manufacturer specific loop type
+	Other(u32),
+}
+
+impl SampleLoopType {
+	/// Get a `SampleLoopType` from a `u32`
+	pub fn from_u32(value: u32) -> Self {
+		match value {
+			0 => Self::Forward,
+			1 => Self::Alternating,
+			2 => Self::Backward,
+			_ => Self::Other(value),
+		}
+	}
+
+	/// Get a `u32` from a `SampleLoopType`
+	pub fn as_u32(&self) -> u32 {
+		match self {
+			Self::Forward => 0,
+			Self::Alternating => 1,
+			Self::Backward => 2,
+			Self::Other(value) => *value,
+		}
+	}
+}
+
+/// A single loop in a [`SamplerInfo`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SampleLoop {
+	/// The ID of a [`CuePoint`] describing the loop, or 0
+	pub cue_point_id: u32,
+	/// The type of loop
+	pub loop_type: SampleLoopType,
+	/// The sample the loop starts at
+	pub start: u32,
+	/// The sample the loop ends at, this sample is also played
+	pub end: u32,
+	/// A fraction of a sample to fine tune the end of the loop, 0 means no fine tuning
+	pub fraction: u32,
+	/// The number of times to play the loop, 0 means infinitely
+	pub play_count: u32,
+}
+
+impl SampleLoop {
+	/// Create a new infinite, forward [`SampleLoop`]
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::iff::wav::{SampleLoop, SampleLoopType};
+	///
+	/// let sample_loop = SampleLoop::new(0, 1024, 2047);
+	/// assert_eq!(sample_loop.loop_type, SampleLoopType::Forward);
+	/// assert_eq!(sample_loop.play_count, 0);
+	/// ```
+	pub fn new(cue_point_id: u32, start: u32, end: u32) -> Self {
+		Self {
+			cue_point_id,
+			loop_type: SampleLoopType::Forward,
+			start,
+			end,
+			fraction: 0,
+			play_count: 0,
+		}
+	}
+}
+
+/// The contents of a `smpl` chunk
+///
+/// This describes how a sampler should play the audio data, along with any loops.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SamplerInfo {
+	/// The MIDI Manufacturers Association manufacturer code, or 0
+	pub manufacturer: u32,
+	/// The manufacturer's product code, or 0
+	pub product: u32,
+	/// The duration of a sample in nanoseconds
+	pub sample_period: u32,
+	/// The MIDI note that will play the audio at its original pitch (Ex. 60 for middle C)
+	pub midi_unity_note: u32,
+	/// A fraction of a semitone to fine tune the unity note
+	pub midi_pitch_fraction: u32,
+	/// The SMPTE format (0, 24, 25, 29, or 30)
+	pub smpte_format: u32,
+	/// The SMPTE offset of the first sample, packed as hours, minutes, seconds, and frames
+	pub smpte_offset: u32,
+	/// The loops
+	pub loops: Vec<SampleLoop>,
+	/// Any manufacturer specific data following the loops
+	pub sampler_data: Vec<u8>,
+}
+
+impl Default for SamplerInfo {
+	fn default() -> Self {
+		Self {
+			manufacturer: 0,
+			product: 0,
+			sample_period: 0,
+			midi_unity_note: 60,
+			midi_pitch_fraction: 0,
+			smpte_format: 0,
+			smpte_offset: 0,
+			loops: Vec::new(),
+			sampler_data: Vec::new(),
+		}
+	}
+}
+
+impl SamplerInfo {
+	pub(super) fn parse(mut content: &[u8]) -> Result<Self> {
+		if content.len() < SMPL_HEADER_SIZE {
+			decode_err!(@BAIL Wav, "\"smpl\" chunk is too small");
+		}
+
+		let reader = &mut content;
+
+		let manufacturer = reader.read_u32::<LittleEndian>()?;
+		let product = reader.read_u32::<LittleEndian>()?;
+		let sample_period = reader.read_u32::<LittleEndian>()?;
+		let midi_unity_note = reader.read_u32::<LittleEndian>()?;
+		let midi_pitch_fraction = reader.read_u32::<LittleEndian>()?;
+		let smpte_format = reader.read_u32::<LittleEndian>()?;
+		let smpte_offset = reader.read_u32::<LittleEndian>()?;
+		let loop_count = reader.read_u32::<LittleEndian>()? as usize;
+		let sampler_data_size = reader.read_u32::<LittleEndian>()? as usize;
+
+		if loop_count > reader.len() / SAMPLE_LOOP_SIZE {
+			decode_err!(@BAIL Wav, "\"smpl\" chunk has an invalid loop count");
+		}
+
+		let mut loops = Vec::with_capacity(loop_count);
+		for _ in 0..loop_count {
+			loops.push(SampleLoop {
+				cue_point_id: reader.read_u32::<LittleEndian>()?,
+				loop_type: SampleLoopType::from_u32(reader.read_u32::<LittleEndian>()?),
+				start: reader.read_u32::<LittleEndian>()?,
+				end: reader.read_u32::<LittleEndian>()?,
+				fraction: reader.read_u32::<LittleEndian>()?,
+				play_count: reader.read_u32::<LittleEndian>()?,
+			});
+		}
+
+		if sampler_data_size > reader.len() {
+			decode_err!(@BAIL Wav, "\"smpl\" chunk has an invalid sampler data size");
+		}
+
+		Ok(Self {
+			manufacturer,
+			product,
+			sample_period,
+			midi_unity_note,
+			midi_pitch_fraction,
+			smpte_format,
+			smpte_offset,
+			loops,
+			sampler_data: reader[..sampler_data_size].to_vec(),
+		})
+	}
+
+	pub(super) fn as_bytes(&self) -> Result<Vec<u8>> {
+		let Ok(loop_count) = u32::try_from(self.loops.len()) else {
+			err!(TooMuchData);
+		};
+
+		let Ok(sampler_data_size) = u32::try_from(self.sampler_data.len()) else {
+			err!(TooMuchData);
+		};
+
+		let mut bytes = Vec::with_capacity(
+			SMPL_HEADER_SIZE + self.loops.len() * SAMPLE_LOOP_SIZE + self.sampler_data.len(),
+		);
+
+		for value in [
+			self.manufacturer,
+			self.product,
+			self.sample_period,
+			self.midi_unity_note,
+			self.midi_pitch_fraction,
+			self.smpte_format,
+			self.smpte_offset,
+			loop_count,
+			sampler_data_size,
+		] {
+			bytes.write_u32::<LittleEndian>(value)?;
+		}
+
+		for sample_loop in &self.loops {
+			for value in [
+				sample_loop.cue_point_id,
+				sample_loop.loop_type.as_u32(),
+				sample_loop.start,
+				sample_loop.end,
+				sample_loop.fraction,
+				sample_loop.play_count,
+			] {
+				bytes.write_u32::<LittleEndian>(value)?;
+			}
+		}
+
+		bytes.extend(&self.sampler_data);
+
+		Ok(bytes)
+	}
+}
+
+pub(super) fn parse_cue_chunk(mut content: &[u8]) -> Result<Vec<CuePoint>> {
+	let reader = &mut content;
+
+	let count = reader.read_u32::<LittleEndian>()? as usize;
+	if count > reader.len() / CUE_POINT_SIZE {
+		decode_err!(@BAIL Wav, "\"cue \" chunk has an invalid cue point count");
+	}
+
+	let mut cue_points = Vec::with_capacity(count);
+	for _ in 0..count {
+		let id = reader.read_u32::<LittleEndian>()?;
+		let position = reader.read_u32::<LittleEndian>()?;
+
+		let mut chunk_id = [0; 4];
+		reader.read_exact(&mut chunk_id)?;
+
+		cue_points.push(CuePoint {
+			id,
+			position,
+			chunk_id,
+			chunk_start: reader.read_u32::<LittleEndian>()?,
+			block_start: reader.read_u32::<LittleEndian>()?,
+			sample_offset: reader.read_u32::<LittleEndian>()?,
+			label: None,
+			note: None,
+			region: None,
+		});
+	}
+
+	Ok(cue_points)
+}
+
+/// The contents of a `LIST` chunk of type `adtl`
+///
+/// These are kept separate until the end of the file, since the `cue ` chunk may come after the list.
+#[derive(Default)]
+pub(super) struct AssociatedData {
+	labels: Vec<(u32, String)>,
+	notes: Vec<(u32, String)>,
+	regions: Vec<(u32, CueRegion)>,
+	// Sub-chunks that are unknown or couldn't be read, including their header and padding
+	unknown: Vec<Vec<u8>>,
+}
+
+impl AssociatedData {
+	/// Parse the content of the list, not including the list type
+	pub(super) fn parse(mut content: &[u8]) -> Result<Self> {
+		let mut associated_data = Self::default();
+
+		while content.len() >= 8 {
+			let sub_chunk_start = content;
+
+			let mut fourcc = [0; 4];
+			content.read_exact(&mut fourcc)?;
+
+			let size = content.read_u32::<LittleEndian>()? as usize;
+			if size > content.len() || size < 4 {
+				decode_err!(@BAIL Wav, "Invalid \"adtl\" sub-chunk size");
+			}
+
+			let (sub_chunk, remaining) = content.split_at(size);
+			content = remaining;
+
+			// Sub-chunks are padded to even sizes, same as normal chunks
+			let mut raw = sub_chunk_start[..8 + size].to_vec();
+			if size % 2 != 0 {
+				raw.push(0);
+
+				if !content.is_empty() {
+					content = &content[1..];
+				}
+			}
+
+			match associated_data.parse_sub_chunk(fourcc, sub_chunk) {
+				Ok(true) => {},
+				Ok(false) => {
+					log::debug!("Keeping unknown \"adtl\" sub-chunk: {:?}", fourcc);
+					associated_data.unknown.push(raw);
+				},
+				Err(e) => {
+					log::warn!(
+						"Unable to read \"adtl\" sub-chunk {:?}, keeping it as-is: {e}",
+						fourcc
+					);
+					associated_data.unknown.push(raw);
+				},
+			}
+		}
+
+		Ok(associated_data)
+	}
+
+	/// Returns `false` if the sub-chunk is unknown
+	fn parse_sub_chunk(&mut self, fourcc: [u8; 4], mut sub_chunk: &[u8]) -> Result<bool> {
+		let id = sub_chunk.read_u32::<LittleEndian>()?;
+		match &fourcc {
+			b"labl" => self.labels.push((id, read_text(sub_chunk)?)),
+			b"note" => self.notes.push((id, read_text(sub_chunk)?)),
+			b"ltxt" => self.regions.push((id, parse_region(sub_chunk)?)),
+			_ => return Ok(false),
+		}
+
+		Ok(true)
+	}
+
+	/// Attach the text to the cue points with matching IDs
+	///
+	/// This returns the sub-chunks that couldn't be attached to a cue point, to be written back as-is.
+	pub(super) fn apply(self, cue_points: &mut [CuePoint]) -> Result<Vec<Vec<u8>>> {
+		let mut unattached = self.unknown;
+
+		for (id, label) in self.labels {
+			match cue_points.iter_mut().find(|c| c.id == id) {
+				Some(cue_point) => cue_point.label = Some(label),
+				None => {
+					let mut raw = Vec::new();
+					write_text_chunk(&mut raw, *b"labl", id, &[], &label)?;
+					unattached.push(raw);
+				},
+			}
+		}
+
+		for (id, note) in self.notes {
+			match cue_points.iter_mut().find(|c| c.id == id) {
+				Some(cue_point) => cue_point.note = Some(note),
+				None => {
+					let mut raw = Vec::new();
+					write_text_chunk(&mut raw, *b"note", id, &[], &note)?;
+					unattached.push(raw);
+				},
+			}
+		}
+
+		for (id, region) in self.regions {
+			match cue_points.iter_mut().find(|c| c.id == id) {
+				Some(cue_point) => cue_point.region = Some(region),
+				None => {
+					let mut raw = Vec::new();
+					write_region_chunk(&mut raw, id, &region)?;
+					unattached.push(raw);
+				},
+			}
+		}
+
+		Ok(unattached)
+	}
+}
+
+fn parse_region(mut sub_chunk: &[u8]) -> Result<CueRegion> {
+	// The cue point ID was already read
+	if sub_chunk.len() < LTXT_HEADER_SIZE - 4 {
+		decode_err!(@BAIL Wav, "\"ltxt\" sub-chunk is too small");
+	}
+
+	let sample_length = sub_chunk.read_u32::<LittleEndian>()?;
+
+	let mut purpose = [0; 4];
+	sub_chunk.read_exact(&mut purpose)?;
+
+	let country = sub_chunk.read_u16::<LittleEndian>()?;
+	let language = sub_chunk.read_u16::<LittleEndian>()?;
+	let dialect = sub_chunk.read_u16::<LittleEndian>()?;
+	let code_page = sub_chunk.read_u16::<LittleEndian>()?;
+
+	let text = read_text(sub_chunk)?;
+
+	Ok(CueRegion {
+		sample_length,
+		purpose,
+		country,
+		language,
+		dialect,
+		code_page,
+		text: (!text.is_empty()).then_some(text),
+	})
+}
+
+fn read_text(content: &[u8]) -> Result<String> {
+	let len = content
+		.iter()
+		.position(|&c| c == 0)
+		.unwrap_or(content.len());
+	utf8_decode(content[..len].to_vec())
+}
+
+pub(super) fn create_cue_chunk(cue_points: &[CuePoint]) -> Result<Vec<u8>> {
+	let Ok(count) = u32::try_from(cue_points.len()) else {
+		err!(TooMuchData);
+	};
+
+	let mut bytes = Vec::with_capacity(4 + cue_points.len() * CUE_POINT_SIZE);
+	bytes.write_u32::<LittleEndian>(count)?;
+
+	for cue_point in cue_points {
+		bytes.write_u32::<LittleEndian>(cue_point.id)?;
+		bytes.write_u32::<LittleEndian>(cue_point.position)?;
+		bytes.extend(cue_point.chunk_id);
+		bytes.write_u32::<LittleEndian>(cue_point.chunk_start)?;
+		bytes.write_u32::<LittleEndian>(cue_point.block_start)?;
+		bytes.write_u32::<LittleEndian>(cue_point.sample_offset)?;
+	}
+
+	Ok(bytes)
+}
+
+/// Create the content of the `adtl` list, not including the list type
+///
+/// Any `unattached` sub-chunks are written as-is, unless the cue point they belong to now has
+/// its own value for them. This will return `None` if there is nothing to write.
+pub(super) fn create_adtl_list(
+	cue_points: &[CuePoint],
+	unattached: &[Vec<u8>],
+) -> Result<Option<Vec<u8>>> {
+	let mut bytes = Vec::new();
+
+	for cue_point in cue_points {
+		if let Some(ref label) = cue_point.label {
+			write_text_chunk(&mut bytes, *b"labl", cue_point.id, &[], label)?;
+		}
+
+		if let Some(ref note) = cue_point.note {
+			write_text_chunk(&mut bytes, *b"note", cue_point.id, &[], note)?;
+		}
+
+		if let Some(ref region) = cue_point.region {
+			write_region_chunk(&mut bytes, cue_point.id, region)?;
+		}
+	}
+
+	for raw in unattached {
+		let id = u32::from_le_bytes([raw[8], raw[9], raw[10], raw[11]]);
+		let replaced = cue_points.iter().any(|c| {
+			c.id == id
+				&& match &raw[..4] {
+					b"labl" => c.label.is_some(),
+					b"note" => c.note.is_some(),
+					b"ltxt" => c.region.is_some(),
+					_ => false,
+				}
+		});
+
+		if !replaced {
+			bytes.extend(raw);
+		}
+	}
+
+	Ok((!bytes.is_empty()).then_some(bytes))
+}
+
+fn write_region_chunk(bytes: &mut Vec<u8>, id: u32, region: &CueRegion) -> Result<()> {
+	let mut header = Vec::with_capacity(LTXT_HEADER_SIZE - 4);
+	header.write_u32::<LittleEndian>(region.sample_length)?;
+	header.extend(region.purpose);
+	header.write_u16::<LittleEndian>(region.country)?;
+	header.write_u16::<LittleEndian>(region.language)?;
+	header.write_u16::<LittleEndian>(region.dialect)?;
+	header.write_u16::<LittleEndian>(region.code_page)?;
+
+	let text = region.text.as_deref().unwrap_or_default();
+	write_text_chunk(bytes, *b"ltxt", id, &header, text)
+}
+
+fn write_text_chunk(
+	bytes: &mut Vec<u8>,
+	fourcc: [u8; 4],
+	id: u32,
+	header: &[u8],
+	text: &str,
+) -> Result<()> {
+	// The `ltxt` text isn't null terminated if it's empty
+	let terminator = usize::from(!text.is_empty());
+
+	let Ok(size) = u32::try_from(4 + header.len() + text.len() + terminator) else {
+		err!(TooMuchData);
+	};
+
+	bytes.extend(fourcc);
+	bytes.write_u32::<LittleEndian>(size)?;
+	bytes.write_u32::<LittleEndian>(id)?;
+	bytes.extend(header);
+	bytes.extend(text.as_bytes());
+
+	if terminator == 1 {
+		bytes.push(0);
+	}
+
+	if size % 2 != 0 {
+		bytes.push(0);
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{AssociatedData, CuePoint, CueRegion, SampleLoop, SamplerInfo};
+
+	#[test_log::test]
+	fn cue_points_round_trip() {
+		let mut marker = CuePoint::new(1, 100);
+		marker.label = Some(String::from("Foo marker"));
+		marker.note = Some(String::from("Bar note"));
+
+		let mut region = CuePoint::new(2, 4800);
+		region.region = Some(CueRegion {
+			text: Some(String::from("Baz region")),
+			..CueRegion::new(2400)
+		});
+
+		let cue_points = vec![marker, region];
+
+		let cue_chunk = super::create_cue_chunk(&cue_points).unwrap();
+		let adtl = super::create_adtl_list(&cue_points, &[]).unwrap().unwrap();
+
+		let mut parsed = super::parse_cue_chunk(&cue_chunk).unwrap();
+		let unattached = AssociatedData::parse(&adtl)
+			.unwrap()
+			.apply(&mut parsed)
+			.unwrap();
+
+		assert_eq!(parsed, cue_points);
+		assert!(unattached.is_empty());
+	}
+
+	#[test_log::test]
+	fn unattached_adtl_kept() {
+		let mut adtl = Vec::new();
+		// A label for a cue point that doesn't exist
+		super::write_text_chunk(&mut adtl, *b"labl", 2, &[], "Foo orphan").unwrap();
+		// A label that isn't valid UTF-8
+		adtl.extend(b"labl\x07\x00\x00\x00\x01\x00\x00\x00\xFF\xFE\x00\x00");
+		// An unknown sub-chunk
+		adtl.extend(b"file\x06\x00\x00\x00\x01\x00\x00\x00ab");
+
+		let mut cue_points = vec![CuePoint::new(1, 100)];
+		let unattached = AssociatedData::parse(&adtl)
+			.unwrap()
+			.apply(&mut cue_points)
+			.unwrap();
+
+		assert_eq!(unattached.len(), 3);
+		assert!(cue_points[0].label.is_none());
+
+		// Everything is written back untouched
+		let written = super::create_adtl_list(&cue_points, &unattached)
+			.unwrap()
+			.unwrap();
+		assert_eq!(written.len(), adtl.len());
+		for raw in &unattached {
+			assert!(written.windows(raw.len()).any(|w| w == raw));
+		}
+
+		// Unless the cue point now has its own label
+		cue_points[0].label = Some(String::from("Bar label"));
+
+		let written = super::create_adtl_list(&cue_points, &unattached)
+			.unwrap()
+			.unwrap();
+		assert!(!written.windows(2).any(|w| w == b"\xFF\xFE"));
+		assert!(written.windows(10).any(|w| w == b"Foo orphan"));
+		assert!(written.windows(9).any(|w| w == b"Bar label"));
+	}
+
+	#[test_log::test]
+	fn sampler_info_round_trip() {
+		let sampler_info = SamplerInfo {
+			sample_period: 20833,
+			loops: vec![SampleLoop::new(0, 1024, 2047), SampleLoop::new(1, 0, 511)],
+			sampler_data: vec![1, 2, 3],
+			..SamplerInfo::default()
+		};
+
+		let bytes = sampler_info.as_bytes().unwrap();
+		assert_eq!(SamplerInfo::parse(&bytes).unwrap(), sampler_info);
+	}
+}
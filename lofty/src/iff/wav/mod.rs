@@ -1,20 +1,50 @@
 //! WAV specific items
+//!
+//! ## File notes
+//!
+//! Along with standard RIFF files, RF64/BW64 and Sony Wave64 files are supported. In Wave64 files,
+//! the RIFF INFO list is stored in a `list` chunk with the same layout as in RIFF files.
+//!
+//! Broadcast Wave `bext` chunks, along with `iXML` and `axml` chunks, are available on [`WavFile`].
+//! These are not tags, and are not included when converting to a [`TaggedFile`](crate::file::TaggedFile).
+//! The same goes for the markers, regions, and loops of the `cue `, `LIST` (`adtl`), and `smpl` chunks.
 
+mod bext;
+pub(crate) mod container;
+mod markers;
 mod properties;
 mod read;
 pub(crate) mod tag;
 
+use crate::config::WriteOptions;
+use crate::error::{LoftyError, Result};
 use crate::id3::v2::tag::Id3v2Tag;
+use crate::iff::chunk::ChunkUpdate;
+use crate::macros::impl_chapters;
+use crate::tag::TagExt;
+use crate::util::io::{FileLike, Length, Truncate};
+use crate::xmp::XmpTag;
 
 use lofty_attr::LoftyFile;
 
 // Exports
+pub use crate::iff::wav::bext::BroadcastExtension;
+pub use crate::iff::wav::markers::{CuePoint, CueRegion, SampleLoop, SampleLoopType, SamplerInfo};
 pub use crate::iff::wav::properties::{WavFormat, WavProperties};
 pub use tag::RiffInfoList;
 
 /// A WAV file
+///
+/// ## Notes
+///
+/// * The `bext`, `iXML`, `axml`, and `smpl` chunks are only written if they are set. Removing one
+///   from a `WavFile` will remove it from the file it is saved to.
+/// * The cue points are only written if they were modified. Removing all of them
+///   will remove the `cue ` chunk and `adtl` list from the file it is saved to.
+/// * Existing chunks are replaced in place, new chunks are inserted before the audio data.
 #[derive(LoftyFile)]
 #[lofty(read_fn = "read::read_from")]
+#[lofty(write_fn = "Self::write_to")]
 #[lofty(internal_write_module_do_not_use_anywhere_else)]
 pub struct WavFile {
 	/// A RIFF INFO LIST
@@ -23,6 +53,226 @@ pub struct WavFile {
 	/// An ID3v2 tag
 	#[lofty(tag_type = "Id3v2")]
 	pub(crate) id3v2_tag: Option<Id3v2Tag>,
+	pub(crate) broadcast_extension: Option<BroadcastExtension>,
+	pub(crate) ixml: Option<String>,
+	pub(crate) axml: Option<String>,
+	// `None` if the cue points were never read or set, to avoid removing them on save
+	pub(crate) cue_points: Option<Vec<CuePoint>>,
+	// The cue points as they were read, they're only written if they differ
+	pub(crate) original_cue_points: Option<Vec<CuePoint>>,
+	// `adtl` sub-chunks that don't belong to a cue point, or couldn't be read, kept as-is
+	pub(crate) unattached_adtl: Vec<Vec<u8>>,
+	pub(crate) sampler_info: Option<SamplerInfo>,
+	// Chunks that were explicitly removed, and need to be removed from the file on save
+	pub(crate) removed_chunks: Vec<[u8; 4]>,
+	/// An XMP packet, stored in a `_PMX` chunk
+	#[lofty(tag_type = "Xmp")]
+	pub(crate) xmp_tag: Option<XmpTag>,
 	/// The file's audio properties
 	pub(crate) properties: WavProperties,
 }
+
+impl_chapters!(WavFile, id3v2_tag, Id3v2Tag);
+
+impl WavFile {
+	/// Returns a reference to the [`BroadcastExtension`], if it exists
+	pub fn broadcast_extension(&self) -> Option<&BroadcastExtension> {
+		self.broadcast_extension.as_ref()
+	}
+
+	/// Returns a mutable reference to the [`BroadcastExtension`], if it exists
+	pub fn broadcast_extension_mut(&mut self) -> Option<&mut BroadcastExtension> {
+		self.broadcast_extension.as_mut()
+	}
+
+	/// Sets the [`BroadcastExtension`], returning the old one if it exists
+	pub fn set_broadcast_extension(
+		&mut self,
+		broadcast_extension: BroadcastExtension,
+	) -> Option<BroadcastExtension> {
+		self.chunk_set(*b"bext");
+		self.broadcast_extension.replace(broadcast_extension)
+	}
+
+	/// Removes the [`BroadcastExtension`], returning it if it exists
+	pub fn remove_broadcast_extension(&mut self) -> Option<BroadcastExtension> {
+		self.chunk_removed(*b"bext");
+		self.broadcast_extension.take()
+	}
+
+	/// Returns the contents of the `iXML` chunk, if it exists
+	///
+	/// The XML is not validated in any way.
+	pub fn ixml(&self) -> Option<&str> {
+		self.ixml.as_deref()
+	}
+
+	/// Sets the contents of the `iXML` chunk, returning the old contents if they exist
+	pub fn set_ixml(&mut self, ixml: String) -> Option<String> {
+		self.chunk_set(*b"iXML");
+		self.ixml.replace(ixml)
+	}
+
+	/// Removes the `iXML` chunk, returning its contents if it exists
+	pub fn remove_ixml(&mut self) -> Option<String> {
+		self.chunk_removed(*b"iXML");
+		self.ixml.take()
+	}
+
+	/// Returns the contents of the `axml` chunk, if it exists
+	///
+	/// This is typically an EBU Core or aXML document. The XML is not validated in any way.
+	pub fn axml(&self) -> Option<&str> {
+		self.axml.as_deref()
+	}
+
+	/// Sets the contents of the `axml` chunk, returning the old contents if they exist
+	pub fn set_axml(&mut self, axml: String) -> Option<String> {
+		self.chunk_set(*b"axml");
+		self.axml.replace(axml)
+	}
+
+	/// Removes the `axml` chunk, returning its contents if it exists
+	pub fn remove_axml(&mut self) -> Option<String> {
+		self.chunk_removed(*b"axml");
+		self.axml.take()
+	}
+
+	/// Returns the cue points
+	///
+	/// The labels, notes, and regions from the `adtl` list are attached to their cue points.
+	pub fn cue_points(&self) -> &[CuePoint] {
+		self.cue_points.as_deref().unwrap_or_default()
+	}
+
+	/// Returns a mutable reference to the cue points
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// use lofty::config::ParseOptions;
+	/// use lofty::file::AudioFile;
+	/// use lofty::iff::wav::{CuePoint, WavFile};
+	///
+	/// # fn main() -> lofty::error::Result<()> {
+	/// # let mut file = std::fs::File::open("foo.wav")?;
+	/// let mut wav_file = WavFile::read_from(&mut file, ParseOptions::new())?;
+	///
+	/// let mut marker = CuePoint::new(1, 48000);
+	/// marker.label = Some(String::from("Chorus"));
+	///
+	/// wav_file.cue_points_mut().push(marker);
+	/// # Ok(()) }
+	/// ```
+	pub fn cue_points_mut(&mut self) -> &mut Vec<CuePoint> {
+		self.cue_points.get_or_insert_with(Vec::new)
+	}
+
+	/// Removes all cue points, returning them
+	///
+	/// This will remove the `cue ` chunk and `adtl` list from the file it is saved to.
+	pub fn remove_cue_points(&mut self) -> Vec<CuePoint> {
+		self.unattached_adtl.clear();
+		self.cue_points.replace(Vec::new()).unwrap_or_default()
+	}
+
+	/// Returns a reference to the [`SamplerInfo`], if it exists
+	pub fn sampler_info(&self) -> Option<&SamplerInfo> {
+		self.sampler_info.as_ref()
+	}
+
+	/// Returns a mutable reference to the [`SamplerInfo`], if it exists
+	pub fn sampler_info_mut(&mut self) -> Option<&mut SamplerInfo> {
+		self.sampler_info.as_mut()
+	}
+
+	/// Sets the [`SamplerInfo`], returning the old one if it exists
+	pub fn set_sampler_info(&mut self, sampler_info: SamplerInfo) -> Option<SamplerInfo> {
+		self.chunk_set(*b"smpl");
+		self.sampler_info.replace(sampler_info)
+	}
+
+	/// Removes the [`SamplerInfo`], returning it if it exists
+	pub fn remove_sampler_info(&mut self) -> Option<SamplerInfo> {
+		self.chunk_removed(*b"smpl");
+		self.sampler_info.take()
+	}
+
+	fn chunk_set(&mut self, fourcc: [u8; 4]) {
+		self.removed_chunks.retain(|removed| *removed != fourcc);
+	}
+
+	fn chunk_removed(&mut self, fourcc: [u8; 4]) {
+		if !self.removed_chunks.contains(&fourcc) {
+			self.removed_chunks.push(fourcc);
+		}
+	}
+
+	// The extra chunks aren't tags, so they need to be written separately
+	fn write_to<F>(&self, file: &mut F, write_options: WriteOptions) -> Result<()>
+	where
+		F: FileLike,
+		LoftyError: From<<F as Truncate>::Error>,
+		LoftyError: From<<F as Length>::Error>,
+	{
+		if let Some(ref riff_info) = self.riff_info_tag {
+			file.rewind()?;
+			riff_info.save_to(file, write_options)?;
+		}
+
+		if let Some(ref id3v2) = self.id3v2_tag {
+			file.rewind()?;
+			id3v2.save_to(file, write_options)?;
+		}
+
+		if let Some(ref xmp) = self.xmp_tag {
+			file.rewind()?;
+			xmp.save_to(file, write_options)?;
+		}
+
+		let mut chunks = Vec::new();
+		if let Some(ref bext) = self.broadcast_extension {
+			chunks.push(ChunkUpdate::new(*b"bext", Some(bext.as_bytes()?)));
+		}
+
+		if let Some(ref ixml) = self.ixml {
+			chunks.push(ChunkUpdate::new(*b"iXML", Some(ixml.as_bytes().to_vec())));
+		}
+
+		if let Some(ref axml) = self.axml {
+			chunks.push(ChunkUpdate::new(*b"axml", Some(axml.as_bytes().to_vec())));
+		}
+
+		// Unchanged cue points are left alone, so nothing that couldn't be read is lost
+		if self.cue_points != self.original_cue_points {
+			if let Some(ref cue_points) = self.cue_points {
+				let cue_chunk = if cue_points.is_empty() {
+					None
+				} else {
+					Some(markers::create_cue_chunk(cue_points)?)
+				};
+
+				chunks.push(ChunkUpdate::new(*b"cue ", cue_chunk));
+				chunks.push(ChunkUpdate::list(
+					*b"adtl",
+					markers::create_adtl_list(cue_points, &self.unattached_adtl)?,
+				));
+			}
+		}
+
+		if let Some(ref sampler_info) = self.sampler_info {
+			chunks.push(ChunkUpdate::new(*b"smpl", Some(sampler_info.as_bytes()?)));
+		}
+
+		for fourcc in &self.removed_chunks {
+			chunks.push(ChunkUpdate::new(*fourcc, Vec::new()));
+		}
+
+		if chunks.is_empty() {
+			return Ok(());
+		}
+
+		file.rewind()?;
+		container::write_chunks(file, &chunks)
+	}
+}
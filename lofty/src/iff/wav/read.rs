@@ -1,69 +1,83 @@
+use super::bext::BroadcastExtension;
+use super::container::RiffContainer;
+use super::markers::{self, AssociatedData, SamplerInfo};
 use super::properties::WavProperties;
 use super::tag::RiffInfoList;
 use super::WavFile;
-use crate::config::ParseOptions;
+use crate::config::{ParseOptions, ParsingMode};
 use crate::error::Result;
 use crate::id3::v2::tag::Id3v2Tag;
-use crate::iff::chunk::Chunks;
-use crate::macros::{decode_err, err};
+use crate::iff::chunk::{ChunkContainer, Chunks};
+use crate::macros::{decode_err, err, parse_mode_choice, try_vec};
+use crate::util::text::utf8_decode;
+use crate::xmp::read::read_xmp;
 
 use std::io::{Read, Seek, SeekFrom};
 
 use byteorder::{LittleEndian, ReadBytesExt};
 
-pub(super) fn verify_wav<T>(data: &mut T) -> Result<()>
-where
-	T: Read + Seek,
-{
-	let mut id = [0; 12];
-	data.read_exact(&mut id)?;
-
-	if &id[..4] != b"RIFF" {
-		decode_err!(@BAIL Wav, "WAV file doesn't contain a RIFF chunk");
-	}
-
-	if &id[8..] != b"WAVE" {
-		decode_err!(@BAIL Wav, "Found RIFF file, format is not WAVE");
-	}
-
-	log::debug!("File verified to be WAV");
-	Ok(())
-}
-
 pub(super) fn read_from<R>(data: &mut R, parse_options: ParseOptions) -> Result<WavFile>
 where
 	R: Read + Seek,
 {
-	verify_wav(data)?;
+	let parse_mode = parse_options.parsing_mode;
+
+	let container = RiffContainer::read(data)?;
 
 	let current_pos = data.stream_position()?;
 	let file_len = data.seek(SeekFrom::End(0))?;
 
 	data.seek(SeekFrom::Start(current_pos))?;
 
-	let mut stream_len = 0_u32;
-	let mut total_samples = 0_u32;
+	let mut stream_len = 0_u64;
+	let mut total_samples = 0_u64;
 	let mut fmt = Vec::new();
 
 	let mut riff_info = RiffInfoList::default();
 	let mut id3v2_tag: Option<Id3v2Tag> = None;
 
-	let mut chunks = Chunks::<LittleEndian>::new(file_len);
+	let mut broadcast_extension = None;
+	let mut ixml = None;
+	let mut axml = None;
+
+	let mut cue_points = None;
+	let mut associated_data = None;
+	let mut sampler_info = None;
+
+	let mut xmp_tag = None;
+
+	let mut chunks = container.chunks(file_len);
 
 	while chunks.next(data).is_ok() {
 		match &chunks.fourcc {
 			b"fmt " if parse_options.read_properties => {
 				if fmt.is_empty() {
 					fmt = chunks.content(data)?;
+					chunks.correct_position(data)?;
 				} else {
 					chunks.skip(data)?;
 				}
 			},
 			b"fact" if parse_options.read_properties => {
-				if total_samples == 0 {
-					total_samples = data.read_u32::<LittleEndian>()?;
+				let content = chunks.content(data)?;
+				chunks.correct_position(data)?;
+
+				if total_samples != 0 {
+					continue;
+				}
+
+				let reader = &mut &content[..];
+				if container.is_wave64() {
+					total_samples = reader.read_u64::<LittleEndian>()?;
 				} else {
-					data.seek(SeekFrom::Current(4))?;
+					total_samples = u64::from(reader.read_u32::<LittleEndian>()?);
+
+					// RF64 stores the real sample count in the `ds64` chunk
+					if total_samples == u64::from(u32::MAX) {
+						if let Some(sample_count) = container.sample_count() {
+							total_samples = sample_count;
+						}
+					}
 				}
 			},
 			b"data" if parse_options.read_properties => {
@@ -88,18 +102,42 @@ where
 					b"INFO" if parse_options.read_tags => {
 						// TODO: We already get the current position above, just keep it up to date and use it here
 						//       to avoid the seeks.
-						let end = data.stream_position()? + u64::from(size);
+						let end = data.stream_position()? + size;
 						if end > file_len {
 							err!(SizeMismatch);
 						}
 
+						// The items are always RIFF chunks, even in Wave64 files
 						super::tag::read::parse_riff_info(
 							data,
-							&mut chunks,
+							&mut Chunks::<LittleEndian>::new(size),
 							end,
 							&mut riff_info,
-							parse_options.parsing_mode,
+							parse_mode,
 						)?;
+
+						data.seek(SeekFrom::Start(end))?;
+						chunks.correct_position(data)?;
+					},
+					b"adtl" if parse_options.read_tags && associated_data.is_none() => {
+						if data.stream_position()? + size > file_len {
+							err!(SizeMismatch);
+						}
+
+						let mut content = try_vec![0; size as usize];
+						data.read_exact(&mut content)?;
+						chunks.correct_position(data)?;
+
+						match AssociatedData::parse(&content) {
+							Ok(adtl) => associated_data = Some(adtl),
+							Err(e) => {
+								parse_mode_choice!(
+									parse_mode,
+									STRICT: return Err(e),
+									DEFAULT: log::warn!("Unable to read \"adtl\" list, discarding")
+								);
+							},
+						}
 					},
 					_ => {
 						data.seek(SeekFrom::Current(-4))?;
@@ -121,10 +159,74 @@ where
 				}
 				id3v2_tag = Some(tag);
 			},
+			b"bext" if parse_options.read_tags && broadcast_extension.is_none() => {
+				let content = chunks.content(data)?;
+				chunks.correct_position(data)?;
+
+				match BroadcastExtension::parse(&content) {
+					Ok(bext) => broadcast_extension = Some(bext),
+					Err(e) => {
+						parse_mode_choice!(
+							parse_mode,
+							STRICT: return Err(e),
+							DEFAULT: log::warn!("Unable to read \"bext\" chunk, discarding")
+						);
+					},
+				}
+			},
+			b"iXML" if parse_options.read_tags && ixml.is_none() => {
+				ixml = read_xml_chunk(data, &mut chunks, parse_mode)?;
+			},
+			b"axml" if parse_options.read_tags && axml.is_none() => {
+				axml = read_xml_chunk(data, &mut chunks, parse_mode)?;
+			},
+			b"cue " if parse_options.read_tags && cue_points.is_none() => {
+				let content = chunks.content(data)?;
+				chunks.correct_position(data)?;
+
+				match markers::parse_cue_chunk(&content) {
+					Ok(points) => cue_points = Some(points),
+					Err(e) => {
+						parse_mode_choice!(
+							parse_mode,
+							STRICT: return Err(e),
+							DEFAULT: log::warn!("Unable to read \"cue \" chunk, discarding")
+						);
+					},
+				}
+			},
+			b"smpl" if parse_options.read_tags && sampler_info.is_none() => {
+				let content = chunks.content(data)?;
+				chunks.correct_position(data)?;
+
+				match SamplerInfo::parse(&content) {
+					Ok(smpl) => sampler_info = Some(smpl),
+					Err(e) => {
+						parse_mode_choice!(
+							parse_mode,
+							STRICT: return Err(e),
+							DEFAULT: log::warn!("Unable to read \"smpl\" chunk, discarding")
+						);
+					},
+				}
+			},
+			b"_PMX" if parse_options.read_tags && xmp_tag.is_none() => {
+				let content = chunks.content(data)?;
+				chunks.correct_position(data)?;
+
+				xmp_tag = read_xmp(&content, parse_mode)?;
+			},
 			_ => chunks.skip(data)?,
 		}
 	}
 
+	// The `adtl` list can come before the `cue ` chunk, so it can only be applied once everything is read
+	let mut unattached_adtl = Vec::new();
+	if let Some(associated_data) = associated_data {
+		let points = cue_points.as_deref_mut().unwrap_or_default();
+		unattached_adtl = associated_data.apply(points)?;
+	}
+
 	let properties = if parse_options.read_properties {
 		let file_length = data.stream_position()?;
 
@@ -137,5 +239,40 @@ where
 		properties,
 		riff_info_tag: (!riff_info.items.is_empty()).then_some(riff_info),
 		id3v2_tag,
+		broadcast_extension,
+		ixml,
+		axml,
+		original_cue_points: cue_points.clone(),
+		cue_points,
+		unattached_adtl,
+		sampler_info,
+		removed_chunks: Vec::new(),
+		xmp_tag,
 	})
 }
+
+fn read_xml_chunk<R>(
+	data: &mut R,
+	chunks: &mut Chunks<LittleEndian>,
+	parse_mode: ParsingMode,
+) -> Result<Option<String>>
+where
+	R: Read + Seek,
+{
+	let content = chunks.content(data)?;
+	chunks.correct_position(data)?;
+
+	match utf8_decode(content) {
+		Ok(xml) => Ok(Some(xml)),
+		Err(e) => {
+			parse_mode_choice!(
+				parse_mode,
+				STRICT: return Err(e),
+				DEFAULT: {
+					log::warn!("Encountered an XML chunk that isn't valid UTF-8, discarding");
+					Ok(None)
+				}
+			)
+		},
+	}
+}
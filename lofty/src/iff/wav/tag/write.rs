@@ -1,14 +1,13 @@
 use super::RIFFInfoListRef;
 use crate::config::WriteOptions;
 use crate::error::{LoftyError, Result};
-use crate::iff::chunk::Chunks;
-use crate::iff::wav::read::verify_wav;
+use crate::iff::chunk::ChunkContainer;
+use crate::iff::wav::container::RiffContainer;
 use crate::macros::err;
-use crate::util::io::{FileLike, Length, Truncate};
+use crate::util::io::{splice_file, spliced_len, FileLike, Length, Truncate};
 
 use std::io::{Read, Seek, SeekFrom};
-
-use byteorder::{LittleEndian, WriteBytesExt};
+use std::ops::Range;
 
 pub(in crate::iff::wav) fn write_riff_info<'a, F, I>(
 	file: &mut F,
@@ -21,75 +20,62 @@ where
 	LoftyError: From<<F as Length>::Error>,
 	I: Iterator<Item = (&'a str, &'a str)>,
 {
-	verify_wav(file)?;
-	let file_len = file.len()?.saturating_sub(12);
+	let container = RiffContainer::read(file)?;
+	let file_len = file.len()?;
 
 	let mut riff_info_bytes = Vec::new();
 	create_riff_info(&mut tag.items, &mut riff_info_bytes)?;
 
-	let Some(info_list_size) = find_info_list(file, file_len)? else {
-		// Simply append the info list to the end of the file and update the file size
-		file.seek(SeekFrom::End(0))?;
-
-		file.write_all(&riff_info_bytes)?;
-
-		let len = (file.stream_position()? - 8) as u32;
-
-		file.seek(SeekFrom::Start(4))?;
-		file.write_u32::<LittleEndian>(len)?;
-
-		return Ok(());
-	};
-
-	// Replace the existing tag
-
-	let info_list_start = file.seek(SeekFrom::Current(-12))? as usize;
-	let info_list_end = info_list_start + 8 + info_list_size as usize;
-
-	file.rewind()?;
-
-	let mut file_bytes = Vec::new();
-	file.read_to_end(&mut file_bytes)?;
+	// The list header depends on the container, only the content can be reused
+	if !riff_info_bytes.is_empty() {
+		riff_info_bytes = container.create_chunk(*b"LIST", &riff_info_bytes[8..])?;
+	}
 
-	let _ = file_bytes.splice(info_list_start..info_list_end, riff_info_bytes);
+	let info_list = find_info_list(file, &container, file_len)?;
 
-	let total_size = (file_bytes.len() - 8) as u32;
-	let _ = file_bytes.splice(4..8, total_size.to_le_bytes());
+	// Replace the existing tag, or simply append the info list to the end of the file
+	let edits = vec![(info_list.unwrap_or(file_len..file_len), riff_info_bytes)];
 
-	file.rewind()?;
-	file.truncate(0)?;
-	file.write_all(&file_bytes)?;
+	container.update_file_size(file, spliced_len(file_len, &edits))?;
+	splice_file(file, edits)?;
 
 	Ok(())
 }
 
-fn find_info_list<R>(data: &mut R, file_size: u64) -> Result<Option<u32>>
+/// Find the range of the existing INFO list, including its header and padding
+fn find_info_list<R>(
+	data: &mut R,
+	container: &RiffContainer,
+	file_size: u64,
+) -> Result<Option<Range<u64>>>
 where
 	R: Read + Seek,
 {
-	let mut info = None;
-
-	let mut chunks = Chunks::<LittleEndian>::new(file_size);
+	let mut chunks = container.chunks(file_size);
 
+	let mut start = data.stream_position()?;
 	while chunks.next(data).is_ok() {
+		let mut is_info_list = false;
 		if &chunks.fourcc == b"LIST" {
 			let mut list_type = [0; 4];
 			data.read_exact(&mut list_type)?;
+			data.seek(SeekFrom::Current(-4))?;
 
-			if &list_type == b"INFO" {
-				log::debug!("Found existing RIFF INFO list, size: {} bytes", chunks.size);
+			is_info_list = &list_type == b"INFO";
+		}
 
-				info = Some(chunks.size);
-				break;
-			}
+		chunks.skip(data)?;
 
-			data.seek(SeekFrom::Current(-8))?;
+		let end = data.stream_position()?.min(file_size);
+		if is_info_list {
+			log::debug!("Found existing RIFF INFO list, size: {} bytes", chunks.size);
+			return Ok(Some(start..end));
 		}
 
-		chunks.skip(data)?;
+		start = end;
 	}
 
-	Ok(info)
+	Ok(None)
 }
 
 pub(super) fn create_riff_info(
@@ -119,14 +119,18 @@ mod util;
 
 pub mod aac;
 pub mod ape;
+pub mod asf;
+pub mod dsd;
 pub mod flac;
 pub mod id3;
 pub mod iff;
+pub mod matroska;
 pub mod mp4;
 pub mod mpeg;
 pub mod musepack;
 pub mod ogg;
 pub mod wavpack;
+pub mod xmp;
 
 pub use crate::probe::{read_from, read_from_path};
 
@@ -95,4 +95,58 @@ macro_rules! parse_mode_choice {
 	};
 }
 
-pub(crate) use {decode_err, err, parse_mode_choice, try_vec};
+// Implements `chapters`, `set_chapters`, and `remove_chapters` for a file, using the chapters of one of its tags
+//
+// Usage:
+//
+// - impl_chapters!(FileStruct, tag_field, TagStruct)            -> for an `Option<TagStruct>` field
+// - impl_chapters!(@REQUIRED FileStruct, tag_field, TagStruct)  -> for a `TagStruct` field
+macro_rules! impl_chapters {
+	($file:ident, $field:ident, $tag:ident) => {
+		impl $file {
+			#[doc = concat!("Returns the chapters stored in the [`", stringify!($tag), "`]")]
+			/// This will be empty if the file has no such tag.
+			pub fn chapters(&self) -> Vec<crate::tag::items::Chapter> {
+				self.$field
+					.as_ref()
+					.map(|tag| tag.chapters())
+					.unwrap_or_default()
+			}
+
+			#[doc = concat!("Replaces the chapters stored in the [`", stringify!($tag), "`]")]
+			/// The tag will be created if the file doesn't have one.
+			pub fn set_chapters(&mut self, chapters: Vec<crate::tag::items::Chapter>) {
+				self.$field
+					.get_or_insert_with($tag::default)
+					.set_chapters(chapters);
+			}
+
+			#[doc = concat!("Removes all chapters from the [`", stringify!($tag), "`]")]
+			pub fn remove_chapters(&mut self) {
+				if let Some(tag) = self.$field.as_mut() {
+					tag.remove_chapters();
+				}
+			}
+		}
+	};
+	(@REQUIRED $file:ident, $field:ident, $tag:ident) => {
+		impl $file {
+			#[doc = concat!("Returns the chapters stored in the [`", stringify!($tag), "`]")]
+			pub fn chapters(&self) -> Vec<crate::tag::items::Chapter> {
+				self.$field.chapters()
+			}
+
+			#[doc = concat!("Replaces the chapters stored in the [`", stringify!($tag), "`]")]
+			pub fn set_chapters(&mut self, chapters: Vec<crate::tag::items::Chapter>) {
+				self.$field.set_chapters(chapters);
+			}
+
+			#[doc = concat!("Removes all chapters from the [`", stringify!($tag), "`]")]
+			pub fn remove_chapters(&mut self) {
+				self.$field.remove_chapters();
+			}
+		}
+	};
+}
+
+pub(crate) use {decode_err, err, impl_chapters, parse_mode_choice, try_vec};
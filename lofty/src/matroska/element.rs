@@ -0,0 +1,377 @@
+//! EBML primitives
+//!
+//! See <https://www.rfc-editor.org/rfc/rfc8794.html>
+
+use crate::error::Result;
+use crate::macros::{decode_err, err, try_vec};
+
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use byteorder::ReadBytesExt;
+
+// EBML header
+pub(crate) const EBML_ID: u32 = 0x1A45_DFA3;
+pub(crate) const DOC_TYPE_ID: u32 = 0x4282;
+
+// Global elements
+pub(crate) const VOID_ID: u32 = 0xEC;
+
+// Segment
+pub(crate) const SEGMENT_ID: u32 = 0x1853_8067;
+
+pub(crate) const SEEK_HEAD_ID: u32 = 0x114D_9B74;
+pub(crate) const SEEK_ID: u32 = 0x4DBB;
+pub(crate) const SEEK_ID_ID: u32 = 0x53AB;
+pub(crate) const SEEK_POSITION_ID: u32 = 0x53AC;
+
+pub(crate) const INFO_ID: u32 = 0x1549_A966;
+pub(crate) const TIMESTAMP_SCALE_ID: u32 = 0x2A_D7B1;
+pub(crate) const DURATION_ID: u32 = 0x4489;
+
+pub(crate) const TRACKS_ID: u32 = 0x1654_AE6B;
+pub(crate) const TRACK_ENTRY_ID: u32 = 0xAE;
+pub(crate) const TRACK_TYPE_ID: u32 = 0x83;
+pub(crate) const CODEC_ID_ID: u32 = 0x86;
+pub(crate) const AUDIO_ID: u32 = 0xE1;
+pub(crate) const SAMPLING_FREQUENCY_ID: u32 = 0xB5;
+pub(crate) const OUTPUT_SAMPLING_FREQUENCY_ID: u32 = 0x78B5;
+pub(crate) const CHANNELS_ID: u32 = 0x9F;
+pub(crate) const BIT_DEPTH_ID: u32 = 0x6264;
+
+pub(crate) const CLUSTER_ID: u32 = 0x1F43_B675;
+
+pub(crate) const ATTACHMENTS_ID: u32 = 0x1941_A469;
+pub(crate) const ATTACHED_FILE_ID: u32 = 0x61A7;
+pub(crate) const FILE_DESCRIPTION_ID: u32 = 0x467E;
+pub(crate) const FILE_NAME_ID: u32 = 0x466E;
+pub(crate) const FILE_MEDIA_TYPE_ID: u32 = 0x4660;
+pub(crate) const FILE_DATA_ID: u32 = 0x465C;
+
+pub(crate) const TAGS_ID: u32 = 0x1254_C367;
+pub(crate) const TAG_ID: u32 = 0x7373;
+pub(crate) const TARGETS_ID: u32 = 0x63C0;
+pub(crate) const TARGET_TYPE_VALUE_ID: u32 = 0x68CA;
+pub(crate) const TARGET_TYPE_ID: u32 = 0x63CA;
+pub(crate) const TAG_TRACK_UID_ID: u32 = 0x63C5;
+pub(crate) const TAG_EDITION_UID_ID: u32 = 0x63C9;
+pub(crate) const TAG_CHAPTER_UID_ID: u32 = 0x63C4;
+pub(crate) const TAG_ATTACHMENT_UID_ID: u32 = 0x63C6;
+pub(crate) const SIMPLE_TAG_ID: u32 = 0x67C8;
+pub(crate) const TAG_NAME_ID: u32 = 0x45A3;
+pub(crate) const TAG_LANGUAGE_ID: u32 = 0x447A;
+pub(crate) const TAG_DEFAULT_ID: u32 = 0x4484;
+pub(crate) const TAG_STRING_ID: u32 = 0x4487;
+pub(crate) const TAG_BINARY_ID: u32 = 0x4485;
+
+// Element IDs are limited to 4 bytes (EBMLMaxIDLength), and sizes to 8 bytes (EBMLMaxSizeLength)
+const MAX_ID_LENGTH: u8 = 4;
+const MAX_SIZE_LENGTH: u8 = 8;
+
+// The smallest possible element, an ID and a size of 0
+pub(crate) const MIN_VOID_SIZE: u64 = 2;
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct ElementHeader {
+	pub(crate) id: u32,
+	/// The size of the element's content, `None` if the size is unknown
+	pub(crate) size: Option<u64>,
+	/// The length of the ID and size
+	pub(crate) len: u8,
+}
+
+impl ElementHeader {
+	pub(crate) fn read<R>(reader: &mut R) -> Result<Self>
+	where
+		R: Read,
+	{
+		let first = reader.read_u8()?;
+		let id_len = first.leading_zeros() as u8 + 1;
+		if id_len > MAX_ID_LENGTH {
+			decode_err!(@BAIL Matroska, "Encountered an invalid element ID");
+		}
+
+		let mut id = u32::from(first);
+		for _ in 1..id_len {
+			id = (id << 8) | u32::from(reader.read_u8()?);
+		}
+
+		let (size, size_len) = read_vint(reader)?;
+
+		// A size with all of its value bits set is reserved for elements of an unknown size
+		let size = if size == vint_max(size_len) {
+			None
+		} else {
+			Some(size)
+		};
+
+		Ok(Self {
+			id,
+			size,
+			len: id_len + size_len,
+		})
+	}
+
+	/// Get the size of the element's content, failing if it is unknown
+	pub(crate) fn known_size(&self) -> Result<u64> {
+		match self.size {
+			Some(size) => Ok(size),
+			None => decode_err!(@BAIL Matroska, "Encountered an element of unknown size"),
+		}
+	}
+
+	/// Read the element's content into memory
+	pub(crate) fn read_content<R>(&self, reader: &mut R) -> Result<Vec<u8>>
+	where
+		R: Read,
+	{
+		let Ok(size) = usize::try_from(self.known_size()?) else {
+			err!(TooMuchData);
+		};
+
+		let mut content = try_vec![0; size];
+		reader.read_exact(&mut content)?;
+		Ok(content)
+	}
+
+	/// Skip the element's content
+	pub(crate) fn skip<R>(&self, reader: &mut R) -> Result<()>
+	where
+		R: Seek,
+	{
+		let size = self.known_size()?;
+		let Ok(size) = i64::try_from(size) else {
+			err!(TooMuchData);
+		};
+
+		reader.seek(SeekFrom::Current(size))?;
+		Ok(())
+	}
+}
+
+/// Read a variable size integer, returning the value (with its length marker removed) and its length
+pub(crate) fn read_vint<R>(reader: &mut R) -> Result<(u64, u8)>
+where
+	R: Read,
+{
+	let first = reader.read_u8()?;
+	let len = first.leading_zeros() as u8 + 1;
+	if len > MAX_SIZE_LENGTH {
+		decode_err!(@BAIL Matroska, "Encountered an invalid variable size integer");
+	}
+
+	let mut value = u64::from(first) & (0xFF >> len);
+	for _ in 1..len {
+		value = (value << 8) | u64::from(reader.read_u8()?);
+	}
+
+	Ok((value, len))
+}
+
+/// The largest value a variable size integer of `len` bytes can hold
+///
+/// NOTE: This value itself is reserved, see [`ElementHeader::size`]
+pub(crate) fn vint_max(len: u8) -> u64 {
+	(1 << (7 * u32::from(len))) - 1
+}
+
+/// The smallest number of bytes needed to store `value` as a variable size integer
+pub(crate) fn vint_len(value: u64) -> u8 {
+	let mut len = 1;
+	while len < MAX_SIZE_LENGTH && value >= vint_max(len) {
+		len += 1;
+	}
+
+	len
+}
+
+/// Write a variable size integer, padded to `len` bytes
+pub(crate) fn write_vint<W>(writer: &mut W, value: u64, len: u8) -> Result<()>
+where
+	W: Write,
+{
+	if len == 0 || len > MAX_SIZE_LENGTH || value >= vint_max(len) {
+		err!(TooMuchData);
+	}
+
+	let marked = value | (1 << (7 * u32::from(len)));
+	writer.write_all(&marked.to_be_bytes()[8 - usize::from(len)..])?;
+	Ok(())
+}
+
+fn write_id<W>(writer: &mut W, id: u32) -> Result<()>
+where
+	W: Write,
+{
+	let bytes = id.to_be_bytes();
+	let skip = bytes.iter().take_while(|b| **b == 0).count();
+	writer.write_all(&bytes[skip..])?;
+	Ok(())
+}
+
+/// Write an element with the provided content
+pub(crate) fn write_element<W>(writer: &mut W, id: u32, content: &[u8]) -> Result<()>
+where
+	W: Write,
+{
+	let size = content.len() as u64;
+
+	write_id(writer, id)?;
+	write_vint(writer, size, vint_len(size))?;
+	writer.write_all(content)?;
+	Ok(())
+}
+
+pub(crate) fn write_uint_element<W>(writer: &mut W, id: u32, value: u64) -> Result<()>
+where
+	W: Write,
+{
+	let bytes = value.to_be_bytes();
+	let skip = bytes.iter().take_while(|b| **b == 0).count();
+	write_element(writer, id, &bytes[skip..])
+}
+
+/// Write a `Void` element spanning exactly `total_len` bytes, including its header
+///
+/// `total_len` must be at least [`MIN_VOID_SIZE`].
+pub(crate) fn write_void<W>(writer: &mut W, total_len: u64) -> Result<()>
+where
+	W: Write,
+{
+	if total_len < MIN_VOID_SIZE {
+		err!(SizeMismatch);
+	}
+
+	// Find the size length that leaves room for the content
+	let content_len_with_size = total_len - 1;
+	let mut size_len = 1;
+	while content_len_with_size - u64::from(size_len) >= vint_max(size_len) {
+		size_len += 1;
+		if size_len > MAX_SIZE_LENGTH || u64::from(size_len) > content_len_with_size {
+			err!(TooMuchData);
+		}
+	}
+
+	let content_len = content_len_with_size - u64::from(size_len);
+
+	write_id(writer, VOID_ID)?;
+	write_vint(writer, content_len, size_len)?;
+	std::io::copy(&mut std::io::repeat(0).take(content_len), writer)?;
+	Ok(())
+}
+
+/// An iterator over the children of an in-memory master element
+pub(crate) struct Children<'a> {
+	content: &'a [u8],
+}
+
+impl<'a> Children<'a> {
+	pub(crate) fn new(content: &'a [u8]) -> Self {
+		Self { content }
+	}
+}
+
+impl<'a> Iterator for Children<'a> {
+	/// The child's ID and content
+	type Item = Result<(u32, &'a [u8])>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.content.is_empty() {
+			return None;
+		}
+
+		let mut reader = self.content;
+		let header = match ElementHeader::read(&mut reader) {
+			Ok(header) => header,
+			Err(e) => {
+				self.content = &[];
+				return Some(Err(e));
+			},
+		};
+
+		let content_start = usize::from(header.len);
+		let content_end = header
+			.size
+			.and_then(|size| usize::try_from(size).ok())
+			.and_then(|size| content_start.checked_add(size));
+
+		match content_end {
+			Some(end) if end <= self.content.len() => {
+				let child = &self.content[content_start..end];
+				self.content = &self.content[end..];
+				Some(Ok((header.id, child)))
+			},
+			_ => {
+				self.content = &[];
+				Some(Err(decode_err!(
+					Matroska,
+					"Child element extends past its parent"
+				)))
+			},
+		}
+	}
+}
+
+pub(crate) fn read_uint(content: &[u8]) -> Result<u64> {
+	if content.len() > 8 {
+		decode_err!(@BAIL Matroska, "Encountered an unsigned integer larger than 8 bytes");
+	}
+
+	Ok(content
+		.iter()
+		.fold(0, |value, byte| (value << 8) | u64::from(*byte)))
+}
+
+pub(crate) fn read_float(content: &[u8]) -> Result<f64> {
+	match content.len() {
+		0 => Ok(0.0),
+		4 => Ok(f64::from(f32::from_be_bytes(content.try_into().unwrap()))),
+		8 => Ok(f64::from_be_bytes(content.try_into().unwrap())),
+		_ => decode_err!(@BAIL Matroska, "Encountered a float with an invalid size"),
+	}
+}
+
+/// Read a string or UTF-8 element
+///
+/// Strings may be padded with trailing null bytes, which are discarded.
+pub(crate) fn read_string(content: &[u8]) -> Result<String> {
+	let end = content
+		.iter()
+		.position(|b| *b == 0)
+		.unwrap_or(content.len());
+	Ok(String::from_utf8(content[..end].to_vec())?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{read_vint, vint_len, write_vint, write_void, ElementHeader, VOID_ID};
+
+	#[test_log::test]
+	fn vint_round_trip() {
+		for value in [0, 1, 126, 127, 16_382, 16_383, 0xFF_FFFF_FFFF] {
+			let len = vint_len(value);
+
+			let mut encoded = Vec::new();
+			write_vint(&mut encoded, value, len).unwrap();
+			assert_eq!(encoded.len(), usize::from(len));
+
+			assert_eq!(read_vint(&mut &encoded[..]).unwrap(), (value, len));
+		}
+	}
+
+	#[test_log::test]
+	fn void_sizes() {
+		for total_len in [2, 3, 9, 128, 129, 130, 16_385, 16_386] {
+			let mut void = Vec::new();
+			write_void(&mut void, total_len).unwrap();
+			assert_eq!(void.len() as u64, total_len);
+
+			let header = ElementHeader::read(&mut &void[..]).unwrap();
+			assert_eq!(header.id, VOID_ID);
+			assert_eq!(
+				u64::from(header.len) + header.size.unwrap(),
+				total_len,
+				"bad void header for length {total_len}"
+			);
+		}
+	}
+}
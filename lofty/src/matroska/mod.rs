@@ -0,0 +1,56 @@
+//! Matroska specific items
+//!
+//! ## File notes
+//!
+//! This covers both Matroska (`.mkv`, `.mka`) and WebM files, see [`MatroskaFile::doc_type`].
+//!
+//! The only supported tag format is [`MatroskaTag`]. Pictures are read from the file's attachments,
+//! but are never written.
+mod element;
+mod properties;
+mod read;
+pub(crate) mod tag;
+
+use lofty_attr::LoftyFile;
+
+// Exports
+
+pub use properties::MatroskaProperties;
+pub use tag::{MatroskaTag, SimpleTag, SimpleTagValue, Target, TargetType};
+
+/// A Matroska file
+#[derive(LoftyFile)]
+#[lofty(read_fn = "read::read_from")]
+pub struct MatroskaFile {
+	/// The document type from the EBML header ("matroska" or "webm")
+	pub(crate) doc_type: String,
+	/// The file's `SimpleTag`s
+	#[lofty(tag_type = "Matroska")]
+	pub(crate) matroska_tag: Option<MatroskaTag>,
+	/// The file's audio properties
+	pub(crate) properties: MatroskaProperties,
+}
+
+impl MatroskaFile {
+	/// Returns the document type from the EBML header
+	///
+	/// This will either be "matroska" or "webm".
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// use lofty::config::ParseOptions;
+	/// use lofty::file::AudioFile;
+	/// use lofty::matroska::MatroskaFile;
+	///
+	/// # fn main() -> lofty::error::Result<()> {
+	/// # let mut webm_reader = std::io::Cursor::new(&[]);
+	/// let webm_file = MatroskaFile::read_from(&mut webm_reader, ParseOptions::new())?;
+	///
+	/// assert_eq!(webm_file.doc_type(), "webm");
+	/// # Ok(()) }
+	/// ```
+	pub fn doc_type(&self) -> &str {
+		&self.doc_type
+	}
+}
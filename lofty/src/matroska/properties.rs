@@ -0,0 +1,230 @@
+use super::element::{
+	read_float, read_string, read_uint, Children, AUDIO_ID, BIT_DEPTH_ID, CHANNELS_ID, CODEC_ID_ID,
+	DURATION_ID, OUTPUT_SAMPLING_FREQUENCY_ID, SAMPLING_FREQUENCY_ID, TIMESTAMP_SCALE_ID,
+	TRACK_ENTRY_ID, TRACK_TYPE_ID,
+};
+use crate::error::Result;
+use crate::properties::FileProperties;
+
+use std::time::Duration;
+
+// The default TimestampScale, 1ms
+const DEFAULT_TIMESTAMP_SCALE: u64 = 1_000_000;
+
+// The default SamplingFrequency, 8kHz
+const DEFAULT_SAMPLING_FREQUENCY: f64 = 8000.0;
+
+// TrackType for audio tracks
+const TRACK_TYPE_AUDIO: u64 = 2;
+
+/// A Matroska file's audio properties
+///
+/// These describe the first audio track in the file.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub struct MatroskaProperties {
+	pub(crate) duration: Duration,
+	pub(crate) overall_bitrate: u32,
+	pub(crate) audio_bitrate: u32,
+	pub(crate) sample_rate: u32,
+	pub(crate) bit_depth: Option<u8>,
+	pub(crate) channels: u8,
+	pub(crate) codec_id: String,
+}
+
+impl From<MatroskaProperties> for FileProperties {
+	fn from(input: MatroskaProperties) -> Self {
+		Self {
+			duration: input.duration,
+			overall_bitrate: Some(input.overall_bitrate),
+			audio_bitrate: Some(input.audio_bitrate),
+			sample_rate: Some(input.sample_rate),
+			bit_depth: input.bit_depth,
+			channels: Some(input.channels),
+			channel_mask: None,
+		}
+	}
+}
+
+impl MatroskaProperties {
+	/// Duration of the audio
+	pub fn duration(&self) -> Duration {
+		self.duration
+	}
+
+	/// Overall bitrate (kbps)
+	pub fn overall_bitrate(&self) -> u32 {
+		self.overall_bitrate
+	}
+
+	/// Audio bitrate (kbps)
+	///
+	/// NOTE: This is calculated from the size of all clusters, which may contain other tracks.
+	pub fn audio_bitrate(&self) -> u32 {
+		self.audio_bitrate
+	}
+
+	/// Sample rate (Hz)
+	pub fn sample_rate(&self) -> u32 {
+		self.sample_rate
+	}
+
+	/// Bits per sample
+	///
+	/// This is usually only available for uncompressed audio
+	pub fn bit_depth(&self) -> Option<u8> {
+		self.bit_depth
+	}
+
+	/// Channel count
+	pub fn channels(&self) -> u8 {
+		self.channels
+	}
+
+	/// The track's codec ID (Ex. "A_OPUS")
+	///
+	/// See <https://www.matroska.org/technical/codec_specs.html>
+	pub fn codec_id(&self) -> &str {
+		&self.codec_id
+	}
+}
+
+/// The relevant parts of the `Info` element
+#[derive(Default)]
+pub(super) struct SegmentInfo {
+	timestamp_scale: Option<u64>,
+	duration: Option<f64>,
+}
+
+impl SegmentInfo {
+	pub(super) fn parse(content: &[u8]) -> Result<Self> {
+		let mut info = Self::default();
+
+		for child in Children::new(content) {
+			let (id, content) = child?;
+			match id {
+				TIMESTAMP_SCALE_ID => info.timestamp_scale = Some(read_uint(content)?),
+				DURATION_ID => info.duration = Some(read_float(content)?),
+				_ => {},
+			}
+		}
+
+		Ok(info)
+	}
+
+	fn duration(&self) -> Duration {
+		let Some(duration) = self.duration else {
+			return Duration::ZERO;
+		};
+
+		let timestamp_scale = self.timestamp_scale.unwrap_or(DEFAULT_TIMESTAMP_SCALE);
+
+		let nanos = duration * timestamp_scale as f64;
+		if !nanos.is_finite() || nanos <= 0.0 {
+			return Duration::ZERO;
+		}
+
+		Duration::from_nanos(nanos as u64)
+	}
+}
+
+/// The relevant parts of an audio `TrackEntry` element
+#[derive(Default)]
+pub(super) struct AudioTrack {
+	codec_id: String,
+	sample_rate: Option<f64>,
+	output_sample_rate: Option<f64>,
+	channels: Option<u64>,
+	bit_depth: Option<u64>,
+}
+
+impl AudioTrack {
+	/// Find the first audio track in the `Tracks` element
+	pub(super) fn parse_first(content: &[u8]) -> Result<Option<Self>> {
+		for child in Children::new(content) {
+			let (id, content) = child?;
+			if id != TRACK_ENTRY_ID {
+				continue;
+			}
+
+			if let Some(track) = Self::parse(content)? {
+				return Ok(Some(track));
+			}
+		}
+
+		Ok(None)
+	}
+
+	fn parse(content: &[u8]) -> Result<Option<Self>> {
+		let mut track = Self::default();
+		let mut track_type = None;
+
+		for child in Children::new(content) {
+			let (id, content) = child?;
+			match id {
+				TRACK_TYPE_ID => track_type = Some(read_uint(content)?),
+				CODEC_ID_ID => track.codec_id = read_string(content)?,
+				AUDIO_ID => {
+					for audio_child in Children::new(content) {
+						let (id, content) = audio_child?;
+						match id {
+							SAMPLING_FREQUENCY_ID => track.sample_rate = Some(read_float(content)?),
+							OUTPUT_SAMPLING_FREQUENCY_ID => {
+								track.output_sample_rate = Some(read_float(content)?)
+							},
+							CHANNELS_ID => track.channels = Some(read_uint(content)?),
+							BIT_DEPTH_ID => track.bit_depth = Some(read_uint(content)?),
+							_ => {},
+						}
+					}
+				},
+				_ => {},
+			}
+		}
+
+		if track_type != Some(TRACK_TYPE_AUDIO) {
+			return Ok(None);
+		}
+
+		Ok(Some(track))
+	}
+}
+
+pub(super) fn read_properties(
+	info: Option<SegmentInfo>,
+	track: Option<AudioTrack>,
+	file_length: u64,
+	audio_length: u64,
+) -> MatroskaProperties {
+	let mut properties = MatroskaProperties::default();
+
+	if let Some(info) = info {
+		properties.duration = info.duration();
+	}
+
+	if let Some(track) = track {
+		// The output sampling frequency takes SBR into account, so it is preferred.
+		// Missing values fall back to the defaults from the specification.
+		let sample_rate = track
+			.output_sample_rate
+			.or(track.sample_rate)
+			.unwrap_or(DEFAULT_SAMPLING_FREQUENCY);
+
+		properties.sample_rate = sample_rate.round() as u32;
+		properties.channels = track
+			.channels
+			.map_or(1, |c| c.min(u64::from(u8::MAX)) as u8);
+		properties.bit_depth = track
+			.bit_depth
+			.and_then(|bit_depth| u8::try_from(bit_depth).ok());
+		properties.codec_id = track.codec_id;
+	}
+
+	let length = properties.duration.as_millis();
+	if length > 0 {
+		properties.overall_bitrate = ((u128::from(file_length) * 8) / length) as u32;
+		properties.audio_bitrate = ((u128::from(audio_length) * 8) / length) as u32;
+	}
+
+	properties
+}
@@ -0,0 +1,118 @@
+use super::element::{
+	read_string, Children, ElementHeader, ATTACHMENTS_ID, CLUSTER_ID, DOC_TYPE_ID, EBML_ID,
+	INFO_ID, SEGMENT_ID, TAGS_ID, TRACKS_ID,
+};
+use super::properties::{read_properties, AudioTrack, MatroskaProperties, SegmentInfo};
+use super::tag::read::{read_attachments, read_tags};
+use super::tag::MatroskaTag;
+use super::MatroskaFile;
+use crate::config::ParseOptions;
+use crate::error::Result;
+use crate::macros::decode_err;
+use crate::util::io::SeekStreamLen;
+
+use std::io::{Read, Seek};
+
+/// Read the EBML header, returning the `DocType`
+///
+/// This leaves the reader at the end of the header.
+pub(in crate::matroska) fn read_ebml_header<R>(reader: &mut R) -> Result<String>
+where
+	R: Read,
+{
+	let header = ElementHeader::read(reader)?;
+	if header.id != EBML_ID {
+		decode_err!(@BAIL Matroska, "File missing EBML header");
+	}
+
+	let content = header.read_content(reader)?;
+
+	let mut doc_type = None;
+	for child in Children::new(&content) {
+		let (id, content) = child?;
+		if id == DOC_TYPE_ID {
+			doc_type = Some(read_string(content)?);
+		}
+	}
+
+	match doc_type {
+		Some(doc_type) if doc_type == "matroska" || doc_type == "webm" => Ok(doc_type),
+		Some(_) => decode_err!(@BAIL Matroska, "Unsupported EBML DocType"),
+		None => decode_err!(@BAIL Matroska, "EBML header missing DocType"),
+	}
+}
+
+pub(super) fn read_from<R>(reader: &mut R, parse_options: ParseOptions) -> Result<MatroskaFile>
+where
+	R: Read + Seek,
+{
+	let file_length = reader.stream_len_hack()?;
+
+	let doc_type = read_ebml_header(reader)?;
+
+	let segment = ElementHeader::read(reader)?;
+	if segment.id != SEGMENT_ID {
+		decode_err!(@BAIL Matroska, "File missing segment");
+	}
+
+	let segment_start = reader.stream_position()?;
+	let segment_end = segment
+		.size
+		.map_or(file_length, |size| (segment_start + size).min(file_length));
+
+	let mut tag = None;
+	let mut info = None;
+	let mut audio_track = None;
+	let mut audio_length = 0;
+
+	while reader.stream_position()? < segment_end {
+		let header = ElementHeader::read(reader)?;
+
+		// Only clusters can reasonably have an unknown size, there's no way to find the end of one
+		// without parsing its blocks.
+		let Some(size) = header.size else {
+			log::debug!("Encountered an element of unknown size, stopping");
+			break;
+		};
+
+		match header.id {
+			INFO_ID if parse_options.read_properties => {
+				info = Some(SegmentInfo::parse(&header.read_content(reader)?)?);
+			},
+			TRACKS_ID if parse_options.read_properties => {
+				audio_track = AudioTrack::parse_first(&header.read_content(reader)?)?;
+			},
+			TAGS_ID if parse_options.read_tags => {
+				let tag = tag.get_or_insert_with(MatroskaTag::default);
+				read_tags(
+					&header.read_content(reader)?,
+					tag,
+					parse_options.parsing_mode,
+				)?;
+			},
+			ATTACHMENTS_ID if parse_options.read_tags && parse_options.read_cover_art => {
+				let tag = tag.get_or_insert_with(MatroskaTag::default);
+				read_attachments(reader, header, tag)?;
+			},
+			_ => {
+				if header.id == CLUSTER_ID {
+					audio_length += size;
+				}
+
+				header.skip(reader)?;
+			},
+		}
+	}
+
+	let properties = if parse_options.read_properties {
+		read_properties(info, audio_track, file_length, audio_length)
+	} else {
+		MatroskaProperties::default()
+	};
+
+	Ok(MatroskaFile {
+		doc_type,
+		matroska_tag: tag,
+		properties,
+	})
+}
@@ -0,0 +1,700 @@
+pub(super) mod read;
+pub(crate) mod write;
+
+use crate::config::WriteOptions;
+use crate::error::LoftyError;
+use crate::picture::Picture;
+use crate::tag::{
+	try_parse_year, Accessor, ItemKey, ItemValue, MergeTag, SplitTag, Tag, TagExt, TagItem, TagType,
+};
+use crate::util::io::{FileLike, Length, Truncate};
+
+use std::borrow::Cow;
+use std::io::Write;
+use std::ops::Deref;
+
+use lofty_attr::tag;
+
+// Names that take on a different meaning at the album level
+const ALBUM_KEYS: [(&str, ItemKey); 7] = [
+	("TITLE", ItemKey::AlbumTitle),
+	("TITLE_SORT", ItemKey::AlbumTitleSortOrder),
+	("ARTIST", ItemKey::AlbumArtist),
+	("ARTIST_SORT", ItemKey::AlbumArtistSortOrder),
+	("TOTAL_PARTS", ItemKey::TrackTotal),
+	("REPLAYGAIN_GAIN", ItemKey::ReplayGainAlbumGain),
+	("REPLAYGAIN_PEAK", ItemKey::ReplayGainAlbumPeak),
+];
+
+macro_rules! impl_accessor {
+	($($name:ident => $target_type:ident, $key:literal;)+) => {
+		paste::paste! {
+			$(
+				fn $name(&self) -> Option<Cow<'_, str>> {
+					self.get_string(TargetType::$target_type, $key).map(Cow::Borrowed)
+				}
+
+				fn [<set_ $name>](&mut self, value: String) {
+					self.insert(SimpleTag::new(
+						TargetType::$target_type,
+						String::from($key),
+						SimpleTagValue::String(value),
+					))
+				}
+
+				fn [<remove_ $name>](&mut self) {
+					let _ = self.remove(TargetType::$target_type, $key);
+				}
+			)+
+		}
+	}
+}
+
+/// The logical level a [`Target`] describes
+///
+/// See <https://www.matroska.org/technical/tagging.html#target-types>
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[repr(u8)]
+#[allow(missing_docs)]
+pub enum TargetType {
+	Collection = 70,
+	Edition = 60,
+	/// The default target type
+	#[default]
+	Album = 50,
+	Part = 40,
+	Track = 30,
+	Subtrack = 20,
+	Shot = 10,
+}
+
+impl TryFrom<u8> for TargetType {
+	type Error = u8;
+
+	fn try_from(value: u8) -> std::result::Result<Self, Self::Error> {
+		match value {
+			70 => Ok(Self::Collection),
+			60 => Ok(Self::Edition),
+			50 => Ok(Self::Album),
+			40 => Ok(Self::Part),
+			30 => Ok(Self::Track),
+			20 => Ok(Self::Subtrack),
+			10 => Ok(Self::Shot),
+			_ => Err(value),
+		}
+	}
+}
+
+/// What a [`SimpleTag`] applies to
+///
+/// If all of the UID lists are empty, the tag applies to the entire file at the level of `target_type`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Target {
+	/// The logical level of the target
+	pub target_type: TargetType,
+	/// An informational name for the level (Ex. "ALBUM")
+	pub name: Option<String>,
+	/// The UIDs of the tracks the tag applies to
+	pub track_uids: Vec<u64>,
+	/// The UIDs of the editions the tag applies to
+	pub edition_uids: Vec<u64>,
+	/// The UIDs of the chapters the tag applies to
+	pub chapter_uids: Vec<u64>,
+	/// The UIDs of the attachments the tag applies to
+	pub attachment_uids: Vec<u64>,
+}
+
+impl Target {
+	/// Create a new [`Target`] for the entire file at the level of `target_type`
+	pub fn new(target_type: TargetType) -> Self {
+		Self {
+			target_type,
+			..Self::default()
+		}
+	}
+
+	/// Whether the target applies to the entire file
+	///
+	/// A UID of 0 is the same as not specifying any UID.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::matroska::{Target, TargetType};
+	///
+	/// let mut target = Target::new(TargetType::Track);
+	/// assert!(target.is_global());
+	///
+	/// target.track_uids.push(1234);
+	/// assert!(!target.is_global());
+	/// ```
+	pub fn is_global(&self) -> bool {
+		[
+			&self.track_uids,
+			&self.edition_uids,
+			&self.chapter_uids,
+			&self.attachment_uids,
+		]
+		.into_iter()
+		.flatten()
+		.all(|uid| *uid == 0)
+	}
+}
+
+/// The value of a [`SimpleTag`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimpleTagValue {
+	/// A UTF-8 string
+	String(String),
+	/// Binary data
+	Binary(Vec<u8>),
+}
+
+/// A single Matroska tag
+///
+/// See <https://www.matroska.org/technical/tagging.html>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimpleTag {
+	/// The name of the tag (Ex. "ARTIST")
+	///
+	/// See <https://www.matroska.org/technical/tagging.html#tag-names> for a list of official names.
+	pub name: String,
+	/// The value of the tag
+	pub value: SimpleTagValue,
+	/// The language of the value, as an ISO 639-2 code
+	pub language: String,
+	/// Whether this is the default (or original) language of the tag
+	pub default: bool,
+	/// What the tag applies to
+	pub target: Target,
+}
+
+impl SimpleTag {
+	/// Create a new [`SimpleTag`] applying to the entire file at the level of `target_type`
+	///
+	/// The language will be undefined ("und"), and the tag will be marked as the default.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::matroska::{SimpleTag, SimpleTagValue, TargetType};
+	///
+	/// let album_title = SimpleTag::new(
+	/// 	TargetType::Album,
+	/// 	String::from("TITLE"),
+	/// 	SimpleTagValue::String(String::from("Foo album")),
+	/// );
+	///
+	/// assert_eq!(album_title.language, "und");
+	/// assert!(album_title.target.is_global());
+	/// ```
+	pub fn new(target_type: TargetType, name: String, value: SimpleTagValue) -> Self {
+		Self {
+			name,
+			value,
+			language: String::from("und"),
+			default: true,
+			target: Target::new(target_type),
+		}
+	}
+
+	fn matches(&self, target_type: TargetType, name: &str) -> bool {
+		self.target.target_type == target_type
+			&& self.target.is_global()
+			&& self.name.eq_ignore_ascii_case(name)
+	}
+}
+
+/// ## Targets
+///
+/// Every [`SimpleTag`] has a [`Target`], describing what it applies to. The same name can have a different
+/// meaning depending on the target. For example, `TITLE` at the [`TargetType::Track`] level is the
+/// track title, while at the [`TargetType::Album`] level it is the album title.
+///
+/// The methods on `MatroskaTag` (and the [`Accessor`] methods) only deal with tags applying to
+/// the entire file, see [`Target::is_global`].
+///
+/// ## Pictures
+///
+/// Pictures are read from image attachments, but are never written. Attachments must be edited
+/// by other means.
+///
+/// ## Conversions
+///
+/// ### To `Tag`
+///
+/// Only global, default-language string tags at the [`TargetType::Track`] and [`TargetType::Album`] levels
+/// are converted to [`TagItem`]s. Tags with unknown names at the track level are stored as [`ItemKey::Unknown`].
+///
+/// ### From `Tag`
+///
+/// Album-specific items, such as [`ItemKey::AlbumTitle`] and [`ItemKey::TrackTotal`], are stored at the
+/// [`TargetType::Album`] level. All other items are stored at the [`TargetType::Track`] level.
+///
+/// Binary items are discarded.
+#[derive(Default, PartialEq, Eq, Debug, Clone)]
+#[tag(description = "Matroska `SimpleTag`s", supported_formats(Matroska))]
+pub struct MatroskaTag {
+	pub(crate) items: Vec<SimpleTag>,
+	pub(crate) pictures: Vec<Picture>,
+}
+
+impl MatroskaTag {
+	/// Create a new empty `MatroskaTag`
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::matroska::MatroskaTag;
+	/// use lofty::tag::TagExt;
+	///
+	/// let matroska_tag = MatroskaTag::new();
+	/// assert!(matroska_tag.is_empty());
+	/// ```
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Get all items
+	pub fn items(&self) -> impl ExactSizeIterator<Item = &SimpleTag> + Clone {
+		self.items.iter()
+	}
+
+	/// Get the first global item with `name` at the level of `target_type`
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::matroska::{MatroskaTag, SimpleTag, SimpleTagValue, TargetType};
+	///
+	/// let mut tag = MatroskaTag::default();
+	/// tag.push(SimpleTag::new(
+	/// 	TargetType::Track,
+	/// 	String::from("TITLE"),
+	/// 	SimpleTagValue::String(String::from("Foo title")),
+	/// ));
+	///
+	/// assert!(tag.get(TargetType::Track, "TITLE").is_some());
+	///
+	/// // There is no album title
+	/// assert!(tag.get(TargetType::Album, "TITLE").is_none());
+	/// ```
+	pub fn get(&self, target_type: TargetType, name: &str) -> Option<&SimpleTag> {
+		self.items
+			.iter()
+			.find(|item| item.matches(target_type, name))
+	}
+
+	/// Get the string value of the first global item with `name` at the level of `target_type`
+	///
+	/// See [`MatroskaTag::get`]
+	pub fn get_string(&self, target_type: TargetType, name: &str) -> Option<&str> {
+		match self.get(target_type, name) {
+			Some(SimpleTag {
+				value: SimpleTagValue::String(value),
+				..
+			}) => Some(value),
+			_ => None,
+		}
+	}
+
+	/// Insert an item
+	///
+	/// This is the same as [`MatroskaTag::push`], except it will remove any items with the same name and target.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::matroska::{MatroskaTag, SimpleTag, SimpleTagValue, TargetType};
+	/// use lofty::tag::TagExt;
+	///
+	/// let mut tag = MatroskaTag::default();
+	/// for title in ["Title 1", "Title 2"] {
+	/// 	tag.insert(SimpleTag::new(
+	/// 		TargetType::Track,
+	/// 		String::from("TITLE"),
+	/// 		SimpleTagValue::String(String::from(title)),
+	/// 	));
+	/// }
+	///
+	/// // We only retain the last title inserted
+	/// assert_eq!(tag.len(), 1);
+	/// assert_eq!(tag.get_string(TargetType::Track, "TITLE"), Some("Title 2"));
+	/// ```
+	pub fn insert(&mut self, item: SimpleTag) {
+		self.items
+			.retain(|i| !(i.target == item.target && i.name.eq_ignore_ascii_case(&item.name)));
+		self.items.push(item);
+	}
+
+	/// Append an item
+	pub fn push(&mut self, item: SimpleTag) {
+		self.items.push(item);
+	}
+
+	/// Remove all global items with `name` at the level of `target_type`, returning an iterator
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::matroska::{MatroskaTag, SimpleTag, SimpleTagValue, TargetType};
+	/// use lofty::tag::TagExt;
+	///
+	/// let mut tag = MatroskaTag::default();
+	/// tag.push(SimpleTag::new(
+	/// 	TargetType::Track,
+	/// 	String::from("TITLE"),
+	/// 	SimpleTagValue::String(String::from("Foo title")),
+	/// ));
+	///
+	/// assert_eq!(tag.remove(TargetType::Track, "TITLE").count(), 1);
+	/// assert!(tag.is_empty());
+	/// ```
+	pub fn remove(
+		&mut self,
+		target_type: TargetType,
+		name: &str,
+	) -> impl Iterator<Item = SimpleTag> + '_ {
+		// TODO: drain_filter
+		let mut split_idx = 0_usize;
+
+		for read_idx in 0..self.items.len() {
+			if self.items[read_idx].matches(target_type, name) {
+				self.items.swap(split_idx, read_idx);
+				split_idx += 1;
+			}
+		}
+
+		self.items.drain(..split_idx)
+	}
+
+	/// Get all pictures
+	///
+	/// These are read from the file's image attachments.
+	pub fn pictures(&self) -> &[Picture] {
+		&self.pictures
+	}
+}
+
+impl Accessor for MatroskaTag {
+	impl_accessor!(
+		artist  => Track, "ARTIST";
+		title   => Track, "TITLE";
+		album   => Album, "TITLE";
+		genre   => Track, "GENRE";
+		comment => Track, "COMMENT";
+	);
+
+	fn track(&self) -> Option<u32> {
+		self.get_string(TargetType::Track, "PART_NUMBER")
+			.and_then(|part_number| part_number.parse().ok())
+	}
+
+	fn set_track(&mut self, value: u32) {
+		self.insert(SimpleTag::new(
+			TargetType::Track,
+			String::from("PART_NUMBER"),
+			SimpleTagValue::String(value.to_string()),
+		));
+	}
+
+	fn remove_track(&mut self) {
+		let _ = self.remove(TargetType::Track, "PART_NUMBER");
+	}
+
+	fn track_total(&self) -> Option<u32> {
+		self.get_string(TargetType::Album, "TOTAL_PARTS")
+			.and_then(|total_parts| total_parts.parse().ok())
+	}
+
+	fn set_track_total(&mut self, value: u32) {
+		self.insert(SimpleTag::new(
+			TargetType::Album,
+			String::from("TOTAL_PARTS"),
+			SimpleTagValue::String(value.to_string()),
+		));
+	}
+
+	fn remove_track_total(&mut self) {
+		let _ = self.remove(TargetType::Album, "TOTAL_PARTS");
+	}
+
+	fn year(&self) -> Option<u32> {
+		self.get_string(TargetType::Track, "DATE_RELEASED")
+			.or_else(|| self.get_string(TargetType::Track, "DATE_RECORDED"))
+			.and_then(try_parse_year)
+	}
+
+	fn set_year(&mut self, value: u32) {
+		self.insert(SimpleTag::new(
+			TargetType::Track,
+			String::from("DATE_RELEASED"),
+			SimpleTagValue::String(value.to_string()),
+		));
+	}
+
+	fn remove_year(&mut self) {
+		let _ = self.remove(TargetType::Track, "DATE_RELEASED");
+		let _ = self.remove(TargetType::Track, "DATE_RECORDED");
+	}
+}
+
+impl TagExt for MatroskaTag {
+	type Err = LoftyError;
+	type RefKey<'a> = &'a str;
+
+	#[inline]
+	fn tag_type(&self) -> TagType {
+		TagType::Matroska
+	}
+
+	fn len(&self) -> usize {
+		self.items.len() + self.pictures.len()
+	}
+
+	fn contains<'a>(&'a self, key: Self::RefKey<'a>) -> bool {
+		self.items
+			.iter()
+			.any(|item| item.name.eq_ignore_ascii_case(key))
+	}
+
+	fn is_empty(&self) -> bool {
+		self.items.is_empty() && self.pictures.is_empty()
+	}
+
+	/// Writes the tag to a file
+	///
+	/// # Errors
+	///
+	/// * Attempting to write the tag to a format that does not support it
+	/// * The file's segment is too large to be resized
+	/// * [`std::io::Error`]
+	fn save_to<F>(
+		&self,
+		file: &mut F,
+		write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err>
+	where
+		F: FileLike,
+		LoftyError: From<<F as Truncate>::Error>,
+		LoftyError: From<<F as Length>::Error>,
+	{
+		write::write_to(file, self, write_options)
+	}
+
+	/// Dumps the tag to a writer
+	///
+	/// This will write a single `Tags` element.
+	///
+	/// # Errors
+	///
+	/// * [`std::io::Error`]
+	fn dump_to<W: Write>(
+		&self,
+		writer: &mut W,
+		_write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err> {
+		writer.write_all(&write::create_tags_element(&self.items)?)?;
+		Ok(())
+	}
+
+	fn clear(&mut self) {
+		self.items.clear();
+		self.pictures.clear();
+	}
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SplitTagRemainder(MatroskaTag);
+
+impl From<SplitTagRemainder> for MatroskaTag {
+	fn from(from: SplitTagRemainder) -> Self {
+		from.0
+	}
+}
+
+impl Deref for SplitTagRemainder {
+	type Target = MatroskaTag;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+fn item_key(simple_tag: &SimpleTag) -> Option<ItemKey> {
+	if !simple_tag.default || !simple_tag.target.is_global() {
+		return None;
+	}
+
+	match simple_tag.target.target_type {
+		TargetType::Track => Some(ItemKey::from_key(TagType::Matroska, &simple_tag.name)),
+		TargetType::Album => {
+			if let Some((_, key)) = ALBUM_KEYS
+				.iter()
+				.find(|(name, _)| name.eq_ignore_ascii_case(&simple_tag.name))
+			{
+				return Some(key.clone());
+			}
+
+			// Unknown album level tags have nowhere to go when converting back
+			match ItemKey::from_key(TagType::Matroska, &simple_tag.name) {
+				ItemKey::Unknown(_) => None,
+				key => Some(key),
+			}
+		},
+		_ => None,
+	}
+}
+
+impl SplitTag for MatroskaTag {
+	type Remainder = SplitTagRemainder;
+
+	fn split_tag(mut self) -> (Self::Remainder, Tag) {
+		let mut tag = Tag::new(TagType::Matroska);
+
+		for simple_tag in std::mem::take(&mut self.items) {
+			match (item_key(&simple_tag), simple_tag.value) {
+				(Some(key), SimpleTagValue::String(value)) => {
+					tag.items.push(TagItem::new(key, ItemValue::Text(value)));
+				},
+				(_, value) => self.items.push(SimpleTag {
+					value,
+					..simple_tag
+				}),
+			}
+		}
+
+		for picture in std::mem::take(&mut self.pictures) {
+			tag.push_picture(picture);
+		}
+
+		(SplitTagRemainder(self), tag)
+	}
+}
+
+impl MergeTag for SplitTagRemainder {
+	type Merged = MatroskaTag;
+
+	fn merge_tag(self, tag: Tag) -> Self::Merged {
+		let Self(mut merged) = self;
+
+		for item in tag.items {
+			let item_key = item.item_key;
+
+			// Binary items are discarded, as there is no way of knowing how they should be stored
+			let (ItemValue::Text(value) | ItemValue::Locator(value)) = item.item_value else {
+				continue;
+			};
+
+			let (target_type, name) = match ALBUM_KEYS.iter().find(|(_, key)| *key == item_key) {
+				Some((name, _)) => (TargetType::Album, String::from(*name)),
+				None => match item_key {
+					ItemKey::Unknown(unknown) if !unknown.is_empty() => {
+						(TargetType::Track, unknown)
+					},
+					_ => match item_key.map_key(TagType::Matroska, false) {
+						Some(name) => (TargetType::Track, name.to_string()),
+						None => continue, // No mapping exists, discard the item
+					},
+				},
+			};
+
+			merged.items.push(SimpleTag::new(
+				target_type,
+				name,
+				SimpleTagValue::String(value),
+			));
+		}
+
+		merged.pictures.extend(tag.pictures);
+
+		merged
+	}
+}
+
+impl From<MatroskaTag> for Tag {
+	fn from(input: MatroskaTag) -> Self {
+		input.split_tag().1
+	}
+}
+
+impl From<Tag> for MatroskaTag {
+	fn from(input: Tag) -> Self {
+		SplitTagRemainder::default().merge_tag(input)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::matroska::{MatroskaTag, SimpleTag, SimpleTagValue, Target, TargetType};
+	use crate::prelude::*;
+	use crate::tag::{Tag, TagType};
+
+	fn simple_tag(target_type: TargetType, name: &str, value: &str) -> SimpleTag {
+		SimpleTag::new(
+			target_type,
+			String::from(name),
+			SimpleTagValue::String(String::from(value)),
+		)
+	}
+
+	#[test_log::test]
+	fn matroska_to_tag() {
+		let mut matroska_tag = MatroskaTag::default();
+		matroska_tag.push(simple_tag(TargetType::Track, "TITLE", "Foo title"));
+		matroska_tag.push(simple_tag(TargetType::Track, "ARTIST", "Bar artist"));
+		matroska_tag.push(simple_tag(TargetType::Album, "TITLE", "Baz album"));
+		matroska_tag.push(simple_tag(TargetType::Album, "TOTAL_PARTS", "2"));
+		matroska_tag.push(simple_tag(TargetType::Track, "PART_NUMBER", "1"));
+
+		let mut track_specific = simple_tag(TargetType::Track, "ENCODER", "Lavf");
+		track_specific.target.track_uids.push(1234);
+		matroska_tag.push(track_specific.clone());
+
+		let (remainder, tag) = matroska_tag.split_tag();
+
+		assert_eq!(tag.title().as_deref(), Some("Foo title"));
+		assert_eq!(tag.artist().as_deref(), Some("Bar artist"));
+		assert_eq!(tag.album().as_deref(), Some("Baz album"));
+		assert_eq!(tag.track(), Some(1));
+		assert_eq!(tag.track_total(), Some(2));
+		assert_eq!(tag.len(), 5);
+
+		// Tags targeting specific tracks are left alone
+		assert_eq!(remainder.items().collect::<Vec<_>>(), [&track_specific]);
+	}
+
+	#[test_log::test]
+	fn tag_to_matroska() {
+		let mut tag = Tag::new(TagType::Matroska);
+		tag.set_title(String::from("Foo title"));
+		tag.set_album(String::from("Bar album"));
+		tag.set_track_total(5);
+
+		let matroska_tag: MatroskaTag = tag.into();
+
+		assert_eq!(
+			matroska_tag.get_string(TargetType::Track, "TITLE"),
+			Some("Foo title")
+		);
+		assert_eq!(
+			matroska_tag.get_string(TargetType::Album, "TITLE"),
+			Some("Bar album")
+		);
+		assert_eq!(
+			matroska_tag.get_string(TargetType::Album, "TOTAL_PARTS"),
+			Some("5")
+		);
+		assert_eq!(matroska_tag.title().as_deref(), Some("Foo title"));
+		assert_eq!(matroska_tag.album().as_deref(), Some("Bar album"));
+	}
+
+	#[test_log::test]
+	fn global_target() {
+		let mut target = Target::new(TargetType::Track);
+		target.track_uids.push(0);
+
+		// A UID of 0 applies to everything
+		assert!(target.is_global());
+	}
+}
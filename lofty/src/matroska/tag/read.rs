@@ -0,0 +1,203 @@
+use super::{MatroskaTag, SimpleTag, SimpleTagValue, Target, TargetType};
+use crate::config::ParsingMode;
+use crate::error::Result;
+use crate::macros::{decode_err, parse_mode_choice};
+use crate::matroska::element::{
+	read_string, read_uint, Children, ElementHeader, ATTACHED_FILE_ID, FILE_DATA_ID,
+	FILE_DESCRIPTION_ID, FILE_MEDIA_TYPE_ID, FILE_NAME_ID, SIMPLE_TAG_ID, TAG_ATTACHMENT_UID_ID,
+	TAG_BINARY_ID, TAG_CHAPTER_UID_ID, TAG_DEFAULT_ID, TAG_EDITION_UID_ID, TAG_ID, TAG_LANGUAGE_ID,
+	TAG_NAME_ID, TAG_STRING_ID, TAG_TRACK_UID_ID, TARGETS_ID, TARGET_TYPE_ID, TARGET_TYPE_VALUE_ID,
+};
+use crate::picture::{MimeType, Picture, PictureType};
+
+use std::io::{Read, Seek, SeekFrom};
+
+/// Read all `SimpleTag`s from a `Tags` element
+pub(in crate::matroska) fn read_tags(
+	content: &[u8],
+	tag: &mut MatroskaTag,
+	parse_mode: ParsingMode,
+) -> Result<()> {
+	for child in Children::new(content) {
+		let (id, content) = child?;
+		if id != TAG_ID {
+			continue;
+		}
+
+		let mut target = Target::default();
+		let mut simple_tags = Vec::new();
+
+		for tag_child in Children::new(content) {
+			let (id, content) = tag_child?;
+			match id {
+				TARGETS_ID => target = read_target(content, parse_mode)?,
+				SIMPLE_TAG_ID => simple_tags.extend(read_simple_tag(content, parse_mode)?),
+				_ => {},
+			}
+		}
+
+		for simple_tag in &mut simple_tags {
+			simple_tag.target = target.clone();
+		}
+
+		tag.items.extend(simple_tags);
+	}
+
+	Ok(())
+}
+
+fn read_target(content: &[u8], parse_mode: ParsingMode) -> Result<Target> {
+	let mut target = Target::default();
+
+	for child in Children::new(content) {
+		let (id, content) = child?;
+		match id {
+			TARGET_TYPE_VALUE_ID => {
+				let value = read_uint(content)?;
+				match u8::try_from(value).ok().map(TargetType::try_from) {
+					Some(Ok(target_type)) => target.target_type = target_type,
+					_ => {
+						parse_mode_choice!(
+							parse_mode,
+							STRICT: decode_err!(@BAIL Matroska, "Encountered an invalid TargetTypeValue"),
+							DEFAULT: log::warn!("Encountered an invalid TargetTypeValue ({value}), using the default")
+						);
+					},
+				}
+			},
+			TARGET_TYPE_ID => target.name = Some(read_string(content)?),
+			TAG_TRACK_UID_ID => target.track_uids.push(read_uint(content)?),
+			TAG_EDITION_UID_ID => target.edition_uids.push(read_uint(content)?),
+			TAG_CHAPTER_UID_ID => target.chapter_uids.push(read_uint(content)?),
+			TAG_ATTACHMENT_UID_ID => target.attachment_uids.push(read_uint(content)?),
+			_ => {},
+		}
+	}
+
+	Ok(target)
+}
+
+fn read_simple_tag(content: &[u8], parse_mode: ParsingMode) -> Result<Option<SimpleTag>> {
+	let mut name = None;
+	let mut value = None;
+	let mut language = String::from("und");
+	let mut default = true;
+
+	for child in Children::new(content) {
+		let (id, content) = child?;
+		match id {
+			TAG_NAME_ID => name = Some(read_string(content)?),
+			TAG_LANGUAGE_ID => language = read_string(content)?,
+			TAG_DEFAULT_ID => default = read_uint(content)? != 0,
+			TAG_STRING_ID => value = Some(SimpleTagValue::String(read_string(content)?)),
+			TAG_BINARY_ID => value = Some(SimpleTagValue::Binary(content.to_vec())),
+			// Nested `SimpleTag`s are not supported
+			SIMPLE_TAG_ID => log::debug!("Skipping nested SimpleTag"),
+			_ => {},
+		}
+	}
+
+	let Some(name) = name else {
+		parse_mode_choice!(
+			parse_mode,
+			STRICT: decode_err!(@BAIL Matroska, "Encountered a SimpleTag with no name"),
+		);
+
+		return Ok(None);
+	};
+
+	let Some(value) = value else {
+		log::debug!("Skipping SimpleTag \"{name}\" with no value");
+		return Ok(None);
+	};
+
+	Ok(Some(SimpleTag {
+		name,
+		value,
+		language,
+		default,
+		target: Target::default(),
+	}))
+}
+
+/// Read all image `AttachedFile`s from an `Attachments` element
+///
+/// This expects the reader to be positioned at the start of the element's content.
+pub(in crate::matroska) fn read_attachments<R>(
+	reader: &mut R,
+	attachments: ElementHeader,
+	tag: &mut MatroskaTag,
+) -> Result<()>
+where
+	R: Read + Seek,
+{
+	let end = reader.stream_position()? + attachments.known_size()?;
+
+	while reader.stream_position()? < end {
+		let header = ElementHeader::read(reader)?;
+		if header.id != ATTACHED_FILE_ID {
+			header.skip(reader)?;
+			continue;
+		}
+
+		let file_end = reader.stream_position()? + header.known_size()?;
+		if let Some(picture) = read_attached_file(reader, file_end)? {
+			tag.pictures.push(picture);
+		}
+
+		reader.seek(SeekFrom::Start(file_end))?;
+	}
+
+	Ok(())
+}
+
+fn read_attached_file<R>(reader: &mut R, end: u64) -> Result<Option<Picture>>
+where
+	R: Read + Seek,
+{
+	let mut media_type = None;
+	let mut file_name = None;
+	let mut description = None;
+	let mut data = None;
+
+	// Attachments can be large (Ex. fonts), so the data is only read if it could be an image
+	while reader.stream_position()? < end {
+		let header = ElementHeader::read(reader)?;
+		match header.id {
+			FILE_MEDIA_TYPE_ID => media_type = Some(read_string(&header.read_content(reader)?)?),
+			FILE_NAME_ID => file_name = Some(read_string(&header.read_content(reader)?)?),
+			FILE_DESCRIPTION_ID => {
+				description = Some(read_string(&header.read_content(reader)?)?);
+			},
+			FILE_DATA_ID if media_type.as_deref().is_none_or(is_image) => {
+				data = Some(header.read_content(reader)?);
+			},
+			_ => header.skip(reader)?,
+		}
+	}
+
+	let (Some(media_type), Some(data)) = (media_type, data) else {
+		return Ok(None);
+	};
+
+	if !is_image(&media_type) {
+		return Ok(None);
+	}
+
+	// https://www.matroska.org/technical/attachments.html#cover-art
+	let pic_type = match file_name {
+		Some(name) if name.to_ascii_lowercase().starts_with("cover.") => PictureType::CoverFront,
+		_ => PictureType::Other,
+	};
+
+	Ok(Some(Picture::new_unchecked(
+		pic_type,
+		Some(MimeType::from_str(&media_type)),
+		description,
+		data,
+	)))
+}
+
+fn is_image(media_type: &str) -> bool {
+	media_type.starts_with("image/")
+}
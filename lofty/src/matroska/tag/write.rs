@@ -0,0 +1,576 @@
+use super::{MatroskaTag, SimpleTag, SimpleTagValue, Target, TargetType};
+use crate::config::WriteOptions;
+use crate::error::{LoftyError, Result};
+use crate::macros::{decode_err, err};
+use crate::matroska::element::{
+	vint_max, write_element, write_uint_element, write_vint, write_void, ElementHeader,
+	MIN_VOID_SIZE, SEEK_HEAD_ID, SEEK_ID, SEEK_ID_ID, SEEK_POSITION_ID, SEGMENT_ID, SIMPLE_TAG_ID,
+	TAGS_ID, TAG_ATTACHMENT_UID_ID, TAG_BINARY_ID, TAG_CHAPTER_UID_ID, TAG_DEFAULT_ID,
+	TAG_EDITION_UID_ID, TAG_ID, TAG_LANGUAGE_ID, TAG_NAME_ID, TAG_STRING_ID, TAG_TRACK_UID_ID,
+	TARGETS_ID, TARGET_TYPE_ID, TARGET_TYPE_VALUE_ID, VOID_ID,
+};
+use crate::matroska::read::read_ebml_header;
+use crate::util::io::{FileLike, Length, Truncate};
+
+use std::io::{Read, Seek, SeekFrom, Write};
+
+/// A top level element within the segment
+#[derive(Copy, Clone, Debug)]
+struct Element {
+	id: u32,
+	offset: u64,
+	/// The total length of the element, including its header
+	len: u64,
+}
+
+impl Element {
+	fn end(&self) -> u64 {
+		self.offset + self.len
+	}
+}
+
+/// A `Seek` element referencing a `Tags` element
+#[derive(Copy, Clone, Debug)]
+struct TagsSeek {
+	seek: Element,
+	/// The offset of the `SeekPosition` content
+	position_offset: u64,
+	/// The length of the `SeekPosition` content
+	position_len: u64,
+}
+
+/// The first `SeekHead` element in the segment
+#[derive(Copy, Clone, Debug)]
+struct SeekHead {
+	element: Element,
+	/// The offset of the `SeekHead`'s size
+	size_offset: u64,
+	/// The length of the `SeekHead`'s size
+	size_len: u8,
+	/// The size of the `SeekHead`'s content
+	size: u64,
+}
+
+struct SegmentLayout {
+	/// The offset of the segment's size
+	size_offset: u64,
+	/// The length of the segment's size
+	size_len: u8,
+	/// The size of the segment's content, `None` if unknown
+	size: Option<u64>,
+	/// The offset of the segment's content, which all `SeekPosition`s are relative to
+	content_offset: u64,
+	/// The end of the segment's content
+	end: u64,
+	elements: Vec<Element>,
+	seek_head: Option<SeekHead>,
+	tags_seeks: Vec<TagsSeek>,
+}
+
+impl SegmentLayout {
+	fn read<R>(reader: &mut R, file_length: u64) -> Result<Self>
+	where
+		R: Read + Seek,
+	{
+		read_ebml_header(reader)?;
+
+		let segment_offset = reader.stream_position()?;
+		let segment = ElementHeader::read(reader)?;
+		if segment.id != SEGMENT_ID {
+			decode_err!(@BAIL Matroska, "File missing segment");
+		}
+
+		// The segment ID is always 4 bytes
+		let size_offset = segment_offset + 4;
+		let size_len = segment.len - 4;
+
+		let content_offset = reader.stream_position()?;
+		let end = segment
+			.size
+			.map_or(file_length, |size| (content_offset + size).min(file_length));
+
+		let mut elements = Vec::new();
+		let mut seek_head = None;
+		let mut tags_seeks = Vec::new();
+
+		let mut offset = content_offset;
+		while offset < end {
+			let header = ElementHeader::read(reader)?;
+
+			// Nothing past an element of unknown size can be located, anything we append will
+			// have to go at the end of the file
+			let Some(size) = header.size else {
+				break;
+			};
+
+			let content_offset = offset + u64::from(header.len);
+			let element = Element {
+				id: header.id,
+				offset,
+				len: u64::from(header.len) + size,
+			};
+
+			if header.id == SEEK_HEAD_ID {
+				let content = header.read_content(reader)?;
+				read_tags_seeks(&content, content_offset, &mut tags_seeks)?;
+
+				// The `SeekHead` ID is always 4 bytes
+				seek_head.get_or_insert(SeekHead {
+					element,
+					size_offset: offset + 4,
+					size_len: header.len - 4,
+					size,
+				});
+			} else {
+				header.skip(reader)?;
+			}
+
+			offset = element.end();
+			elements.push(element);
+		}
+
+		Ok(Self {
+			size_offset,
+			size_len,
+			size: segment.size,
+			content_offset,
+			end,
+			elements,
+			seek_head,
+			tags_seeks,
+		})
+	}
+
+	fn tags(&self) -> impl Iterator<Item = &Element> {
+		self.elements.iter().filter(|e| e.id == TAGS_ID)
+	}
+
+	/// Find a `Void` element directly following `element`
+	fn following_void(&self, element: &Element) -> Option<&Element> {
+		self.elements
+			.iter()
+			.find(|e| e.offset == element.end())
+			.filter(|e| e.id == VOID_ID)
+	}
+}
+
+// Find all `Seek` elements in a `SeekHead` that point to a `Tags` element
+fn read_tags_seeks(content: &[u8], offset: u64, tags_seeks: &mut Vec<TagsSeek>) -> Result<()> {
+	let tags_id = TAGS_ID.to_be_bytes();
+
+	for (seek, seek_content) in children_with_offsets(content, offset)? {
+		if seek.id != SEEK_ID {
+			continue;
+		}
+
+		let mut seek_id = None;
+		let mut position = None;
+		for (child, child_content) in children_with_offsets(seek_content, seek.offset)? {
+			match child.id {
+				SEEK_ID_ID => seek_id = Some(child_content),
+				SEEK_POSITION_ID => position = Some((child, child_content)),
+				_ => {},
+			}
+		}
+
+		if let (Some(seek_id), Some((position, position_content))) = (seek_id, position) {
+			if seek_id == tags_id {
+				let position_len = position_content.len() as u64;
+				tags_seeks.push(TagsSeek {
+					seek,
+					position_offset: position.end() - position_len,
+					position_len,
+				});
+			}
+		}
+	}
+
+	Ok(())
+}
+
+// Split a master element's content into its children, with their absolute offsets
+//
+// For each child, the second item is its content, which is located at the end of the element.
+fn children_with_offsets(content: &[u8], offset: u64) -> Result<Vec<(Element, &[u8])>> {
+	let mut children = Vec::new();
+
+	let mut reader = content;
+	while !reader.is_empty() {
+		let child_offset = offset + (content.len() - reader.len()) as u64;
+
+		let header = ElementHeader::read(&mut reader)?;
+		let size = header.known_size()?;
+		let Some(child_content) = usize::try_from(size).ok().and_then(|s| reader.get(..s)) else {
+			decode_err!(@BAIL Matroska, "Child element extends past its parent");
+		};
+
+		reader = &reader[child_content.len()..];
+		children.push((
+			Element {
+				id: header.id,
+				offset: child_offset,
+				len: u64::from(header.len) + size,
+			},
+			child_content,
+		));
+	}
+
+	Ok(children)
+}
+
+pub(crate) fn write_to<F>(
+	file: &mut F,
+	tag: &MatroskaTag,
+	write_options: WriteOptions,
+) -> Result<()>
+where
+	F: FileLike,
+	LoftyError: From<<F as Truncate>::Error>,
+	LoftyError: From<<F as Length>::Error>,
+{
+	let file_length = file.len()?;
+	let layout = SegmentLayout::read(file, file_length)?;
+
+	let new_tags = create_tags_element(&tag.items)?;
+	let existing_tags = layout.tags().copied().collect::<Vec<_>>();
+
+	if new_tags.is_empty() {
+		log::trace!("Removing all Tags elements");
+		for element in existing_tags {
+			file.seek(SeekFrom::Start(element.offset))?;
+			write_void(file, element.len)?;
+		}
+
+		for tags_seek in &layout.tags_seeks {
+			file.seek(SeekFrom::Start(tags_seek.seek.offset))?;
+			write_void(file, tags_seek.seek.len)?;
+		}
+
+		return Ok(());
+	}
+
+	let new_len = new_tags.len() as u64;
+
+	// We can get away with overwriting the existing element if there's enough space
+	if let [existing] = existing_tags[..] {
+		let mut available = existing.len;
+		if let Some(void) = layout.following_void(&existing) {
+			available += void.len;
+		}
+
+		if new_len == available || new_len + MIN_VOID_SIZE <= available {
+			log::trace!("Overwriting existing Tags element");
+
+			file.seek(SeekFrom::Start(existing.offset))?;
+			file.write_all(&new_tags)?;
+			if available > new_len {
+				write_void(file, available - new_len)?;
+			}
+
+			return Ok(());
+		}
+	}
+
+	let mut padding = Vec::new();
+	if let Some(preferred_padding) = write_options.preferred_padding {
+		let preferred_padding = u64::from(preferred_padding);
+		if preferred_padding >= MIN_VOID_SIZE {
+			write_void(&mut padding, preferred_padding)?;
+		}
+	}
+
+	let appended_len = new_len + padding.len() as u64;
+
+	// Verify the segment can hold the new element before modifying anything
+	let new_segment_size = match layout.size {
+		Some(size) => {
+			let new_size = size + appended_len;
+			if new_size >= vint_max(layout.size_len) {
+				err!(TooMuchData);
+			}
+
+			Some(new_size)
+		},
+		None => None,
+	};
+
+	log::trace!("Appending new Tags element to the end of the segment");
+
+	for element in existing_tags {
+		file.seek(SeekFrom::Start(element.offset))?;
+		write_void(file, element.len)?;
+	}
+
+	let tags_offset = layout.end;
+
+	// Anything after the segment needs to be shifted
+	let mut trailing = Vec::new();
+	file.seek(SeekFrom::Start(tags_offset))?;
+	file.read_to_end(&mut trailing)?;
+
+	file.seek(SeekFrom::Start(tags_offset))?;
+	file.write_all(&new_tags)?;
+	file.write_all(&padding)?;
+	file.write_all(&trailing)?;
+
+	if let Some(new_segment_size) = new_segment_size {
+		file.seek(SeekFrom::Start(layout.size_offset))?;
+		write_vint(file, new_segment_size, layout.size_len)?;
+	}
+
+	update_tags_seeks(file, &layout, tags_offset - layout.content_offset)
+}
+
+// Point the first `Seek` referencing a `Tags` element to the new position, if it fits
+//
+// If there is no such `Seek`, one is added to the `SeekHead`.
+fn update_tags_seeks<F>(file: &mut F, layout: &SegmentLayout, position: u64) -> Result<()>
+where
+	F: Write + Seek,
+{
+	let mut tags_seeks = layout.tags_seeks.iter();
+
+	let mut updated = false;
+	if let Some(tags_seek) = tags_seeks.next() {
+		let position_len = tags_seek.position_len;
+		if (1..=8).contains(&position_len) && position < 1 << (position_len * 8).min(63) {
+			let bytes = position.to_be_bytes();
+
+			file.seek(SeekFrom::Start(tags_seek.position_offset))?;
+			file.write_all(&bytes[(8 - position_len as usize)..])?;
+			updated = true;
+		} else {
+			file.seek(SeekFrom::Start(tags_seek.seek.offset))?;
+			write_void(file, tags_seek.seek.len)?;
+		}
+	}
+
+	// Any others would point to voided elements
+	for tags_seek in tags_seeks {
+		file.seek(SeekFrom::Start(tags_seek.seek.offset))?;
+		write_void(file, tags_seek.seek.len)?;
+	}
+
+	if updated {
+		return Ok(());
+	}
+
+	add_tags_seek(file, layout, position)
+}
+
+// Add a `Seek` referencing the `Tags` element to the `SeekHead`, using the `Void` element following it
+fn add_tags_seek<F>(file: &mut F, layout: &SegmentLayout, position: u64) -> Result<()>
+where
+	F: Write + Seek,
+{
+	let Some(seek_head) = layout.seek_head else {
+		log::debug!("No SeekHead found, not adding a Seek for the Tags element");
+		return Ok(());
+	};
+
+	let mut seek_content = Vec::new();
+	write_element(&mut seek_content, SEEK_ID_ID, &TAGS_ID.to_be_bytes())?;
+	write_uint_element(&mut seek_content, SEEK_POSITION_ID, position)?;
+
+	let mut seek = Vec::new();
+	write_element(&mut seek, SEEK_ID, &seek_content)?;
+
+	let seek_len = seek.len() as u64;
+	let new_size = seek_head.size + seek_len;
+
+	let Some(void) = layout
+		.following_void(&seek_head.element)
+		.filter(|void| void.len == seek_len || seek_len + MIN_VOID_SIZE <= void.len)
+		.filter(|_| new_size < vint_max(seek_head.size_len))
+	else {
+		log::warn!("No room in the SeekHead for the Tags element, it will not be referenced");
+		return Ok(());
+	};
+
+	log::trace!("Adding a Seek for the Tags element to the SeekHead");
+
+	file.seek(SeekFrom::Start(void.offset))?;
+	file.write_all(&seek)?;
+	if void.len > seek_len {
+		write_void(file, void.len - seek_len)?;
+	}
+
+	file.seek(SeekFrom::Start(seek_head.size_offset))?;
+	write_vint(file, new_size, seek_head.size_len)?;
+
+	Ok(())
+}
+
+/// Create a `Tags` element, with a `Tag` for each distinct [`Target`]
+///
+/// This will be empty if there are no items.
+pub(crate) fn create_tags_element(items: &[SimpleTag]) -> Result<Vec<u8>> {
+	if items.is_empty() {
+		return Ok(Vec::new());
+	}
+
+	// Group the items by their targets, keeping the original order
+	let mut targets: Vec<(&Target, Vec<&SimpleTag>)> = Vec::new();
+	for item in items {
+		match targets
+			.iter_mut()
+			.find(|(target, _)| **target == item.target)
+		{
+			Some((_, group)) => group.push(item),
+			None => targets.push((&item.target, vec![item])),
+		}
+	}
+
+	let mut tags_content = Vec::new();
+	for (target, group) in targets {
+		let mut tag_content = Vec::new();
+		write_element(&mut tag_content, TARGETS_ID, &create_targets(target)?)?;
+
+		for item in group {
+			write_element(&mut tag_content, SIMPLE_TAG_ID, &create_simple_tag(item)?)?;
+		}
+
+		write_element(&mut tags_content, TAG_ID, &tag_content)?;
+	}
+
+	let mut tags = Vec::new();
+	write_element(&mut tags, TAGS_ID, &tags_content)?;
+	Ok(tags)
+}
+
+fn create_targets(target: &Target) -> Result<Vec<u8>> {
+	let mut content = Vec::new();
+
+	// The default level doesn't need to be written
+	if target.target_type != TargetType::Album {
+		write_uint_element(
+			&mut content,
+			TARGET_TYPE_VALUE_ID,
+			u64::from(target.target_type as u8),
+		)?;
+	}
+
+	if let Some(name) = &target.name {
+		write_element(&mut content, TARGET_TYPE_ID, name.as_bytes())?;
+	}
+
+	for (id, uids) in [
+		(TAG_TRACK_UID_ID, &target.track_uids),
+		(TAG_EDITION_UID_ID, &target.edition_uids),
+		(TAG_CHAPTER_UID_ID, &target.chapter_uids),
+		(TAG_ATTACHMENT_UID_ID, &target.attachment_uids),
+	] {
+		for uid in uids {
+			write_uint_element(&mut content, id, *uid)?;
+		}
+	}
+
+	Ok(content)
+}
+
+fn create_simple_tag(item: &SimpleTag) -> Result<Vec<u8>> {
+	let mut content = Vec::new();
+
+	write_element(&mut content, TAG_NAME_ID, item.name.as_bytes())?;
+	write_element(&mut content, TAG_LANGUAGE_ID, item.language.as_bytes())?;
+	write_uint_element(&mut content, TAG_DEFAULT_ID, u64::from(item.default))?;
+
+	match &item.value {
+		SimpleTagValue::String(value) => {
+			write_element(&mut content, TAG_STRING_ID, value.as_bytes())?;
+		},
+		SimpleTagValue::Binary(value) => write_element(&mut content, TAG_BINARY_ID, value)?,
+	}
+
+	Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{write_to, SegmentLayout};
+	use crate::config::{ParsingMode, WriteOptions};
+	use crate::matroska::element::{write_void, ElementHeader};
+	use crate::matroska::tag::read::read_tags;
+	use crate::matroska::{MatroskaTag, SimpleTag, SimpleTagValue, TargetType};
+
+	use std::io::Cursor;
+
+	#[test_log::test]
+	fn tags_seek_added() {
+		let mut file =
+			crate::tag::utils::test_utils::read_path("tests/files/assets/minimal/full_test.mka");
+
+		// Remove the `Seek` referencing the `Tags` element
+		let layout = SegmentLayout::read(&mut Cursor::new(&file), file.len() as u64).unwrap();
+		let [tags_seek] = layout.tags_seeks[..] else {
+			panic!("Expected a single Seek for the Tags element");
+		};
+		write_void(
+			&mut &mut file[tags_seek.seek.offset as usize..],
+			tags_seek.seek.len,
+		)
+		.unwrap();
+
+		// Too large to fit in place, the tags will be appended to the segment
+		let mut tag = MatroskaTag::default();
+		tag.push(SimpleTag::new(
+			TargetType::Album,
+			String::from("TITLE"),
+			SimpleTagValue::String("Foo title ".repeat(50)),
+		));
+
+		let mut file = Cursor::new(file);
+		write_to(&mut file, &tag, WriteOptions::default()).unwrap();
+
+		let file = file.into_inner();
+		let layout = SegmentLayout::read(&mut Cursor::new(&file), file.len() as u64).unwrap();
+
+		let tags = layout.tags().copied().collect::<Vec<_>>();
+		let ([tags], [tags_seek]) = (&tags[..], &layout.tags_seeks[..]) else {
+			panic!("Expected a single Tags element, referenced by a single Seek");
+		};
+
+		let start = tags_seek.position_offset as usize;
+		let end = start + tags_seek.position_len as usize;
+		let position = file[start..end]
+			.iter()
+			.fold(0, |position, byte| (position << 8) | u64::from(*byte));
+		assert_eq!(position, tags.offset - layout.content_offset);
+	}
+
+	#[test_log::test]
+	fn tags_round_trip() {
+		let mut tag = MatroskaTag::default();
+		tag.push(SimpleTag::new(
+			TargetType::Track,
+			String::from("TITLE"),
+			SimpleTagValue::String(String::from("Foo title")),
+		));
+		tag.push(SimpleTag::new(
+			TargetType::Album,
+			String::from("TITLE"),
+			SimpleTagValue::String(String::from("Bar album")),
+		));
+
+		let mut binary = SimpleTag::new(
+			TargetType::Track,
+			String::from("BINARY"),
+			SimpleTagValue::Binary(vec![1, 2, 3]),
+		);
+		binary.language = String::from("eng");
+		binary.default = false;
+		binary.target.track_uids.push(1234);
+		tag.push(binary);
+
+		let tags_element = super::create_tags_element(&tag.items).unwrap();
+
+		let mut reader = &tags_element[..];
+		let header = ElementHeader::read(&mut reader).unwrap();
+		let content = header.read_content(&mut reader).unwrap();
+
+		let mut parsed = MatroskaTag::default();
+		read_tags(&content, &mut parsed, ParsingMode::Strict).unwrap();
+
+		assert_eq!(parsed, tag);
+	}
+}
@@ -0,0 +1,635 @@
+//! 3GPP asset information
+//!
+//! 3GPP files, along with many phone recordings, store their metadata in "asset" atoms directly in
+//! `moov.udta` (see 3GPP TS 26.244), rather than in an `ilst` atom. Unlike `ilst` atoms, each of these
+//! has a language.
+
+use super::atom_info::{AtomIdent, AtomInfo, ATOM_HEADER_LEN};
+use crate::error::Result;
+use crate::macros::{err, try_vec};
+use crate::tag::items::Lang;
+use crate::tag::{ItemKey, ItemValue, Tag, TagItem};
+use crate::util::text::{utf16_decode_bytes, utf8_decode};
+
+use std::io::{Read, Write};
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+
+// Longitude, latitude, and altitude are stored as 16.16 fixed point numbers
+const FIXED_POINT_SCALE: f64 = 65536.0;
+
+/// The type of a 3GPP text asset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AssetTextType {
+	/// Title (`titl`)
+	Title,
+	/// Performer (`perf`)
+	Performer,
+	/// Author (`auth`)
+	Author,
+	/// Genre (`gnre`)
+	Genre,
+	/// Description (`dscp`)
+	Description,
+	/// Copyright notice (`cprt`)
+	Copyright,
+}
+
+impl AssetTextType {
+	const ALL: [Self; 6] = [
+		Self::Title,
+		Self::Performer,
+		Self::Author,
+		Self::Genre,
+		Self::Description,
+		Self::Copyright,
+	];
+
+	/// The FOURCC of the asset's atom
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::mp4::AssetTextType;
+	///
+	/// assert_eq!(AssetTextType::Title.fourcc(), *b"titl");
+	/// ```
+	pub fn fourcc(self) -> [u8; 4] {
+		match self {
+			Self::Title => *b"titl",
+			Self::Performer => *b"perf",
+			Self::Author => *b"auth",
+			Self::Genre => *b"gnre",
+			Self::Description => *b"dscp",
+			Self::Copyright => *b"cprt",
+		}
+	}
+
+	/// Get an `AssetTextType` from the FOURCC of its atom
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::mp4::AssetTextType;
+	///
+	/// assert_eq!(
+	/// 	AssetTextType::from_fourcc(*b"perf"),
+	/// 	Some(AssetTextType::Performer)
+	/// );
+	/// assert_eq!(AssetTextType::from_fourcc(*b"\xa9nam"), None);
+	/// ```
+	pub fn from_fourcc(fourcc: [u8; 4]) -> Option<Self> {
+		Self::ALL.into_iter().find(|ty| ty.fourcc() == fourcc)
+	}
+
+	fn item_key(self) -> ItemKey {
+		match self {
+			Self::Title => ItemKey::TrackTitle,
+			Self::Performer => ItemKey::TrackArtist,
+			Self::Author => ItemKey::Composer,
+			Self::Genre => ItemKey::Genre,
+			Self::Description => ItemKey::Description,
+			Self::Copyright => ItemKey::CopyrightMessage,
+		}
+	}
+}
+
+/// A 3GPP text asset, such as a title
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetText {
+	/// The language of the text
+	pub language: Lang,
+	/// The text
+	pub text: String,
+}
+
+/// A 3GPP album asset (`albm`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetAlbum {
+	/// The language of the title
+	pub language: Lang,
+	/// The album title
+	pub title: String,
+	/// The track number on the album, if known
+	pub track_number: Option<u8>,
+}
+
+/// A 3GPP location asset (`loci`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetLocation {
+	/// The language of the text
+	pub language: Lang,
+	/// The name of the location
+	pub name: String,
+	/// The role of the location
+	///
+	/// This is 0 for the shooting location, 1 for the real location, and 2 for a fictional location.
+	pub role: u8,
+	/// The longitude, in degrees
+	pub longitude: f64,
+	/// The latitude, in degrees
+	pub latitude: f64,
+	/// The altitude, in meters
+	pub altitude: f64,
+	/// The astronomical body (Ex. "earth")
+	pub astronomical_body: String,
+	/// Any additional notes on the location
+	pub additional_notes: String,
+}
+
+/// 3GPP asset information
+///
+/// These are the `titl`, `perf`, `auth`, `gnre`, `dscp`, `cprt`, `yrrc`, `albm`, and `loci` atoms in `moov.udta`.
+///
+/// ## Conversions
+///
+/// When converting an [`Ilst`](crate::mp4::Ilst) to a [`Tag`], the text assets, year, and album are
+/// mapped to their [`ItemKey`] equivalents, if the `ilst` atom doesn't already provide them.
+/// The location has no equivalent.
+///
+/// When converting back, only those assets that already exist will be updated. Values that came from
+/// the assets are only written back to them, not to the `ilst` atom.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct AssetInformation {
+	texts: Vec<(AssetTextType, AssetText)>,
+	year: Option<u16>,
+	album: Option<AssetAlbum>,
+	location: Option<AssetLocation>,
+}
+
+impl AssetInformation {
+	/// Create a new empty `AssetInformation`
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::mp4::AssetInformation;
+	///
+	/// let assets = AssetInformation::new();
+	/// assert!(assets.is_empty());
+	/// ```
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Get the first text asset of a type
+	///
+	/// A file can store a text asset in multiple languages, see [`AssetInformation::texts`].
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::mp4::{AssetInformation, AssetText, AssetTextType};
+	///
+	/// let mut assets = AssetInformation::new();
+	/// assets.insert_text(
+	/// 	AssetTextType::Title,
+	/// 	AssetText {
+	/// 		language: *b"eng",
+	/// 		text: String::from("Foo title"),
+	/// 	},
+	/// );
+	///
+	/// let title = assets.text(AssetTextType::Title).unwrap();
+	/// assert_eq!(title.text, "Foo title");
+	/// ```
+	pub fn text(&self, ty: AssetTextType) -> Option<&AssetText> {
+		self.texts(ty).next()
+	}
+
+	/// Get all text assets of a type
+	pub fn texts(&self, ty: AssetTextType) -> impl Iterator<Item = &AssetText> {
+		self.texts
+			.iter()
+			.filter(move |(text_ty, _)| *text_ty == ty)
+			.map(|(_, text)| text)
+	}
+
+	/// Insert a text asset, replacing any of the same type and language
+	pub fn insert_text(&mut self, ty: AssetTextType, text: AssetText) {
+		self.texts
+			.retain(|(text_ty, existing)| *text_ty != ty || existing.language != text.language);
+		self.texts.push((ty, text));
+	}
+
+	/// Remove all text assets of a type
+	pub fn remove_text(&mut self, ty: AssetTextType) {
+		self.texts.retain(|(text_ty, _)| *text_ty != ty);
+	}
+
+	/// Returns the recording year (`yrrc`)
+	pub fn year(&self) -> Option<u16> {
+		self.year
+	}
+
+	/// Set the recording year (`yrrc`)
+	pub fn set_year(&mut self, year: u16) {
+		self.year = Some(year);
+	}
+
+	/// Remove the recording year (`yrrc`)
+	pub fn remove_year(&mut self) {
+		self.year = None;
+	}
+
+	/// Returns the album (`albm`)
+	pub fn album(&self) -> Option<&AssetAlbum> {
+		self.album.as_ref()
+	}
+
+	/// Set the album (`albm`)
+	pub fn set_album(&mut self, album: AssetAlbum) {
+		self.album = Some(album);
+	}
+
+	/// Remove the album (`albm`)
+	pub fn remove_album(&mut self) {
+		self.album = None;
+	}
+
+	/// Returns the location (`loci`)
+	pub fn location(&self) -> Option<&AssetLocation> {
+		self.location.as_ref()
+	}
+
+	/// Set the location (`loci`)
+	pub fn set_location(&mut self, location: AssetLocation) {
+		self.location = Some(location);
+	}
+
+	/// Remove the location (`loci`)
+	pub fn remove_location(&mut self) {
+		self.location = None;
+	}
+
+	/// Whether there are no assets
+	pub fn is_empty(&self) -> bool {
+		self.texts.is_empty()
+			&& self.year.is_none()
+			&& self.album.is_none()
+			&& self.location.is_none()
+	}
+
+	/// Remove all assets
+	pub fn clear(&mut self) {
+		*self = Self::default();
+	}
+
+	// Copies the assets with an `ItemKey` equivalent into `tag`, unless it already has them
+	pub(super) fn copy_to_tag(&self, tag: &mut Tag) {
+		for ty in AssetTextType::ALL {
+			let item_key = ty.item_key();
+			if tag.get(&item_key).is_some() {
+				continue;
+			}
+
+			for text in self.texts(ty) {
+				tag.push(TagItem::new(
+					item_key.clone(),
+					ItemValue::Text(text.text.clone()),
+				));
+			}
+		}
+
+		if let Some(year) = self.year {
+			if tag.get(&ItemKey::RecordingDate).is_none() {
+				tag.insert_text(ItemKey::RecordingDate, year.to_string());
+			}
+		}
+
+		if let Some(album) = &self.album {
+			if tag.get(&ItemKey::AlbumTitle).is_none() {
+				tag.insert_text(ItemKey::AlbumTitle, album.title.clone());
+			}
+
+			if let Some(track_number) = album.track_number {
+				if tag.get(&ItemKey::TrackNumber).is_none() {
+					tag.insert_text(ItemKey::TrackNumber, track_number.to_string());
+				}
+			}
+		}
+	}
+
+	// Updates the existing assets with an `ItemKey` equivalent from `tag`
+	//
+	// Assets that `tag` no longer has are removed.
+	pub(super) fn update_from_tag(&mut self, tag: &Tag) {
+		for ty in AssetTextType::ALL {
+			let item_key = ty.item_key();
+			let mut values = tag.get_strings(&item_key);
+
+			// Only the first of each type is updated, keeping its language
+			let mut first = true;
+			self.texts.retain_mut(|(text_ty, text)| {
+				if *text_ty != ty {
+					return true;
+				}
+
+				let keep = first;
+				first = false;
+
+				match values.next() {
+					Some(value) if keep => {
+						value.clone_into(&mut text.text);
+						true
+					},
+					_ => false,
+				}
+			});
+		}
+
+		if self.year.is_some() {
+			// Only the year of a date is stored
+			self.year = tag
+				.get_string(&ItemKey::RecordingDate)
+				.and_then(|date| date.get(..4))
+				.and_then(|year| year.parse().ok());
+		}
+
+		if let Some(album) = &mut self.album {
+			let Some(title) = tag.get_string(&ItemKey::AlbumTitle) else {
+				self.album = None;
+				return;
+			};
+
+			title.clone_into(&mut album.title);
+			album.track_number = tag
+				.get_string(&ItemKey::TrackNumber)
+				.and_then(|track_number| track_number.parse().ok());
+		}
+	}
+}
+
+pub(super) fn is_asset(fourcc: [u8; 4]) -> bool {
+	matches!(&fourcc, b"yrrc" | b"albm" | b"loci") || AssetTextType::from_fourcc(fourcc).is_some()
+}
+
+/// Parse an asset atom, adding it to `assets`
+///
+/// NOTE: This expects the reader to be at the start of the atom's content.
+pub(super) fn parse_asset<R>(
+	reader: &mut R,
+	atom: &AtomInfo,
+	assets: &mut AssetInformation,
+) -> Result<()>
+where
+	R: Read,
+{
+	let AtomIdent::Fourcc(fourcc) = atom.ident else {
+		return Ok(());
+	};
+
+	let mut content = try_vec![0; (atom.len - atom.header_size()) as usize];
+	reader.read_exact(&mut content)?;
+
+	let mut content = &content[..];
+
+	let _version_flags = content.read_u32::<BigEndian>()?;
+
+	if &fourcc == b"yrrc" {
+		if assets.year.is_some() {
+			log::warn!("Encountered multiple `yrrc` atoms, discarding");
+			return Ok(());
+		}
+
+		assets.year = Some(content.read_u16::<BigEndian>()?);
+		return Ok(());
+	}
+
+	let language = decode_language(content.read_u16::<BigEndian>()?);
+	let text = read_string(&mut content)?;
+
+	match &fourcc {
+		b"albm" => {
+			if assets.album.is_some() {
+				log::warn!("Encountered multiple `albm` atoms, discarding");
+				return Ok(());
+			}
+
+			// The track number is optional, with 0 meaning unknown
+			let track_number = content.read_u8().ok().filter(|n| *n > 0);
+
+			assets.album = Some(AssetAlbum {
+				language,
+				title: text,
+				track_number,
+			});
+		},
+		b"loci" => {
+			if assets.location.is_some() {
+				log::warn!("Encountered multiple `loci` atoms, discarding");
+				return Ok(());
+			}
+
+			let role = content.read_u8()?;
+			let longitude = f64::from(content.read_i32::<BigEndian>()?) / FIXED_POINT_SCALE;
+			let latitude = f64::from(content.read_i32::<BigEndian>()?) / FIXED_POINT_SCALE;
+			let altitude = f64::from(content.read_i32::<BigEndian>()?) / FIXED_POINT_SCALE;
+			let astronomical_body = read_string(&mut content)?;
+			let additional_notes = read_string(&mut content)?;
+
+			assets.location = Some(AssetLocation {
+				language,
+				name: text,
+				role,
+				longitude,
+				latitude,
+				altitude,
+				astronomical_body,
+				additional_notes,
+			});
+		},
+		_ => {
+			if let Some(ty) = AssetTextType::from_fourcc(fourcc) {
+				assets.texts.push((ty, AssetText { language, text }));
+			}
+		},
+	}
+
+	Ok(())
+}
+
+/// Create the asset atoms
+///
+/// This will return an empty `Vec` if there are no assets to write.
+pub(super) fn build_assets(assets: &AssetInformation) -> Result<Vec<u8>> {
+	let mut ret = Vec::new();
+
+	for (ty, text) in &assets.texts {
+		let mut content = Vec::new();
+		content.write_u16::<BigEndian>(encode_language(text.language))?;
+		write_string(&mut content, &text.text)?;
+
+		write_asset(&mut ret, ty.fourcc(), &content)?;
+	}
+
+	if let Some(year) = assets.year {
+		write_asset(&mut ret, *b"yrrc", &year.to_be_bytes())?;
+	}
+
+	if let Some(album) = &assets.album {
+		let mut content = Vec::new();
+		content.write_u16::<BigEndian>(encode_language(album.language))?;
+		write_string(&mut content, &album.title)?;
+
+		if let Some(track_number) = album.track_number {
+			content.write_u8(track_number)?;
+		}
+
+		write_asset(&mut ret, *b"albm", &content)?;
+	}
+
+	if let Some(location) = &assets.location {
+		let mut content = Vec::new();
+		content.write_u16::<BigEndian>(encode_language(location.language))?;
+		write_string(&mut content, &location.name)?;
+		content.write_u8(location.role)?;
+
+		for value in [location.longitude, location.latitude, location.altitude] {
+			content.write_i32::<BigEndian>((value * FIXED_POINT_SCALE).round() as i32)?;
+		}
+
+		write_string(&mut content, &location.astronomical_body)?;
+		write_string(&mut content, &location.additional_notes)?;
+
+		write_asset(&mut ret, *b"loci", &content)?;
+	}
+
+	Ok(ret)
+}
+
+fn write_asset(writer: &mut Vec<u8>, fourcc: [u8; 4], content: &[u8]) -> Result<()> {
+	// Header + version (1) + flags (3)
+	let Ok(size) = u32::try_from(ATOM_HEADER_LEN as usize + 4 + content.len()) else {
+		err!(TooMuchData);
+	};
+
+	writer.write_u32::<BigEndian>(size)?;
+	writer.write_all(&fourcc)?;
+	writer.write_u32::<BigEndian>(0)?;
+	writer.write_all(content)?;
+
+	Ok(())
+}
+
+// Pad (1 bit)
+// Language (15 bits), three 5 bit characters, each offset by 0x60
+pub(super) fn decode_language(packed_language: u16) -> Lang {
+	[10, 5, 0].map(|shift| (((packed_language >> shift) & 0x1F) as u8) + 0x60)
+}
+
+fn encode_language(language: Lang) -> u16 {
+	language.iter().fold(0, |packed, c| {
+		(packed << 5) | (u16::from(c.wrapping_sub(0x60)) & 0x1F)
+	})
+}
+
+// A null terminated string, which is UTF-16 if it starts with a BOM, and UTF-8 otherwise
+fn read_string(content: &mut &[u8]) -> Result<String> {
+	let bytes = *content;
+
+	if bytes.starts_with(&[0xFE, 0xFF]) {
+		let end = bytes
+			.chunks_exact(2)
+			.position(|c| c == [0, 0])
+			.map_or(bytes.len(), |pos| pos * 2);
+
+		let text = utf16_decode_bytes(&bytes[..end], u16::from_be_bytes)?;
+		*content = bytes.get(end + 2..).unwrap_or_default();
+
+		return Ok(text);
+	}
+
+	let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+
+	let text = utf8_decode(bytes[..end].to_vec())?;
+	*content = bytes.get(end + 1..).unwrap_or_default();
+
+	Ok(text)
+}
+
+fn write_string(writer: &mut Vec<u8>, text: &str) -> Result<()> {
+	writer.write_all(text.as_bytes())?;
+	writer.write_u8(0)?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test_log::test]
+	fn language() {
+		let packed = encode_language(*b"eng");
+		assert_eq!(packed, 0x15C7);
+		assert_eq!(decode_language(packed), *b"eng");
+	}
+
+	#[test_log::test]
+	fn assets_round_trip() {
+		let mut assets = AssetInformation::new();
+		assets.insert_text(
+			AssetTextType::Title,
+			AssetText {
+				language: *b"eng",
+				text: String::from("Foo title"),
+			},
+		);
+		assets.insert_text(
+			AssetTextType::Title,
+			AssetText {
+				language: *b"deu",
+				text: String::from("Foo Titel"),
+			},
+		);
+		assets.set_year(2024);
+		assets.set_album(AssetAlbum {
+			language: *b"eng",
+			title: String::from("Foo album"),
+			track_number: Some(3),
+		});
+		assets.set_location(AssetLocation {
+			language: *b"eng",
+			name: String::from("Foo location"),
+			role: 0,
+			longitude: -122.25,
+			latitude: 37.5,
+			altitude: 10.0,
+			astronomical_body: String::from("earth"),
+			additional_notes: String::new(),
+		});
+
+		let bytes = build_assets(&assets).unwrap();
+
+		let mut parsed = AssetInformation::new();
+		let mut reader = &bytes[..];
+		while !reader.is_empty() {
+			let len = u32::from_be_bytes(reader[..4].try_into().unwrap()) as usize;
+			let atom = AtomInfo {
+				start: 0,
+				len: len as u64,
+				extended: false,
+				ident: AtomIdent::Fourcc(reader[4..8].try_into().unwrap()),
+			};
+
+			let mut content = &reader[8..len];
+			parse_asset(&mut content, &atom, &mut parsed).unwrap();
+
+			reader = &reader[len..];
+		}
+
+		assert_eq!(parsed, assets);
+		assert_eq!(parsed.texts(AssetTextType::Title).count(), 2);
+	}
+
+	#[test_log::test]
+	fn utf16_string() {
+		let mut content = &[
+			0xFE, 0xFF, 0x00, b'F', 0x00, b'o', 0x00, b'o', 0x00, 0x00, 0x01,
+		][..];
+		assert_eq!(read_string(&mut content).unwrap(), "Foo");
+		assert_eq!(content, &[0x01]);
+	}
+}
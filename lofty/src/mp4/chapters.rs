@@ -0,0 +1,748 @@
+//! MP4 chapter handling
+//!
+//! Chapters can be stored in two ways:
+//!
+//! * A Nero-style `chpl` atom in `moov.udta`
+//! * A QuickTime text track, referenced by another track's `tref.chap` atom
+//!
+//! Both are read, with the `chpl` atom taking precedence. When writing, the chapters are written back
+//! to whichever of the two the file already has. A `chpl` atom is only created if the file has neither.
+
+use super::atom_info::{AtomIdent, AtomInfo, ATOM_HEADER_LEN};
+use super::read::find_child_atom;
+use super::write::ContextualAtom;
+use crate::config::ParsingMode;
+use crate::error::Result;
+use crate::macros::{err, try_vec};
+use crate::tag::items::{chapter_end, Chapter};
+use crate::util::text::{utf16_decode_bytes, utf8_decode};
+
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::time::Duration;
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+
+// `chpl` timestamps are stored in units of 100 nanoseconds
+const CHPL_UNIT_NANOS: u64 = 100;
+
+/// Parse a `chpl` atom
+///
+/// NOTE: This expects the reader to be at the start of the atom's content.
+pub(super) fn parse_chpl<R>(reader: &mut R, atom: &AtomInfo) -> Result<Vec<Chapter>>
+where
+	R: Read,
+{
+	let content = read_content(reader, atom)?;
+	let mut content = &content[..];
+
+	let version = content.read_u8()?;
+	let _flags = content.read_u24::<BigEndian>()?;
+
+	if version > 0 {
+		// Reserved, always zero
+		let _reserved = content.read_u32::<BigEndian>()?;
+	}
+
+	let chapter_count = content.read_u8()?;
+
+	let mut chapters = Vec::with_capacity(usize::from(chapter_count));
+	for _ in 0..chapter_count {
+		let start = content.read_u64::<BigEndian>()?;
+
+		let title_len = content.read_u8()?;
+		let mut title = try_vec![0; usize::from(title_len)];
+		content.read_exact(&mut title)?;
+
+		let mut chapter = Chapter::new(Duration::from_nanos(start.saturating_mul(CHPL_UNIT_NANOS)));
+
+		match utf8_decode(title) {
+			Ok(title) if !title.is_empty() => chapter.title = Some(title),
+			Ok(_) => {},
+			Err(_) => log::warn!("Chapter title is not valid UTF-8, discarding"),
+		}
+
+		chapters.push(chapter);
+	}
+
+	Ok(chapters)
+}
+
+/// Create a `chpl` atom
+///
+/// This will return an empty `Vec` if there are no chapters to write.
+///
+/// NOTE: A `chpl` atom can only hold 255 chapters, anything past that will be discarded.
+pub(super) fn build_chpl(chapters: &[Chapter]) -> Result<Vec<u8>> {
+	if chapters.is_empty() {
+		return Ok(Vec::new());
+	}
+
+	log::debug!("Building `chpl` atom");
+
+	let mut chapters = chapters;
+	if chapters.len() > usize::from(u8::MAX) {
+		log::warn!(
+			"Discarding {} chapters, a `chpl` atom can only hold {}",
+			chapters.len() - usize::from(u8::MAX),
+			u8::MAX
+		);
+		chapters = &chapters[..usize::from(u8::MAX)];
+	}
+
+	let mut content = Vec::new();
+
+	// Version 1 is the only one understood by every reader
+	content.write_u8(1)?;
+	content.write_u24::<BigEndian>(0)?;
+	content.write_u32::<BigEndian>(0)?;
+
+	content.write_u8(chapters.len() as u8)?;
+
+	for chapter in chapters {
+		let start = chapter.start.as_nanos() / u128::from(CHPL_UNIT_NANOS);
+		content.write_u64::<BigEndian>(u64::try_from(start).unwrap_or(u64::MAX))?;
+
+		let title = chapter.title.as_deref().unwrap_or_default();
+		let title = truncate_to_char_boundary(title, usize::from(u8::MAX));
+
+		content.write_u8(title.len() as u8)?;
+		content.write_all(title.as_bytes())?;
+	}
+
+	build_atom(*b"chpl", &content)
+}
+
+/// Read the chapters from the first QuickTime chapter track
+///
+/// NOTE: This expects the reader to be unbounded, as it seeks to absolute positions.
+pub(super) fn read_quicktime_chapters<R>(
+	reader: &mut R,
+	moov: &AtomInfo,
+	parse_mode: ParsingMode,
+) -> Result<Vec<Chapter>>
+where
+	R: Read + Seek,
+{
+	reader.seek(SeekFrom::Start(moov.start))?;
+
+	let mut len = moov.len;
+	let Some(moov) = ContextualAtom::read(reader, &mut len, parse_mode)? else {
+		return Ok(Vec::new());
+	};
+
+	match ChapterTrack::find(reader, &moov, parse_mode)? {
+		Some(track) => track.read_chapters(reader),
+		None => Ok(Vec::new()),
+	}
+}
+
+/// A QuickTime chapter track
+///
+/// A chapter track is a text track that is referenced by another track's `tref.chap` atom. Each sample
+/// in the track holds the title of a single chapter, and lasts until the start of the next.
+pub(super) struct ChapterTrack<'a> {
+	pub(super) trak: &'a ContextualAtom,
+	pub(super) mdia: &'a ContextualAtom,
+	pub(super) minf: &'a ContextualAtom,
+	pub(super) stbl: &'a ContextualAtom,
+	mdhd: &'a AtomInfo,
+	pub(super) timescale: u32,
+	/// The duration of the track, in `timescale` units
+	pub(super) duration: u64,
+}
+
+impl<'a> ChapterTrack<'a> {
+	/// Finds the first chapter track in `moov`
+	pub(super) fn find<R>(
+		reader: &mut R,
+		moov: &'a ContextualAtom,
+		parse_mode: ParsingMode,
+	) -> Result<Option<Self>>
+	where
+		R: Read + Seek,
+	{
+		let traks = moov
+			.children
+			.iter()
+			.filter(|atom| atom.info.ident == AtomIdent::Fourcc(*b"trak"))
+			.collect::<Vec<_>>();
+
+		let mut track_ids = Vec::with_capacity(traks.len());
+		let mut chapter_track_id = None;
+		for trak in &traks {
+			let mut track_id = None;
+			if let Some(tkhd) = find_child(trak, *b"tkhd") {
+				skip_to_after_timestamps(reader, &tkhd.info)?;
+				track_id = Some(reader.read_u32::<BigEndian>()?);
+			}
+
+			if chapter_track_id.is_none() {
+				chapter_track_id = read_chapter_reference(reader, trak, parse_mode)?;
+			}
+
+			track_ids.push(track_id);
+		}
+
+		// Only the first chapter track is used
+		let Some(chapter_track_id) = chapter_track_id else {
+			return Ok(None);
+		};
+
+		log::debug!("Found a reference to chapter track {chapter_track_id}");
+
+		let Some(trak) = track_ids
+			.iter()
+			.position(|id| *id == Some(chapter_track_id))
+			.map(|pos| traks[pos])
+		else {
+			log::warn!("Chapter track {chapter_track_id} does not exist");
+			return Ok(None);
+		};
+
+		let Some(mdia) = find_child(trak, *b"mdia") else {
+			return Ok(None);
+		};
+
+		let (Some(mdhd), Some(hdlr), Some(minf)) = (
+			find_child(mdia, *b"mdhd"),
+			find_child(mdia, *b"hdlr"),
+			find_child(mdia, *b"minf"),
+		) else {
+			return Ok(None);
+		};
+
+		let Some(stbl) = find_child(minf, *b"stbl") else {
+			return Ok(None);
+		};
+
+		let hdlr = read_content_at(reader, &hdlr.info)?;
+		// Version (1) + Flags (3) + Pre-defined (4) + Handler type (4)
+		match hdlr.get(8..12) {
+			Some(b"text" | b"sbtl") => {},
+			_ => {
+				log::warn!("Chapter track is not a text track, ignoring");
+				return Ok(None);
+			},
+		}
+
+		let version = skip_to_after_timestamps(reader, &mdhd.info)?;
+		let timescale = reader.read_u32::<BigEndian>()?;
+		let duration = if version == 1 {
+			reader.read_u64::<BigEndian>()?
+		} else {
+			u64::from(reader.read_u32::<BigEndian>()?)
+		};
+
+		if timescale == 0 {
+			log::warn!("Chapter track has a timescale of 0, ignoring");
+			return Ok(None);
+		}
+
+		Ok(Some(Self {
+			trak,
+			mdia,
+			minf,
+			stbl,
+			mdhd: &mdhd.info,
+			timescale,
+			duration,
+		}))
+	}
+
+	/// Reads the chapter titles from the track's samples
+	///
+	/// NOTE: This expects the reader to be unbounded, as it seeks to absolute positions.
+	pub(super) fn read_chapters<R>(&self, reader: &mut R) -> Result<Vec<Chapter>>
+	where
+		R: Read + Seek,
+	{
+		self.read_sample_table(reader)?
+			.read_chapters(reader, self.timescale)
+	}
+
+	/// The position of the track's samples, if they're all stored in a single chunk
+	///
+	/// NOTE: This expects the reader to be unbounded, as it seeks to absolute positions.
+	pub(super) fn sample_range<R>(&self, reader: &mut R) -> Result<Option<Range<u64>>>
+	where
+		R: Read + Seek,
+	{
+		let sample_table = self.read_sample_table(reader)?;
+
+		let [offset] = sample_table.chunk_offsets[..] else {
+			return Ok(None);
+		};
+
+		let size = if sample_table.sample_size == 0 {
+			sample_table
+				.sample_sizes
+				.iter()
+				.fold(0_u64, |total, size| total.saturating_add(u64::from(*size)))
+		} else {
+			u64::from(sample_table.sample_size) * u64::from(sample_table.sample_count)
+		};
+
+		Ok(Some(offset..offset.saturating_add(size)))
+	}
+
+	fn read_sample_table<R>(&self, reader: &mut R) -> Result<SampleTable>
+	where
+		R: Read + Seek,
+	{
+		let mut sample_table = SampleTable::default();
+		for child in &self.stbl.children {
+			let AtomIdent::Fourcc(fourcc) = &child.info.ident else {
+				continue;
+			};
+
+			match fourcc {
+				b"stts" => {
+					sample_table.time_to_sample = read_table(reader, &child.info, 8, |entry| {
+						(read_be_u32(&entry[..4]), read_be_u32(&entry[4..]))
+					})?;
+				},
+				b"stsc" => {
+					// The sample description index is unused
+					sample_table.sample_to_chunk = read_table(reader, &child.info, 12, |entry| {
+						(read_be_u32(&entry[..4]), read_be_u32(&entry[4..8]))
+					})?;
+				},
+				b"stsz" => {
+					let content = read_content_at(reader, &child.info)?;
+					let mut content = &content[..];
+
+					let _version_flags = content.read_u32::<BigEndian>()?;
+					sample_table.sample_size = content.read_u32::<BigEndian>()?;
+					sample_table.sample_count = content.read_u32::<BigEndian>()?;
+
+					if sample_table.sample_size == 0 {
+						sample_table.sample_sizes = content
+							.chunks_exact(4)
+							.take(sample_table.sample_count as usize)
+							.map(read_be_u32)
+							.collect();
+					}
+				},
+				b"stco" => {
+					sample_table.chunk_offsets = read_table(reader, &child.info, 4, |entry| {
+						u64::from(read_be_u32(entry))
+					})?;
+				},
+				b"co64" => {
+					sample_table.chunk_offsets = read_table(reader, &child.info, 8, |entry| {
+						(u64::from(read_be_u32(&entry[..4])) << 32)
+							| u64::from(read_be_u32(&entry[4..]))
+					})?;
+				},
+				_ => {},
+			}
+		}
+
+		Ok(sample_table)
+	}
+
+	/// The chapters as they would be read back after writing them to this track
+	///
+	/// See [`ChapterTrack::sample_durations`].
+	pub(super) fn normalize(&self, chapters: &[Chapter]) -> Vec<Chapter> {
+		let mut elapsed = 0_u64;
+		chapters
+			.iter()
+			.zip(self.sample_durations(chapters))
+			.map(|(chapter, duration)| {
+				let mut normalized = Chapter::new(units_to_duration(elapsed, self.timescale));
+				normalized.title = chapter.title.clone().filter(|title| !title.is_empty());
+
+				elapsed = elapsed.saturating_add(u64::from(duration));
+				normalized
+			})
+			.collect()
+	}
+
+	/// Creates the sample tables (`stts`, `stsc`, `stsz`, and `stco` or `co64`) and sample data for `chapters`
+	///
+	/// All samples are stored in a single chunk, starting at `data_offset`. A `co64` atom is only used
+	/// if the offset doesn't fit in 32 bits.
+	pub(super) fn build_samples(
+		&self,
+		chapters: &[Chapter],
+		data_offset: u64,
+	) -> Result<(Vec<u8>, Vec<u8>)> {
+		let mut samples = Vec::new();
+		let mut sample_sizes = Vec::with_capacity(chapters.len());
+		for chapter in chapters {
+			let start = samples.len();
+
+			let title = chapter.title.as_deref().unwrap_or_default();
+			let title = truncate_to_char_boundary(title, usize::from(u16::MAX));
+
+			samples.write_u16::<BigEndian>(title.len() as u16)?;
+			samples.write_all(title.as_bytes())?;
+
+			// An `encd` atom, marking the text as UTF-8
+			samples.write_u32::<BigEndian>(12)?;
+			samples.write_all(b"encd")?;
+			samples.write_u32::<BigEndian>(0x0000_0100)?;
+
+			sample_sizes.push((samples.len() - start) as u32);
+		}
+
+		// Consecutive samples with the same duration share an entry
+		let mut time_to_sample: Vec<(u32, u32)> = Vec::new();
+		for duration in self.sample_durations(chapters) {
+			match time_to_sample.last_mut() {
+				Some((count, delta)) if *delta == duration => *count += 1,
+				_ => time_to_sample.push((1, duration)),
+			}
+		}
+
+		let mut tables = Vec::new();
+
+		let mut stts = Vec::new();
+		stts.write_u32::<BigEndian>(0)?;
+		stts.write_u32::<BigEndian>(time_to_sample.len() as u32)?;
+		for (count, delta) in time_to_sample {
+			stts.write_u32::<BigEndian>(count)?;
+			stts.write_u32::<BigEndian>(delta)?;
+		}
+		tables.extend(build_atom(*b"stts", &stts)?);
+
+		let mut stsc = Vec::new();
+		stsc.write_u32::<BigEndian>(0)?;
+		if chapters.is_empty() {
+			stsc.write_u32::<BigEndian>(0)?;
+		} else {
+			// First chunk + samples per chunk + sample description index
+			stsc.write_u32::<BigEndian>(1)?;
+			stsc.write_u32::<BigEndian>(1)?;
+			stsc.write_u32::<BigEndian>(chapters.len() as u32)?;
+			stsc.write_u32::<BigEndian>(1)?;
+		}
+		tables.extend(build_atom(*b"stsc", &stsc)?);
+
+		let mut stsz = Vec::new();
+		stsz.write_u32::<BigEndian>(0)?;
+		stsz.write_u32::<BigEndian>(0)?;
+		stsz.write_u32::<BigEndian>(sample_sizes.len() as u32)?;
+		for size in sample_sizes {
+			stsz.write_u32::<BigEndian>(size)?;
+		}
+		tables.extend(build_atom(*b"stsz", &stsz)?);
+
+		let mut chunk_offsets = Vec::new();
+		chunk_offsets.write_u32::<BigEndian>(0)?;
+		if chapters.is_empty() {
+			chunk_offsets.write_u32::<BigEndian>(0)?;
+			tables.extend(build_atom(*b"stco", &chunk_offsets)?);
+		} else if let Ok(data_offset) = u32::try_from(data_offset) {
+			chunk_offsets.write_u32::<BigEndian>(1)?;
+			chunk_offsets.write_u32::<BigEndian>(data_offset)?;
+			tables.extend(build_atom(*b"stco", &chunk_offsets)?);
+		} else {
+			chunk_offsets.write_u32::<BigEndian>(1)?;
+			chunk_offsets.write_u64::<BigEndian>(data_offset)?;
+			tables.extend(build_atom(*b"co64", &chunk_offsets)?);
+		}
+
+		Ok((tables, samples))
+	}
+
+	/// The duration of each chapter's sample, in `timescale` units
+	///
+	/// A chapter track has no way to store a start time, the samples simply follow each other. This means
+	/// the first chapter will always start at the beginning of the track.
+	///
+	/// The last chapter lasts until its end, or the end of the track if it doesn't have one.
+	pub(super) fn sample_durations(&self, chapters: &[Chapter]) -> Vec<u32> {
+		let timescale = u128::from(self.timescale);
+		let to_units = |time: Duration| -> u64 {
+			u64::try_from(time.as_nanos() * timescale / 1_000_000_000).unwrap_or(u64::MAX)
+		};
+
+		(0..chapters.len())
+			.map(|index| {
+				let start = to_units(chapters[index].start);
+				let end = match chapter_end(chapters, index) {
+					Some(end) => to_units(end),
+					None => self.duration,
+				};
+
+				// Every chapter needs to take up *some* time
+				let duration = end.saturating_sub(start).max(1);
+				u32::try_from(duration).unwrap_or(u32::MAX)
+			})
+			.collect()
+	}
+
+	/// Overwrites the duration stored in the track's `mdhd` atom
+	pub(super) fn write_duration<W>(&self, writer: &mut W, duration: u64) -> Result<()>
+	where
+		W: Read + Write + Seek,
+	{
+		let version = skip_to_after_timestamps(writer, self.mdhd)?;
+
+		// Skip the timescale
+		writer.seek(SeekFrom::Current(4))?;
+
+		if version == 1 {
+			writer.write_u64::<BigEndian>(duration)?;
+		} else {
+			writer.write_u32::<BigEndian>(u32::try_from(duration).unwrap_or(u32::MAX))?;
+		}
+
+		Ok(())
+	}
+}
+
+// Reads the ID of the first track referenced by the track's `tref.chap` atom
+fn read_chapter_reference<R>(
+	reader: &mut R,
+	trak: &ContextualAtom,
+	parse_mode: ParsingMode,
+) -> Result<Option<u32>>
+where
+	R: Read + Seek,
+{
+	let Some(tref) = find_child(trak, *b"tref") else {
+		return Ok(None);
+	};
+
+	reader.seek(SeekFrom::Start(tref.info.start + tref.info.header_size()))?;
+	let Some(chap) = find_child_atom(
+		reader,
+		tref.info.len - tref.info.header_size(),
+		*b"chap",
+		parse_mode,
+	)?
+	else {
+		return Ok(None);
+	};
+
+	if chap.len - chap.header_size() < 4 {
+		return Ok(None);
+	}
+
+	Ok(Some(reader.read_u32::<BigEndian>()?))
+}
+
+#[derive(Default)]
+struct SampleTable {
+	// From `stts`, (sample count, sample delta)
+	time_to_sample: Vec<(u32, u32)>,
+	// From `stsc`, (first chunk, samples per chunk)
+	sample_to_chunk: Vec<(u32, u32)>,
+	// From `stsz`, `sample_sizes` is only used if `sample_size` is 0
+	sample_size: u32,
+	sample_count: u32,
+	sample_sizes: Vec<u32>,
+	// From `stco` or `co64`
+	chunk_offsets: Vec<u64>,
+}
+
+impl SampleTable {
+	fn read_chapters<R>(&self, reader: &mut R, timescale: u32) -> Result<Vec<Chapter>>
+	where
+		R: Read + Seek,
+	{
+		let sample_count = if self.sample_size == 0 {
+			self.sample_sizes.len()
+		} else {
+			self.sample_count as usize
+		};
+
+		let mut elapsed = 0_u64;
+		let mut start_times = self
+			.time_to_sample
+			.iter()
+			.flat_map(|(count, delta)| std::iter::repeat(u64::from(*delta)).take(*count as usize))
+			.map(|delta| {
+				let start = elapsed;
+				elapsed = elapsed.saturating_add(delta);
+				start
+			});
+
+		let mut chapters = Vec::new();
+		let mut sample_index = 0;
+
+		'chunks: for (chunk_index, chunk_offset) in self.chunk_offsets.iter().enumerate() {
+			// Chunk numbers start at 1
+			let samples_in_chunk = self.samples_per_chunk(chunk_index as u32 + 1);
+
+			let mut offset = *chunk_offset;
+			for _ in 0..samples_in_chunk {
+				if sample_index >= sample_count {
+					break 'chunks;
+				}
+
+				let Some(start) = start_times.next() else {
+					break 'chunks;
+				};
+
+				let title = match read_sample_text(reader, offset) {
+					Ok(title) => title,
+					Err(e) => {
+						log::warn!("Unable to read chapter title, stopping: {e}");
+						break 'chunks;
+					},
+				};
+
+				let mut chapter = Chapter::new(units_to_duration(start, timescale));
+				if !title.is_empty() {
+					chapter.title = Some(title);
+				}
+
+				chapters.push(chapter);
+
+				let size = if self.sample_size == 0 {
+					self.sample_sizes[sample_index]
+				} else {
+					self.sample_size
+				};
+
+				offset = offset.saturating_add(u64::from(size));
+				sample_index += 1;
+			}
+		}
+
+		Ok(chapters)
+	}
+
+	fn samples_per_chunk(&self, chunk_number: u32) -> u32 {
+		let mut samples_per_chunk = 0;
+		for (first_chunk, samples) in &self.sample_to_chunk {
+			if *first_chunk > chunk_number {
+				break;
+			}
+
+			samples_per_chunk = *samples;
+		}
+
+		samples_per_chunk
+	}
+}
+
+// A text sample is a 16-bit length, followed by either UTF-8 or UTF-16 (with a BOM) text
+fn read_sample_text<R>(reader: &mut R, offset: u64) -> Result<String>
+where
+	R: Read + Seek,
+{
+	reader.seek(SeekFrom::Start(offset))?;
+
+	let len = reader.read_u16::<BigEndian>()?;
+	let mut text = try_vec![0; usize::from(len)];
+	reader.read_exact(&mut text)?;
+
+	match text.as_slice() {
+		[0xFE, 0xFF, rest @ ..] => utf16_decode_bytes(rest, u16::from_be_bytes),
+		[0xFF, 0xFE, rest @ ..] => utf16_decode_bytes(rest, u16::from_le_bytes),
+		_ => utf8_decode(text),
+	}
+}
+
+fn units_to_duration(units: u64, timescale: u32) -> Duration {
+	let timescale = u64::from(timescale);
+
+	let seconds = units / timescale;
+	let nanos = ((units % timescale) * 1_000_000_000) / timescale;
+
+	Duration::new(seconds, nanos as u32)
+}
+
+// `tkhd` and `mdhd` both start with a version (1), flags (3), creation time, and modification time.
+// The timestamps are 64-bit in version 1, and 32-bit otherwise.
+//
+// This returns the version, leaving the reader directly after the timestamps.
+fn skip_to_after_timestamps<R>(reader: &mut R, atom: &AtomInfo) -> Result<u8>
+where
+	R: Read + Seek,
+{
+	reader.seek(SeekFrom::Start(atom.start + atom.header_size()))?;
+
+	let version = reader.read_u8()?;
+	let _flags = reader.read_u24::<BigEndian>()?;
+
+	if version == 1 {
+		reader.seek(SeekFrom::Current(16))?;
+	} else {
+		reader.seek(SeekFrom::Current(8))?;
+	}
+
+	Ok(version)
+}
+
+// Reads a table of fixed-size entries, preceded by a version (1), flags (3), and entry count (4)
+fn read_table<R, T>(
+	reader: &mut R,
+	atom: &AtomInfo,
+	entry_size: usize,
+	parse_entry: impl FnMut(&[u8]) -> T,
+) -> Result<Vec<T>>
+where
+	R: Read + Seek,
+{
+	let content = read_content_at(reader, atom)?;
+	let mut content = &content[..];
+
+	let _version_flags = content.read_u32::<BigEndian>()?;
+	let entry_count = content.read_u32::<BigEndian>()?;
+
+	Ok(content
+		.chunks_exact(entry_size)
+		.take(entry_count as usize)
+		.map(parse_entry)
+		.collect())
+}
+
+fn read_be_u32(bytes: &[u8]) -> u32 {
+	u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+// NOTE: This expects the reader to be at the start of the atom's content
+fn read_content<R>(reader: &mut R, atom: &AtomInfo) -> Result<Vec<u8>>
+where
+	R: Read,
+{
+	let mut content = try_vec![0; (atom.len - atom.header_size()) as usize];
+	reader.read_exact(&mut content)?;
+
+	Ok(content)
+}
+
+fn read_content_at<R>(reader: &mut R, atom: &AtomInfo) -> Result<Vec<u8>>
+where
+	R: Read + Seek,
+{
+	reader.seek(SeekFrom::Start(atom.start + atom.header_size()))?;
+	read_content(reader, atom)
+}
+
+fn find_child(parent: &ContextualAtom, fourcc: [u8; 4]) -> Option<&ContextualAtom> {
+	parent
+		.children
+		.iter()
+		.find(|atom| atom.info.ident == AtomIdent::Fourcc(fourcc))
+}
+
+// Creates an atom holding `content`
+fn build_atom(fourcc: [u8; 4], content: &[u8]) -> Result<Vec<u8>> {
+	let Ok(size) = u32::try_from(ATOM_HEADER_LEN as usize + content.len()) else {
+		err!(TooMuchData);
+	};
+
+	let mut atom = Vec::with_capacity(size as usize);
+	atom.write_u32::<BigEndian>(size)?;
+	atom.write_all(&fourcc)?;
+	atom.write_all(content)?;
+
+	Ok(atom)
+}
+
+fn truncate_to_char_boundary(text: &str, max_len: usize) -> &str {
+	let mut len = text.len().min(max_len);
+	while !text.is_char_boundary(len) {
+		len -= 1;
+	}
+
+	&text[..len]
+}
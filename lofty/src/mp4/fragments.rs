@@ -0,0 +1,457 @@
+//! Fragmented MP4 handling
+//!
+//! Fragmented files (Ex. DASH segments) store little to nothing in the sample tables of `moov`.
+//! Instead, the samples are described by the `moof` atoms that precede each chunk of media data,
+//! with defaults provided by `moov.mvex`.
+
+use super::atom_info::{AtomIdent, AtomInfo};
+use super::read::{skip_atom, AtomReader};
+use crate::error::Result;
+use crate::macros::try_vec;
+
+use std::io::{Read, Seek};
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+// `tfhd` flags
+const TFHD_BASE_DATA_OFFSET: u32 = 0x01;
+const TFHD_SAMPLE_DESCRIPTION_INDEX: u32 = 0x02;
+const TFHD_DEFAULT_SAMPLE_DURATION: u32 = 0x08;
+const TFHD_DEFAULT_SAMPLE_SIZE: u32 = 0x10;
+
+// `trun` flags
+const TRUN_DATA_OFFSET: u32 = 0x001;
+const TRUN_FIRST_SAMPLE_FLAGS: u32 = 0x004;
+const TRUN_SAMPLE_DURATION: u32 = 0x100;
+const TRUN_SAMPLE_SIZE: u32 = 0x200;
+const TRUN_SAMPLE_FLAGS: u32 = 0x400;
+const TRUN_SAMPLE_COMPOSITION_TIME_OFFSET: u32 = 0x800;
+
+/// The sample defaults for a track, from `moov.mvex.trex`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct TrackExtends {
+	track_id: u32,
+	default_sample_duration: u32,
+	default_sample_size: u32,
+}
+
+/// A parsed `moov.mvex` atom
+///
+/// The presence of this atom means that the file is fragmented.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct Mvex {
+	track_extends: Vec<TrackExtends>,
+}
+
+impl Mvex {
+	/// Parse the `mvex` atom
+	///
+	/// NOTE: This expects the reader to be at the start of the atom's content, and will consume the entire atom.
+	pub(super) fn parse<R>(reader: &mut AtomReader<R>, mvex: &AtomInfo) -> Result<Self>
+	where
+		R: Read + Seek,
+	{
+		let mut track_extends = Vec::new();
+
+		let mut read = mvex.header_size();
+		while read < mvex.len {
+			let Some(atom) = reader.next()? else {
+				break;
+			};
+
+			read += atom.len;
+
+			if atom.ident != AtomIdent::Fourcc(*b"trex") {
+				skip_atom(reader, atom.extended, atom.len)?;
+				continue;
+			}
+
+			// Version (1)
+			// Flags (3)
+			// Track ID (4)
+			// Default sample description index (4)
+			// Default sample duration (4)
+			// Default sample size (4)
+			// Default sample flags (4)
+			let content = read_content(reader, &atom)?;
+			let reader = &mut &content[..];
+
+			let _version_and_flags = reader.read_u32::<BigEndian>()?;
+			let track_id = reader.read_u32::<BigEndian>()?;
+			let _default_sample_description_index = reader.read_u32::<BigEndian>()?;
+
+			track_extends.push(TrackExtends {
+				track_id,
+				default_sample_duration: reader.read_u32::<BigEndian>()?,
+				default_sample_size: reader.read_u32::<BigEndian>()?,
+			});
+		}
+
+		Ok(Self { track_extends })
+	}
+
+	fn track_extends(&self, track_id: Option<u32>) -> TrackExtends {
+		self.track_extends
+			.iter()
+			.find(|trex| track_id.is_none() || Some(trex.track_id) == track_id)
+			.copied()
+			.unwrap_or_default()
+	}
+}
+
+/// The totals of a track's samples across every fragment
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Fragments {
+	/// The total duration, in the track's timescale
+	pub(super) duration: u64,
+	/// The total size of the samples, in bytes
+	pub(super) media_size: u64,
+}
+
+/// Sum the durations and sizes of a track's samples across all `moof` atoms
+///
+/// If `track_id` is `None`, every track is included.
+///
+/// NOTE: This expects the reader to be unbounded, as it starts from the beginning of the file.
+pub(super) fn read_fragments<R>(
+	reader: &mut AtomReader<R>,
+	mvex: &Mvex,
+	track_id: Option<u32>,
+) -> Result<Fragments>
+where
+	R: Read + Seek,
+{
+	let trex = mvex.track_extends(track_id);
+	let mut fragments = Fragments::default();
+
+	reader.rewind()?;
+
+	while let Ok(Some(atom)) = reader.next() {
+		if atom.ident != AtomIdent::Fourcc(*b"moof") {
+			skip_atom(reader, atom.extended, atom.len)?;
+			continue;
+		}
+
+		let mut read = atom.header_size();
+		while read < atom.len {
+			let Some(traf) = reader.next()? else {
+				break;
+			};
+
+			read += traf.len;
+
+			if traf.ident != AtomIdent::Fourcc(*b"traf") {
+				skip_atom(reader, traf.extended, traf.len)?;
+				continue;
+			}
+
+			read_traf(reader, &traf, track_id, trex, &mut fragments)?;
+		}
+	}
+
+	log::debug!(
+		"Read fragments, duration: {}, media size: {}",
+		fragments.duration,
+		fragments.media_size
+	);
+
+	Ok(fragments)
+}
+
+// NOTE: This will consume the entire `traf` atom
+fn read_traf<R>(
+	reader: &mut AtomReader<R>,
+	traf: &AtomInfo,
+	track_id: Option<u32>,
+	mut defaults: TrackExtends,
+	fragments: &mut Fragments,
+) -> Result<()>
+where
+	R: Read + Seek,
+{
+	// `tfhd` always comes first, so we know whether to skip the rest of the atom
+	let mut is_track = true;
+
+	let mut read = traf.header_size();
+	while read < traf.len {
+		let Some(atom) = reader.next()? else {
+			break;
+		};
+
+		read += atom.len;
+
+		let AtomIdent::Fourcc(ref fourcc) = atom.ident else {
+			skip_atom(reader, atom.extended, atom.len)?;
+			continue;
+		};
+
+		match fourcc {
+			b"tfhd" => {
+				let content = read_content(reader, &atom)?;
+				is_track = parse_tfhd(&content, track_id, &mut defaults)?;
+			},
+			b"trun" if is_track => {
+				let content = read_content(reader, &atom)?;
+				parse_trun(&content, defaults, fragments)?;
+			},
+			_ => skip_atom(reader, atom.extended, atom.len)?,
+		}
+	}
+
+	Ok(())
+}
+
+// Version (1)
+// Flags (3)
+// Track ID (4)
+// Optional fields, depending on the flags
+//
+// Returns whether the fragment belongs to the track
+fn parse_tfhd(
+	mut content: &[u8],
+	track_id: Option<u32>,
+	defaults: &mut TrackExtends,
+) -> Result<bool> {
+	let reader = &mut content;
+
+	let flags = reader.read_u32::<BigEndian>()? & 0x00FF_FFFF;
+	let tfhd_track_id = reader.read_u32::<BigEndian>()?;
+
+	if track_id.is_some_and(|track_id| track_id != tfhd_track_id) {
+		return Ok(false);
+	}
+
+	if flags & TFHD_BASE_DATA_OFFSET != 0 {
+		let _base_data_offset = reader.read_u64::<BigEndian>()?;
+	}
+
+	if flags & TFHD_SAMPLE_DESCRIPTION_INDEX != 0 {
+		let _sample_description_index = reader.read_u32::<BigEndian>()?;
+	}
+
+	if flags & TFHD_DEFAULT_SAMPLE_DURATION != 0 {
+		defaults.default_sample_duration = reader.read_u32::<BigEndian>()?;
+	}
+
+	if flags & TFHD_DEFAULT_SAMPLE_SIZE != 0 {
+		defaults.default_sample_size = reader.read_u32::<BigEndian>()?;
+	}
+
+	Ok(true)
+}
+
+// Version (1)
+// Flags (3)
+// Sample count (4)
+// Optional fields, depending on the flags
+// Samples, with optional fields depending on the flags
+fn parse_trun(mut content: &[u8], defaults: TrackExtends, fragments: &mut Fragments) -> Result<()> {
+	let reader = &mut content;
+
+	let flags = reader.read_u32::<BigEndian>()? & 0x00FF_FFFF;
+	let sample_count = reader.read_u32::<BigEndian>()?;
+
+	if flags & TRUN_DATA_OFFSET != 0 {
+		let _data_offset = reader.read_i32::<BigEndian>()?;
+	}
+
+	if flags & TRUN_FIRST_SAMPLE_FLAGS != 0 {
+		let _first_sample_flags = reader.read_u32::<BigEndian>()?;
+	}
+
+	let has_duration = flags & TRUN_SAMPLE_DURATION != 0;
+	let has_size = flags & TRUN_SAMPLE_SIZE != 0;
+
+	// Without any per-sample fields, every sample uses the defaults
+	let sample_len = [
+		TRUN_SAMPLE_DURATION,
+		TRUN_SAMPLE_SIZE,
+		TRUN_SAMPLE_FLAGS,
+		TRUN_SAMPLE_COMPOSITION_TIME_OFFSET,
+	]
+	.into_iter()
+	.filter(|flag| flags & flag != 0)
+	.count()
+		* 4;
+
+	if sample_len == 0 {
+		fragments.duration = fragments
+			.duration
+			.saturating_add(u64::from(sample_count) * u64::from(defaults.default_sample_duration));
+		fragments.media_size = fragments
+			.media_size
+			.saturating_add(u64::from(sample_count) * u64::from(defaults.default_sample_size));
+		return Ok(());
+	}
+
+	for mut sample in reader.chunks_exact(sample_len).take(sample_count as usize) {
+		let duration = if has_duration {
+			sample.read_u32::<BigEndian>()?
+		} else {
+			defaults.default_sample_duration
+		};
+
+		let size = if has_size {
+			sample.read_u32::<BigEndian>()?
+		} else {
+			defaults.default_sample_size
+		};
+
+		fragments.duration = fragments.duration.saturating_add(u64::from(duration));
+		fragments.media_size = fragments.media_size.saturating_add(u64::from(size));
+	}
+
+	Ok(())
+}
+
+fn read_content<R>(reader: &mut AtomReader<R>, atom: &AtomInfo) -> Result<Vec<u8>>
+where
+	R: Read + Seek,
+{
+	let mut content = try_vec![0; (atom.len - atom.header_size()) as usize];
+	reader.read_exact(&mut content)?;
+
+	Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{Fragments, Mvex, TrackExtends};
+	use crate::config::ParsingMode;
+	use crate::mp4::read::AtomReader;
+
+	use std::io::Cursor;
+
+	fn atom(ident: &[u8; 4], content: &[u8]) -> Vec<u8> {
+		let mut atom = Vec::new();
+		atom.extend((content.len() as u32 + 8).to_be_bytes());
+		atom.extend(ident);
+		atom.extend(content);
+		atom
+	}
+
+	fn moof(track_id: u32, sample_count: u32) -> Vec<u8> {
+		let mut tfhd = Vec::new();
+		tfhd.extend(0x0000_0008_u32.to_be_bytes());
+		tfhd.extend(track_id.to_be_bytes());
+		tfhd.extend(1024_u32.to_be_bytes());
+
+		let mut trun = Vec::new();
+		trun.extend(0x0000_0200_u32.to_be_bytes());
+		trun.extend(sample_count.to_be_bytes());
+		for _ in 0..sample_count {
+			trun.extend(100_u32.to_be_bytes());
+		}
+
+		let mut traf = atom(b"tfhd", &tfhd);
+		traf.extend(atom(b"trun", &trun));
+
+		let mut moof = atom(b"mfhd", &[0; 8]);
+		moof.extend(atom(b"traf", &traf));
+
+		atom(b"moof", &moof)
+	}
+
+	#[test_log::test]
+	fn trun_with_defaults() {
+		let defaults = TrackExtends {
+			track_id: 1,
+			default_sample_duration: 1024,
+			default_sample_size: 100,
+		};
+
+		// Only a sample count
+		let mut trun = Vec::new();
+		trun.extend(0_u32.to_be_bytes());
+		trun.extend(10_u32.to_be_bytes());
+
+		let mut fragments = Fragments::default();
+		super::parse_trun(&trun, defaults, &mut fragments).unwrap();
+
+		assert_eq!(fragments.duration, 10 * 1024);
+		assert_eq!(fragments.media_size, 10 * 100);
+	}
+
+	#[test_log::test]
+	fn trun_saturates() {
+		let defaults = TrackExtends {
+			track_id: 1,
+			default_sample_duration: u32::MAX,
+			default_sample_size: u32::MAX,
+		};
+
+		let mut trun = Vec::new();
+		trun.extend(0_u32.to_be_bytes());
+		trun.extend(u32::MAX.to_be_bytes());
+
+		let mut fragments = Fragments::default();
+		for _ in 0..2 {
+			super::parse_trun(&trun, defaults, &mut fragments).unwrap();
+		}
+
+		assert_eq!(fragments.duration, u64::MAX);
+		assert_eq!(fragments.media_size, u64::MAX);
+	}
+
+	#[test_log::test]
+	fn trun_with_sample_sizes() {
+		let defaults = TrackExtends {
+			track_id: 1,
+			default_sample_duration: 1024,
+			default_sample_size: 0,
+		};
+
+		// Data offset, and a size for each sample
+		let mut trun = Vec::new();
+		trun.extend(0x0000_0201_u32.to_be_bytes());
+		trun.extend(3_u32.to_be_bytes());
+		trun.extend(0_i32.to_be_bytes());
+		for size in [100_u32, 200, 300] {
+			trun.extend(size.to_be_bytes());
+		}
+
+		let mut fragments = Fragments::default();
+		super::parse_trun(&trun, defaults, &mut fragments).unwrap();
+
+		assert_eq!(fragments.duration, 3 * 1024);
+		assert_eq!(fragments.media_size, 600);
+	}
+
+	#[test_log::test]
+	fn tfhd_other_track() {
+		let mut defaults = TrackExtends::default();
+
+		// Default sample duration
+		let mut tfhd = Vec::new();
+		tfhd.extend(0x0000_0008_u32.to_be_bytes());
+		tfhd.extend(2_u32.to_be_bytes());
+		tfhd.extend(2048_u32.to_be_bytes());
+
+		assert!(!super::parse_tfhd(&tfhd, Some(1), &mut defaults).unwrap());
+		assert_eq!(defaults.default_sample_duration, 0);
+
+		assert!(super::parse_tfhd(&tfhd, Some(2), &mut defaults).unwrap());
+		assert_eq!(defaults.default_sample_duration, 2048);
+	}
+
+	#[test_log::test]
+	fn multiple_fragments() {
+		let mut file = atom(b"ftyp", b"iso5\0\0\0\0");
+		for (track_id, sample_count) in [(1, 10), (2, 4), (1, 5)] {
+			file.extend(moof(track_id, sample_count));
+			file.extend(atom(b"mdat", &[0; 16]));
+		}
+
+		let mvex = Mvex::default();
+
+		let mut reader = AtomReader::new(Cursor::new(file), ParsingMode::Strict).unwrap();
+
+		let fragments = super::read_fragments(&mut reader, &mvex, Some(1)).unwrap();
+		assert_eq!(fragments.duration, 15 * 1024);
+		assert_eq!(fragments.media_size, 15 * 100);
+
+		let fragments = super::read_fragments(&mut reader, &mvex, None).unwrap();
+		assert_eq!(fragments.duration, 19 * 1024);
+		assert_eq!(fragments.media_size, 19 * 100);
+	}
+}
@@ -20,4 +20,41 @@ pub mod flags {
 	pub const COMPILATION: AtomIdent<'_> = AtomIdent::Fourcc(*b"cpil");
 }
 
+/// Common keys for [`KeyedMetadata`]
+///
+/// See the [QuickTime metadata keys] for the full list.
+///
+/// [`KeyedMetadata`]: crate::mp4::KeyedMetadata
+/// [QuickTime metadata keys]: https://developer.apple.com/library/archive/documentation/QuickTime/QTFF/Metadata/Metadata.html
+pub mod keys {
+	/// Album (`com.apple.quicktime.album`)
+	pub const ALBUM: &str = "com.apple.quicktime.album";
+	/// Artist (`com.apple.quicktime.artist`)
+	pub const ARTIST: &str = "com.apple.quicktime.artist";
+	/// Comment (`com.apple.quicktime.comment`)
+	pub const COMMENT: &str = "com.apple.quicktime.comment";
+	/// Copyright (`com.apple.quicktime.copyright`)
+	pub const COPYRIGHT: &str = "com.apple.quicktime.copyright";
+	/// Creation date, in ISO 8601 format (`com.apple.quicktime.creationdate`)
+	pub const CREATION_DATE: &str = "com.apple.quicktime.creationdate";
+	/// Description (`com.apple.quicktime.description`)
+	pub const DESCRIPTION: &str = "com.apple.quicktime.description";
+	/// Director (`com.apple.quicktime.director`)
+	pub const DIRECTOR: &str = "com.apple.quicktime.director";
+	/// Genre (`com.apple.quicktime.genre`)
+	pub const GENRE: &str = "com.apple.quicktime.genre";
+	/// Location, in ISO 6709 format (`com.apple.quicktime.location.ISO6709`)
+	pub const LOCATION_ISO6709: &str = "com.apple.quicktime.location.ISO6709";
+	/// Device make (`com.apple.quicktime.make`)
+	pub const MAKE: &str = "com.apple.quicktime.make";
+	/// Device model (`com.apple.quicktime.model`)
+	pub const MODEL: &str = "com.apple.quicktime.model";
+	/// Producer (`com.apple.quicktime.producer`)
+	pub const PRODUCER: &str = "com.apple.quicktime.producer";
+	/// Software used to create the file (`com.apple.quicktime.software`)
+	pub const SOFTWARE: &str = "com.apple.quicktime.software";
+	/// Title (`com.apple.quicktime.title`)
+	pub const TITLE: &str = "com.apple.quicktime.title";
+}
+
 pub(crate) const WELL_KNOWN_TYPE_SET: u8 = 0;
@@ -0,0 +1,211 @@
+use super::constants::keys;
+use crate::mp4::AtomData;
+use crate::tag::{ItemKey, ItemValue, Tag, TagItem};
+
+// Keys that have an equivalent `ItemKey`
+const ITEM_KEYS: &[(&str, ItemKey)] = &[
+	(keys::TITLE, ItemKey::TrackTitle),
+	(keys::ARTIST, ItemKey::TrackArtist),
+	(keys::ALBUM, ItemKey::AlbumTitle),
+	(keys::COMMENT, ItemKey::Comment),
+	(keys::DESCRIPTION, ItemKey::Description),
+	(keys::GENRE, ItemKey::Genre),
+	(keys::COPYRIGHT, ItemKey::CopyrightMessage),
+	(keys::CREATION_DATE, ItemKey::RecordingDate),
+	(keys::DIRECTOR, ItemKey::Director),
+	(keys::PRODUCER, ItemKey::Producer),
+	(keys::SOFTWARE, ItemKey::EncoderSoftware),
+];
+
+/// QuickTime metadata, identified by keys
+///
+/// This is stored in a `meta` atom with an `mdta` handler, where the items are identified by keys
+/// such as `com.apple.quicktime.title`, rather than FOURCCs. It is commonly written by iPhones,
+/// as well as FFmpeg with `-movflags use_metadata_tags`.
+///
+/// See [`constants::keys`](crate::mp4::constants::keys) for some common keys.
+///
+/// ## Conversions
+///
+/// When converting an [`Ilst`](crate::mp4::Ilst) to a [`Tag`], the text values of keys with an
+/// [`ItemKey`] equivalent (such as [`keys::TITLE`]) will be used if the `ilst` atom doesn't already
+/// provide them.
+///
+/// When converting back, only those keys that already exist will be updated. Values that came from
+/// the keyed metadata are only written back to it, not to the `ilst` atom.
+#[derive(Default, PartialEq, Debug, Clone)]
+pub struct KeyedMetadata {
+	pub(crate) items: Vec<(String, AtomData)>,
+}
+
+impl KeyedMetadata {
+	/// Create a new empty `KeyedMetadata`
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::mp4::KeyedMetadata;
+	///
+	/// let keyed_metadata = KeyedMetadata::new();
+	/// assert!(keyed_metadata.is_empty());
+	/// ```
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Get the first value of a key
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::mp4::constants::keys;
+	/// use lofty::mp4::{AtomData, KeyedMetadata};
+	///
+	/// let mut keyed_metadata = KeyedMetadata::new();
+	/// keyed_metadata.insert(keys::TITLE, AtomData::UTF8(String::from("Foo title")));
+	///
+	/// assert_eq!(
+	/// 	keyed_metadata.get(keys::TITLE),
+	/// 	Some(&AtomData::UTF8(String::from("Foo title")))
+	/// );
+	/// ```
+	pub fn get(&self, key: &str) -> Option<&AtomData> {
+		self.get_all(key).next()
+	}
+
+	/// Get all of the values of a key
+	pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a AtomData> + 'a {
+		self.items
+			.iter()
+			.filter(move |(k, _)| k == key)
+			.map(|(_, data)| data)
+	}
+
+	/// Insert a value, replacing any existing values of the key
+	pub fn insert(&mut self, key: impl Into<String>, data: AtomData) {
+		let key = key.into();
+
+		self.remove(&key);
+		self.items.push((key, data));
+	}
+
+	/// Add a value, keeping any existing values of the key
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::mp4::constants::keys;
+	/// use lofty::mp4::{AtomData, KeyedMetadata};
+	///
+	/// let mut keyed_metadata = KeyedMetadata::new();
+	/// keyed_metadata.push(keys::ARTIST, AtomData::UTF8(String::from("Foo artist")));
+	/// keyed_metadata.push(keys::ARTIST, AtomData::UTF8(String::from("Bar artist")));
+	///
+	/// assert_eq!(keyed_metadata.get_all(keys::ARTIST).count(), 2);
+	/// ```
+	pub fn push(&mut self, key: impl Into<String>, data: AtomData) {
+		self.items.push((key.into(), data));
+	}
+
+	/// Remove all values of a key
+	pub fn remove(&mut self, key: &str) {
+		self.items.retain(|(k, _)| k != key);
+	}
+
+	/// Returns the location (`com.apple.quicktime.location.ISO6709`), if it exists
+	///
+	/// This is an [ISO 6709](https://en.wikipedia.org/wiki/ISO_6709) string, such as `+37.3349-122.0090+010.000/`.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::mp4::KeyedMetadata;
+	///
+	/// let mut keyed_metadata = KeyedMetadata::new();
+	/// keyed_metadata.set_location(String::from("+37.3349-122.0090/"));
+	///
+	/// assert_eq!(keyed_metadata.location(), Some("+37.3349-122.0090/"));
+	/// ```
+	pub fn location(&self) -> Option<&str> {
+		match self.get(keys::LOCATION_ISO6709)? {
+			AtomData::UTF8(location) | AtomData::UTF16(location) => Some(location),
+			_ => None,
+		}
+	}
+
+	/// Set the location (`com.apple.quicktime.location.ISO6709`)
+	///
+	/// See [`KeyedMetadata::location`].
+	pub fn set_location(&mut self, location: String) {
+		self.insert(keys::LOCATION_ISO6709, AtomData::UTF8(location));
+	}
+
+	/// Returns an iterator over the keys and their values
+	///
+	/// A key will appear once for each of its values.
+	pub fn iter(&self) -> impl Iterator<Item = (&str, &AtomData)> {
+		self.items.iter().map(|(key, data)| (key.as_str(), data))
+	}
+
+	/// Returns the number of values
+	pub fn len(&self) -> usize {
+		self.items.len()
+	}
+
+	/// Whether there are no values
+	pub fn is_empty(&self) -> bool {
+		self.items.is_empty()
+	}
+
+	/// Remove all values
+	pub fn clear(&mut self) {
+		self.items.clear();
+	}
+
+	// Every key, in the order they first appear
+	pub(super) fn keys(&self) -> Vec<&str> {
+		let mut keys = Vec::new();
+		for (key, _) in &self.items {
+			if !keys.contains(&key.as_str()) {
+				keys.push(key.as_str());
+			}
+		}
+
+		keys
+	}
+
+	// Copies the text values of keys with an `ItemKey` equivalent into `tag`, unless it already has them
+	pub(super) fn copy_to_tag(&self, tag: &mut Tag) {
+		for (key, item_key) in ITEM_KEYS {
+			if tag.get(item_key).is_some() {
+				continue;
+			}
+
+			for data in self.get_all(key) {
+				if let AtomData::UTF8(text) | AtomData::UTF16(text) = data {
+					tag.push(TagItem::new(
+						item_key.clone(),
+						ItemValue::Text(text.clone()),
+					));
+				}
+			}
+		}
+	}
+
+	// Replaces the text values of existing keys with an `ItemKey` equivalent with those in `tag`
+	pub(super) fn update_from_tag(&mut self, tag: &Tag) {
+		for (key, item_key) in ITEM_KEYS {
+			if !self.items.iter().any(|(k, _)| k == key) {
+				continue;
+			}
+
+			self.items.retain(|(k, data)| {
+				k != key || !matches!(data, AtomData::UTF8(_) | AtomData::UTF16(_))
+			});
+
+			for text in tag.get_strings(item_key) {
+				self.push(*key, AtomData::UTF8(text.to_owned()));
+			}
+		}
+	}
+}
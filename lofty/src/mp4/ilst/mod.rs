@@ -2,6 +2,7 @@ pub(super) mod advisory_rating;
 pub(super) mod atom;
 pub(super) mod constants;
 pub(super) mod data_type;
+pub(super) mod keyed_metadata;
 pub(super) mod read;
 mod r#ref;
 pub(crate) mod write;
@@ -9,9 +10,11 @@ pub(crate) mod write;
 use super::AtomIdent;
 use crate::config::{global_options, WriteOptions};
 use crate::error::LoftyError;
+use crate::mp4::assets::AssetInformation;
 use crate::mp4::ilst::atom::AtomDataStorage;
 use crate::picture::{Picture, PictureType, TOMBSTONE_PICTURE};
 use crate::tag::companion_tag::CompanionTag;
+use crate::tag::items::Chapter;
 use crate::tag::{
 	try_parse_year, Accessor, ItemKey, ItemValue, MergeTag, SplitTag, Tag, TagExt, TagItem, TagType,
 };
@@ -20,6 +23,7 @@ use crate::util::io::{FileLike, Length, Truncate};
 use advisory_rating::AdvisoryRating;
 use atom::{Atom, AtomData};
 use data_type::DataType;
+use keyed_metadata::KeyedMetadata;
 
 use std::borrow::Cow;
 use std::io::Write;
@@ -114,6 +118,15 @@ macro_rules! impl_flag_accessors {
 #[tag(description = "An MP4 ilst atom", supported_formats(Mp4))]
 pub struct Ilst {
 	pub(crate) atoms: Vec<Atom<'static>>,
+	pub(crate) chapters: Vec<Chapter>,
+	pub(crate) keyed_metadata: KeyedMetadata,
+	pub(crate) assets: AssetInformation,
+	// The items copied into a `Tag` from the keyed metadata or assets, which are written back to
+	// them rather than to the `ilst` atom
+	pub(crate) copied_item_keys: Vec<ItemKey>,
+	// The QuickTime text atoms merged in from `moov.udta`, which are left there unless they're
+	// changed or migrated
+	pub(crate) udta_text: Vec<([u8; 4], String)>,
 }
 
 impl Ilst {
@@ -445,6 +458,101 @@ impl Ilst {
 		})
 	}
 
+	/// Returns the chapters
+	///
+	/// These are read from the `moov.udta.chpl` atom, falling back to a QuickTime chapter track if it
+	/// doesn't exist.
+	pub fn chapters(&self) -> Vec<Chapter> {
+		self.chapters.clone()
+	}
+
+	/// Replaces the chapters
+	///
+	/// These will be written back to the file's `moov.udta.chpl` atom or QuickTime chapter track,
+	/// whichever it already has. If it has neither, a `chpl` atom is created. Both can only store each
+	/// chapter's start and title.
+	///
+	/// NOTE: A `chpl` atom can only hold 255 chapters, anything past that will be discarded.
+	///
+	/// NOTE: A chapter track has no way to store a start time, so the first chapter will always start
+	///       at the beginning of the track.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::mp4::Ilst;
+	/// use lofty::tag::items::Chapter;
+	/// use lofty::tag::TagExt;
+	/// use std::time::Duration;
+	///
+	/// let mut ilst = Ilst::new();
+	///
+	/// let mut chapter = Chapter::new(Duration::ZERO);
+	/// chapter.title = Some(String::from("Intro"));
+	///
+	/// ilst.set_chapters(vec![chapter]);
+	/// assert_eq!(ilst.chapters().len(), 1);
+	/// assert!(!ilst.is_empty());
+	/// ```
+	pub fn set_chapters(&mut self, chapters: Vec<Chapter>) {
+		self.chapters = chapters;
+	}
+
+	/// Removes all chapters
+	pub fn remove_chapters(&mut self) {
+		self.chapters.clear();
+	}
+
+	/// Returns the keyed metadata
+	///
+	/// This is read from a `meta` atom with an `mdta` handler, see [`KeyedMetadata`].
+	pub fn keyed_metadata(&self) -> &KeyedMetadata {
+		&self.keyed_metadata
+	}
+
+	/// Returns a mutable reference to the keyed metadata
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::mp4::constants::keys;
+	/// use lofty::mp4::{AtomData, Ilst};
+	/// use lofty::tag::TagExt;
+	///
+	/// let mut ilst = Ilst::new();
+	///
+	/// ilst.keyed_metadata_mut()
+	/// 	.insert(keys::TITLE, AtomData::UTF8(String::from("Foo title")));
+	/// assert!(!ilst.is_empty());
+	/// ```
+	pub fn keyed_metadata_mut(&mut self) -> &mut KeyedMetadata {
+		&mut self.keyed_metadata
+	}
+
+	/// Returns the 3GPP asset information
+	///
+	/// This is read from the asset atoms in `moov.udta`, see [`AssetInformation`].
+	pub fn assets(&self) -> &AssetInformation {
+		&self.assets
+	}
+
+	/// Returns a mutable reference to the 3GPP asset information
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::mp4::Ilst;
+	/// use lofty::tag::TagExt;
+	///
+	/// let mut ilst = Ilst::new();
+	///
+	/// ilst.assets_mut().set_year(2024);
+	/// assert!(!ilst.is_empty());
+	/// ```
+	pub fn assets_mut(&mut self) -> &mut AssetInformation {
+		&mut self.assets
+	}
+
 	// Extracts a u16 from an integer pair
 	fn extract_number(&self, fourcc: [u8; 4], expected_size: usize) -> Option<u16> {
 		if let Some(atom) = self.get(&AtomIdent::Fourcc(fourcc)) {
@@ -614,6 +722,9 @@ impl TagExt for Ilst {
 
 	fn is_empty(&self) -> bool {
 		self.atoms.is_empty()
+			&& self.chapters.is_empty()
+			&& self.keyed_metadata.is_empty()
+			&& self.assets.is_empty()
 	}
 
 	fn save_to<F>(
@@ -626,7 +737,7 @@ impl TagExt for Ilst {
 		LoftyError: From<<F as Truncate>::Error>,
 		LoftyError: From<<F as Length>::Error>,
 	{
-		self.as_ref().write_to(file, write_options)
+		self.as_ref(write_options).write_to(file, write_options)
 	}
 
 	fn dump_to<W: Write>(
@@ -634,11 +745,16 @@ impl TagExt for Ilst {
 		writer: &mut W,
 		write_options: WriteOptions,
 	) -> std::result::Result<(), Self::Err> {
-		self.as_ref().dump_to(writer, write_options)
+		self.as_ref(write_options).dump_to(writer, write_options)
 	}
 
 	fn clear(&mut self) {
 		self.atoms.clear();
+		self.chapters.clear();
+		self.keyed_metadata.clear();
+		self.assets.clear();
+		self.copied_item_keys.clear();
+		self.udta_text.clear();
 	}
 }
 
@@ -743,6 +859,20 @@ impl SplitTag for Ilst {
 			let _ = self.remove(&ADVISORY_RATING);
 		}
 
+		// The keyed metadata and assets are left in place, so they can be updated when merging
+		let copied_start = tag.items.len();
+		self.keyed_metadata.copy_to_tag(&mut tag);
+		self.assets.copy_to_tag(&mut tag);
+
+		self.copied_item_keys.clear();
+		for item in &tag.items[copied_start..] {
+			if !self.copied_item_keys.contains(item.key()) {
+				self.copied_item_keys.push(item.key().clone());
+			}
+		}
+
+		tag.chapters = std::mem::take(&mut self.chapters);
+
 		(SplitTagRemainder(self), tag)
 	}
 }
@@ -777,12 +907,21 @@ impl MergeTag for SplitTagRemainder {
 
 		let Self(mut merged) = self;
 
+		merged.keyed_metadata.update_from_tag(&tag);
+		merged.assets.update_from_tag(&tag);
+
+		// Items that came from the keyed metadata or assets were just written back to them
+		let copied_item_keys = std::mem::take(&mut merged.copied_item_keys);
+
 		// Storage for integer pairs
 		let mut tracks: (Option<u16>, Option<u16>) = (None, None);
 		let mut discs: (Option<u16>, Option<u16>) = (None, None);
 
 		for item in tag.items {
 			let key = item.item_key;
+			if copied_item_keys.contains(&key) {
+				continue;
+			}
 
 			if let Ok(ident) = TryInto::<AtomIdent<'_>>::try_into(&key) {
 				let ItemValue::Text(text) = item.item_value else {
@@ -849,6 +988,10 @@ impl MergeTag for SplitTagRemainder {
 		create_int_pair(&mut merged, *b"trkn", tracks);
 		create_int_pair(&mut merged, *b"disk", discs);
 
+		if !tag.chapters.is_empty() {
+			merged.chapters = tag.chapters;
+		}
+
 		merged
 	}
 }
@@ -857,7 +1000,10 @@ impl From<Ilst> for Tag {
 	fn from(input: Ilst) -> Self {
 		let (remainder, mut tag) = input.split_tag();
 
-		if unsafe { global_options().preserve_format_specific_items } && remainder.0.len() > 0 {
+		// The QuickTime text atoms need to be kept track of, even if there's nothing else left
+		if unsafe { global_options().preserve_format_specific_items }
+			&& (!remainder.0.is_empty() || !remainder.0.udta_text.is_empty())
+		{
 			tag.companion_tag = Some(CompanionTag::Ilst(remainder.0));
 		}
 
@@ -1232,6 +1378,48 @@ mod tests {
 		);
 	}
 
+	#[test_log::test]
+	fn chapters_roundtrip() {
+		use crate::tag::items::Chapter;
+		use std::time::Duration;
+
+		let file_bytes = read_path("tests/files/assets/minimal/m4a_codec_aac.m4a");
+		let mut file = tempfile::tempfile().unwrap();
+		file.write_all(&file_bytes).unwrap();
+		file.rewind().unwrap();
+
+		let mut intro = Chapter::new(Duration::ZERO);
+		intro.title = Some(String::from("Intro"));
+
+		let mut outro = Chapter::new(Duration::from_millis(1500));
+		outro.title = Some(String::from("Outro"));
+
+		let mut tag = Ilst::default();
+		tag.set_artist(String::from("Foo artist"));
+		tag.set_chapters(vec![intro.clone(), outro.clone()]);
+
+		tag.save_to(&mut file, WriteOptions::default()).unwrap();
+		file.rewind().unwrap();
+
+		let mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+		let ilst = mp4_file.ilst_tag.unwrap();
+		assert_eq!(ilst.artist().as_deref(), Some("Foo artist"));
+		assert_eq!(ilst.chapters(), vec![intro, outro]);
+
+		// Now remove the chapters, leaving the rest of the tag intact
+		let mut tag = ilst;
+		tag.remove_chapters();
+
+		file.rewind().unwrap();
+		tag.save_to(&mut file, WriteOptions::default()).unwrap();
+		file.rewind().unwrap();
+
+		let mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+		let ilst = mp4_file.ilst_tag.unwrap();
+		assert_eq!(ilst.artist().as_deref(), Some("Foo artist"));
+		assert!(ilst.chapters().is_empty());
+	}
+
 	#[test_log::test]
 	fn multi_value_atom() {
 		let ilst = read_ilst_strict("tests/tags/assets/ilst/multi_value_atom.ilst");
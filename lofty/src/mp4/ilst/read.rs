@@ -1,10 +1,11 @@
 use super::constants::WELL_KNOWN_TYPE_SET;
 use super::data_type::DataType;
+use super::keyed_metadata::KeyedMetadata;
 use super::{Atom, AtomData, AtomIdent, Ilst};
 use crate::config::{ParseOptions, ParsingMode};
 use crate::error::{LoftyError, Result};
 use crate::id3::v1::constants::GENRES;
-use crate::macros::{err, try_vec};
+use crate::macros::{decode_err, err, try_vec};
 use crate::mp4::atom_info::{AtomInfo, ATOM_HEADER_LEN};
 use crate::mp4::ilst::atom::AtomDataStorage;
 use crate::mp4::read::{skip_atom, AtomReader};
@@ -12,6 +13,8 @@ use crate::picture::{MimeType, Picture, PictureType};
 use crate::tag::TagExt;
 use crate::util::text::{utf16_decode_bytes, utf8_decode};
 
+use byteorder::{BigEndian, ReadBytesExt};
+
 use std::borrow::Cow;
 use std::io::{Cursor, Read, Seek, SeekFrom};
 
@@ -171,6 +174,124 @@ where
 	Ok(tag)
 }
 
+/// Parse a `keys` atom
+///
+/// The keys are indexed by their position, so any key outside of the `mdta` namespace is `None`.
+pub(in crate::mp4) fn parse_keys<R>(reader: &mut R, len: u64) -> Result<Vec<Option<String>>>
+where
+	R: Read,
+{
+	let mut content = try_vec![0; len as usize];
+	reader.read_exact(&mut content)?;
+
+	let mut content = &content[..];
+
+	let _version_flags = content.read_u32::<BigEndian>()?;
+	let entry_count = content.read_u32::<BigEndian>()?;
+
+	let mut keys = Vec::new();
+	for _ in 0..entry_count {
+		let key_size = content.read_u32::<BigEndian>()? as usize;
+
+		// Size (4) + namespace (4)
+		if key_size < 8 || key_size - 4 > content.len() {
+			decode_err!(@BAIL Mp4, "Found an invalid key size in \"keys\" atom");
+		}
+
+		let (namespace, rest) = content.split_at(4);
+		let (key, rest) = rest.split_at(key_size - 8);
+		content = rest;
+
+		if namespace != b"mdta" {
+			log::warn!(
+				"Encountered a key with an unknown namespace: {:?}, discarding",
+				namespace.escape_ascii().to_string()
+			);
+			keys.push(None);
+			continue;
+		}
+
+		keys.push(Some(utf8_decode(key.to_vec())?));
+	}
+
+	Ok(keys)
+}
+
+/// Parse an `ilst` atom from a `meta` atom with an `mdta` handler
+///
+/// Rather than FOURCCs, the atoms are identified by their (1-based) index in the `keys` atom.
+pub(in crate::mp4) fn parse_keyed_ilst<R>(
+	reader: &mut R,
+	parsing_mode: ParsingMode,
+	len: u64,
+	keys: &[Option<String>],
+) -> Result<KeyedMetadata>
+where
+	R: Read,
+{
+	let mut contents = try_vec![0; len as usize];
+	reader.read_exact(&mut contents)?;
+
+	let mut cursor = Cursor::new(contents);
+	let mut ilst_reader = AtomReader::new(&mut cursor, parsing_mode)?;
+
+	let mut keyed_metadata = KeyedMetadata::default();
+
+	let mut pos = 0;
+	while pos + ATOM_HEADER_LEN <= len {
+		let size = u64::from(ilst_reader.read_u32()?);
+		let index = ilst_reader.read_u32()?;
+
+		if size < ATOM_HEADER_LEN || pos + size > len {
+			if parsing_mode == ParsingMode::Strict {
+				err!(BadAtom("Found an indexed atom with an invalid size"));
+			}
+
+			log::warn!("Found an indexed atom with an invalid size, stopping");
+			break;
+		}
+
+		let atom_info = AtomInfo {
+			start: pos,
+			len: size,
+			extended: false,
+			ident: AtomIdent::Fourcc(index.to_be_bytes()),
+		};
+
+		pos += size;
+
+		let key = (index as usize)
+			.checked_sub(1)
+			.and_then(|index| keys.get(index))
+			.and_then(Option::as_ref);
+		let Some(key) = key else {
+			if parsing_mode == ParsingMode::Strict {
+				err!(BadAtom("Found an indexed atom with no matching key"));
+			}
+
+			log::warn!("Found an indexed atom with no matching key: {index}, discarding");
+			ilst_reader.seek(SeekFrom::Start(pos))?;
+			continue;
+		};
+
+		if let Some(atom_data) = parse_data_inner(&mut ilst_reader, parsing_mode, &atom_info)? {
+			for (flags, content) in atom_data {
+				match interpret_atom_content(flags, content) {
+					Ok(data) => keyed_metadata.push(key.clone(), data),
+					Err(err) if parsing_mode == ParsingMode::Strict => return Err(err),
+					Err(err) => {
+						log::warn!("Skipping value of \"{key}\" with invalid content: {err}")
+					},
+				}
+			}
+		}
+
+		ilst_reader.seek(SeekFrom::Start(pos))?;
+	}
+
+	Ok(keyed_metadata)
+}
+
 fn parse_data<R>(
 	reader: &mut AtomReader<R>,
 	parsing_mode: ParsingMode,
@@ -4,21 +4,52 @@
 
 use crate::config::WriteOptions;
 use crate::error::{LoftyError, Result};
-use crate::mp4::{Atom, AtomData, AtomIdent, Ilst};
+use crate::mp4::ilst::atom::AtomDataStorage;
+use crate::mp4::{AssetInformation, Atom, AtomData, AtomIdent, Ilst, KeyedMetadata};
+use crate::tag::items::Chapter;
 use crate::util::io::{FileLike, Length, Truncate};
 
 use std::io::Write;
 
 impl Ilst {
-	pub(crate) fn as_ref(&self) -> IlstRef<'_, impl IntoIterator<Item = &AtomData>> {
+	pub(crate) fn as_ref(
+		&self,
+		write_options: WriteOptions,
+	) -> IlstRef<'_, impl IntoIterator<Item = &AtomData>> {
+		// Unchanged QuickTime text atoms stay in `moov.udta`, unless they're being migrated
+		let skip_udta_text = !write_options.migrate_quicktime_text;
+
 		IlstRef {
-			atoms: Box::new(self.atoms.iter().map(Atom::as_ref)),
+			atoms: Box::new(
+				self.atoms
+					.iter()
+					.filter(move |atom| !skip_udta_text || !self.is_unchanged_udta_text(atom))
+					.map(Atom::as_ref),
+			),
+			chapters: &self.chapters,
+			keyed_metadata: &self.keyed_metadata,
+			assets: &self.assets,
 		}
 	}
+
+	fn is_unchanged_udta_text(&self, atom: &Atom<'_>) -> bool {
+		let (AtomIdent::Fourcc(fourcc), AtomDataStorage::Single(AtomData::UTF8(text))) =
+			(&atom.ident, &atom.data)
+		else {
+			return false;
+		};
+
+		self.udta_text
+			.iter()
+			.any(|(udta_fourcc, udta_text)| udta_fourcc == fourcc && udta_text == text)
+	}
 }
 
 pub(crate) struct IlstRef<'a, I> {
 	pub(super) atoms: Box<dyn Iterator<Item = AtomRef<'a, I>> + 'a>,
+	pub(super) chapters: &'a [Chapter],
+	pub(super) keyed_metadata: &'a KeyedMetadata,
+	pub(super) assets: &'a AssetInformation,
 }
 
 impl<'a, I: 'a> IlstRef<'a, I>
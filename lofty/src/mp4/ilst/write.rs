@@ -1,19 +1,28 @@
 use super::data_type::DataType;
+use super::keyed_metadata::KeyedMetadata;
 use super::r#ref::IlstRef;
-use crate::config::{ParseOptions, WriteOptions};
+use crate::config::{MoovPosition, ParseOptions, WriteOptions};
 use crate::error::{FileEncodingError, LoftyError, Result};
 use crate::file::FileType;
 use crate::macros::{decode_err, err, try_vec};
+use crate::mp4::assets::{build_assets, is_asset, AssetInformation};
 use crate::mp4::atom_info::{AtomIdent, AtomInfo, ATOM_HEADER_LEN, FOURCC_LEN};
+use crate::mp4::chapters::{build_chpl, ChapterTrack};
 use crate::mp4::ilst::r#ref::AtomRef;
-use crate::mp4::read::{atom_tree, find_child_atom, meta_is_full, verify_mp4, AtomReader};
+use crate::mp4::read::{
+	atom_tree, find_child_atom, meta_is_full, skip_atom, verify_mp4, AtomReader,
+};
+use crate::mp4::udta_text::is_udta_text;
 use crate::mp4::write::{AtomWriter, AtomWriterCompanion, ContextualAtom};
 use crate::mp4::AtomData;
 use crate::picture::{MimeType, Picture};
+use crate::tag::items::Chapter;
 use crate::util::alloc::VecFallibleCapacity;
 use crate::util::io::{FileLike, Length, Truncate};
+use crate::xmp::MP4_UUID;
 
-use std::io::{Cursor, Seek, SeekFrom, Write};
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 
@@ -35,16 +44,173 @@ where
 {
 	log::debug!("Attempting to write `ilst` tag to file");
 
+	write_with_layout(file, write_options, |file| {
+		write_ilst(file, tag, write_options)
+	})
+}
+
+// TODO: We are forcing the use of ParseOptions::DEFAULT_PARSING_MODE. This is not good. It should be caller-specified.
+pub(crate) fn write_id3v2_to<F>(
+	file: &mut F,
+	id3v2: &[u8],
+	write_options: WriteOptions,
+) -> Result<()>
+where
+	F: FileLike,
+	LoftyError: From<<F as Truncate>::Error>,
+	LoftyError: From<<F as Length>::Error>,
+{
+	log::debug!("Attempting to write `ID32` atom to file");
+
+	write_with_layout(file, write_options, |file| {
+		let mut atom_writer = AtomWriter::new_from_file(file, ParseOptions::DEFAULT_PARSING_MODE)?;
+		if atom_writer.find_contextual_atom(*b"moov").is_none() {
+			return Err(FileEncodingError::new(
+				FileType::Mp4,
+				"Could not find \"moov\" atom in target file",
+			)
+			.into());
+		}
+
+		if write_handler_meta(&atom_writer, *b"ID32", build_id32_meta(id3v2)?)? {
+			atom_writer.save_to(file)?;
+		}
+
+		Ok(())
+	})
+}
+
+// TODO: We are forcing the use of ParseOptions::DEFAULT_PARSING_MODE. This is not good. It should be caller-specified.
+pub(crate) fn write_xmp_to<F>(
+	file: &mut F,
+	packet: Option<&[u8]>,
+	write_options: WriteOptions,
+) -> Result<()>
+where
+	F: FileLike,
+	LoftyError: From<<F as Truncate>::Error>,
+	LoftyError: From<<F as Length>::Error>,
+{
+	log::debug!("Attempting to write XMP `uuid` atom to file");
+
+	write_with_layout(file, write_options, |file| {
+		let mut atom_writer = AtomWriter::new_from_file(file, ParseOptions::DEFAULT_PARSING_MODE)?;
+		if atom_writer.find_contextual_atom(*b"moov").is_none() {
+			return Err(FileEncodingError::new(
+				FileType::Mp4,
+				"Could not find \"moov\" atom in target file",
+			)
+			.into());
+		}
+
+		if write_xmp_uuid(&atom_writer, packet)? {
+			atom_writer.save_to(file)?;
+		}
+
+		Ok(())
+	})
+}
+
+// Verifies the file, and then moves the `moov` atom after `write` according to `WriteOptions::moov_position`
+fn write_with_layout<F, W>(file: &mut F, write_options: WriteOptions, write: W) -> Result<()>
+where
+	F: FileLike,
+	LoftyError: From<<F as Truncate>::Error>,
+	LoftyError: From<<F as Length>::Error>,
+	W: FnOnce(&mut F) -> Result<()>,
+{
 	// Create a temporary `AtomReader`, just to verify that this is a valid MP4 file
 	let mut reader = AtomReader::new(file, ParseOptions::DEFAULT_PARSING_MODE)?;
 	verify_mp4(&mut reader)?;
 
+	// If `moov` is at the start of the file, we'll try to keep the media where it was
+	let mut media_start = None;
+	if write_options.moov_position == MoovPosition::Start {
+		media_start = find_media_start(&mut reader)?;
+	}
+
 	// Now we can just read the entire file into memory
 	let file = reader.into_inner();
 	file.rewind()?;
 
+	write(file)?;
+
+	if write_options.moov_position != MoovPosition::Preserve {
+		file.rewind()?;
+
+		let mut atom_writer = AtomWriter::new_from_file(file, ParseOptions::DEFAULT_PARSING_MODE)?;
+		if update_layout(&atom_writer, media_start, write_options)? {
+			atom_writer.save_to(file)?;
+		}
+	}
+
+	Ok(())
+}
+
+// TODO: We are forcing the use of ParseOptions::DEFAULT_PARSING_MODE. This is not good. It should be caller-specified.
+fn write_ilst<'a, F, I>(
+	file: &mut F,
+	tag: &mut IlstRef<'a, I>,
+	write_options: WriteOptions,
+) -> Result<()>
+where
+	F: FileLike,
+	LoftyError: From<<F as Truncate>::Error>,
+	LoftyError: From<<F as Length>::Error>,
+	I: IntoIterator<Item = &'a AtomData> + 'a,
+{
 	let mut atom_writer = AtomWriter::new_from_file(file, ParseOptions::DEFAULT_PARSING_MODE)?;
+	let mut modified = false;
+
+	// The chapters are written separately, since the atom positions need to be recalculated afterward
+	if write_chpl(&atom_writer, tag.chapters)? {
+		atom_writer.reparse(ParseOptions::DEFAULT_PARSING_MODE)?;
+		modified = true;
+	}
+
+	if write_chapter_track(&atom_writer, tag.chapters)? {
+		atom_writer.reparse(ParseOptions::DEFAULT_PARSING_MODE)?;
+		modified = true;
+	}
+
+	// Same for the keyed metadata
+	let keyed_meta = build_keyed_meta(tag.keyed_metadata)?;
+	if write_handler_meta(&atom_writer, *b"mdta", keyed_meta)? {
+		atom_writer.reparse(ParseOptions::DEFAULT_PARSING_MODE)?;
+		modified = true;
+	}
 
+	// And the 3GPP assets
+	if write_assets(&atom_writer, tag.assets)? {
+		atom_writer.reparse(ParseOptions::DEFAULT_PARSING_MODE)?;
+		modified = true;
+	}
+
+	if write_options.migrate_quicktime_text && remove_udta_text(&atom_writer)? {
+		atom_writer.reparse(ParseOptions::DEFAULT_PARSING_MODE)?;
+		modified = true;
+	}
+
+	let ilst = build_ilst(&mut tag.atoms)?;
+	if replace_ilst(&atom_writer, ilst, write_options)? {
+		modified = true;
+	}
+
+	if modified {
+		atom_writer.save_to(file)?;
+	}
+
+	Ok(())
+}
+
+// Replaces, creates, or removes the `moov.udta.meta.ilst` atom
+//
+// Returns `true` if the file was modified
+fn replace_ilst(
+	atom_writer: &AtomWriter,
+	ilst: Vec<u8>,
+	write_options: WriteOptions,
+) -> Result<bool> {
 	let Some(moov) = atom_writer.find_contextual_atom(*b"moov") else {
 		return Err(FileEncodingError::new(
 			FileType::Mp4,
@@ -71,7 +237,6 @@ where
 	let mut write_handle = atom_writer.start_write();
 	write_handle.seek(SeekFrom::Start(moov_data_start))?;
 
-	let ilst = build_ilst(&mut tag.atoms)?;
 	let remove_tag = ilst.is_empty();
 
 	let udta = find_child_atom(
@@ -83,7 +248,7 @@ where
 
 	// Nothing to do
 	if remove_tag && udta.is_none() {
-		return Ok(());
+		return Ok(false);
 	}
 
 	// Total size of new atoms
@@ -102,16 +267,12 @@ where
 		existing_udta_size = udta.len;
 		new_udta_size = existing_udta_size;
 
-		let meta = find_child_atom(
-			&mut write_handle,
-			udta.len,
-			*b"meta",
-			ParseOptions::DEFAULT_PARSING_MODE,
-		)?;
+		// The keyed metadata and `ID32` atom may also be in `udta`, and aren't ours to touch
+		let meta = find_meta(&mut write_handle, udta.len, &[*b"mdta", *b"ID32"])?;
 
 		// Nothing to do
 		if remove_tag && meta.is_none() {
-			return Ok(());
+			return Ok(false);
 		}
 
 		match meta {
@@ -128,7 +289,7 @@ where
 
 				// We can use the existing `udta` and `meta` atoms
 				save_to_existing(
-					&atom_writer,
+					atom_writer,
 					moov,
 					(meta, udta),
 					&mut new_udta_size,
@@ -201,11 +362,7 @@ where
 	);
 	write_handle.write_atom_size(moov_start, new_moov_length, moov_extended)?;
 
-	drop(write_handle);
-
-	atom_writer.save_to(file)?;
-
-	Ok(())
+	Ok(true)
 }
 
 // TODO: We are forcing the use of ParseOptions::DEFAULT_PARSING_MODE. This is not good. It should be caller-specified.
@@ -353,7 +510,7 @@ fn save_to_existing(
 	// Update offset atoms
 	if difference != 0 {
 		let offset = range.start as u64;
-		update_offsets(writer, moov, difference, offset)?;
+		update_offsets(writer, moov, difference, offset..u64::MAX)?;
 	}
 
 	// Replace the `ilst` atom
@@ -364,6 +521,968 @@ fn save_to_existing(
 	Ok(())
 }
 
+// Replaces, creates, or removes the `moov.udta.chpl` atom
+//
+// A new `chpl` atom is only created if the file doesn't have a chapter track, see `write_chapter_track`.
+//
+// Returns `true` if the file was modified
+fn write_chpl(writer: &AtomWriter, chapters: &[Chapter]) -> Result<bool> {
+	let Some(moov) = writer.find_contextual_atom(*b"moov") else {
+		// This will be reported later
+		return Ok(false);
+	};
+
+	let udta = moov
+		.children
+		.iter()
+		.find(|atom| atom.info.ident == AtomIdent::Fourcc(*b"udta"));
+	let existing_chpl = udta.and_then(|udta| {
+		udta.children
+			.iter()
+			.find(|atom| atom.info.ident == AtomIdent::Fourcc(*b"chpl"))
+	});
+
+	let chpl = build_chpl(chapters)?;
+
+	// Nothing to do
+	if chpl.is_empty() && existing_chpl.is_none() {
+		return Ok(false);
+	}
+
+	// The chapters will be written to the chapter track instead
+	if existing_chpl.is_none()
+		&& ChapterTrack::find(
+			&mut writer.start_write(),
+			moov,
+			ParseOptions::DEFAULT_PARSING_MODE,
+		)?
+		.is_some()
+	{
+		return Ok(false);
+	}
+
+	let replacement;
+	let range;
+	let mut new_udta_size = None;
+	match udta {
+		Some(udta) => {
+			range = match existing_chpl {
+				Some(existing_chpl) => {
+					let start = existing_chpl.info.start as usize;
+					start..start + existing_chpl.info.len as usize
+				},
+				// We'll put the new `chpl` atom at the end of `udta`
+				None => {
+					let udta_end = (udta.info.start + udta.info.len) as usize;
+					udta_end..udta_end
+				},
+			};
+
+			new_udta_size = Some((udta.info.len - range.len() as u64) + chpl.len() as u64);
+			replacement = chpl;
+		},
+		None => {
+			log::trace!("No `udta` atom found, creating one for `chpl`");
+
+			let mut udta = Vec::with_capacity(ATOM_HEADER_LEN as usize + chpl.len());
+			udta.write_u32::<BigEndian>((ATOM_HEADER_LEN as usize + chpl.len()) as u32)?;
+			udta.write_all(b"udta")?;
+			udta.extend(chpl);
+
+			// We'll put the new `udta` atom right at the start of `moov`
+			let udta_pos = (moov.info.start + moov.info.header_size()) as usize;
+			range = udta_pos..udta_pos;
+			replacement = udta;
+		},
+	}
+
+	let difference = replacement.len() as i64 - range.len() as i64;
+	if difference != 0 {
+		update_offsets(writer, moov, difference, range.start as u64..u64::MAX)?;
+	}
+
+	let mut write_handle = writer.start_write();
+	write_handle.splice(range, replacement);
+
+	// The size changes have to come last, since they may shift the contents
+	if let (Some(udta), Some(new_udta_size)) = (udta, new_udta_size) {
+		write_handle.seek(SeekFrom::Start(udta.info.start))?;
+		write_handle.write_atom_size(udta.info.start, new_udta_size, udta.info.extended)?;
+	}
+
+	let new_moov_size = (moov.info.len as i64 + difference) as u64;
+	write_handle.seek(SeekFrom::Start(moov.info.start))?;
+	write_handle.write_atom_size(moov.info.start, new_moov_size, moov.info.extended)?;
+
+	Ok(true)
+}
+
+// Rewrites the samples of the file's QuickTime chapter track, if it has one
+//
+// The new samples replace the old ones in place if they fit. Otherwise, they're stored in a new
+// `mdat` atom at the end of the file, replacing the one written last time if it's still there.
+//
+// Returns `true` if the file was modified
+fn write_chapter_track(writer: &AtomWriter, chapters: &[Chapter]) -> Result<bool> {
+	let Some(moov) = writer.find_contextual_atom(*b"moov") else {
+		// This will be reported later
+		return Ok(false);
+	};
+
+	let mut write_handle = writer.start_write();
+	let Some(track) =
+		ChapterTrack::find(&mut write_handle, moov, ParseOptions::DEFAULT_PARSING_MODE)?
+	else {
+		return Ok(false);
+	};
+
+	// Nothing to do
+	if track.read_chapters(&mut write_handle)? == track.normalize(chapters) {
+		return Ok(false);
+	}
+
+	log::debug!("Rewriting QuickTime chapter track");
+
+	let duration = track
+		.sample_durations(chapters)
+		.into_iter()
+		.fold(0_u64, |total, duration| {
+			total.saturating_add(u64::from(duration))
+		});
+	if !chapters.is_empty() && duration != track.duration {
+		track.write_duration(&mut write_handle, duration)?;
+	}
+
+	let old_samples = track.sample_range(&mut write_handle)?;
+	let file_len = write_handle.len() as u64;
+
+	drop(write_handle);
+
+	// An `mdat` atom holding nothing but the old samples, such as one created by a previous write
+	let old_mdat = old_samples.as_ref().and_then(|old_samples| {
+		writer
+			.atoms()
+			.iter()
+			.map(|atom| &atom.info)
+			.find(|info| {
+				info.ident == AtomIdent::Fourcc(*b"mdat")
+					&& info.start + info.header_size() == old_samples.start
+					&& info.start + info.len == old_samples.end
+			})
+			.map(|info| info.start..info.start + info.len)
+	});
+
+	let stbl = track.stbl;
+	let content_start = stbl.info.start + stbl.info.header_size();
+	let range = content_start as usize..(stbl.info.start + stbl.info.len) as usize;
+
+	// The sample tables need to know where the samples will end up, which depends on their own size
+	let kept = rebuild_container(writer, stbl, is_sample_table, Vec::new())?;
+	let (tables, samples) = track.build_samples(chapters, 0)?;
+	let difference = (kept.len() + tables.len()) as i64 - range.len() as i64;
+
+	// Anything after the sample tables will be moved by the size difference
+	let shifted = |pos: u64, difference: i64| {
+		if pos >= content_start {
+			(pos as i64 + difference) as u64
+		} else {
+			pos
+		}
+	};
+
+	let placement = match (old_mdat, old_samples) {
+		(Some(old_mdat), _) if old_mdat.end == file_len => SamplePlacement::ReplaceLast(old_mdat),
+		(_, Some(old_samples))
+			if old_samples.end <= file_len
+				&& samples.len() as u64 <= old_samples.end - old_samples.start =>
+		{
+			SamplePlacement::InPlace(old_samples.start)
+		},
+		(old_mdat, _) => SamplePlacement::Append(old_mdat),
+	};
+
+	let (mut data_offset, data_after_tables) = match &placement {
+		SamplePlacement::InPlace(start) => (shifted(*start, difference), *start >= content_start),
+		SamplePlacement::ReplaceLast(old_mdat) => {
+			(shifted(old_mdat.start, difference) + ATOM_HEADER_LEN, true)
+		},
+		SamplePlacement::Append(_) => (shifted(file_len, difference) + ATOM_HEADER_LEN, true),
+	};
+
+	if data_after_tables && u32::try_from(data_offset).is_err() {
+		// A `co64` atom is 4 bytes larger than an `stco` atom
+		data_offset += 4;
+	}
+
+	let (tables, samples) = track.build_samples(chapters, data_offset)?;
+
+	let mut content = kept;
+	content.extend(tables);
+
+	let difference = content.len() as i64 - range.len() as i64;
+	if difference != 0 {
+		update_offsets(writer, moov, difference, content_start..u64::MAX)?;
+	}
+
+	let mut write_handle = writer.start_write();
+	write_handle.splice(range, content);
+
+	// The size changes have to come last, since they may shift the contents
+	for atom in [track.stbl, track.minf, track.mdia, track.trak, moov] {
+		let new_size = (atom.info.len as i64 + difference) as u64;
+		write_handle.seek(SeekFrom::Start(atom.info.start))?;
+		write_handle.write_atom_size(atom.info.start, new_size, atom.info.extended)?;
+	}
+
+	match placement {
+		SamplePlacement::InPlace(_) => {
+			log::trace!("Replacing the chapter samples in place");
+
+			write_handle.seek(SeekFrom::Start(data_offset))?;
+			write_handle.write_all(&samples)?;
+			return Ok(true);
+		},
+		SamplePlacement::ReplaceLast(old_mdat) => {
+			log::trace!("Replacing the previous chapter `mdat` atom");
+
+			let start = shifted(old_mdat.start, difference) as usize;
+			write_handle.splice(start.., []);
+		},
+		SamplePlacement::Append(Some(old_mdat)) => {
+			log::trace!("Marking the previous chapter `mdat` atom as free space");
+
+			write_handle.seek(SeekFrom::Start(shifted(old_mdat.start, difference) + 4))?;
+			write_handle.write_all(b"free")?;
+		},
+		SamplePlacement::Append(None) => {},
+	}
+
+	if !samples.is_empty() {
+		let Ok(mdat_len) = u32::try_from(ATOM_HEADER_LEN as usize + samples.len()) else {
+			err!(TooMuchData);
+		};
+
+		let mut mdat = Vec::with_capacity(mdat_len as usize);
+		mdat.write_u32::<BigEndian>(mdat_len)?;
+		mdat.write_all(b"mdat")?;
+		mdat.extend(samples);
+
+		let end = write_handle.len();
+		write_handle.splice(end..end, mdat);
+	}
+
+	Ok(true)
+}
+
+// Where `write_chapter_track` puts the new samples
+enum SamplePlacement {
+	// Over the old samples, starting at the given position
+	InPlace(u64),
+	// In a new `mdat` atom, replacing the old one at the end of the file
+	ReplaceLast(Range<u64>),
+	// In a new `mdat` atom at the end of the file, with the old one (if any) turned into a `free` atom
+	Append(Option<Range<u64>>),
+}
+
+fn is_sample_table(fourcc: [u8; 4]) -> bool {
+	matches!(
+		&fourcc,
+		b"stts" | b"stsc" | b"stsz" | b"stz2" | b"stco" | b"co64"
+	)
+}
+
+// Replaces, creates, or removes the 3GPP asset atoms in `moov.udta`
+//
+// Returns `true` if the file was modified
+fn write_assets(writer: &AtomWriter, assets: &AssetInformation) -> Result<bool> {
+	let Some(moov) = writer.find_contextual_atom(*b"moov") else {
+		// This will be reported later
+		return Ok(false);
+	};
+
+	let udta = moov
+		.children
+		.iter()
+		.find(|atom| atom.info.ident == AtomIdent::Fourcc(*b"udta"));
+	let has_existing_assets = udta.is_some_and(|udta| has_children(udta, is_asset));
+
+	let new_assets = build_assets(assets)?;
+
+	// Nothing to do
+	if new_assets.is_empty() && !has_existing_assets {
+		return Ok(false);
+	}
+
+	let Some(udta) = udta else {
+		log::trace!("No `udta` atom found, creating one for the assets");
+
+		let mut udta = Vec::with_capacity(ATOM_HEADER_LEN as usize + new_assets.len());
+		udta.write_u32::<BigEndian>((ATOM_HEADER_LEN as usize + new_assets.len()) as u32)?;
+		udta.write_all(b"udta")?;
+		udta.extend(new_assets);
+
+		// We'll put the new `udta` atom right at the start of `moov`
+		let udta_pos = moov.info.start + moov.info.header_size();
+		update_offsets(writer, moov, udta.len() as i64, udta_pos..u64::MAX)?;
+
+		let new_moov_size = moov.info.len + udta.len() as u64;
+
+		let mut write_handle = writer.start_write();
+		write_handle.splice(udta_pos as usize..udta_pos as usize, udta);
+
+		write_handle.seek(SeekFrom::Start(moov.info.start))?;
+		write_handle.write_atom_size(moov.info.start, new_moov_size, moov.info.extended)?;
+
+		return Ok(true);
+	};
+
+	// The assets can be spread throughout `udta`, so its contents are rebuilt, with the new
+	// assets at the end
+	let content = rebuild_container(writer, udta, is_asset, new_assets)?;
+	replace_udta_content(writer, moov, udta, content)?;
+
+	Ok(true)
+}
+
+// Removes the QuickTime text atoms from `moov.udta`
+//
+// These were merged into the `ilst` atom when reading, see `WriteOptions::migrate_quicktime_text`.
+//
+// Returns `true` if the file was modified
+fn remove_udta_text(writer: &AtomWriter) -> Result<bool> {
+	let Some(moov) = writer.find_contextual_atom(*b"moov") else {
+		// This will be reported later
+		return Ok(false);
+	};
+
+	let Some(udta) = moov
+		.children
+		.iter()
+		.find(|atom| atom.info.ident == AtomIdent::Fourcc(*b"udta"))
+	else {
+		return Ok(false);
+	};
+
+	// Nothing to do
+	if !has_children(udta, is_udta_text) {
+		return Ok(false);
+	}
+
+	log::debug!("Removing QuickTime text atoms from `udta`");
+
+	let content = rebuild_container(writer, udta, is_udta_text, Vec::new())?;
+	replace_udta_content(writer, moov, udta, content)?;
+
+	Ok(true)
+}
+
+fn has_children(container: &ContextualAtom, predicate: fn([u8; 4]) -> bool) -> bool {
+	container
+		.children
+		.iter()
+		.any(|child| matches!(child.info.ident, AtomIdent::Fourcc(fourcc) if predicate(fourcc)))
+}
+
+// Creates the new contents of `container`, dropping the children matching `remove`, and appending `new_children`
+fn rebuild_container(
+	writer: &AtomWriter,
+	container: &ContextualAtom,
+	remove: fn([u8; 4]) -> bool,
+	new_children: Vec<u8>,
+) -> Result<Vec<u8>> {
+	let mut content = Vec::new();
+
+	let mut write_handle = writer.start_write();
+	for child in &container.children {
+		if matches!(child.info.ident, AtomIdent::Fourcc(fourcc) if remove(fourcc)) {
+			continue;
+		}
+
+		write_handle.seek(SeekFrom::Start(child.info.start))?;
+		(&mut write_handle)
+			.take(child.info.len)
+			.read_to_end(&mut content)?;
+	}
+
+	content.extend(new_children);
+	Ok(content)
+}
+
+fn replace_udta_content(
+	writer: &AtomWriter,
+	moov: &ContextualAtom,
+	udta: &ContextualAtom,
+	content: Vec<u8>,
+) -> Result<()> {
+	let content_start = udta.info.start + udta.info.header_size();
+	let range = content_start as usize..(udta.info.start + udta.info.len) as usize;
+
+	let difference = content.len() as i64 - range.len() as i64;
+	if difference != 0 {
+		update_offsets(writer, moov, difference, content_start..u64::MAX)?;
+	}
+
+	let new_udta_size = udta.info.header_size() + content.len() as u64;
+
+	let mut write_handle = writer.start_write();
+	write_handle.splice(range, content);
+
+	// The size changes have to come last, since they may shift the contents
+	write_handle.seek(SeekFrom::Start(udta.info.start))?;
+	write_handle.write_atom_size(udta.info.start, new_udta_size, udta.info.extended)?;
+
+	let new_moov_size = (moov.info.len as i64 + difference) as u64;
+	write_handle.seek(SeekFrom::Start(moov.info.start))?;
+	write_handle.write_atom_size(moov.info.start, new_moov_size, moov.info.extended)?;
+
+	Ok(())
+}
+
+// Replaces, creates, or removes the `meta` atom with the handler `handler_type`
+//
+// An empty `meta` will remove the existing atom.
+//
+// Returns `true` if the file was modified
+fn write_handler_meta(writer: &AtomWriter, handler_type: [u8; 4], meta: Vec<u8>) -> Result<bool> {
+	let Some(moov) = writer.find_contextual_atom(*b"moov") else {
+		// This will be reported later
+		return Ok(false);
+	};
+
+	// The `meta` atom can be in either `moov` or `moov.udta`. For keyed metadata, iPhones use the
+	// former, while FFmpeg uses the latter.
+	let mut existing_meta = None;
+	let mut parent_udta = None;
+	{
+		let mut write_handle = writer.start_write();
+
+		let udta = moov
+			.children
+			.iter()
+			.filter(|atom| atom.info.ident == AtomIdent::Fourcc(*b"udta"));
+		'search: for container in std::iter::once(moov).chain(udta) {
+			for meta in container.find_all_children(*b"meta", false) {
+				write_handle.seek(SeekFrom::Start(meta.start + meta.header_size()))?;
+				if meta_handler(&mut write_handle, meta)? != handler_type {
+					continue;
+				}
+
+				existing_meta = Some(meta);
+				if container.info.start != moov.info.start {
+					parent_udta = Some(&container.info);
+				}
+
+				break 'search;
+			}
+		}
+	}
+
+	// Nothing to do
+	if meta.is_empty() && existing_meta.is_none() {
+		return Ok(false);
+	}
+
+	let range = match existing_meta {
+		Some(existing_meta) => {
+			let start = existing_meta.start as usize;
+			start..start + existing_meta.len as usize
+		},
+		// We'll put the new `meta` atom at the end of `moov`
+		None => {
+			let moov_end = (moov.info.start + moov.info.len) as usize;
+			moov_end..moov_end
+		},
+	};
+
+	let difference = meta.len() as i64 - range.len() as i64;
+	if difference != 0 {
+		update_offsets(writer, moov, difference, range.start as u64..u64::MAX)?;
+	}
+
+	let mut write_handle = writer.start_write();
+	write_handle.splice(range, meta);
+
+	// The size changes have to come last, since they may shift the contents
+	if let Some(udta) = parent_udta {
+		let new_udta_size = (udta.len as i64 + difference) as u64;
+		write_handle.seek(SeekFrom::Start(udta.start))?;
+		write_handle.write_atom_size(udta.start, new_udta_size, udta.extended)?;
+	}
+
+	let new_moov_size = (moov.info.len as i64 + difference) as u64;
+	write_handle.seek(SeekFrom::Start(moov.info.start))?;
+	write_handle.write_atom_size(moov.info.start, new_moov_size, moov.info.extended)?;
+
+	Ok(true)
+}
+
+// Creates a `meta` atom with an `mdta` handler, holding the `keys` and `ilst` atoms
+fn build_keyed_meta(keyed_metadata: &KeyedMetadata) -> Result<Vec<u8>> {
+	if keyed_metadata.is_empty() {
+		return Ok(Vec::new());
+	}
+
+	log::debug!("Building keyed `meta` atom");
+
+	// Each key is only written once, with all of its values
+	let keys = keyed_metadata.keys();
+
+	let mut meta = Vec::new();
+
+	// Size, written later
+	meta.write_u32::<BigEndian>(0)?;
+	meta.write_all(b"meta")?;
+	meta.write_u32::<BigEndian>(0)?;
+
+	meta.write_u32::<BigEndian>(HDLR_SIZE as u32)?;
+	meta.write_all(b"hdlr")?;
+	meta.write_u64::<BigEndian>(0)?;
+	meta.write_all(b"mdta")?;
+	meta.write_all(&[0; 13])?;
+
+	let keys_start = meta.len();
+
+	// Size, written later
+	meta.write_u32::<BigEndian>(0)?;
+	meta.write_all(b"keys")?;
+	meta.write_u32::<BigEndian>(0)?;
+	meta.write_u32::<BigEndian>(keys.len() as u32)?;
+
+	for key in &keys {
+		let Ok(key_size) = u32::try_from(ATOM_HEADER_LEN as usize + key.len()) else {
+			err!(TooMuchData);
+		};
+
+		meta.write_u32::<BigEndian>(key_size)?;
+		meta.write_all(b"mdta")?;
+		meta.write_all(key.as_bytes())?;
+	}
+
+	let keys_size = (meta.len() - keys_start) as u32;
+	meta[keys_start..keys_start + 4].copy_from_slice(&keys_size.to_be_bytes());
+
+	// The atoms are identified by the (1-based) index of their key
+	let mut atoms = keys.iter().zip(1u32..).map(|(key, index)| AtomRef {
+		ident: AtomIdent::Fourcc(index.to_be_bytes()),
+		data: keyed_metadata.get_all(key).collect::<Vec<_>>(),
+	});
+	meta.extend(build_ilst(&mut atoms)?);
+
+	let Ok(meta_size) = u32::try_from(meta.len()) else {
+		err!(TooMuchData);
+	};
+
+	meta[..4].copy_from_slice(&meta_size.to_be_bytes());
+
+	Ok(meta)
+}
+
+// Replaces, creates, or removes the top-level XMP `uuid` atom
+//
+// A new atom is placed directly after `moov`.
+//
+// Returns `true` if the file was modified
+fn write_xmp_uuid(writer: &AtomWriter, packet: Option<&[u8]>) -> Result<bool> {
+	let Some(moov) = writer.find_contextual_atom(*b"moov") else {
+		// This will be reported later
+		return Ok(false);
+	};
+
+	let mut existing = None;
+	{
+		let mut write_handle = writer.start_write();
+		for atom in writer.atoms() {
+			if atom.info.ident != AtomIdent::Fourcc(*b"uuid")
+				|| atom.info.len < atom.info.header_size() + MP4_UUID.len() as u64
+			{
+				continue;
+			}
+
+			let mut uuid = [0; 16];
+			write_handle.seek(SeekFrom::Start(atom.info.start + atom.info.header_size()))?;
+			write_handle.read_exact(&mut uuid)?;
+
+			if uuid == MP4_UUID {
+				existing = Some(atom.info.start..atom.info.start + atom.info.len);
+				break;
+			}
+		}
+	}
+
+	let mut replacement = Vec::new();
+	if let Some(packet) = packet {
+		let Ok(size) = u32::try_from(ATOM_HEADER_LEN as usize + MP4_UUID.len() + packet.len())
+		else {
+			err!(TooMuchData);
+		};
+
+		replacement.write_u32::<BigEndian>(size)?;
+		replacement.write_all(b"uuid")?;
+		replacement.write_all(&MP4_UUID)?;
+		replacement.write_all(packet)?;
+	}
+
+	let range = match existing {
+		Some(range) => range.start as usize..range.end as usize,
+		// Nothing to do
+		None if replacement.is_empty() => return Ok(false),
+		None => {
+			let moov_end = (moov.info.start + moov.info.len) as usize;
+			moov_end..moov_end
+		},
+	};
+
+	let difference = replacement.len() as i64 - range.len() as i64;
+	if difference != 0 {
+		update_offsets(writer, moov, difference, range.start as u64..u64::MAX)?;
+	}
+
+	writer.start_write().splice(range, replacement);
+
+	Ok(true)
+}
+
+// Creates a `meta` atom with an `ID32` handler, holding the `ID32` atom
+fn build_id32_meta(id3v2: &[u8]) -> Result<Vec<u8>> {
+	if id3v2.is_empty() {
+		return Ok(Vec::new());
+	}
+
+	log::debug!("Building `ID32` `meta` atom");
+
+	// Header + version (1) + flags (3) + pad (1 bit) + language (15 bits)
+	let id32_size = FULL_ATOM_SIZE as usize + 2 + id3v2.len();
+	let meta_size = FULL_ATOM_SIZE as usize + HDLR_SIZE as usize + id32_size;
+
+	let (Ok(id32_size), Ok(meta_size)) = (u32::try_from(id32_size), u32::try_from(meta_size))
+	else {
+		err!(TooMuchData);
+	};
+
+	let mut meta = Vec::with_capacity(meta_size as usize);
+
+	meta.write_u32::<BigEndian>(meta_size)?;
+	meta.write_all(b"meta")?;
+	meta.write_u32::<BigEndian>(0)?;
+
+	meta.write_u32::<BigEndian>(HDLR_SIZE as u32)?;
+	meta.write_all(b"hdlr")?;
+	meta.write_u64::<BigEndian>(0)?;
+	meta.write_all(b"ID32")?;
+	meta.write_all(&[0; 13])?;
+
+	meta.write_u32::<BigEndian>(id32_size)?;
+	meta.write_all(b"ID32")?;
+	meta.write_u32::<BigEndian>(0)?;
+	// "und", undetermined
+	meta.write_u16::<BigEndian>(0x55C4)?;
+	meta.write_all(id3v2)?;
+
+	Ok(meta)
+}
+
+// The handler type of a `meta` atom
+//
+// A `meta` atom without a `hdlr` atom is treated as `mdir`.
+//
+// NOTE: This expects the reader to be at the start of the `meta` atom's content
+fn meta_handler<R>(reader: &mut R, meta: &AtomInfo) -> Result<[u8; 4]>
+where
+	R: Read + Seek,
+{
+	let mut len = meta.len - meta.header_size();
+	if meta_is_full(reader)? {
+		len -= 4;
+	}
+
+	let Some(hdlr) = find_child_atom(reader, len, *b"hdlr", ParseOptions::DEFAULT_PARSING_MODE)?
+	else {
+		return Ok(*b"mdir");
+	};
+
+	// Version (1) + flags (3) + pre-defined (4) + handler type (4)
+	if hdlr.len - hdlr.header_size() < 12 {
+		return Ok(*b"mdir");
+	}
+
+	reader.seek(SeekFrom::Current(8))?;
+
+	let mut handler_type = [0; 4];
+	reader.read_exact(&mut handler_type)?;
+
+	Ok(handler_type)
+}
+
+// Finds the first `meta` atom with a handler type that isn't in `skip`
+//
+// NOTE: This will leave the reader at the start of the `meta` atom's content
+fn find_meta<R>(reader: &mut R, mut len: u64, skip: &[[u8; 4]]) -> Result<Option<AtomInfo>>
+where
+	R: Read + Seek,
+{
+	loop {
+		let search_start = reader.stream_position()?;
+		let Some(meta) =
+			find_child_atom(reader, len, *b"meta", ParseOptions::DEFAULT_PARSING_MODE)?
+		else {
+			return Ok(None);
+		};
+
+		let content_start = reader.stream_position()?;
+		if !skip.contains(&meta_handler(reader, &meta)?) {
+			reader.seek(SeekFrom::Start(content_start))?;
+			return Ok(Some(meta));
+		}
+
+		let meta_end = meta.start + meta.len;
+		len = len.saturating_sub(meta_end - search_start);
+		reader.seek(SeekFrom::Start(meta_end))?;
+	}
+}
+
+// The start of the first `mdat` atom, if there is one
+//
+// NOTE: This expects the reader to be at the start of a top-level atom
+fn find_media_start<R>(reader: &mut AtomReader<R>) -> Result<Option<u64>>
+where
+	R: Read + Seek,
+{
+	while let Ok(Some(atom)) = reader.next() {
+		if atom.ident == AtomIdent::Fourcc(*b"mdat") {
+			return Ok(Some(atom.start));
+		}
+
+		skip_atom(reader, atom.extended, atom.len)?;
+	}
+
+	Ok(None)
+}
+
+// Moves the `moov` atom to the position specified in the `WriteOptions`
+//
+// `old_media_start` is the position of the `mdat` atom before the tag was written.
+//
+// Returns `true` if the file was modified
+fn update_layout(
+	writer: &AtomWriter,
+	old_media_start: Option<u64>,
+	write_options: WriteOptions,
+) -> Result<bool> {
+	let (Some(moov), Some(mdat)) = (
+		writer.find_contextual_atom(*b"moov"),
+		writer.find_contextual_atom(*b"mdat"),
+	) else {
+		log::warn!("Unable to find `moov` and `mdat` atoms, not changing the layout");
+		return Ok(false);
+	};
+
+	// In fragmented files, `moov` has to come before all of the `moof` atoms
+	let is_fragmented = moov
+		.children
+		.iter()
+		.any(|child| child.info.ident == AtomIdent::Fourcc(*b"mvex"))
+		|| writer.find_contextual_atom(*b"moof").is_some();
+	if is_fragmented {
+		log::warn!("Not changing the layout of a fragmented file");
+		return Ok(false);
+	}
+
+	let moov_range = moov.info.start..moov.info.start + moov.info.len;
+	let media_start = mdat.info.start;
+
+	match write_options.moov_position {
+		MoovPosition::Start if moov_range.start > media_start => {
+			log::debug!("Moving `moov` atom before `mdat` atom");
+
+			let padding = layout_padding(write_options);
+
+			// Everything between the start of the media and the old `moov` position gets pushed back
+			let difference = moov.info.len + u64::from(padding);
+			update_offsets(
+				writer,
+				moov,
+				difference as i64,
+				media_start..moov_range.start,
+			)?;
+
+			// Everything after the old `moov` position only gets pushed back by the padding
+			if padding > 0 {
+				update_offsets(writer, moov, i64::from(padding), moov_range.end..u64::MAX)?;
+			}
+
+			let mut write_handle = writer.start_write();
+
+			let mut replacement = take_range(&mut write_handle, moov_range)?;
+			if padding > 0 {
+				write_free_atom(&mut replacement, padding)?;
+			}
+
+			write_handle.splice(media_start as usize..media_start as usize, replacement);
+		},
+		MoovPosition::Start => {
+			let Some(old_media_start) = old_media_start else {
+				return Ok(false);
+			};
+
+			return resize_padding(writer, moov, media_start, old_media_start, write_options);
+		},
+		MoovPosition::End if moov_range.start < media_start => {
+			log::debug!("Moving `moov` atom to the end of the file");
+
+			let mut write_handle = writer.start_write();
+
+			// An `mdat` atom with a size of 0 extends to the end of the file, which would
+			// include the `moov` atom after the move.
+			write_handle.seek(SeekFrom::Start(media_start))?;
+			let mut mdat_header = None;
+			if write_handle.read_u32::<BigEndian>()? == 0 {
+				let mdat_len = write_handle.len() as u64 - media_start;
+
+				let mut header = Vec::new();
+				if let Ok(mdat_len) = u32::try_from(mdat_len) {
+					header.write_u32::<BigEndian>(mdat_len)?;
+					header.write_all(b"mdat")?;
+				} else {
+					// Too large for a 32-bit size, so the header needs to be extended
+					log::trace!("Extending `mdat` atom header to hold a 64-bit size");
+
+					header.write_u32::<BigEndian>(1)?;
+					header.write_all(b"mdat")?;
+					header.write_u64::<BigEndian>(mdat_len + 8)?;
+				}
+
+				mdat_header = Some(header);
+			}
+
+			drop(write_handle);
+
+			// Everything after the old `moov` position gets pulled forward, and pushed back by
+			// the extended `mdat` header, if there is one
+			let header_growth = mdat_header
+				.as_ref()
+				.map_or(0, |header| header.len() as i64 - ATOM_HEADER_LEN as i64);
+			update_offsets(
+				writer,
+				moov,
+				header_growth - moov.info.len as i64,
+				moov_range.end..u64::MAX,
+			)?;
+
+			let mut write_handle = writer.start_write();
+
+			if let Some(header) = mdat_header {
+				let header_range = media_start as usize..(media_start + ATOM_HEADER_LEN) as usize;
+				write_handle.splice(header_range, header);
+			}
+
+			let moov_bytes = take_range(&mut write_handle, moov_range)?;
+
+			let end = write_handle.len();
+			write_handle.splice(end..end, moov_bytes);
+		},
+		_ => return Ok(false),
+	}
+
+	Ok(true)
+}
+
+// Resizes the `free` atoms between `moov` and `mdat` to account for any change in the size of `moov`
+//
+// If there isn't enough padding to keep the media in place, it will be replaced with the preferred padding.
+//
+// Returns `true` if the file was modified
+fn resize_padding(
+	writer: &AtomWriter,
+	moov: &ContextualAtom,
+	media_start: u64,
+	old_media_start: u64,
+	write_options: WriteOptions,
+) -> Result<bool> {
+	let shift = media_start as i64 - old_media_start as i64;
+	if shift == 0 {
+		return Ok(false);
+	}
+
+	let padding_start = moov.info.start + moov.info.len;
+
+	// We can only make use of the space if it's entirely made up of `free` atoms
+	let mut write_handle = writer.start_write();
+
+	let mut pos = padding_start;
+	while pos < media_start {
+		write_handle.seek(SeekFrom::Start(pos))?;
+
+		let len = write_handle.read_u32::<BigEndian>()?;
+
+		let mut ident = [0; 4];
+		write_handle.read_exact(&mut ident)?;
+
+		if &ident != b"free" || u64::from(len) < ATOM_HEADER_LEN {
+			log::debug!("Found non-padding atoms between `moov` and `mdat`, not resizing");
+			return Ok(false);
+		}
+
+		pos += u64::from(len);
+	}
+
+	drop(write_handle);
+
+	let padding_len = (media_start - padding_start) as i64;
+
+	let mut new_padding_len = padding_len - shift;
+	if new_padding_len != 0 && new_padding_len < ATOM_HEADER_LEN as i64 {
+		log::trace!("Not enough padding to keep the media in place, using the preferred padding");
+		new_padding_len = i64::from(layout_padding(write_options));
+	}
+
+	let difference = new_padding_len - padding_len;
+	if difference == 0 {
+		return Ok(false);
+	}
+
+	let Ok(new_padding_len) = u32::try_from(new_padding_len) else {
+		err!(TooMuchData);
+	};
+
+	log::trace!(
+		"Resizing padding after `moov` atom, old size: {}, new size: {}",
+		padding_len,
+		new_padding_len
+	);
+
+	update_offsets(writer, moov, difference, media_start..u64::MAX)?;
+
+	let mut padding = Vec::new();
+	if new_padding_len > 0 {
+		write_free_atom(&mut padding, new_padding_len)?;
+	}
+
+	let mut write_handle = writer.start_write();
+	write_handle.splice(padding_start as usize..media_start as usize, padding);
+
+	Ok(true)
+}
+
+// The size of the `free` atom to place after `moov`, which has to be large enough to hold its header
+fn layout_padding(write_options: WriteOptions) -> u32 {
+	write_options
+		.preferred_padding
+		.filter(|padding| u64::from(*padding) >= ATOM_HEADER_LEN)
+		.unwrap_or(0)
+}
+
+// Removes the given range from the writer, returning its contents
+fn take_range(write_handle: &mut AtomWriterCompanion<'_>, range: Range<u64>) -> Result<Vec<u8>> {
+	let mut contents = try_vec![0; (range.end - range.start) as usize];
+
+	write_handle.seek(SeekFrom::Start(range.start))?;
+	write_handle.read_exact(&mut contents)?;
+
+	write_handle.splice(range.start as usize..range.end as usize, []);
+
+	Ok(contents)
+}
+
 fn pad_atom<W>(
 	writer: &mut W,
 	mut atom_size_difference: i64,
@@ -427,11 +1546,12 @@ where
 	Ok(())
 }
 
+// Shifts all chunk offsets within `offsets` by `difference`
 fn update_offsets(
 	writer: &AtomWriter,
 	moov: &ContextualAtom,
 	difference: i64,
-	ilst_offset: u64,
+	offsets: Range<u64>,
 ) -> Result<()> {
 	log::debug!("Checking for offset atoms to update");
 
@@ -451,17 +1571,18 @@ fn update_offsets(
 		let count = write_handle.read_u32::<BigEndian>()?;
 		for _ in 0..count {
 			let read_offset = write_handle.read_u32::<BigEndian>()?;
-			if u64::from(read_offset) < ilst_offset {
+			if !offsets.contains(&u64::from(read_offset)) {
 				continue;
 			}
+
+			let Ok(new_offset) = u32::try_from(i64::from(read_offset) + difference) else {
+				err!(TooMuchData);
+			};
+
 			write_handle.seek(SeekFrom::Current(-4))?;
-			write_handle.write_u32::<BigEndian>((i64::from(read_offset) + difference) as u32)?;
+			write_handle.write_u32::<BigEndian>(new_offset)?;
 
-			log::trace!(
-				"Updated offset from {} to {}",
-				read_offset,
-				(i64::from(read_offset) + difference) as u32
-			);
+			log::trace!("Updated offset from {} to {}", read_offset, new_offset);
 		}
 	}
 
@@ -479,7 +1600,7 @@ fn update_offsets(
 		let count = write_handle.read_u32::<BigEndian>()?;
 		for _ in 0..count {
 			let read_offset = write_handle.read_u64::<BigEndian>()?;
-			if read_offset < ilst_offset {
+			if !offsets.contains(&read_offset) {
 				continue;
 			}
 
@@ -494,14 +1615,15 @@ fn update_offsets(
 		}
 	}
 
-	let Some(moof) = writer.find_contextual_atom(*b"moof") else {
-		return Ok(());
-	};
-
-	log::trace!("Found `moof` atom, checking for `tfhd` atoms to update");
+	// Every fragment has its own `tfhd` atoms
+	let tfhd_atoms = writer
+		.atoms()
+		.iter()
+		.filter(|atom| atom.info.ident == AtomIdent::Fourcc(*b"moof"))
+		.flat_map(|moof| moof.find_all_children(*b"tfhd", true));
 
 	// 64-bit offsets
-	for tfhd in moof.find_all_children(*b"tfhd", true) {
+	for tfhd in tfhd_atoms {
 		log::trace!("Found `tfhd` atom");
 
 		let tfhd_start = tfhd.start;
@@ -517,7 +1639,7 @@ fn update_offsets(
 
 		if base_data_offset {
 			let read_offset = write_handle.read_u64::<BigEndian>()?;
-			if read_offset < ilst_offset {
+			if !offsets.contains(&read_offset) {
 				continue;
 			}
 
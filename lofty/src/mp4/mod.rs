@@ -2,14 +2,30 @@
 //!
 //! ## File notes
 //!
-//! The only supported tag format is [`Ilst`].
+//! The primary tag format is [`Ilst`], which also holds the file's [`KeyedMetadata`] and
+//! 3GPP [`AssetInformation`].
+//!
+//! Some broadcast and DASH encoders also store an [`Id3v2Tag`](crate::id3::v2::Id3v2Tag) in an
+//! `ID32` atom, within a `meta` atom in `moov`. It is written back to the same place, or to the end
+//! of `moov` if the file doesn't have one yet.
+//!
+//! An [`XmpTag`](crate::xmp::XmpTag) is read from a top-level `uuid` atom, and new ones are
+//! written directly after `moov`.
+mod assets;
 mod atom_info;
+mod chapters;
+mod fragments;
 pub(crate) mod ilst;
 mod moov;
 mod properties;
 mod read;
+mod udta_text;
 mod write;
 
+use crate::id3::v2::tag::Id3v2Tag;
+use crate::macros::impl_chapters;
+use crate::xmp::XmpTag;
+
 use lofty_attr::LoftyFile;
 
 // Exports
@@ -21,11 +37,13 @@ pub mod constants {
 	pub use super::ilst::constants::*;
 }
 
-pub use crate::mp4::properties::{AudioObjectType, Mp4Codec, Mp4Properties};
+pub use crate::mp4::properties::{AudioObjectType, Mp4Codec, Mp4Properties, Mp4Track};
+pub use assets::{AssetAlbum, AssetInformation, AssetLocation, AssetText, AssetTextType};
 pub use atom_info::AtomIdent;
 pub use ilst::advisory_rating::AdvisoryRating;
 pub use ilst::atom::{Atom, AtomData};
 pub use ilst::data_type::DataType;
+pub use ilst::keyed_metadata::KeyedMetadata;
 pub use ilst::Ilst;
 
 pub(crate) use properties::SAMPLE_RATES;
@@ -39,10 +57,20 @@ pub struct Mp4File {
 	#[lofty(tag_type = "Mp4Ilst")]
 	/// The parsed `ilst` (metadata) atom, if it exists
 	pub(crate) ilst_tag: Option<Ilst>,
+	#[lofty(tag_type = "Id3v2")]
+	/// The ID3v2 tag from an `ID32` atom, if it exists
+	pub(crate) id3v2_tag: Option<Id3v2Tag>,
+	#[lofty(tag_type = "Xmp")]
+	/// The XMP packet from a top-level `uuid` atom, if it exists
+	pub(crate) xmp_tag: Option<XmpTag>,
+	/// Every track in the file
+	pub(crate) tracks: Vec<Mp4Track>,
 	/// The file's audio properties
 	pub(crate) properties: Mp4Properties,
 }
 
+impl_chapters!(Mp4File, ilst_tag, Ilst);
+
 impl Mp4File {
 	/// Returns the file format from ftyp's "major brand" (Ex. "M4A ")
 	///
@@ -63,4 +91,166 @@ impl Mp4File {
 	pub fn ftyp(&self) -> &str {
 		self.ftyp.as_ref()
 	}
+
+	/// Returns all of the tracks in the file
+	///
+	/// This will be empty if the file was read without its properties
+	/// (see [`ParseOptions::read_properties`](crate::config::ParseOptions::read_properties)).
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// use lofty::config::ParseOptions;
+	/// use lofty::file::AudioFile;
+	/// use lofty::mp4::Mp4File;
+	///
+	/// # fn main() -> lofty::error::Result<()> {
+	/// # let mut m4a_reader = std::io::Cursor::new(&[]);
+	/// let m4a_file = Mp4File::read_from(&mut m4a_reader, ParseOptions::new())?;
+	///
+	/// for track in m4a_file.tracks() {
+	/// 	println!(
+	/// 		"Track {}: {:?}, {:?}",
+	/// 		track.id(),
+	/// 		track.handler_type().escape_ascii().to_string(),
+	/// 		track.duration()
+	/// 	);
+	/// }
+	/// # Ok(()) }
+	/// ```
+	pub fn tracks(&self) -> &[Mp4Track] {
+		&self.tracks
+	}
+
+	/// Select the audio track described by [`AudioFile::properties`](crate::file::AudioFile::properties)
+	///
+	/// By default, this is the first audio track in the file.
+	///
+	/// This returns `false` if there is no audio track with the given ID, leaving the properties unchanged.
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// use lofty::config::ParseOptions;
+	/// use lofty::file::AudioFile;
+	/// use lofty::mp4::Mp4File;
+	///
+	/// # fn main() -> lofty::error::Result<()> {
+	/// # let mut m4a_reader = std::io::Cursor::new(&[]);
+	/// let mut m4a_file = Mp4File::read_from(&mut m4a_reader, ParseOptions::new())?;
+	///
+	/// // Describe the second track instead, which holds the commentary
+	/// assert!(m4a_file.select_audio_track(2));
+	/// assert_eq!(m4a_file.properties().channels(), 1);
+	/// # Ok(()) }
+	/// ```
+	pub fn select_audio_track(&mut self, track_id: u32) -> bool {
+		let Some(properties) = self
+			.tracks
+			.iter()
+			.filter(|track| track.id == track_id)
+			.find_map(|track| track.properties.as_ref())
+		else {
+			return false;
+		};
+
+		self.properties = properties.clone();
+		true
+	}
+
+	/// Returns the keyed metadata, if there is any
+	///
+	/// This is an alias for [`Ilst::keyed_metadata`], see [`KeyedMetadata`].
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// use lofty::config::ParseOptions;
+	/// use lofty::file::AudioFile;
+	/// use lofty::mp4::Mp4File;
+	///
+	/// # fn main() -> lofty::error::Result<()> {
+	/// # let mut mov_reader = std::io::Cursor::new(&[]);
+	/// let mov_file = Mp4File::read_from(&mut mov_reader, ParseOptions::new())?;
+	///
+	/// if let Some(location) = mov_file
+	/// 	.keyed_metadata()
+	/// 	.and_then(|keyed_metadata| keyed_metadata.location())
+	/// {
+	/// 	println!("Recorded at: {location}");
+	/// }
+	/// # Ok(()) }
+	/// ```
+	pub fn keyed_metadata(&self) -> Option<&KeyedMetadata> {
+		self.ilst_tag
+			.as_ref()
+			.map(Ilst::keyed_metadata)
+			.filter(|keyed_metadata| !keyed_metadata.is_empty())
+	}
+
+	/// Returns a mutable reference to the keyed metadata
+	///
+	/// This will create an empty [`Ilst`] if the file doesn't have one.
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// use lofty::config::{ParseOptions, WriteOptions};
+	/// use lofty::file::AudioFile;
+	/// use lofty::mp4::constants::keys;
+	/// use lofty::mp4::{AtomData, Mp4File};
+	///
+	/// # fn main() -> lofty::error::Result<()> {
+	/// # let path = "foo.mov";
+	/// let mut mov_file = Mp4File::read_from(&mut std::fs::File::open(path)?, ParseOptions::new())?;
+	///
+	/// mov_file
+	/// 	.keyed_metadata_mut()
+	/// 	.insert(keys::TITLE, AtomData::UTF8(String::from("Foo title")));
+	///
+	/// mov_file.save_to_path(path, WriteOptions::default())?;
+	/// # Ok(()) }
+	/// ```
+	pub fn keyed_metadata_mut(&mut self) -> &mut KeyedMetadata {
+		self.ilst_tag
+			.get_or_insert_with(Ilst::default)
+			.keyed_metadata_mut()
+	}
+
+	/// Returns the 3GPP asset information, if there is any
+	///
+	/// This is an alias for [`Ilst::assets`], see [`AssetInformation`].
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// use lofty::config::ParseOptions;
+	/// use lofty::file::AudioFile;
+	/// use lofty::mp4::{AssetTextType, Mp4File};
+	///
+	/// # fn main() -> lofty::error::Result<()> {
+	/// # let mut reader = std::io::Cursor::new(&[]);
+	/// let file = Mp4File::read_from(&mut reader, ParseOptions::new())?;
+	///
+	/// if let Some(title) = file
+	/// 	.assets()
+	/// 	.and_then(|assets| assets.text(AssetTextType::Title))
+	/// {
+	/// 	println!("Title: {}", title.text);
+	/// }
+	/// # Ok(()) }
+	/// ```
+	pub fn assets(&self) -> Option<&AssetInformation> {
+		self.ilst_tag
+			.as_ref()
+			.map(Ilst::assets)
+			.filter(|assets| !assets.is_empty())
+	}
+
+	/// Returns a mutable reference to the 3GPP asset information
+	///
+	/// This will create an empty [`Ilst`] if the file doesn't have one.
+	pub fn assets_mut(&mut self) -> &mut AssetInformation {
+		self.ilst_tag.get_or_insert_with(Ilst::default).assets_mut()
+	}
 }
@@ -1,18 +1,47 @@
+use super::assets::{is_asset, parse_asset, AssetInformation};
 use super::atom_info::{AtomIdent, AtomInfo};
-use super::ilst::read::parse_ilst;
+use super::chapters::parse_chpl;
+use super::fragments::Mvex;
+use super::ilst::keyed_metadata::KeyedMetadata;
+use super::ilst::read::{parse_ilst, parse_keyed_ilst, parse_keys};
 use super::ilst::Ilst;
-use super::read::{find_child_atom, meta_is_full, skip_atom, AtomReader};
-use crate::config::ParseOptions;
+use super::read::{meta_is_full, skip_atom, AtomReader};
+use super::udta_text::{is_udta_text, parse_udta_text};
+use crate::config::{ParseOptions, ParsingMode};
 use crate::error::Result;
-use crate::macros::decode_err;
+use crate::id3::v2::header::Id3v2Header;
+use crate::id3::v2::read::parse_id3v2;
+use crate::id3::v2::tag::Id3v2Tag;
+use crate::macros::{decode_err, try_vec};
+use crate::tag::items::Chapter;
 
-use std::io::{Read, Seek};
+use std::io::{Read, Seek, SeekFrom};
 
-pub(crate) struct Moov {
+pub(crate) struct Trak {
+	// The track ID from trak.tkhd
+	pub(crate) track_id: Option<u32>,
+	// The "track enabled" flag from trak.tkhd
+	pub(crate) enabled: bool,
 	// Represents the trak.mdia atom
-	pub(crate) traks: Vec<AtomInfo>,
+	pub(crate) mdia: AtomInfo,
+}
+
+pub(crate) struct Moov {
+	pub(crate) traks: Vec<Trak>,
+	// Represents a parsed moov.mvex, only present in fragmented files
+	pub(crate) mvex: Option<Mvex>,
 	// Represents a parsed moov.udta.meta.ilst
 	pub(crate) ilst: Option<Ilst>,
+	// Represents a parsed moov.udta.chpl
+	pub(crate) chapters: Option<Vec<Chapter>>,
+	// Represents a parsed moov.meta or moov.udta.meta with an `mdta` handler
+	pub(crate) keyed_metadata: Option<KeyedMetadata>,
+	// Represents the parsed 3GPP asset atoms in moov.udta
+	pub(crate) assets: AssetInformation,
+	// Represents the parsed QuickTime text atoms in moov.udta
+	pub(crate) udta_text: Vec<([u8; 4], String)>,
+	// Represents a parsed moov.meta.ID32 or moov.udta.meta.ID32
+	pub(crate) id3v2: Option<Id3v2Tag>,
 }
 
 impl Moov {
@@ -39,23 +68,46 @@ impl Moov {
 		R: Read + Seek,
 	{
 		let mut traks = Vec::new();
+		let mut mvex = None;
 		let mut ilst = None;
+		let mut chapters = None;
+		let mut keyed_metadata = None;
+		let mut assets = AssetInformation::default();
+		let mut udta_text = Vec::new();
+		let mut id3v2 = None;
 
 		while let Ok(Some(atom)) = reader.next() {
 			if let AtomIdent::Fourcc(fourcc) = atom.ident {
 				match &fourcc {
 					b"trak" if parse_options.read_properties => {
-						// All we need from here is trak.mdia
-						if let Some(mdia) =
-							find_child_atom(reader, atom.len, *b"mdia", parse_options.parsing_mode)?
-						{
-							skip_atom(reader, mdia.extended, mdia.len)?;
-							traks.push(mdia);
+						if let Some(trak) = parse_trak(reader, &atom)? {
+							traks.push(trak);
 						}
 					},
+					b"mvex" if parse_options.read_properties => {
+						mvex = Some(Mvex::parse(reader, &atom)?);
+					},
 					b"udta" if parse_options.read_tags => {
-						let ilst_parsed = ilst_from_udta(reader, parse_options, atom.len - 8)?;
-						if let Some(ilst_parsed) = ilst_parsed {
+						let udta = parse_udta(reader, parse_options, &atom)?;
+						if let Some(udta_chapters) = udta.chapters {
+							chapters = Some(udta_chapters);
+						}
+
+						if !udta.assets.is_empty() {
+							assets = udta.assets;
+						}
+
+						udta_text.extend(udta.text);
+
+						if udta.id3v2.is_some() {
+							id3v2 = udta.id3v2;
+						}
+
+						if let Some(udta_keyed_metadata) = udta.keyed_metadata {
+							insert_keyed_metadata(&mut keyed_metadata, udta_keyed_metadata);
+						}
+
+						if let Some(ilst_parsed) = udta.ilst {
 							let Some(mut existing_ilst) = ilst else {
 								ilst = Some(ilst_parsed);
 								continue;
@@ -69,6 +121,17 @@ impl Moov {
 							ilst = Some(existing_ilst);
 						}
 					},
+					// iPhones store their metadata directly in `moov`
+					b"meta" if parse_options.read_tags => {
+						let meta = parse_meta(reader, parse_options, &atom)?;
+						if let Some(meta_keyed_metadata) = meta.keyed_metadata {
+							insert_keyed_metadata(&mut keyed_metadata, meta_keyed_metadata);
+						}
+
+						if meta.id3v2.is_some() {
+							id3v2 = meta.id3v2;
+						}
+					},
 					_ => skip_atom(reader, atom.extended, atom.len)?,
 				}
 
@@ -78,72 +141,263 @@ impl Moov {
 			skip_atom(reader, atom.extended, atom.len)?
 		}
 
-		Ok(Self { traks, ilst })
+		Ok(Self {
+			traks,
+			mvex,
+			ilst,
+			chapters,
+			keyed_metadata,
+			assets,
+			udta_text,
+			id3v2,
+		})
 	}
 }
 
-fn ilst_from_udta<R>(
-	reader: &mut AtomReader<R>,
-	parse_options: ParseOptions,
-	len: u64,
-) -> Result<Option<Ilst>>
+fn insert_keyed_metadata(existing: &mut Option<KeyedMetadata>, keyed_metadata: KeyedMetadata) {
+	match existing {
+		Some(existing) => {
+			log::warn!("Multiple `meta` atoms with an `mdta` handler found, combining them");
+			existing.items.extend(keyed_metadata.items);
+		},
+		None => *existing = Some(keyed_metadata),
+	}
+}
+
+// NOTE: This will consume the entire `trak` atom
+fn parse_trak<R>(reader: &mut AtomReader<R>, trak: &AtomInfo) -> Result<Option<Trak>>
 where
 	R: Read + Seek,
 {
-	let mut read = 8;
-	let mut found_meta = false;
-	let mut meta_atom_size = 0;
+	let mut track_id = None;
+	let mut enabled = true;
+	let mut mdia = None;
 
-	while read < len {
+	let mut read = trak.header_size();
+	while read < trak.len {
 		let Some(atom) = reader.next()? else {
 			break;
 		};
 
-		if atom.ident == AtomIdent::Fourcc(*b"meta") {
-			found_meta = true;
-			meta_atom_size = atom.len;
-			break;
+		read += atom.len;
+
+		match atom.ident {
+			AtomIdent::Fourcc(ref fourcc) if fourcc == b"tkhd" => {
+				let version = reader.read_u8()?;
+				let flags = reader.read_u24()?;
+				enabled = flags & 0x01 != 0;
+
+				// Creation time + modification time
+				let timestamps_len: u64 = if version == 1 { 16 } else { 8 };
+
+				// Version (1) + flags (3) + timestamps + track ID (4)
+				let consumed = 8 + timestamps_len;
+				if atom.len < atom.header_size() + consumed {
+					decode_err!(@BAIL Mp4, "Found an incomplete \"tkhd\" atom");
+				}
+
+				reader.seek(SeekFrom::Current(timestamps_len as i64))?;
+				track_id = Some(reader.read_u32()?);
+
+				skip_atom(reader, atom.extended, atom.len - consumed)?;
+			},
+			// All we need from here is trak.mdia
+			AtomIdent::Fourcc(ref fourcc) if fourcc == b"mdia" => {
+				skip_atom(reader, atom.extended, atom.len)?;
+				mdia = Some(atom);
+			},
+			_ => skip_atom(reader, atom.extended, atom.len)?,
 		}
+	}
+
+	Ok(mdia.map(|mdia| Trak {
+		track_id,
+		enabled,
+		mdia,
+	}))
+}
+
+struct Udta {
+	ilst: Option<Ilst>,
+	chapters: Option<Vec<Chapter>>,
+	keyed_metadata: Option<KeyedMetadata>,
+	assets: AssetInformation,
+	text: Vec<([u8; 4], String)>,
+	id3v2: Option<Id3v2Tag>,
+}
+
+fn parse_udta<R>(
+	reader: &mut AtomReader<R>,
+	parse_options: ParseOptions,
+	udta: &AtomInfo,
+) -> Result<Udta>
+where
+	R: Read + Seek,
+{
+	let mut ret = Udta {
+		ilst: None,
+		chapters: None,
+		keyed_metadata: None,
+		assets: AssetInformation::default(),
+		text: Vec::new(),
+		id3v2: None,
+	};
+
+	let mut read = udta.header_size();
+	while read < udta.len {
+		let Some(atom) = reader.next()? else {
+			break;
+		};
 
 		read += atom.len;
-		skip_atom(reader, atom.extended, atom.len)?;
-	}
 
-	if !found_meta {
-		return Ok(None);
+		match atom.ident {
+			AtomIdent::Fourcc(ref fourcc) if fourcc == b"meta" => {
+				let meta = parse_meta(reader, parse_options, &atom)?;
+				if meta.ilst.is_some() {
+					ret.ilst = meta.ilst;
+				}
+
+				if meta.keyed_metadata.is_some() {
+					ret.keyed_metadata = meta.keyed_metadata;
+				}
+
+				if meta.id3v2.is_some() {
+					ret.id3v2 = meta.id3v2;
+				}
+			},
+			AtomIdent::Fourcc(ref fourcc) if fourcc == b"chpl" => match parse_chpl(reader, &atom) {
+				Ok(chapters) => ret.chapters = Some(chapters),
+				Err(e) if parse_options.parsing_mode == ParsingMode::Strict => return Err(e),
+				Err(e) => log::warn!("Unable to read `chpl` atom, skipping: {e}"),
+			},
+			AtomIdent::Fourcc(fourcc) if is_asset(fourcc) => {
+				match parse_asset(reader, &atom, &mut ret.assets) {
+					Ok(()) => {},
+					Err(e) if parse_options.parsing_mode == ParsingMode::Strict => return Err(e),
+					Err(e) => log::warn!(
+						"Unable to read `{}` atom, skipping: {e}",
+						fourcc.escape_ascii()
+					),
+				}
+			},
+			AtomIdent::Fourcc(fourcc) if is_udta_text(fourcc) => {
+				match parse_udta_text(reader, &atom) {
+					Ok(Some(text)) => ret.text.push((fourcc, text)),
+					Ok(None) => {},
+					Err(e) if parse_options.parsing_mode == ParsingMode::Strict => return Err(e),
+					Err(e) => log::warn!(
+						"Unable to read `{}` atom, skipping: {e}",
+						fourcc.escape_ascii()
+					),
+				}
+			},
+			_ => skip_atom(reader, atom.extended, atom.len)?,
+		}
 	}
 
+	Ok(ret)
+}
+
+struct Meta {
+	ilst: Option<Ilst>,
+	keyed_metadata: Option<KeyedMetadata>,
+	id3v2: Option<Id3v2Tag>,
+}
+
+// NOTE: This will consume the entire `meta` atom
+fn parse_meta<R>(
+	reader: &mut AtomReader<R>,
+	parse_options: ParseOptions,
+	meta: &AtomInfo,
+) -> Result<Meta>
+where
+	R: Read + Seek,
+{
+	let mut read = meta.header_size();
+
 	// It's possible for the `meta` atom to be non-full,
 	// so we have to check for that case
-	let full_meta_atom = meta_is_full(reader)?;
-
-	if full_meta_atom {
-		read = 12;
-	} else {
-		read = 8;
+	if meta_is_full(reader)? {
+		read += 4;
 	}
 
-	let mut found_ilst = false;
-	let mut ilst_atom_size = 0;
+	let mut ret = Meta {
+		ilst: None,
+		keyed_metadata: None,
+		id3v2: None,
+	};
 
-	while read < meta_atom_size {
+	// A missing `hdlr` atom is treated as `mdir`
+	let mut handler_type = *b"mdir";
+	let mut keys = Vec::new();
+	while read < meta.len {
 		let Some(atom) = reader.next()? else {
 			break;
 		};
 
-		if atom.ident == AtomIdent::Fourcc(*b"ilst") {
-			found_ilst = true;
-			ilst_atom_size = atom.len;
-			break;
-		}
-
 		read += atom.len;
-		skip_atom(reader, atom.extended, atom.len)?;
-	}
 
-	if found_ilst {
-		return parse_ilst(reader, parse_options, ilst_atom_size - 8).map(Some);
+		let AtomIdent::Fourcc(fourcc) = atom.ident else {
+			skip_atom(reader, atom.extended, atom.len)?;
+			continue;
+		};
+
+		let content_len = atom.len - atom.header_size();
+		match &fourcc {
+			// Version (1) + flags (3) + pre-defined (4) + handler type (4)
+			b"hdlr" if content_len >= 12 => {
+				reader.seek(SeekFrom::Current(8))?;
+				reader.read_exact(&mut handler_type)?;
+
+				reader.seek(SeekFrom::Current((content_len - 12) as i64))?;
+			},
+			b"keys" => match parse_keys(reader, content_len) {
+				Ok(parsed_keys) => keys = parsed_keys,
+				Err(e) if parse_options.parsing_mode == ParsingMode::Strict => return Err(e),
+				Err(e) => log::warn!("Unable to read `keys` atom, skipping: {e}"),
+			},
+			b"ilst" if &handler_type == b"mdta" && ret.keyed_metadata.is_none() => {
+				ret.keyed_metadata = Some(parse_keyed_ilst(
+					reader,
+					parse_options.parsing_mode,
+					content_len,
+					&keys,
+				)?);
+			},
+			b"ID32" if &handler_type == b"ID32" && ret.id3v2.is_none() => {
+				match parse_id32(reader, &atom, parse_options) {
+					Ok(tag) => ret.id3v2 = Some(tag),
+					Err(e) if parse_options.parsing_mode == ParsingMode::Strict => return Err(e),
+					Err(e) => log::warn!("Unable to read `ID32` atom, skipping: {e}"),
+				}
+			},
+			b"ilst" if &handler_type != b"mdta" && ret.ilst.is_none() => {
+				ret.ilst = Some(parse_ilst(reader, parse_options, content_len)?);
+			},
+			_ => skip_atom(reader, atom.extended, atom.len)?,
+		}
 	}
 
-	Ok(None)
+	Ok(ret)
+}
+
+// Parse an `ID32` atom, holding an ID3v2 tag
+//
+// NOTE: This expects the reader to be at the start of the atom's content
+fn parse_id32<R>(reader: &mut R, atom: &AtomInfo, parse_options: ParseOptions) -> Result<Id3v2Tag>
+where
+	R: Read,
+{
+	// The whole atom is read up front, so a bad tag can't leave the reader in the middle of it
+	let mut content = try_vec![0; (atom.len - atom.header_size()) as usize];
+	reader.read_exact(&mut content)?;
+
+	// Version (1) + flags (3) + pad (1 bit) + language (15 bits)
+	let Some(mut id3v2) = content.get(6..) else {
+		decode_err!(@BAIL Mp4, "Found an incomplete \"ID32\" atom");
+	};
+
+	let header = Id3v2Header::parse(&mut id3v2)?;
+	parse_id3v2(&mut id3v2, header, parse_options)
 }
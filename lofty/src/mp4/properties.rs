@@ -1,9 +1,13 @@
+use super::assets::decode_language;
 use super::atom_info::{AtomIdent, AtomInfo};
+use super::fragments::{read_fragments, Mvex};
+use super::moov::Trak;
 use super::read::{find_child_atom, skip_atom, AtomReader};
 use crate::config::ParsingMode;
 use crate::error::{LoftyError, Result};
 use crate::macros::{decode_err, err, try_vec};
 use crate::properties::FileProperties;
+use crate::tag::items::Lang;
 use crate::util::alloc::VecFallibleCapacity;
 use crate::util::math::RoundedDivision;
 
@@ -23,6 +27,7 @@ pub enum Mp4Codec {
 	ALAC,
 	MP3,
 	FLAC,
+	Opus,
 }
 
 #[allow(missing_docs)]
@@ -143,6 +148,7 @@ pub struct Mp4Properties {
 	pub(crate) sample_rate: u32,
 	pub(crate) bit_depth: Option<u8>,
 	pub(crate) channels: u8,
+	pub(crate) pre_skip: Option<u16>,
 	pub(crate) drm_protected: bool,
 }
 
@@ -205,34 +211,99 @@ impl Mp4Properties {
 		self.extended_audio_object_type
 	}
 
+	/// The number of samples to discard from the start of the decoded audio
+	///
+	/// This is only applicable to Opus, see [here](https://opus-codec.org/docs/opus_in_isobmff.html#4.3.2)
+	/// for more information.
+	pub fn pre_skip(&self) -> Option<u16> {
+		self.pre_skip
+	}
+
 	/// Whether or not the file is DRM protected
 	pub fn is_drm_protected(&self) -> bool {
 		self.drm_protected
 	}
 }
 
-struct AudioTrak {
-	mdhd: AtomInfo,
-	minf: Option<AtomInfo>,
+/// A track in an MP4 file
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Mp4Track {
+	pub(crate) id: u32,
+	pub(crate) handler_type: [u8; 4],
+	pub(crate) language: Option<Lang>,
+	pub(crate) enabled: bool,
+	pub(crate) duration: Duration,
+	pub(crate) properties: Option<Mp4Properties>,
 }
 
-/// Search through all the traks to find the first one with audio
-fn find_audio_trak<R>(reader: &mut AtomReader<R>, traks: &[AtomInfo]) -> Result<AudioTrak>
-where
-	R: Read + Seek,
-{
-	let mut audio_track = false;
-	let mut mdhd = None;
-	let mut minf = None;
+impl Mp4Track {
+	/// The track ID
+	///
+	/// This will be 0 if the track has no `tkhd` atom, which is never a valid ID.
+	pub fn id(&self) -> u32 {
+		self.id
+	}
 
-	// We have to search through the traks with a mdia atom to find the audio track
-	for mdia in traks {
-		if audio_track {
-			break;
-		}
+	/// The handler type, describing the type of media in the track
+	///
+	/// Common values are `soun` (audio), `vide` (video), `text`, and `sbtl` (subtitles).
+	pub fn handler_type(&self) -> [u8; 4] {
+		self.handler_type
+	}
 
-		mdhd = None;
-		minf = None;
+	/// Whether the track contains audio
+	pub fn is_audio(&self) -> bool {
+		&self.handler_type == b"soun"
+	}
+
+	/// The ISO-639-2/T language code of the track
+	///
+	/// This will be `None` if the track uses a Macintosh language code.
+	pub fn language(&self) -> Option<Lang> {
+		self.language
+	}
+
+	/// Whether the track is enabled
+	pub fn is_enabled(&self) -> bool {
+		self.enabled
+	}
+
+	/// Duration of the track
+	pub fn duration(&self) -> Duration {
+		self.duration
+	}
+
+	/// Audio codec
+	///
+	/// This will always be [`Mp4Codec::Unknown`] for non-audio tracks.
+	pub fn codec(&self) -> Mp4Codec {
+		self.properties
+			.as_ref()
+			.map_or(Mp4Codec::Unknown, |properties| properties.codec)
+	}
+
+	/// The audio properties of the track, if it is an audio track
+	pub fn properties(&self) -> Option<&Mp4Properties> {
+		self.properties.as_ref()
+	}
+}
+
+/// The atoms we need from a `trak.mdia` atom
+struct Mdia {
+	handler_type: [u8; 4],
+	mdhd: Option<AtomInfo>,
+	minf: Option<AtomInfo>,
+}
+
+impl Mdia {
+	fn parse<R>(reader: &mut AtomReader<R>, mdia: &AtomInfo) -> Result<Self>
+	where
+		R: Read + Seek,
+	{
+		let mut handler_type = [0; 4];
+		let mut mdhd = None;
+		let mut minf = None;
 
 		reader.seek(SeekFrom::Start(mdia.start + 8))?;
 
@@ -257,14 +328,8 @@ where
 
 						// The hdlr atom is followed by 8 zeros
 						reader.seek(SeekFrom::Current(8))?;
-
-						let mut handler_type = [0; 4];
 						reader.read_exact(&mut handler_type)?;
 
-						if &handler_type == b"soun" {
-							audio_track = true
-						}
-
 						skip_atom(reader, atom.extended, atom.len - 12)?;
 					},
 					b"minf" => minf = Some(atom),
@@ -278,22 +343,122 @@ where
 
 			skip_atom(reader, atom.extended, atom.len)?;
 		}
+
+		Ok(Self {
+			handler_type,
+			mdhd,
+			minf,
+		})
+	}
+}
+
+/// Read every track in the file, along with the audio properties of the audio tracks
+///
+/// Tracks that can't be read are skipped, unless there are no audio tracks left.
+pub(super) fn read_tracks<R>(
+	reader: &mut AtomReader<R>,
+	traks: &[Trak],
+	mvex: Option<&Mvex>,
+	file_length: u64,
+	parse_mode: ParsingMode,
+) -> Result<Vec<Mp4Track>>
+where
+	R: Read + Seek,
+{
+	let mut tracks = Vec::with_capacity(traks.len());
+	let mut first_error = None;
+
+	for trak in traks {
+		match read_track(reader, trak, mvex, file_length, parse_mode) {
+			Ok(track) => tracks.push(track),
+			Err(e) => {
+				if parse_mode == ParsingMode::Strict {
+					return Err(e);
+				}
+
+				log::warn!(
+					"Unable to read track (ID: {}), skipping: {e}",
+					trak.track_id.unwrap_or(0)
+				);
+				first_error.get_or_insert(e);
+			},
+		}
 	}
 
-	if !audio_track {
-		decode_err!(@BAIL Mp4, "File contains no audio tracks");
+	// The track that failed may have been the audio track, which is the only one we need
+	if let Some(e) = first_error {
+		if !tracks.iter().any(Mp4Track::is_audio) {
+			return Err(e);
+		}
 	}
 
-	let Some(mdhd) = mdhd else {
-		err!(BadAtom("Expected atom \"trak.mdia.mdhd\""));
+	Ok(tracks)
+}
+
+fn read_track<R>(
+	reader: &mut AtomReader<R>,
+	trak: &Trak,
+	mvex: Option<&Mvex>,
+	file_length: u64,
+	parse_mode: ParsingMode,
+) -> Result<Mp4Track>
+where
+	R: Read + Seek,
+{
+	let Mdia {
+		handler_type,
+		mdhd,
+		minf,
+	} = Mdia::parse(reader, &trak.mdia)?;
+
+	let mdhd = match mdhd {
+		Some(mdhd) => {
+			reader.seek(SeekFrom::Start(mdhd.start + 8))?;
+			Some(Mdhd::parse(reader)?)
+		},
+		None => None,
 	};
 
-	Ok(AudioTrak { mdhd, minf })
+	let mut track = Mp4Track {
+		id: trak.track_id.unwrap_or(0),
+		handler_type,
+		language: mdhd.as_ref().and_then(|mdhd| mdhd.language),
+		enabled: trak.enabled,
+		duration: Duration::ZERO,
+		properties: None,
+	};
+
+	if track.is_audio() {
+		let properties = read_properties(
+			reader,
+			trak.track_id,
+			mdhd,
+			minf,
+			mvex,
+			file_length,
+			parse_mode,
+		)?;
+
+		track.duration = properties.duration;
+		track.properties = Some(properties);
+	} else if let Some(Mdhd {
+		timescale,
+		duration,
+		..
+	}) = mdhd
+	{
+		if timescale > 0 {
+			track.duration = duration_from_timescale(duration, timescale);
+		}
+	}
+
+	Ok(track)
 }
 
 struct Mdhd {
 	timescale: u32,
 	duration: u64,
+	language: Option<Lang>,
 }
 
 impl Mdhd {
@@ -323,20 +488,21 @@ impl Mdhd {
 			(timescale, u64::from(duration))
 		};
 
+		// Values below 0x400 are Macintosh language codes, which we don't bother with.
+		let packed_language = reader.read_u16()?;
+		let language = (packed_language >= 0x400).then(|| decode_language(packed_language));
+
 		Ok(Mdhd {
 			timescale,
 			duration,
+			language,
 		})
 	}
 }
 
-// TODO: Estimate duration from stts?
-//       Since this has the number of samples and the duration of each sample,
-//       it would be pretty simple to do, and would help in the case that we have
-//       no timescale available.
 #[derive(Debug)]
 struct SttsEntry {
-	_sample_count: u32,
+	sample_count: u32,
 	sample_duration: u32,
 }
 
@@ -360,13 +526,27 @@ impl Stts {
 			let sample_duration = reader.read_u32::<BigEndian>()?;
 
 			entries.push(SttsEntry {
-				_sample_count: sample_count,
+				sample_count,
 				sample_duration,
 			});
 		}
 
 		Ok(Self { entries })
 	}
+
+	/// Whether the entries hold actual sample durations
+	///
+	/// A single entry with a duration of 1 is used when the durations aren't known.
+	fn specifies_duration(&self) -> bool {
+		!(self.entries.len() == 1 && self.entries[0].sample_duration == 1)
+	}
+
+	/// The total duration of all samples, in the track's timescale
+	fn duration(&self) -> u64 {
+		self.entries.iter().fold(0u64, |total, entry| {
+			total.saturating_add(u64::from(entry.sample_count) * u64::from(entry.sample_duration))
+		})
+	}
 }
 
 struct Minf {
@@ -441,12 +621,10 @@ where
 		};
 
 		match fourcc {
-			b"mp4a" => mp4a_properties(reader, properties)?,
-			b"alac" => alac_properties(reader, properties)?,
-			b"fLaC" => flac_properties(reader, properties)?,
-			// Maybe do these?
-			// TODO: dops (opus)
-			// TODO: wave (https://developer.apple.com/library/archive/documentation/QuickTime/QTFF/QTFFChap3/qtff3.html#//apple_ref/doc/uid/TP40000939-CH205-134202)
+			b"mp4a" => mp4a_properties(reader, &atom, properties)?,
+			b"alac" => alac_properties(reader, &atom, properties)?,
+			b"fLaC" => flac_properties(reader, &atom, properties)?,
+			b"Opus" => opus_properties(reader, &atom, properties)?,
 
 			// Special case to detect encrypted files
 			b"drms" => {
@@ -472,39 +650,66 @@ where
 	Ok(())
 }
 
-pub(super) fn read_properties<R>(
+fn read_properties<R>(
 	reader: &mut AtomReader<R>,
-	traks: &[AtomInfo],
+	track_id: Option<u32>,
+	mdhd: Option<Mdhd>,
+	minf: Option<AtomInfo>,
+	mvex: Option<&Mvex>,
 	file_length: u64,
 	parse_mode: ParsingMode,
 ) -> Result<Mp4Properties>
 where
 	R: Read + Seek,
 {
-	// We need the mdhd and minf atoms from the audio track
-	let AudioTrak { mdhd, minf } = find_audio_trak(reader, traks)?;
-
-	reader.seek(SeekFrom::Start(mdhd.start + 8))?;
-	let Mdhd {
-		timescale,
-		duration,
-	} = Mdhd::parse(reader)?;
+	let mut timescale = 0;
+	let mut duration = 0;
+	if let Some(mdhd) = &mdhd {
+		timescale = mdhd.timescale;
+		duration = mdhd.duration;
+	}
 
 	// We create the properties here, since it is possible the other information isn't available
 	let mut properties = Mp4Properties::default();
 
+	let minf = match minf {
+		Some(minf_info) => {
+			reader.seek(SeekFrom::Start(minf_info.start + 8))?;
+			Minf::parse(reader, minf_info.len, parse_mode)?
+		},
+		None => None,
+	};
+
+	if duration == 0 {
+		let stts = minf.as_ref().and_then(|minf| minf.stts.as_ref());
+		if let Some(stts) = stts.filter(|stts| stts.specifies_duration()) {
+			log::debug!("Duration is 0, calculating from 'stts'");
+			duration = stts.duration();
+		}
+	}
+
+	// Fragmented files have (next to) empty sample tables, the samples are instead described
+	// in the `moof` atoms throughout the file
+	let mut media_size = None;
+	if let Some(mvex) = mvex {
+		let fragments = read_fragments(reader, mvex, track_id)?;
+		if duration == 0 {
+			duration = fragments.duration;
+		}
+
+		media_size = Some(fragments.media_size);
+	}
+
 	if timescale > 0 {
-		let duration_millis = (duration * 1000).div_round(u64::from(timescale));
-		properties.duration = Duration::from_millis(duration_millis);
+		properties.duration = duration_from_timescale(duration, timescale);
 	}
 
-	// We need an `mdhd` atom at the bare minimum, everything else can be optional.
-	let Some(minf_info) = minf else {
-		return Ok(properties);
-	};
+	// We need either an `mdhd` or `stsd` atom at the bare minimum, everything else can be optional.
+	let Some(Minf { stsd_data, stts }) = minf else {
+		if mdhd.is_none() {
+			err!(BadAtom("Expected atom \"trak.mdia.mdhd\""));
+		}
 
-	reader.seek(SeekFrom::Start(minf_info.start + 8))?;
-	let Some(Minf { stsd_data, stts }) = Minf::parse(reader, minf_info.len, parse_mode)? else {
 		return Ok(properties);
 	};
 
@@ -513,19 +718,27 @@ where
 	let mut stsd_reader = AtomReader::new(&mut cursor, parse_mode)?;
 	read_stsd(&mut stsd_reader, &mut properties)?;
 
+	// Without an `mdhd` atom, we have to assume that the track's timescale is its sample rate,
+	// which is almost always the case for audio
+	if timescale == 0 && properties.sample_rate > 0 {
+		timescale = properties.sample_rate;
+		properties.duration = duration_from_timescale(duration, timescale);
+	}
+
 	// We do the mdat check up here, so we have access to the entire file
-	if duration > 0 {
-		// TODO: We should keep track of the `mdat` length when first reading the file.
-		//       This extra read is unnecessary.
-		let mdat_len = mdat_length(reader)?;
+	if duration > 0 && timescale > 0 {
+		let media_size = match media_size {
+			Some(media_size) => media_size,
+			// TODO: We should keep track of the `mdat` length when first reading the file.
+			//       This extra read is unnecessary.
+			None => mdat_length(reader)?,
+		};
 
 		if let Some(stts) = stts {
-			let stts_specifies_duration =
-				!(stts.entries.len() == 1 && stts.entries[0].sample_duration == 1);
-			if stts_specifies_duration {
+			if stts.specifies_duration() {
 				// We do a basic audio bitrate calculation below for each stream type.
 				// Up here, we can do a more accurate calculation if the duration is available.
-				let audio_bitrate_bps = (((u128::from(mdat_len) * 8) * u128::from(timescale))
+				let audio_bitrate_bps = (((u128::from(media_size) * 8) * u128::from(timescale))
 					/ u128::from(duration)) as u32;
 
 				// kb/s
@@ -533,34 +746,152 @@ where
 			}
 		}
 
-		// TODO: We need to eventually calculate the duration from the stts atom
-		//       if there is no timescale available.
 		let duration_millis = properties.duration.as_millis();
 		if duration_millis == 0 {
 			log::warn!("Duration is 0, unable to calculate bitrate");
 			return Ok(properties);
 		}
 
-		let overall_bitrate = u128::from(file_length * 8) / duration_millis;
+		let overall_bitrate = u128::from(file_length) * 8 / duration_millis;
 		properties.overall_bitrate = overall_bitrate as u32;
 
 		if properties.audio_bitrate == 0 {
 			log::warn!("Estimating audio bitrate from 'mdat' size");
 
-			properties.audio_bitrate =
-				(u128::from(mdat_length(reader)? * 8) / duration_millis) as u32;
+			properties.audio_bitrate = (u128::from(media_size) * 8 / duration_millis) as u32;
 		}
 	}
 
 	Ok(properties)
 }
 
+fn duration_from_timescale(duration: u64, timescale: u32) -> Duration {
+	let duration_millis = (u128::from(duration) * 1000).div_round(u128::from(timescale));
+	Duration::from_millis(u64::try_from(duration_millis).unwrap_or(u64::MAX))
+}
+
 // https://wiki.multimedia.cx/index.php?title=MPEG-4_Audio#Sampling_Frequencies
 pub(crate) const SAMPLE_RATES: [u32; 15] = [
 	96000, 88200, 64000, 48000, 44100, 32000, 24000, 22050, 16000, 12000, 11025, 8000, 7350, 0, 0,
 ];
 
-fn mp4a_properties<R>(stsd: &mut AtomReader<R>, properties: &mut Mp4Properties) -> Result<()>
+/// The fields shared by all sound sample entries
+///
+/// QuickTime files may use a newer version of the sample description, which can be read
+/// as well. See [here](https://developer.apple.com/library/archive/documentation/QuickTime/QTFF/QTFFChap3/qtff3.html#//apple_ref/doc/uid/TP40000939-CH205-75770)
+/// for the layout of each version.
+struct SoundSampleDescription {
+	channels: u16,
+	sample_size: u16,
+	sample_rate: u32,
+}
+
+impl SoundSampleDescription {
+	/// Parse the sample description
+	///
+	/// NOTE: This expects the reader to be at the start of the sample entry's content, and will leave
+	///       it at the first child atom.
+	fn parse<R>(stsd: &mut AtomReader<R>) -> Result<Self>
+	where
+		R: Read + Seek,
+	{
+		// Skipping 8 bytes
+		// Reserved (6)
+		// Data reference index (2)
+		stsd.seek(SeekFrom::Current(8))?;
+
+		let version = stsd.read_u16()?;
+
+		// Skipping 6 bytes
+		// Revision level (2)
+		// Vendor (4)
+		stsd.seek(SeekFrom::Current(6))?;
+
+		let mut channels = stsd.read_u16()?;
+		let mut sample_size = stsd.read_u16()?;
+
+		// Skipping 4 bytes
+		// Compression ID (2)
+		// Packet size (2)
+		stsd.seek(SeekFrom::Current(4))?;
+
+		// 16.16 fixed point, we only care about the integer part
+		let mut sample_rate = stsd.read_u32()? >> 16;
+
+		match version {
+			1 => {
+				// Skipping 16 bytes
+				// Samples per packet (4)
+				// Bytes per packet (4)
+				// Bytes per frame (4)
+				// Bytes per sample (4)
+				stsd.seek(SeekFrom::Current(16))?;
+			},
+			2 => {
+				// The version 0 fields are fixed, the actual values are stored here
+				let _size_of_struct_only = stsd.read_u32()?;
+
+				sample_rate = f64::from_bits(stsd.read_u64()?) as u32;
+				channels = stsd.read_u32()? as u16;
+
+				// Always 0x7F000000
+				let _reserved = stsd.read_u32()?;
+
+				sample_size = stsd.read_u32()? as u16;
+
+				// Skipping 12 bytes
+				// Format specific flags (4)
+				// Bytes per audio packet (4)
+				// LPCM frames per audio packet (4)
+				stsd.seek(SeekFrom::Current(12))?;
+			},
+			_ => {},
+		}
+
+		Ok(Self {
+			channels,
+			sample_size,
+			sample_rate,
+		})
+	}
+}
+
+/// Find the atom holding the codec configuration in a sample entry
+///
+/// QuickTime files can wrap the configuration in a `wave` atom, which will be searched as well.
+///
+/// NOTE: This expects the reader to be past the sample description.
+fn find_codec_config<R>(
+	stsd: &mut AtomReader<R>,
+	entry: &AtomInfo,
+	ident: [u8; 4],
+) -> Result<Option<AtomInfo>>
+where
+	R: Read + Seek,
+{
+	let entry_end = entry.start + entry.len;
+	while stsd.stream_position()? < entry_end {
+		let Ok(Some(atom)) = stsd.next() else {
+			break;
+		};
+
+		match atom.ident {
+			AtomIdent::Fourcc(fourcc) if fourcc == ident => return Ok(Some(atom)),
+			// The children of `wave` are read in place, the rest of its contents
+			// (`frma`, a terminator atom, etc.) are of no use to us.
+			AtomIdent::Fourcc(ref fourcc) if fourcc == b"wave" => continue,
+			_ => skip_atom(stsd, atom.extended, atom.len)?,
+		}
+	}
+
+	Ok(None)
+}
+
+fn mp4a_properties<R>(
+	stsd: &mut AtomReader<R>,
+	entry: &AtomInfo,
+	properties: &mut Mp4Properties,
+) -> Result<()>
 where
 	R: Read + Seek,
 {
@@ -571,31 +902,12 @@ where
 	// Set the codec to AAC, which is a good guess if we fail before reaching the `esds`
 	properties.codec = Mp4Codec::AAC;
 
-	// Skipping 16 bytes
-	// Reserved (6)
-	// Data reference index (2)
-	// Version (2)
-	// Revision level (2)
-	// Vendor (4)
-	stsd.seek(SeekFrom::Current(16))?;
-
-	properties.channels = stsd.read_u16()? as u8;
-
-	// Skipping 4 bytes
-	// Sample size (2)
-	// Compression ID (2)
-	stsd.seek(SeekFrom::Current(4))?;
-
-	properties.sample_rate = stsd.read_u32()?;
-
-	stsd.seek(SeekFrom::Current(2))?;
+	let description = SoundSampleDescription::parse(stsd)?;
+	properties.channels = description.channels as u8;
+	properties.sample_rate = description.sample_rate;
 
 	// This information is often followed by an esds (elementary stream descriptor) atom containing the bitrate
-	let Ok(Some(esds)) = stsd.next() else {
-		return Ok(());
-	};
-
-	if esds.ident != AtomIdent::Fourcc(*b"esds") {
+	if find_codec_config(stsd, entry, *b"esds")?.is_none() {
 		return Ok(());
 	}
 
@@ -729,29 +1041,25 @@ where
 	Ok(())
 }
 
-fn alac_properties<R>(stsd: &mut AtomReader<R>, properties: &mut Mp4Properties) -> Result<()>
+fn alac_properties<R>(
+	stsd: &mut AtomReader<R>,
+	entry: &AtomInfo,
+	properties: &mut Mp4Properties,
+) -> Result<()>
 where
 	R: Read + Seek,
 {
-	// With ALAC, we can expect the length to be exactly 88 (80 here since we removed the size and identifier)
-	if stsd.seek(SeekFrom::End(0))? != 80 {
-		return Ok(());
-	}
-
 	// Unlike the "mp4a" atom, we cannot read the data that immediately follows it
-	// For ALAC, we have to skip the first "alac" atom entirely, and read the one that
-	// immediately follows it.
-	//
-	// We are skipping over 44 bytes total
-	// stsd information/alac atom header (16, see `read_properties`)
-	// First alac atom's content (28)
-	stsd.seek(SeekFrom::Start(44))?;
+	// For ALAC, we have to skip the sample description entirely, and read the "alac" atom that
+	// follows it.
+	SoundSampleDescription::parse(stsd)?;
 
-	let Ok(Some(alac)) = stsd.next() else {
+	let Some(alac) = find_codec_config(stsd, entry, *b"alac")? else {
 		return Ok(());
 	};
 
-	if alac.ident != AtomIdent::Fourcc(*b"alac") {
+	// Header (8) + version (4) + the ALAC specific config (24)
+	if alac.len < 36 {
 		return Ok(());
 	}
 
@@ -786,43 +1094,26 @@ where
 	Ok(())
 }
 
-fn flac_properties<R>(stsd: &mut AtomReader<R>, properties: &mut Mp4Properties) -> Result<()>
+fn flac_properties<R>(
+	stsd: &mut AtomReader<R>,
+	entry: &AtomInfo,
+	properties: &mut Mp4Properties,
+) -> Result<()>
 where
 	R: Read + Seek,
 {
 	properties.codec = Mp4Codec::FLAC;
 
-	// Skipping 16 bytes
-	//
-	// Reserved (6)
-	// Data reference index (2)
-	// Version (2)
-	// Revision level (2)
-	// Vendor (4)
-	stsd.seek(SeekFrom::Current(16))?;
-
-	properties.channels = stsd.read_u16()? as u8;
-	properties.bit_depth = Some(stsd.read_u16()? as u8);
-
-	// Skipping 4 bytes
-	//
-	// Compression ID (2)
-	// Packet size (2)
-	stsd.seek(SeekFrom::Current(4))?;
-
-	properties.sample_rate = u32::from(stsd.read_u16()?);
-
-	let _reserved = stsd.read_u16()?;
+	let description = SoundSampleDescription::parse(stsd)?;
+	properties.channels = description.channels as u8;
+	properties.bit_depth = Some(description.sample_size as u8);
+	properties.sample_rate = description.sample_rate;
 
 	// There should be a dfla atom, but it's not worth erroring if absent.
-	let Some(dfla) = stsd.next()? else {
+	let Some(dfla) = find_codec_config(stsd, entry, *b"dfLa")? else {
 		return Ok(());
 	};
 
-	if dfla.ident != AtomIdent::Fourcc(*b"dfLa") {
-		return Ok(());
-	}
-
 	// Skipping 4 bytes
 	//
 	// Version (1)
@@ -847,6 +1138,52 @@ where
 	Ok(())
 }
 
+fn opus_properties<R>(
+	stsd: &mut AtomReader<R>,
+	entry: &AtomInfo,
+	properties: &mut Mp4Properties,
+) -> Result<()>
+where
+	R: Read + Seek,
+{
+	properties.codec = Mp4Codec::Opus;
+
+	// The sample rate here is always 48000, since that's what Opus is decoded at
+	let description = SoundSampleDescription::parse(stsd)?;
+	properties.channels = description.channels as u8;
+	properties.sample_rate = description.sample_rate;
+
+	// https://opus-codec.org/docs/opus_in_isobmff.html#4.3.2
+	let Some(dops) = find_codec_config(stsd, entry, *b"dOps")? else {
+		return Ok(());
+	};
+
+	// Version (1)
+	// Output channel count (1)
+	// Pre-skip (2)
+	// Input sample rate (4)
+	// Output gain (2)
+	// Channel mapping family (1)
+	if dops.len - dops.header_size() < 11 {
+		log::warn!("Incomplete 'dOps' atom, skipping");
+		return Ok(());
+	}
+
+	let _version = stsd.read_u8()?;
+	properties.channels = stsd.read_u8()?;
+	properties.pre_skip = Some(stsd.read_u16()?);
+
+	// Same as with Ogg Opus, the input sample rate is reported rather than the decoded one
+	let input_sample_rate = stsd.read_u32()?;
+	if input_sample_rate > 0 {
+		properties.sample_rate = input_sample_rate;
+	}
+
+	// Bitrate values are calculated later...
+
+	Ok(())
+}
+
 // Used to calculate the bitrate, when it isn't readily available to us
 fn mdat_length<R>(reader: &mut AtomReader<R>) -> Result<u64>
 where
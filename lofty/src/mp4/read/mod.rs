@@ -1,14 +1,18 @@
 mod atom_reader;
 
 use super::atom_info::{AtomIdent, AtomInfo};
+use super::chapters::read_quicktime_chapters;
+use super::ilst::Ilst;
 use super::moov::Moov;
 use super::properties::Mp4Properties;
-use super::Mp4File;
+use super::{Atom, AtomData, Mp4File};
 use crate::config::{ParseOptions, ParsingMode};
 use crate::error::{ErrorKind, LoftyError, Result};
-use crate::macros::{decode_err, err};
+use crate::macros::{decode_err, err, try_vec};
 use crate::util::io::SeekStreamLen;
 use crate::util::text::utf8_decode_str;
+use crate::xmp::read::read_xmp;
+use crate::xmp::{XmpTag, MP4_UUID};
 
 use std::io::{Read, Seek, SeekFrom};
 
@@ -65,24 +69,117 @@ where
 
 	let moov = Moov::parse(&mut reader, parse_options)?;
 
+	let mut ilst = moov.ilst;
+
+	let mut chapters = moov.chapters.unwrap_or_default();
+	if parse_options.read_tags && chapters.is_empty() {
+		// No `chpl` atom, check for a QuickTime chapter track instead
+		reader.reset_bounds(0, file_length);
+		match read_quicktime_chapters(&mut reader, &moov_info, parse_options.parsing_mode) {
+			Ok(quicktime_chapters) => chapters = quicktime_chapters,
+			Err(e) if parse_options.parsing_mode == ParsingMode::Strict => return Err(e),
+			Err(e) => log::warn!("Unable to read QuickTime chapter track, skipping: {e}"),
+		}
+	}
+
+	if !chapters.is_empty() {
+		ilst.get_or_insert_with(Ilst::default).chapters = chapters;
+	}
+
+	if let Some(keyed_metadata) = moov.keyed_metadata {
+		ilst.get_or_insert_with(Ilst::default).keyed_metadata = keyed_metadata;
+	}
+
+	if !moov.assets.is_empty() {
+		ilst.get_or_insert_with(Ilst::default).assets = moov.assets;
+	}
+
+	// QuickTime text atoms are only used for items the `ilst` atom doesn't have
+	if !moov.udta_text.is_empty() {
+		let ilst = ilst.get_or_insert_with(Ilst::default);
+		for (fourcc, text) in moov.udta_text {
+			let ident = AtomIdent::Fourcc(fourcc);
+			if ilst.get(&ident).is_some() {
+				continue;
+			}
+
+			ilst.udta_text.push((fourcc, text.clone()));
+			ilst.atoms.push(Atom::new(ident, AtomData::UTF8(text)));
+		}
+	}
+
+	let mut xmp = None;
+	if parse_options.read_tags {
+		reader.reset_bounds(0, file_length);
+		xmp = read_xmp_uuid(&mut reader, parse_options.parsing_mode)?;
+	}
+
+	let mut tracks = Vec::new();
+	let mut properties = Mp4Properties::default();
+	if parse_options.read_properties {
+		// Remove the length restriction
+		reader.reset_bounds(0, file_length);
+		tracks = super::properties::read_tracks(
+			&mut reader,
+			&moov.traks,
+			moov.mvex.as_ref(),
+			file_length,
+			parse_options.parsing_mode,
+		)?;
+
+		// The first audio track is described by default
+		let Some(audio_properties) = tracks.iter().find_map(|track| track.properties.as_ref())
+		else {
+			decode_err!(@BAIL Mp4, "File contains no audio tracks");
+		};
+
+		properties = audio_properties.clone();
+	}
+
 	Ok(Mp4File {
 		ftyp,
-		ilst_tag: moov.ilst,
-		properties: if parse_options.read_properties {
-			// Remove the length restriction
-			reader.reset_bounds(0, file_length);
-			super::properties::read_properties(
-				&mut reader,
-				&moov.traks,
-				file_length,
-				parse_options.parsing_mode,
-			)?
-		} else {
-			Mp4Properties::default()
-		},
+		ilst_tag: ilst,
+		id3v2_tag: moov.id3v2,
+		xmp_tag: xmp,
+		tracks,
+		properties,
 	})
 }
 
+// Searches the top level of the file for the `uuid` atom holding an XMP packet
+fn read_xmp_uuid<R>(reader: &mut AtomReader<R>, parse_mode: ParsingMode) -> Result<Option<XmpTag>>
+where
+	R: Read + Seek,
+{
+	reader.rewind()?;
+
+	while let Ok(Some(atom)) = reader.next() {
+		let content_len = atom.len - atom.header_size();
+		if atom.ident != AtomIdent::Fourcc(*b"uuid") || content_len < MP4_UUID.len() as u64 {
+			skip_atom(reader, atom.extended, atom.len)?;
+			continue;
+		}
+
+		let mut uuid = [0; 16];
+		reader.read_exact(&mut uuid)?;
+
+		let packet_len = content_len - uuid.len() as u64;
+		if uuid != MP4_UUID {
+			reader.seek(SeekFrom::Current(packet_len as i64))?;
+			continue;
+		}
+
+		log::debug!("Found an XMP `uuid` atom, parsing");
+
+		let mut packet = try_vec![0; packet_len as usize];
+		reader.read_exact(&mut packet)?;
+
+		return read_xmp(&packet, parse_mode);
+	}
+
+	Ok(None)
+}
+
 /// Seeks the reader to the end of the atom
 ///
 /// This should be used immediately after [`AtomInfo::read`] to skip an unwanted atom.
@@ -0,0 +1,122 @@
+//! QuickTime user data text atoms
+//!
+//! Before `ilst` atoms, QuickTime stored its metadata as "international text" atoms directly in
+//! `moov.udta`, using the same `©`-prefixed FOURCCs (Ex. `©nam`). Each of these holds one or
+//! more strings, each with its own language.
+//!
+//! These are read as a fallback for the `ilst` atom. They are never written, but can be removed
+//! when saving (see [`WriteOptions::migrate_quicktime_text`](crate::config::WriteOptions::migrate_quicktime_text)).
+
+use super::atom_info::AtomInfo;
+use crate::error::Result;
+use crate::macros::try_vec;
+use crate::util::text::{utf16_decode_bytes, utf8_decode};
+
+use std::io::Read;
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+// Mac OS Roman, from 0x80 to 0xFF
+#[rustfmt::skip]
+const MAC_ROMAN: [char; 128] = [
+	'Ä', 'Å', 'Ç', 'É', 'Ñ', 'Ö', 'Ü', 'á', 'à', 'â', 'ä', 'ã', 'å', 'ç', 'é', 'è',
+	'ê', 'ë', 'í', 'ì', 'î', 'ï', 'ñ', 'ó', 'ò', 'ô', 'ö', 'õ', 'ú', 'ù', 'û', 'ü',
+	'†', '°', '¢', '£', '§', '•', '¶', 'ß', '®', '©', '™', '´', '¨', '≠', 'Æ', 'Ø',
+	'∞', '±', '≤', '≥', '¥', 'µ', '∂', '∑', '∏', 'π', '∫', 'ª', 'º', 'Ω', 'æ', 'ø',
+	'¿', '¡', '¬', '√', 'ƒ', '≈', '∆', '«', '»', '…', '\u{A0}', 'À', 'Ã', 'Õ', 'Œ', 'œ',
+	'–', '—', '“', '”', '‘', '’', '÷', '◊', 'ÿ', 'Ÿ', '⁄', '€', '‹', '›', 'ﬁ', 'ﬂ',
+	'‡', '·', '‚', '„', '‰', 'Â', 'Ê', 'Á', 'Ë', 'È', 'Í', 'Î', 'Ï', 'Ì', 'Ó', 'Ô',
+	'\u{F8FF}', 'Ò', 'Ú', 'Û', 'Ù', 'ı', 'ˆ', '˜', '¯', '˘', '˙', '˚', '¸', '˝', '˛', 'ˇ',
+];
+
+// Language codes below this are Macintosh language codes, above are packed ISO 639-2/T codes
+const FIRST_PACKED_LANGUAGE: u16 = 0x400;
+
+pub(super) fn is_udta_text(fourcc: [u8; 4]) -> bool {
+	fourcc[0] == 0xA9
+}
+
+/// Parse an international text atom, returning its first string
+///
+/// NOTE: This expects the reader to be at the start of the atom's content.
+pub(super) fn parse_udta_text<R>(reader: &mut R, atom: &AtomInfo) -> Result<Option<String>>
+where
+	R: Read,
+{
+	let mut content = try_vec![0; (atom.len - atom.header_size()) as usize];
+	reader.read_exact(&mut content)?;
+
+	let mut content = &content[..];
+
+	// Text size (2) + language (2) + text
+	while content.len() >= 4 {
+		let text_size = content.read_u16::<BigEndian>()?;
+		let language = content.read_u16::<BigEndian>()?;
+
+		let mut text = try_vec![0; usize::from(text_size)];
+		content.read_exact(&mut text)?;
+
+		let text = decode_text(text, language)?;
+		if !text.is_empty() {
+			return Ok(Some(text));
+		}
+	}
+
+	Ok(None)
+}
+
+fn decode_text(text: Vec<u8>, language: u16) -> Result<String> {
+	if text.starts_with(&[0xFE, 0xFF]) {
+		return utf16_decode_bytes(&text, u16::from_be_bytes);
+	}
+
+	if language >= FIRST_PACKED_LANGUAGE || text.is_ascii() {
+		return utf8_decode(text);
+	}
+
+	// Macintosh language codes use the classic Mac OS encodings. Only Mac OS Roman is supported,
+	// which covers most Western languages.
+	let mut decoded = text
+		.into_iter()
+		.map(|b| match b {
+			0x80.. => MAC_ROMAN[usize::from(b - 0x80)],
+			_ => char::from(b),
+		})
+		.collect::<String>();
+
+	decoded.truncate(decoded.trim_end_matches('\0').len());
+	Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mp4::AtomIdent;
+
+	fn atom(content: &[u8]) -> AtomInfo {
+		AtomInfo {
+			start: 0,
+			len: 8 + content.len() as u64,
+			extended: false,
+			ident: AtomIdent::Fourcc(*b"\xa9nam"),
+		}
+	}
+
+	#[test_log::test]
+	fn mac_roman() {
+		// "Café", English
+		let content = [0x00, 0x04, 0x00, 0x00, b'C', b'a', b'f', 0x8E];
+		let text = parse_udta_text(&mut &content[..], &atom(&content)).unwrap();
+		assert_eq!(text.as_deref(), Some("Café"));
+	}
+
+	#[test_log::test]
+	fn first_non_empty_string() {
+		// An empty English string, followed by "Café" in UTF-8, with the packed language "fra"
+		let content = [
+			0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x1A, 0x41, b'C', b'a', b'f', 0xC3, 0xA9,
+		];
+		let text = parse_udta_text(&mut &content[..], &atom(&content)).unwrap();
+		assert_eq!(text.as_deref(), Some("Café"));
+	}
+}
@@ -24,11 +24,12 @@ const META_ATOM_IDENT: AtomIdent<'_> = AtomIdent::Fourcc(*b"meta");
 const IMPORTANT_CONTAINERS: &[[u8; 4]] = &[
 	*b"moov",
 		*b"udta",
-		*b"moof",
 		*b"trak",
 			*b"mdia",
 				*b"minf",
 					*b"stbl",
+	*b"moof",
+		*b"traf",
 ];
 impl ContextualAtom {
 	pub(super) fn read<R>(
@@ -131,21 +132,37 @@ impl AtomWriter {
 		LoftyError: From<<F as Truncate>::Error>,
 		LoftyError: From<<F as Length>::Error>,
 	{
-		let mut contents = Cursor::new(Vec::new());
-		file.read_to_end(contents.get_mut())?;
+		let mut contents = Vec::new();
+		file.read_to_end(&mut contents)?;
+
+		let mut writer = Self::new(contents, parse_mode);
+		writer.reparse(parse_mode)?;
+
+		Ok(writer)
+	}
+
+	/// Parse the atoms of the current contents
+	///
+	/// This needs to be done after any change that moves atoms, before their positions can be used again.
+	pub(super) fn reparse(&mut self, parse_mode: ParsingMode) -> Result<()> {
+		let contents = self.contents.get_mut();
+		contents.rewind()?;
 
 		let mut len = contents.get_ref().len() as u64;
 		let mut atoms = Vec::new();
-		while let Some(atom) = ContextualAtom::read(&mut contents, &mut len, parse_mode)? {
+		while let Some(atom) = ContextualAtom::read(contents, &mut len, parse_mode)? {
 			atoms.push(atom);
 		}
 
 		contents.rewind()?;
 
-		Ok(Self {
-			contents: RefCell::new(contents),
-			atoms,
-		})
+		self.atoms = atoms;
+		Ok(())
+	}
+
+	/// The top-level atoms of the file
+	pub(super) fn atoms(&self) -> &[ContextualAtom] {
+		&self.atoms
 	}
 
 	pub(super) fn find_contextual_atom(&self, fourcc: [u8; 4]) -> Option<&ContextualAtom> {
@@ -10,6 +10,8 @@ pub use properties::MpegProperties;
 use crate::ape::tag::ApeTag;
 use crate::id3::v1::tag::Id3v1Tag;
 use crate::id3::v2::tag::Id3v2Tag;
+use crate::macros::impl_chapters;
+use crate::xmp::XmpTag;
 
 use lofty_attr::LoftyFile;
 
@@ -27,6 +29,11 @@ pub struct MpegFile {
 	/// An APEv1/v2 tag
 	#[lofty(tag_type = "Ape")]
 	pub(crate) ape_tag: Option<ApeTag>,
+	/// An XMP packet, stored in an ID3v2 `PRIV` frame
+	#[lofty(tag_type = "Xmp")]
+	pub(crate) xmp_tag: Option<XmpTag>,
 	/// The file's audio properties
 	pub(crate) properties: MpegProperties,
 }
+
+impl_chapters!(MpegFile, id3v2_tag, Id3v2Tag);
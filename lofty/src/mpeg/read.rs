@@ -9,6 +9,7 @@ use crate::id3::{find_id3v1, find_lyrics3v2, FindId3v2Config, ID3FindResults};
 use crate::io::SeekStreamLen;
 use crate::macros::{decode_err, err};
 use crate::mpeg::header::HEADER_MASK;
+use crate::xmp::read::read_from_id3v2;
 
 use std::io::{Read, Seek, SeekFrom};
 
@@ -146,6 +147,10 @@ where
 		}
 	}
 
+	if let Some(id3v2) = &file.id3v2_tag {
+		file.xmp_tag = read_from_id3v2(id3v2, parse_options.parsing_mode)?;
+	}
+
 	#[allow(unused_variables)]
 	let ID3FindResults(header, id3v1) = find_id3v1(reader, parse_options.read_tags)?;
 
@@ -0,0 +1,144 @@
+pub(super) mod properties;
+
+use super::read::read_comments;
+use super::tag::VorbisComments;
+use super::verify_signature;
+use crate::config::ParseOptions;
+use crate::error::Result;
+use crate::flac::block::{BLOCK_ID_STREAMINFO, BLOCK_ID_VORBIS_COMMENTS};
+use crate::flac::FlacProperties;
+use crate::macros::{decode_err, impl_chapters};
+use crate::ogg::constants::OGG_FLAC_HEAD;
+use crate::xmp::read::read_from_vorbis_comments;
+use crate::xmp::XmpTag;
+
+use std::io::{Read, Seek, SeekFrom};
+
+use byteorder::{BigEndian, ReadBytesExt};
+use lofty_attr::LoftyFile;
+use ogg_pager::{Packets, Page, PageHeader};
+
+// Signature (5)
+// Mapping version (2)
+// Header packet count (2)
+// "fLaC" (4)
+// STREAMINFO block header (4)
+// STREAMINFO (34)
+const IDENTIFICATION_PACKET_SIZE: usize = 51;
+const STREAMINFO_OFFSET: usize = 17;
+
+pub(in crate::ogg) const BLOCK_HEADER_SIZE: usize = 4;
+
+/// An OGG FLAC file
+///
+/// ## Notes
+///
+/// * Unlike [`FlacFile`](crate::flac::FlacFile), pictures are stored in the [`VorbisComments`] tag,
+///   as `METADATA_BLOCK_PICTURE` fields. Any `PICTURE` blocks are ignored.
+#[derive(LoftyFile)]
+#[lofty(read_fn = "Self::read_from")]
+pub struct OggFlacFile {
+	/// The vorbis comments contained in the file
+	///
+	/// NOTE: While a metadata packet is required, it isn't required to actually have any data.
+	#[lofty(tag_type = "VorbisComments")]
+	pub(crate) vorbis_comments_tag: VorbisComments,
+	/// An XMP packet, stored in an `XMP` comment
+	#[lofty(tag_type = "Xmp")]
+	pub(crate) xmp_tag: Option<XmpTag>,
+	/// The file's audio properties
+	pub(crate) properties: FlacProperties,
+}
+
+impl_chapters!(@REQUIRED OggFlacFile, vorbis_comments_tag, VorbisComments);
+
+impl OggFlacFile {
+	fn read_from<R>(reader: &mut R, parse_options: ParseOptions) -> Result<Self>
+	where
+		R: Read + Seek,
+	{
+		let start = reader.stream_position()?;
+		let first_page_header = PageHeader::read(reader)?;
+
+		reader.seek(SeekFrom::Start(start))?;
+
+		// Reading every header packet leaves us at the start of the first audio page. If the count
+		// is unknown, we can only rely on the comment packet being present.
+		let header_packet_count = match header_packet_count(reader)? {
+			0 => 2,
+			count => count,
+		};
+
+		let packets = Packets::read_count(reader, header_packet_count)?;
+
+		let identification_packet = packets
+			.get(0)
+			.ok_or_else(|| decode_err!("OGG: Expected identification packet"))?;
+		verify_identification_packet(identification_packet)?;
+
+		let comment_packet = packets
+			.get(1)
+			.ok_or_else(|| decode_err!("OGG: Expected comment packet"))?;
+		if comment_packet.len() < BLOCK_HEADER_SIZE
+			|| comment_packet[0] & 0x7F != BLOCK_ID_VORBIS_COMMENTS
+		{
+			decode_err!(@BAIL OggFlac, "Expected a VORBIS_COMMENT block as the second header packet");
+		}
+
+		let mut vorbis_comments_tag = VorbisComments::default();
+		if parse_options.read_tags {
+			let reader = &mut &comment_packet[BLOCK_HEADER_SIZE..];
+			vorbis_comments_tag = read_comments(reader, reader.len() as u64, parse_options)?;
+		}
+
+		let xmp_tag = read_from_vorbis_comments(&vorbis_comments_tag, parse_options.parsing_mode)?;
+
+		Ok(Self {
+			properties: if parse_options.read_properties {
+				properties::read_properties(reader, &first_page_header, &packets)?
+			} else {
+				FlacProperties::default()
+			},
+			// A metadata packet is mandatory in OGG FLAC
+			vorbis_comments_tag,
+			xmp_tag,
+		})
+	}
+}
+
+fn verify_identification_packet(packet: &[u8]) -> Result<()> {
+	verify_signature(packet, OGG_FLAC_HEAD)?;
+
+	if packet.len() < IDENTIFICATION_PACKET_SIZE
+		|| &packet[9..13] != b"fLaC"
+		|| packet[13] & 0x7F != BLOCK_ID_STREAMINFO
+	{
+		decode_err!(@BAIL OggFlac, "File has an invalid identification packet");
+	}
+
+	Ok(())
+}
+
+/// Get the number of header packets, including the identification packet
+///
+/// The identification packet is required to be alone on the first page. This will return 0 if the
+/// number of header packets is unknown.
+pub(in crate::ogg) fn header_packet_count<R>(reader: &mut R) -> Result<isize>
+where
+	R: Read + Seek,
+{
+	let start = reader.stream_position()?;
+	let first_page = Page::read(reader)?;
+	reader.seek(SeekFrom::Start(start))?;
+
+	let identification_packet = first_page.content();
+	verify_identification_packet(identification_packet)?;
+
+	// The count stored doesn't include the identification packet
+	let count = (&identification_packet[7..9]).read_u16::<BigEndian>()?;
+	if count == 0 {
+		return Ok(0);
+	}
+
+	Ok(isize::from(count) + 1)
+}
@@ -0,0 +1,56 @@
+use super::STREAMINFO_OFFSET;
+use crate::error::Result;
+use crate::flac::FlacProperties;
+use crate::ogg::find_last_page;
+
+use std::io::{Read, Seek, SeekFrom};
+use std::time::Duration;
+
+use ogg_pager::{Packets, PageHeader};
+
+pub(in crate::ogg) fn read_properties<R>(
+	data: &mut R,
+	first_page_header: &PageHeader,
+	packets: &Packets,
+) -> Result<FlacProperties>
+where
+	R: Read + Seek,
+{
+	// It's impossible to get this far without the identification packet, safe to unwrap
+	let identification_packet = packets.get(0).expect("Identification packet expected");
+
+	// The header packets are followed by the audio, starting on a fresh page
+	let audio_start = data.stream_position()?;
+	let file_length = data.seek(SeekFrom::End(0))?;
+	let stream_length = file_length.saturating_sub(audio_start);
+
+	let stream_info = &mut &identification_packet[STREAMINFO_OFFSET..];
+	let mut properties =
+		crate::flac::properties::read_properties(stream_info, stream_length, file_length)?;
+
+	// The total sample count in STREAMINFO is allowed to be 0, in which case we have to fall back
+	// to the granule position of the last page
+	if properties.duration.is_zero() && properties.sample_rate > 0 {
+		data.seek(SeekFrom::Start(audio_start))?;
+
+		if let Ok(last_page) = find_last_page(data) {
+			let total_samples = last_page
+				.header()
+				.abgp
+				.saturating_sub(first_page_header.abgp);
+
+			properties.total_samples = total_samples;
+
+			let length =
+				(u128::from(total_samples) * 1000 / u128::from(properties.sample_rate)) as u64;
+			properties.duration = Duration::from_millis(length);
+
+			if length > 0 {
+				properties.overall_bitrate = ((file_length * 8) / length) as u32;
+				properties.audio_bitrate = ((stream_length * 8) / length) as u32;
+			}
+		}
+	}
+
+	Ok(properties)
+}
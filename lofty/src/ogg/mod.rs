@@ -4,6 +4,7 @@
 //!
 //! The only supported tag format is [`VorbisComments`]
 pub(crate) mod constants;
+pub(crate) mod flac;
 pub(crate) mod opus;
 mod picture_storage;
 pub(crate) mod read;
@@ -21,6 +22,7 @@ use ogg_pager::{Page, PageHeader};
 
 // Exports
 
+pub use flac::OggFlacFile;
 pub use opus::properties::OpusProperties;
 pub use opus::OpusFile;
 pub use picture_storage::OggPictureStorage;
@@ -3,7 +3,10 @@ pub(super) mod properties;
 use super::tag::VorbisComments;
 use crate::config::ParseOptions;
 use crate::error::Result;
+use crate::macros::impl_chapters;
 use crate::ogg::constants::SPEEXHEADER;
+use crate::xmp::read::read_from_vorbis_comments;
+use crate::xmp::XmpTag;
 use properties::SpeexProperties;
 
 use std::io::{Read, Seek};
@@ -19,10 +22,15 @@ pub struct SpeexFile {
 	/// NOTE: While a metadata packet is required, it isn't required to actually have any data.
 	#[lofty(tag_type = "VorbisComments")]
 	pub(crate) vorbis_comments_tag: VorbisComments,
+	/// An XMP packet, stored in an `XMP` comment
+	#[lofty(tag_type = "Xmp")]
+	pub(crate) xmp_tag: Option<XmpTag>,
 	/// The file's audio properties
 	pub(crate) properties: SpeexProperties,
 }
 
+impl_chapters!(@REQUIRED SpeexFile, vorbis_comments_tag, VorbisComments);
+
 impl SpeexFile {
 	fn read_from<R>(reader: &mut R, parse_options: ParseOptions) -> Result<Self>
 	where
@@ -30,6 +38,9 @@ impl SpeexFile {
 	{
 		let file_information = super::read::read_from(reader, SPEEXHEADER, &[], 2, parse_options)?;
 
+		let vorbis_comments_tag = file_information.0.unwrap_or_default();
+		let xmp_tag = read_from_vorbis_comments(&vorbis_comments_tag, parse_options.parsing_mode)?;
+
 		Ok(Self {
 			properties: if parse_options.read_properties {
 				properties::read_properties(reader, &file_information.1, &file_information.2)?
@@ -37,7 +48,8 @@ impl SpeexFile {
 				SpeexProperties::default()
 			},
 			// A metadata packet is mandatory in Speex
-			vorbis_comments_tag: file_information.0.unwrap_or_default(),
+			vorbis_comments_tag,
+			xmp_tag,
 		})
 	}
 }
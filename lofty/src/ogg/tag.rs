@@ -6,6 +6,7 @@ use crate::ogg::picture_storage::OggPictureStorage;
 use crate::ogg::write::OGGFormat;
 use crate::picture::{Picture, PictureInformation};
 use crate::probe::Probe;
+use crate::tag::items::Chapter;
 use crate::tag::{
 	try_parse_year, Accessor, ItemKey, ItemValue, MergeTag, SplitTag, Tag, TagExt, TagItem, TagType,
 };
@@ -13,8 +14,10 @@ use crate::util::flag_item;
 use crate::util::io::{FileLike, Length, Truncate};
 
 use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
 use std::io::Write;
 use std::ops::Deref;
+use std::time::Duration;
 
 use lofty_attr::tag;
 
@@ -61,7 +64,7 @@ macro_rules! impl_accessor {
 #[derive(Default, PartialEq, Eq, Debug, Clone)]
 #[tag(
 	description = "Vorbis comments",
-	supported_formats(Flac, Opus, Speex, Vorbis)
+	supported_formats(Flac, OggFlac, Opus, Speex, Vorbis)
 )]
 pub struct VorbisComments {
 	/// An identifier for the encoding software
@@ -300,6 +303,231 @@ impl VorbisComments {
 
 		self.items.drain(..split_idx).map(|(_, v)| v)
 	}
+
+	/// Gets all chapters stored in `CHAPTERxxx` fields
+	///
+	/// A chapter is made up of the following fields, where `xxx` is the chapter number:
+	///
+	/// * `CHAPTERxxx` - The start time, formatted as `HH:MM:SS.mmm`
+	/// * `CHAPTERxxxNAME` - The title
+	/// * `CHAPTERxxxURL` - A URL
+	///
+	/// Chapters are returned in order of their numbers, and any chapter without a valid start time is skipped.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::ogg::VorbisComments;
+	/// use std::time::Duration;
+	///
+	/// let mut vorbis_comments = VorbisComments::default();
+	///
+	/// vorbis_comments.push(String::from("CHAPTER001"), String::from("00:00:00.000"));
+	/// vorbis_comments.push(String::from("CHAPTER001NAME"), String::from("Intro"));
+	/// vorbis_comments.push(String::from("CHAPTER002"), String::from("00:01:30.500"));
+	///
+	/// let chapters = vorbis_comments.chapters();
+	/// assert_eq!(chapters.len(), 2);
+	/// assert_eq!(chapters[0].title.as_deref(), Some("Intro"));
+	/// assert_eq!(chapters[1].start, Duration::from_millis(90_500));
+	/// ```
+	pub fn chapters(&self) -> Vec<Chapter> {
+		self.chapter_fields()
+			.into_values()
+			.filter_map(|(start, title, url)| {
+				Some(Chapter {
+					start: start?,
+					title,
+					url,
+					..Chapter::default()
+				})
+			})
+			.collect()
+	}
+
+	// Groups the `CHAPTERxxx` fields by their chapter number
+	fn chapter_fields(&self) -> BTreeMap<u32, (Option<Duration>, Option<String>, Option<String>)> {
+		let mut fields: BTreeMap<u32, (Option<Duration>, Option<String>, Option<String>)> =
+			BTreeMap::new();
+
+		for (key, value) in &self.items {
+			let Some((number, field)) = split_chapter_key(key) else {
+				continue;
+			};
+
+			let entry = fields.entry(number).or_default();
+			match field {
+				ChapterField::Start => entry.0 = parse_chapter_timestamp(value),
+				ChapterField::Name => entry.1 = Some(value.clone()),
+				ChapterField::Url => entry.2 = Some(value.clone()),
+			}
+		}
+
+		fields
+	}
+
+	/// Replaces all `CHAPTERxxx` fields
+	///
+	/// The chapters will be numbered in the order given, starting at `CHAPTER001`.
+	///
+	/// NOTE: Vorbis comments have no way to store a chapter's end time or picture, so they are discarded.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::ogg::VorbisComments;
+	/// use lofty::tag::items::Chapter;
+	/// use std::time::Duration;
+	///
+	/// let mut vorbis_comments = VorbisComments::default();
+	///
+	/// let mut chapter = Chapter::new(Duration::from_secs(75));
+	/// chapter.title = Some(String::from("Verse"));
+	///
+	/// vorbis_comments.set_chapters(vec![chapter]);
+	///
+	/// assert_eq!(vorbis_comments.get("CHAPTER001"), Some("00:01:15.000"));
+	/// assert_eq!(vorbis_comments.get("CHAPTER001NAME"), Some("Verse"));
+	/// ```
+	pub fn set_chapters(&mut self, chapters: Vec<Chapter>) {
+		self.remove_chapters();
+		self.items
+			.extend(create_chapter_comments(&chapters, std::iter::empty()));
+	}
+
+	/// Removes all `CHAPTERxxx` fields
+	pub fn remove_chapters(&mut self) {
+		self.items
+			.retain(|(key, _)| split_chapter_key(key).is_none());
+	}
+}
+
+#[derive(Copy, Clone)]
+enum ChapterField {
+	Start,
+	Name,
+	Url,
+}
+
+// Splits a key such as "CHAPTER001NAME" into its number and field
+fn split_chapter_key(key: &str) -> Option<(u32, ChapterField)> {
+	const PREFIX: &str = "CHAPTER";
+
+	let prefix = key.get(..PREFIX.len())?;
+	if !prefix.eq_ignore_ascii_case(PREFIX) {
+		return None;
+	}
+
+	let rest = &key[PREFIX.len()..];
+	let number_len = rest
+		.bytes()
+		.position(|b| !b.is_ascii_digit())
+		.unwrap_or(rest.len());
+	if number_len == 0 {
+		return None;
+	}
+
+	let number = rest[..number_len].parse::<u32>().ok()?;
+	let field = match &rest[number_len..] {
+		"" => ChapterField::Start,
+		suffix if suffix.eq_ignore_ascii_case("NAME") => ChapterField::Name,
+		suffix if suffix.eq_ignore_ascii_case("URL") => ChapterField::Url,
+		_ => return None,
+	};
+
+	Some((number, field))
+}
+
+// Parses a timestamp in the format `HH:MM:SS[.sss]`
+fn parse_chapter_timestamp(value: &str) -> Option<Duration> {
+	let value = value.trim();
+	let (hms, fraction) = match value.split_once('.') {
+		Some((hms, fraction)) => (hms, Some(fraction)),
+		None => (value, None),
+	};
+
+	let mut components = hms.split(':');
+	let (Some(hours), Some(minutes), Some(seconds), None) = (
+		components.next(),
+		components.next(),
+		components.next(),
+		components.next(),
+	) else {
+		return None;
+	};
+
+	let hours = hours.parse::<u64>().ok()?;
+	let minutes = minutes.parse::<u64>().ok()?;
+	let seconds = seconds.parse::<u64>().ok()?;
+	if minutes >= 60 || seconds >= 60 {
+		return None;
+	}
+
+	let mut nanos = 0;
+	if let Some(fraction) = fraction {
+		if fraction.is_empty()
+			|| fraction.len() > 9
+			|| !fraction.bytes().all(|b| b.is_ascii_digit())
+		{
+			return None;
+		}
+
+		nanos = fraction.parse::<u32>().ok()? * 10_u32.pow(9 - fraction.len() as u32);
+	}
+
+	let seconds = hours
+		.checked_mul(3600)?
+		.checked_add(minutes * 60)?
+		.checked_add(seconds)?;
+	Some(Duration::new(seconds, nanos))
+}
+
+fn format_chapter_timestamp(time: Duration) -> String {
+	let seconds = time.as_secs();
+	format!(
+		"{:02}:{:02}:{:02}.{:03}",
+		seconds / 3600,
+		(seconds / 60) % 60,
+		seconds % 60,
+		time.subsec_millis()
+	)
+}
+
+// Creates the `CHAPTERxxx` fields for `chapters`
+//
+// The chapters are numbered in order, skipping any numbers already used by `existing_keys`.
+pub(crate) fn create_chapter_comments<'a>(
+	chapters: &[Chapter],
+	existing_keys: impl IntoIterator<Item = &'a str>,
+) -> Vec<(String, String)> {
+	let used_numbers = existing_keys
+		.into_iter()
+		.filter_map(|key| split_chapter_key(key).map(|(number, _)| number))
+		.collect::<BTreeSet<_>>();
+
+	let mut comments = Vec::with_capacity(chapters.len() * 2);
+
+	let mut number = 0_u32;
+	for chapter in chapters {
+		number += 1;
+		while used_numbers.contains(&number) {
+			number += 1;
+		}
+
+		let key = format!("CHAPTER{number:03}");
+
+		comments.push((key.clone(), format_chapter_timestamp(chapter.start)));
+
+		if let Some(title) = &chapter.title {
+			comments.push((format!("{key}NAME"), title.clone()));
+		}
+
+		if let Some(url) = &chapter.url {
+			comments.push((format!("{key}URL"), url.clone()));
+		}
+	}
+
+	comments
 }
 
 // A case-insensitive field name that may consist of ASCII 0x20 through 0x7D, 0x3D ('=') excluded.
@@ -529,6 +757,23 @@ impl SplitTag for VorbisComments {
 	fn split_tag(mut self) -> (Self::Remainder, Tag) {
 		let mut tag = Tag::new(TagType::VorbisComments);
 
+		let chapters = self.chapters();
+		if !chapters.is_empty() {
+			// Any fields of a chapter without a valid start time are kept as normal items,
+			// since they can't be represented as a `Chapter`
+			let complete = self
+				.chapter_fields()
+				.into_iter()
+				.filter_map(|(number, (start, ..))| start.map(|_| number))
+				.collect::<BTreeSet<_>>();
+
+			self.items.retain(|(key, _)| {
+				!split_chapter_key(key).is_some_and(|(number, _)| complete.contains(&number))
+			});
+
+			tag.chapters = chapters;
+		}
+
 		for (k, v) in std::mem::take(&mut self.items) {
 			tag.items.push(TagItem::new(
 				ItemKey::from_key(TagType::VorbisComments, &k),
@@ -607,6 +852,14 @@ impl MergeTag for SplitTagRemainder {
 			merged.items.push((key, val));
 		}
 
+		if !tag.chapters.is_empty() {
+			let chapter_comments = create_chapter_comments(
+				&tag.chapters,
+				merged.items.iter().map(|(key, _)| key.as_str()),
+			);
+			merged.items.extend(chapter_comments);
+		}
+
 		for picture in tag.pictures {
 			if let Ok(information) = PictureInformation::from_picture(&picture) {
 				merged.pictures.push((picture, information))
@@ -666,9 +919,9 @@ where
 			return crate::flac::write::write_to_inner(file, self, write_options);
 		}
 
-		let (format, header_packet_count) = OGGFormat::from_filetype(file_type);
+		let format = OGGFormat::from_filetype(file_type);
 
-		super::write::write(file, self, format, header_packet_count, write_options)
+		super::write::write(file, self, format, write_options)
 	}
 
 	pub(crate) fn dump_to<W: Write>(
@@ -682,12 +935,23 @@ where
 	}
 }
 
-pub(crate) fn create_vorbis_comments_ref(
-	tag: &Tag,
+// Creates the `CHAPTERxxx` fields for `tag`, avoiding any chapter numbers used by its items
+pub(crate) fn tag_chapter_comments(tag: &Tag) -> Vec<(String, String)> {
+	create_chapter_comments(
+		&tag.chapters,
+		tag.items
+			.iter()
+			.filter_map(|item| item.key().map_key(TagType::VorbisComments, true)),
+	)
+}
+
+pub(crate) fn create_vorbis_comments_ref<'a>(
+	tag: &'a Tag,
+	chapter_comments: &'a [(String, String)],
 ) -> (
-	&str,
-	impl Iterator<Item = (&str, &str)>,
-	impl Iterator<Item = (&Picture, PictureInformation)>,
+	&'a str,
+	impl Iterator<Item = (&'a str, &'a str)>,
+	impl Iterator<Item = (&'a Picture, PictureInformation)>,
 ) {
 	let vendor = tag.get_string(&ItemKey::EncoderSoftware).unwrap_or("");
 
@@ -699,11 +963,15 @@ pub(crate) fn create_vorbis_comments_ref(
 		_ => None,
 	});
 
+	let chapters = chapter_comments
+		.iter()
+		.map(|(key, value)| (key.as_str(), value.as_str()));
+
 	let pictures = tag
 		.pictures
 		.iter()
 		.map(|p| (p, PictureInformation::from_picture(p).unwrap_or_default()));
-	(vendor, items, pictures)
+	(vendor, items.chain(chapters), pictures)
 }
 
 #[cfg(test)]
@@ -931,4 +1199,53 @@ mod tests {
 		assert_eq!(tag.pictures().len(), 0); // Artist, no picture
 		assert!(tag.artist().is_some());
 	}
+
+	#[test_log::test]
+	fn chapters_tag_conversion() {
+		use crate::tag::items::Chapter;
+		use std::time::Duration;
+
+		let mut vorbis_comments = VorbisComments::default();
+		vorbis_comments.push(String::from("TITLE"), String::from("Foo title"));
+		vorbis_comments.push(String::from("CHAPTER003"), String::from("00:02:05.250"));
+		vorbis_comments.push(String::from("CHAPTER003NAME"), String::from("Verse"));
+		vorbis_comments.push(String::from("chapter001"), String::from("00:00:00"));
+		vorbis_comments.push(
+			String::from("chapter001url"),
+			String::from("https://example.com"),
+		);
+		// No start time, not a chapter
+		vorbis_comments.push(String::from("CHAPTER002NAME"), String::from("Outro"));
+
+		let mut intro = Chapter::new(Duration::ZERO);
+		intro.url = Some(String::from("https://example.com"));
+
+		let mut verse = Chapter::new(Duration::from_millis(125_250));
+		verse.title = Some(String::from("Verse"));
+
+		assert_eq!(
+			vorbis_comments.chapters(),
+			vec![intro.clone(), verse.clone()]
+		);
+
+		let tag: Tag = vorbis_comments.into();
+		assert_eq!(tag.chapters(), vec![intro.clone(), verse.clone()]);
+		assert!(tag
+			.get_string(&ItemKey::Unknown(String::from("CHAPTER003")))
+			.is_none());
+
+		// The incomplete chapter is kept as a normal item
+		assert_eq!(
+			tag.get_string(&ItemKey::Unknown(String::from("CHAPTER002NAME"))),
+			Some("Outro")
+		);
+
+		// Chapters are renumbered when written back, skipping the number still in use
+		let vorbis_comments: VorbisComments = tag.into();
+		assert_eq!(vorbis_comments.get("TITLE"), Some("Foo title"));
+		assert_eq!(vorbis_comments.get("CHAPTER001"), Some("00:00:00.000"));
+		assert_eq!(vorbis_comments.get("CHAPTER002NAME"), Some("Outro"));
+		assert_eq!(vorbis_comments.get("CHAPTER003NAME"), Some("Verse"));
+		assert_eq!(vorbis_comments.chapters(), vec![intro, verse]);
+	}
 }
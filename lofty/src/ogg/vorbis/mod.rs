@@ -4,7 +4,10 @@ use super::find_last_page;
 use super::tag::VorbisComments;
 use crate::config::ParseOptions;
 use crate::error::Result;
+use crate::macros::impl_chapters;
 use crate::ogg::constants::{VORBIS_COMMENT_HEAD, VORBIS_IDENT_HEAD};
+use crate::xmp::read::read_from_vorbis_comments;
+use crate::xmp::XmpTag;
 use properties::VorbisProperties;
 
 use std::io::{Read, Seek};
@@ -20,10 +23,15 @@ pub struct VorbisFile {
 	/// NOTE: While a metadata packet is required, it isn't required to actually have any data.
 	#[lofty(tag_type = "VorbisComments")]
 	pub(crate) vorbis_comments_tag: VorbisComments,
+	/// An XMP packet, stored in an `XMP` comment
+	#[lofty(tag_type = "Xmp")]
+	pub(crate) xmp_tag: Option<XmpTag>,
 	/// The file's audio properties
 	pub(crate) properties: VorbisProperties,
 }
 
+impl_chapters!(@REQUIRED VorbisFile, vorbis_comments_tag, VorbisComments);
+
 impl VorbisFile {
 	fn read_from<R>(reader: &mut R, parse_options: ParseOptions) -> Result<Self>
 	where
@@ -37,6 +45,9 @@ impl VorbisFile {
 			parse_options,
 		)?;
 
+		let vorbis_comments_tag = file_information.0.unwrap_or_default();
+		let xmp_tag = read_from_vorbis_comments(&vorbis_comments_tag, parse_options.parsing_mode)?;
+
 		Ok(Self {
 			properties: if parse_options.read_properties {
 				properties::read_properties(reader, &file_information.1, &file_information.2)?
@@ -44,7 +55,8 @@ impl VorbisFile {
 				VorbisProperties::default()
 			},
 			// A metadata packet is mandatory in OGG Vorbis
-			vorbis_comments_tag: file_information.0.unwrap_or_default(),
+			vorbis_comments_tag,
+			xmp_tag,
 		})
 	}
 }
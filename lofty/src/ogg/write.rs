@@ -2,9 +2,10 @@ use super::verify_signature;
 use crate::config::WriteOptions;
 use crate::error::{LoftyError, Result};
 use crate::file::FileType;
+use crate::flac::block::{BLOCK_ID_VORBIS_COMMENTS, MAX_BLOCK_SIZE};
 use crate::macros::{decode_err, err, try_vec};
 use crate::ogg::constants::{OPUSTAGS, VORBIS_COMMENT_HEAD};
-use crate::ogg::tag::{create_vorbis_comments_ref, VorbisCommentsRef};
+use crate::ogg::tag::{create_vorbis_comments_ref, tag_chapter_comments, VorbisCommentsRef};
 use crate::picture::{Picture, PictureInformation};
 use crate::tag::{Tag, TagType};
 use crate::util::io::{FileLike, Length, Truncate};
@@ -20,6 +21,7 @@ pub(crate) enum OGGFormat {
 	Opus,
 	Vorbis,
 	Speex,
+	Flac,
 }
 
 impl OGGFormat {
@@ -27,18 +29,38 @@ impl OGGFormat {
 		match self {
 			OGGFormat::Opus => Some(OPUSTAGS),
 			OGGFormat::Vorbis => Some(VORBIS_COMMENT_HEAD),
-			OGGFormat::Speex => None,
+			OGGFormat::Speex | OGGFormat::Flac => None,
 		}
 	}
 
-	pub(super) fn from_filetype(file_type: FileType) -> (Self, isize) {
+	pub(super) fn from_filetype(file_type: FileType) -> Self {
 		match file_type {
-			FileType::Opus => (OGGFormat::Opus, 2),
-			FileType::Vorbis => (OGGFormat::Vorbis, 3),
-			FileType::Speex => (OGGFormat::Speex, 2),
+			FileType::Opus => OGGFormat::Opus,
+			FileType::Vorbis => OGGFormat::Vorbis,
+			FileType::Speex => OGGFormat::Speex,
+			FileType::OggFlac => OGGFormat::Flac,
 			_ => unreachable!("You forgot to add support for FileType::{:?}!", file_type),
 		}
 	}
+
+	/// The number of packets that need to be read to get past the comment packet
+	fn header_packet_count<R>(self, reader: &mut R) -> Result<isize>
+	where
+		R: Read + Seek,
+	{
+		match self {
+			OGGFormat::Opus | OGGFormat::Speex => Ok(2),
+			OGGFormat::Vorbis => Ok(3),
+			// OGG FLAC can have any number of header packets, which are counted in the
+			// identification packet
+			OGGFormat::Flac => match super::flac::header_packet_count(reader)? {
+				0 => {
+					decode_err!(@BAIL OggFlac, "Unable to write to a file with an unknown number of header packets")
+				},
+				count => Ok(count),
+			},
+		}
+	}
 }
 
 pub(crate) fn write_to<F>(
@@ -52,11 +74,20 @@ where
 	LoftyError: From<<F as Truncate>::Error>,
 	LoftyError: From<<F as Length>::Error>,
 {
-	if tag.tag_type() != TagType::VorbisComments {
-		err!(UnsupportedTag);
+	match tag.tag_type() {
+		TagType::VorbisComments => {},
+		TagType::Xmp => {
+			return crate::xmp::write::write_to(
+				file,
+				&Into::<crate::xmp::XmpTag>::into(tag.clone()),
+				write_options,
+			);
+		},
+		_ => err!(UnsupportedTag),
 	}
 
-	let (vendor, items, pictures) = create_vorbis_comments_ref(tag);
+	let chapter_comments = tag_chapter_comments(tag);
+	let (vendor, items, pictures) = create_vorbis_comments_ref(tag, &chapter_comments);
 
 	let mut comments_ref = VorbisCommentsRef {
 		vendor: Cow::from(vendor),
@@ -64,22 +95,15 @@ where
 		pictures,
 	};
 
-	let (format, header_packet_count) = OGGFormat::from_filetype(file_type);
+	let format = OGGFormat::from_filetype(file_type);
 
-	write(
-		file,
-		&mut comments_ref,
-		format,
-		header_packet_count,
-		write_options,
-	)
+	write(file, &mut comments_ref, format, write_options)
 }
 
 pub(super) fn write<'a, F, II, IP>(
 	file: &mut F,
 	tag: &mut VorbisCommentsRef<'a, II, IP>,
 	format: OGGFormat,
-	header_packet_count: isize,
 	_write_options: WriteOptions,
 ) -> Result<()>
 where
@@ -98,6 +122,7 @@ where
 	let stream_serial = first_page_header.stream_serial;
 
 	file.seek(SeekFrom::Start(start))?;
+	let header_packet_count = format.header_packet_count(file)?;
 	let mut packets = Packets::read_count(file, header_packet_count)?;
 
 	let mut remaining_file_content = Vec::new();
@@ -114,8 +139,25 @@ where
 
 	let comment_signature = comment_signature.unwrap_or_default();
 
+	// OGG FLAC wraps the comments in a regular FLAC metadata block
+	let mut block_header = None;
+	if format == OGGFormat::Flac {
+		if comment_packet.len() < super::flac::BLOCK_HEADER_SIZE
+			|| comment_packet[0] & 0x7F != BLOCK_ID_VORBIS_COMMENTS
+		{
+			decode_err!(@BAIL OggFlac, "Expected a VORBIS_COMMENT block as the second header packet");
+		}
+
+		block_header = Some(comment_packet[0]);
+	}
+
+	let comment_offset = match block_header {
+		Some(_) => super::flac::BLOCK_HEADER_SIZE,
+		None => comment_signature.len(),
+	};
+
 	// Retain the file's vendor string
-	let md_reader = &mut &comment_packet[comment_signature.len()..];
+	let md_reader = &mut &comment_packet[comment_offset..];
 
 	let vendor_len = md_reader.read_u32::<LittleEndian>()?;
 	let mut vendor = try_vec![0; vendor_len as usize];
@@ -134,7 +176,20 @@ where
 	tag.vendor = vendor_str;
 
 	let add_framing_bit = format == OGGFormat::Vorbis;
-	let new_metadata_packet = create_metadata_packet(tag, comment_signature, add_framing_bit)?;
+	let mut new_metadata_packet = create_metadata_packet(tag, comment_signature, add_framing_bit)?;
+
+	if let Some(block_header) = block_header {
+		let len = match u32::try_from(new_metadata_packet.len()) {
+			Ok(len) if len <= MAX_BLOCK_SIZE => len,
+			_ => err!(TooMuchData),
+		};
+
+		// Keep the original "last block" flag, since other metadata blocks may follow
+		let mut header = len.to_be_bytes();
+		header[0] = (block_header & 0x80) | BLOCK_ID_VORBIS_COMMENTS;
+
+		new_metadata_packet.splice(0..0, header);
+	}
 
 	// Replace the old comment packet
 	packets.set(1, new_metadata_packet);
@@ -2,17 +2,22 @@
 
 use crate::aac::AacFile;
 use crate::ape::ApeFile;
+use crate::asf::AsfFile;
 use crate::config::{global_options, ParseOptions};
+use crate::dsd::dsdiff::DsdiffFile;
+use crate::dsd::dsf::DsfFile;
 use crate::error::Result;
 use crate::file::{AudioFile, FileType, FileTypeGuessResult, TaggedFile};
 use crate::flac::FlacFile;
 use crate::iff::aiff::AiffFile;
 use crate::iff::wav::WavFile;
 use crate::macros::err;
+use crate::matroska::MatroskaFile;
 use crate::mp4::Mp4File;
 use crate::mpeg::header::search_for_frame_sync;
 use crate::mpeg::MpegFile;
 use crate::musepack::MpcFile;
+use crate::ogg::flac::OggFlacFile;
 use crate::ogg::opus::OpusFile;
 use crate::ogg::speex::SpeexFile;
 use crate::ogg::vorbis::VorbisFile;
@@ -474,8 +479,13 @@ impl<R: Read + Seek> Probe<R> {
 				FileType::Vorbis => VorbisFile::read_from(reader, options)?.into(),
 				FileType::Wav => WavFile::read_from(reader, options)?.into(),
 				FileType::Mp4 => Mp4File::read_from(reader, options)?.into(),
+				FileType::Matroska => MatroskaFile::read_from(reader, options)?.into(),
+				FileType::Asf => AsfFile::read_from(reader, options)?.into(),
+				FileType::Dsf => DsfFile::read_from(reader, options)?.into(),
+				FileType::Dsdiff => DsdiffFile::read_from(reader, options)?.into(),
 				FileType::Mpc => MpcFile::read_from(reader, options)?.into(),
 				FileType::Speex => SpeexFile::read_from(reader, options)?.into(),
+				FileType::OggFlac => OggFlacFile::read_from(reader, options)?.into(),
 				FileType::WavPack => WavPackFile::read_from(reader, options)?.into(),
 				FileType::Custom(c) => {
 					if !unsafe { global_options().use_custom_resolvers } {
@@ -694,6 +704,14 @@ mod tests {
 		test_probe("tests/files/assets/minimal/untagged.aac", FileType::Aac);
 	}
 
+	#[test_log::test]
+	fn probe_aac_adif() {
+		test_probe(
+			"tests/files/assets/minimal/untagged_adif.aac",
+			FileType::Aac,
+		);
+	}
+
 	#[test_log::test]
 	fn probe_aac_with_id3v2() {
 		test_probe("tests/files/assets/minimal/full_test.aac", FileType::Aac);
@@ -744,6 +762,21 @@ mod tests {
 		test_probe("tests/files/assets/minimal/full_test.spx", FileType::Speex);
 	}
 
+	#[test_log::test]
+	fn probe_ogg_flac() {
+		// `.oga` isn't specific to FLAC, so only the contents can be used
+		test_probe_file(
+			"tests/files/assets/minimal/full_test.oga",
+			FileType::OggFlac,
+		);
+		assert_eq!(
+			Probe::open("tests/files/assets/minimal/full_test.oga")
+				.unwrap()
+				.file_type(),
+			None
+		);
+	}
+
 	#[test_log::test]
 	fn probe_mp4() {
 		test_probe(
@@ -759,4 +792,43 @@ mod tests {
 			FileType::Wav,
 		);
 	}
+
+	#[test_log::test]
+	fn probe_wav_rf64() {
+		test_probe(
+			"tests/files/assets/minimal/wav_format_rf64.wav",
+			FileType::Wav,
+		);
+	}
+
+	#[test_log::test]
+	fn probe_wav_wave64() {
+		test_probe(
+			"tests/files/assets/minimal/wav_format_w64.w64",
+			FileType::Wav,
+		);
+	}
+
+	#[test_log::test]
+	fn probe_matroska() {
+		test_probe(
+			"tests/files/assets/minimal/full_test.mka",
+			FileType::Matroska,
+		);
+	}
+
+	#[test_log::test]
+	fn probe_asf() {
+		test_probe("tests/files/assets/minimal/full_test.wma", FileType::Asf);
+	}
+
+	#[test_log::test]
+	fn probe_dsf() {
+		test_probe("tests/files/assets/minimal/full_test.dsf", FileType::Dsf);
+	}
+
+	#[test_log::test]
+	fn probe_dsdiff() {
+		test_probe("tests/files/assets/minimal/full_test.dff", FileType::Dsdiff);
+	}
 }
@@ -1,10 +1,15 @@
-use crate::aac::{AACProperties, AacFile};
+use crate::aac::{AACProperties, AacFile, AacFormat};
 use crate::ape::{ApeFile, ApeProperties};
+use crate::asf::{AsfCodec, AsfFile, AsfProperties};
 use crate::config::ParseOptions;
+use crate::dsd::dsdiff::DsdiffFile;
+use crate::dsd::dsf::DsfFile;
+use crate::dsd::DsdProperties;
 use crate::file::AudioFile;
 use crate::flac::{FlacFile, FlacProperties};
 use crate::iff::aiff::{AiffFile, AiffProperties};
 use crate::iff::wav::{WavFile, WavFormat, WavProperties};
+use crate::matroska::{MatroskaFile, MatroskaProperties};
 use crate::mp4::{AudioObjectType, Mp4Codec, Mp4File, Mp4Properties};
 use crate::mpeg::{ChannelMode, Layer, MpegFile, MpegProperties, MpegVersion};
 use crate::musepack::sv4to6::MpcSv4to6Properties;
@@ -12,7 +17,7 @@ use crate::musepack::sv7::{Link, MpcSv7Properties, Profile};
 use crate::musepack::sv8::{EncoderInfo, MpcSv8Properties, ReplayGain, StreamHeader};
 use crate::musepack::{MpcFile, MpcProperties};
 use crate::ogg::{
-	OpusFile, OpusProperties, SpeexFile, SpeexProperties, VorbisFile, VorbisProperties,
+	OggFlacFile, OpusFile, OpusProperties, SpeexFile, SpeexProperties, VorbisFile, VorbisProperties,
 };
 use crate::properties::ChannelMask;
 use crate::wavpack::{WavPackFile, WavPackProperties};
@@ -25,6 +30,7 @@ use std::time::Duration;
 // is an issue.
 
 const AAC_PROPERTIES: AACProperties = AACProperties {
+	format: AacFormat::Adts,
 	version: MpegVersion::V4,
 	audio_object_type: AudioObjectType::AacLowComplexity,
 	duration: Duration::from_millis(1474), /* TODO: This is ~100ms greater than FFmpeg's report, can we do better? */
@@ -64,6 +70,26 @@ const FLAC_PROPERTIES: FlacProperties = FlacProperties {
 	sample_rate: 48000,
 	bit_depth: 16,
 	channels: 2,
+	min_block_size: 4608,
+	max_block_size: 4608,
+	min_frame_size: 783,
+	max_frame_size: 4744,
+	total_samples: 68546,
+	signature: 164_506_065_180_489_231_127_156_351_872_182_799_315,
+};
+
+const OGG_FLAC_PROPERTIES: FlacProperties = FlacProperties {
+	duration: Duration::from_millis(1428),
+	overall_bitrate: 279,
+	audio_bitrate: 278,
+	sample_rate: 48000,
+	bit_depth: 16,
+	channels: 2,
+	min_block_size: 4608,
+	max_block_size: 4608,
+	min_frame_size: 783,
+	max_frame_size: 4744,
+	total_samples: 68546,
 	signature: 164_506_065_180_489_231_127_156_351_872_182_799_315,
 };
 
@@ -121,6 +147,7 @@ const MP4_AAC_PROPERTIES: Mp4Properties = Mp4Properties {
 	sample_rate: 48000,
 	bit_depth: None,
 	channels: 2,
+	pre_skip: None,
 	drm_protected: false,
 };
 
@@ -133,6 +160,7 @@ const MP4_ALAC_PROPERTIES: Mp4Properties = Mp4Properties {
 	sample_rate: 48000,
 	bit_depth: Some(16),
 	channels: 2,
+	pre_skip: None,
 	drm_protected: false,
 };
 
@@ -145,6 +173,7 @@ const MP4_ALS_PROPERTIES: Mp4Properties = Mp4Properties {
 	sample_rate: 48000,
 	bit_depth: None,
 	channels: 2,
+	pre_skip: None,
 	drm_protected: false,
 };
 
@@ -157,6 +186,20 @@ const MP4_FLAC_PROPERTIES: Mp4Properties = Mp4Properties {
 	sample_rate: 48000,
 	bit_depth: Some(16),
 	channels: 2,
+	pre_skip: None,
+	drm_protected: false,
+};
+
+const MP4_OPUS_PROPERTIES: Mp4Properties = Mp4Properties {
+	codec: Mp4Codec::Opus,
+	extended_audio_object_type: None,
+	duration: Duration::from_millis(1440),
+	overall_bitrate: 122,
+	audio_bitrate: 118,
+	sample_rate: 48000,
+	bit_depth: None,
+	channels: 2,
+	pre_skip: Some(312),
 	drm_protected: false,
 };
 
@@ -269,6 +312,28 @@ const WAV_PROPERTIES: WavProperties = WavProperties {
 	channel_mask: None,
 };
 
+const WAV_RF64_PROPERTIES: WavProperties = WavProperties {
+	format: WavFormat::PCM,
+	duration: Duration::from_millis(100),
+	overall_bitrate: 1630,
+	audio_bitrate: 1536,
+	sample_rate: 48000,
+	bit_depth: 16,
+	channels: 2,
+	channel_mask: None,
+};
+
+const WAV_WAVE64_PROPERTIES: WavProperties = WavProperties {
+	format: WavFormat::PCM,
+	duration: Duration::from_millis(100),
+	overall_bitrate: 1635,
+	audio_bitrate: 1536,
+	sample_rate: 48000,
+	bit_depth: 16,
+	channels: 2,
+	channel_mask: None,
+};
+
 const WAVPACK_PROPERTIES: WavPackProperties = WavPackProperties {
 	version: 1040,
 	duration: Duration::from_millis(1428),
@@ -301,6 +366,28 @@ fn aac_properties() {
 	);
 }
 
+#[test_log::test]
+fn aac_adif_properties() {
+	assert_eq!(
+		get_properties::<AacFile>("tests/files/assets/minimal/untagged_adif.aac"),
+		AACProperties {
+			format: AacFormat::Adif {
+				variable_bitrate: false,
+			},
+			version: MpegVersion::V2,
+			audio_object_type: AudioObjectType::AacLowComplexity,
+			duration: Duration::from_millis(1000),
+			overall_bitrate: 128,
+			audio_bitrate: 128,
+			sample_rate: 44100,
+			channels: 2,
+			channel_mask: Some(ChannelMask::stereo()),
+			copyright: false,
+			original: false,
+		}
+	);
+}
+
 #[test_log::test]
 fn aiff_properties() {
 	assert_eq!(
@@ -325,6 +412,14 @@ fn flac_properties() {
 	)
 }
 
+#[test_log::test]
+fn ogg_flac_properties() {
+	assert_eq!(
+		get_properties::<OggFlacFile>("tests/files/assets/minimal/full_test.oga"),
+		OGG_FLAC_PROPERTIES
+	)
+}
+
 #[test_log::test]
 fn mp1_properties() {
 	assert_eq!(
@@ -381,6 +476,14 @@ fn mp4_flac_properties() {
 	)
 }
 
+#[test_log::test]
+fn mp4_opus_properties() {
+	assert_eq!(
+		get_properties::<Mp4File>("tests/files/assets/minimal/mp4_codec_opus.mp4"),
+		MP4_OPUS_PROPERTIES
+	)
+}
+
 #[test_log::test]
 fn mpc_sv5_properties() {
 	assert_eq!(
@@ -437,6 +540,22 @@ fn wav_properties() {
 	)
 }
 
+#[test_log::test]
+fn wav_rf64_properties() {
+	assert_eq!(
+		get_properties::<WavFile>("tests/files/assets/minimal/wav_format_rf64.wav"),
+		WAV_RF64_PROPERTIES
+	)
+}
+
+#[test_log::test]
+fn wav_wave64_properties() {
+	assert_eq!(
+		get_properties::<WavFile>("tests/files/assets/minimal/wav_format_w64.w64"),
+		WAV_WAVE64_PROPERTIES
+	)
+}
+
 #[test_log::test]
 fn wavpack_properties() {
 	assert_eq!(
@@ -444,3 +563,63 @@ fn wavpack_properties() {
 		WAVPACK_PROPERTIES
 	)
 }
+
+#[test_log::test]
+fn matroska_properties() {
+	assert_eq!(
+		get_properties::<MatroskaFile>("tests/files/assets/minimal/full_test.mka"),
+		MatroskaProperties {
+			duration: Duration::from_millis(1000),
+			overall_bitrate: 130,
+			audio_bitrate: 128,
+			sample_rate: 8000,
+			bit_depth: Some(16),
+			channels: 1,
+			codec_id: String::from("A_PCM/INT/LIT"),
+		}
+	)
+}
+
+#[test_log::test]
+fn asf_properties() {
+	assert_eq!(
+		get_properties::<AsfFile>("tests/files/assets/minimal/full_test.wma"),
+		AsfProperties {
+			codec: AsfCodec::WmaV2,
+			duration: Duration::from_millis(1000),
+			overall_bitrate: 131,
+			audio_bitrate: 128,
+			sample_rate: 44100,
+			bit_depth: 16,
+			channels: 2,
+		}
+	)
+}
+
+#[test_log::test]
+fn dsf_properties() {
+	assert_eq!(
+		get_properties::<DsfFile>("tests/files/assets/minimal/full_test.dsf"),
+		DsdProperties {
+			duration: Duration::from_millis(100),
+			overall_bitrate: 5908,
+			audio_bitrate: 5645,
+			sample_rate: 2_822_400,
+			channels: 2,
+		}
+	)
+}
+
+#[test_log::test]
+fn dsdiff_properties() {
+	assert_eq!(
+		get_properties::<DsdiffFile>("tests/files/assets/minimal/full_test.dff"),
+		DsdProperties {
+			duration: Duration::from_millis(100),
+			overall_bitrate: 5664,
+			audio_bitrate: 5645,
+			sample_rate: 2_822_400,
+			channels: 2,
+		}
+	)
+}
@@ -136,6 +136,69 @@ gen_map!(
 	"MUSICBRAINZ_WORKID"           => MusicBrainzWorkId
 );
 
+gen_map!(
+	ASF_MAP;
+
+	"WM/AlbumTitle"                => AlbumTitle,
+	"WM/SetSubTitle"               => SetSubtitle,
+	"WM/ContentGroupDescription"   => ContentGroup,
+	"Title"                        => TrackTitle,
+	"WM/SubTitle"                  => TrackSubtitle,
+	"WM/OriginalAlbumTitle"        => OriginalAlbumTitle,
+	"WM/OriginalArtist"            => OriginalArtist,
+	"WM/OriginalLyricist"          => OriginalLyricist,
+	"WM/AlbumSortOrder"            => AlbumTitleSortOrder,
+	"WM/AlbumArtistSortOrder"      => AlbumArtistSortOrder,
+	"WM/TitleSortOrder"            => TrackTitleSortOrder,
+	"WM/ArtistSortOrder"           => TrackArtistSortOrder,
+	"WM/ComposerSortOrder"         => ComposerSortOrder,
+	"WM/AlbumArtist"               => AlbumArtist,
+	"Author"                       => TrackArtist,
+	"WM/ARTISTS"                   => TrackArtists,
+	"WM/Writer"                    => Lyricist,
+	"WM/Composer"                  => Composer,
+	"WM/Conductor"                 => Conductor,
+	"WM/Director"                  => Director,
+	"WM/Engineer"                  => Engineer,
+	"WM/Producer"                  => Producer,
+	"WM/Publisher"                 => Label,
+	"WM/ModifiedBy"                => Remixer,
+	"WM/PartOfSet"                 => DiscNumber,
+	"WM/PartOfSet"                 => DiscTotal,
+	"WM/TrackNumber"               => TrackNumber,
+	"WM/Year"                      => Year,
+	"WM/OriginalReleaseYear"       => OriginalReleaseDate,
+	"WM/ISRC"                      => Isrc,
+	"WM/Barcode"                   => Barcode,
+	"WM/CatalogNo"                 => CatalogNumber,
+	"WM/IsCompilation"             => FlagCompilation,
+	"WM/Media"                     => OriginalMediaType,
+	"WM/EncodedBy"                 => EncodedBy,
+	"WM/ToolName"                  => EncoderSoftware,
+	"WM/EncodingSettings"          => EncoderSettings,
+	"WM/EncodingTime"              => EncodingTime,
+	"ReplayGain_Album_Gain"        => ReplayGainAlbumGain,
+	"ReplayGain_Album_Peak"        => ReplayGainAlbumPeak,
+	"ReplayGain_Track_Gain"        => ReplayGainTrackGain,
+	"ReplayGain_Track_Peak"        => ReplayGainTrackPeak,
+	"WM/Genre"                     => Genre,
+	"WM/InitialKey"                => InitialKey,
+	"WM/Mood"                      => Mood,
+	"WM/BeatsPerMinute"            => Bpm,
+	"Copyright"                    => CopyrightMessage,
+	"Description"                  => Comment,
+	"WM/Language"                  => Language,
+	"WM/Script"                    => Script,
+	"WM/Lyrics"                    => Lyrics,
+	"MusicBrainz/Track Id"         => MusicBrainzRecordingId,
+	"MusicBrainz/Release Track Id" => MusicBrainzTrackId,
+	"MusicBrainz/Album Id"         => MusicBrainzReleaseId,
+	"MusicBrainz/Release Group Id" => MusicBrainzReleaseGroupId,
+	"MusicBrainz/Artist Id"        => MusicBrainzArtistId,
+	"MusicBrainz/Album Artist Id"  => MusicBrainzReleaseArtistId,
+	"MusicBrainz/Work Id"          => MusicBrainzWorkId
+);
+
 gen_map!(
 	ID3V2_MAP;
 
@@ -311,6 +374,49 @@ gen_map!(
 	"----:com.apple.iTunes:MusicBrainz Work Id"          => MusicBrainzWorkId
 );
 
+gen_map!(
+	MATROSKA_MAP;
+
+	"TITLE"                     => TrackTitle,
+	"SUBTITLE"                  => TrackSubtitle,
+	"TITLE_SORT"                => TrackTitleSortOrder,
+	"ARTIST"                    => TrackArtist,
+	"ARTIST_SORT"               => TrackArtistSortOrder,
+	"ARRANGER"                  => Arranger,
+	"WRITTEN_BY"                => Writer,
+	"COMPOSER"                  => Composer,
+	"COMPOSER_SORT"             => ComposerSortOrder,
+	"CONDUCTOR"                 => Conductor,
+	"DIRECTOR"                  => Director,
+	"LYRICIST"                  => Lyricist,
+	"MIXED_BY"                  => MixEngineer,
+	"PRODUCER"                  => Producer,
+	"PUBLISHER"                 => Publisher,
+	"LABEL"                     => Label,
+	"REMIXED_BY"                => Remixer,
+	"PART_NUMBER"               => TrackNumber,
+	"DATE_RECORDED"             => RecordingDate,
+	"DATE_RELEASED"             => ReleaseDate,
+	"ISRC"                      => Isrc,
+	"BARCODE"                   => Barcode,
+	"CATALOG_NUMBER"            => CatalogNumber,
+	"ORIGINAL_MEDIA_TYPE"       => OriginalMediaType,
+	"ENCODED_BY"                => EncodedBy,
+	"ENCODER"                   => EncoderSoftware,
+	"ENCODER_SETTINGS"          => EncoderSettings,
+	"REPLAYGAIN_GAIN"           => ReplayGainTrackGain,
+	"REPLAYGAIN_PEAK"           => ReplayGainTrackPeak,
+	"GENRE"                     => Genre,
+	"MOOD"                      => Mood,
+	"BPM"                       => Bpm,
+	"INITIAL_KEY"               => InitialKey,
+	"COPYRIGHT"                 => CopyrightMessage,
+	"LICENSE"                   => License,
+	"COMMENT"                   => Comment,
+	"DESCRIPTION"               => Description,
+	"LYRICS"                    => Lyrics
+);
+
 gen_map!(
 	RIFF_INFO_MAP;
 
@@ -412,6 +518,34 @@ gen_map!(
 	"MUSICBRAINZ_WORKID"                      => MusicBrainzWorkId
 );
 
+gen_map!(
+	XMP_MAP;
+
+	"xmpDM:album"             => AlbumTitle,
+	"dc:title"                => TrackTitle,
+	"xmpDM:albumArtist"       => AlbumArtist,
+	"xmpDM:artist"            => TrackArtist,
+	"dc:creator"              => TrackArtists,
+	"xmpDM:composer"          => Composer,
+	"xmpDM:engineer"          => Engineer,
+	"dc:publisher"            => Publisher,
+	"xmpDM:discNumber"        => DiscNumber,
+	"xmpDM:discNumber"        => DiscTotal,
+	"xmpDM:trackNumber"       => TrackNumber,
+	"xmpDM:releaseDate"       => ReleaseDate,
+	"xmpDM:partOfCompilation" => FlagCompilation,
+	"xmp:CreatorTool"         => EncoderSoftware,
+	"xmpDM:genre"             => Genre,
+	"xmpDM:tempo"             => Bpm,
+	"xmpDM:key"               => InitialKey,
+	"dc:rights"               => CopyrightMessage,
+	"xmpRights:WebStatement"  => CopyrightUrl,
+	"dc:description"          => Description,
+	"xmpDM:logComment"        => Comment,
+	"dc:language"             => Language,
+	"xmpDM:lyrics"            => Lyrics
+);
+
 macro_rules! gen_item_keys {
 	(
 		MAPS => [
@@ -490,13 +624,19 @@ gen_item_keys!(
 
 		[TagType::Ape, APE_MAP],
 
+		[TagType::Asf, ASF_MAP],
+
 		[TagType::Id3v2, ID3V2_MAP],
 
+		[TagType::Matroska, MATROSKA_MAP],
+
 		[TagType::Mp4Ilst, ILST_MAP],
 
 		[TagType::RiffInfo, RIFF_INFO_MAP],
 
-		[TagType::VorbisComments, VORBIS_MAP]
+		[TagType::VorbisComments, VORBIS_MAP],
+
+		[TagType::Xmp, XMP_MAP]
 	];
 
 	KEYS => [
@@ -0,0 +1,69 @@
+use crate::picture::Picture;
+
+use std::time::Duration;
+
+/// A format-agnostic chapter
+///
+/// Chapters are stored differently in every format:
+///
+/// * ID3v2 - `CHAP` frames, ordered by a top-level `CTOC` frame
+/// * MP4 - A Nero-style `chpl` atom in `moov.udta`, or a QuickTime chapter track
+/// * Vorbis Comments - `CHAPTERxxx`, `CHAPTERxxxNAME`, and `CHAPTERxxxURL` fields
+///
+/// Not every format is able to store every field. When writing, anything the format has no room for
+/// is silently discarded:
+///
+/// | Field     | ID3v2 | MP4 | Vorbis Comments |
+/// |-----------|-------|-----|-----------------|
+/// | `start`   | ✓     | ✓   | ✓               |
+/// | `end`     | ✓     |     |                 |
+/// | `title`   | ✓     | ✓   | ✓               |
+/// | `url`     | ✓     |     | ✓               |
+/// | `picture` | ✓     |     |                 |
+///
+/// # Examples
+///
+/// ```rust
+/// use lofty::tag::items::Chapter;
+/// use std::time::Duration;
+///
+/// let mut chapter = Chapter::new(Duration::from_secs(30));
+/// chapter.title = Some(String::from("Intro"));
+///
+/// assert_eq!(chapter.start, Duration::from_secs(30));
+/// assert!(chapter.end.is_none());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Default)]
+pub struct Chapter {
+	/// The start of the chapter, relative to the start of the stream
+	pub start: Duration,
+	/// The end of the chapter
+	///
+	/// If this is `None`, the chapter lasts until the start of the next chapter,
+	/// or the end of the stream if it is the last.
+	pub end: Option<Duration>,
+	/// The title of the chapter
+	pub title: Option<String>,
+	/// A URL associated with the chapter
+	pub url: Option<String>,
+	/// An image associated with the chapter
+	pub picture: Option<Picture>,
+}
+
+impl Chapter {
+	/// Create a new `Chapter` starting at `start`, with all other fields empty
+	pub fn new(start: Duration) -> Self {
+		Self {
+			start,
+			..Self::default()
+		}
+	}
+}
+
+/// Gets the end of the chapter at `index`, falling back to the start of the next chapter
+pub(crate) fn chapter_end(chapters: &[Chapter], index: usize) -> Option<Duration> {
+	let chapter = chapters.get(index)?;
+	chapter
+		.end
+		.or_else(|| chapters.get(index + 1).map(|next| next.start))
+}
@@ -1,6 +1,6 @@
 /// A three character language code, as specified by [ISO-639-2].
 ///
-/// For now, this is used exclusively in ID3v2.
+/// This is used in ID3v2, and for the language of MP4 tracks.
 ///
 /// Excerpt from <https://mutagen-specs.readthedocs.io/en/latest/id3/id3v2.4.0-structure.html>:
 ///
@@ -1,7 +1,10 @@
 //! Various generic representations of tag items
 
+mod chapter;
 mod lang;
 mod timestamp;
 
+pub(crate) use chapter::chapter_end;
+pub use chapter::Chapter;
 pub use lang::*;
 pub use timestamp::Timestamp;
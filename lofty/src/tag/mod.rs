@@ -14,6 +14,7 @@ use crate::error::{LoftyError, Result};
 use crate::macros::err;
 use crate::picture::{Picture, PictureType};
 use crate::probe::Probe;
+use crate::tag::items::Chapter;
 use crate::util::io::{FileLike, Length, Truncate};
 
 use std::borrow::Cow;
@@ -112,6 +113,7 @@ pub struct Tag {
 	tag_type: TagType,
 	pub(crate) pictures: Vec<Picture>,
 	pub(crate) items: Vec<TagItem>,
+	pub(crate) chapters: Vec<Chapter>,
 	pub(crate) companion_tag: Option<companion_tag::CompanionTag>,
 }
 
@@ -229,6 +231,7 @@ impl Tag {
 			tag_type,
 			pictures: Vec::new(),
 			items: Vec::new(),
+			chapters: Vec::new(),
 			companion_tag: None,
 		}
 	}
@@ -634,6 +637,44 @@ impl Tag {
 	pub fn remove_picture(&mut self, index: usize) -> Picture {
 		self.pictures.remove(index)
 	}
+
+	/// Returns the stored [`Chapter`]s
+	pub fn chapters(&self) -> Vec<Chapter> {
+		self.chapters.clone()
+	}
+
+	/// Replaces all [`Chapter`]s
+	///
+	/// The chapters are stored in the order given, and will be written in the
+	/// target format's native representation. See [`Chapter`] for what each format
+	/// is able to store.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::tag::items::Chapter;
+	/// use lofty::tag::{Tag, TagType};
+	/// use std::time::Duration;
+	///
+	/// let mut tag = Tag::new(TagType::Id3v2);
+	///
+	/// let mut intro = Chapter::new(Duration::ZERO);
+	/// intro.title = Some(String::from("Intro"));
+	///
+	/// let mut outro = Chapter::new(Duration::from_secs(120));
+	/// outro.title = Some(String::from("Outro"));
+	///
+	/// tag.set_chapters(vec![intro, outro]);
+	/// assert_eq!(tag.chapters().len(), 2);
+	/// ```
+	pub fn set_chapters(&mut self, chapters: Vec<Chapter>) {
+		self.chapters = chapters;
+	}
+
+	/// Removes all [`Chapter`]s
+	pub fn remove_chapters(&mut self) {
+		self.chapters.clear();
+	}
 }
 
 impl TagExt for Tag {
@@ -654,7 +695,7 @@ impl TagExt for Tag {
 	}
 
 	fn is_empty(&self) -> bool {
-		self.items.is_empty() && self.pictures.is_empty()
+		self.items.is_empty() && self.pictures.is_empty() && self.chapters.is_empty()
 	}
 
 	/// Save the `Tag` to a [`FileLike`]
@@ -717,6 +758,7 @@ impl TagExt for Tag {
 	fn clear(&mut self) {
 		self.items.clear();
 		self.pictures.clear();
+		self.chapters.clear();
 	}
 }
 
@@ -27,6 +27,12 @@ pub enum TagType {
 	RiffInfo,
 	/// Represents AIFF text chunks
 	AiffText,
+	/// Represents Matroska `SimpleTag`s
+	Matroska,
+	/// Represents ASF attributes
+	Asf,
+	/// Represents an XMP packet
+	Xmp,
 }
 
 impl TagType {
@@ -2,15 +2,18 @@ use crate::config::WriteOptions;
 use crate::error::{LoftyError, Result};
 use crate::file::FileType;
 use crate::macros::err;
-use crate::tag::{Tag, TagType};
+use crate::tag::{Tag, TagExt, TagType};
 use crate::util::io::{FileLike, Length, Truncate};
-use crate::{aac, ape, flac, iff, mpeg, musepack, wavpack};
+use crate::{aac, ape, dsd, flac, iff, mpeg, musepack, wavpack};
 
+use crate::asf::AsfTag;
 use crate::id3::v1::tag::Id3v1TagRef;
 use crate::id3::v2::tag::Id3v2TagRef;
 use crate::id3::v2::{self, Id3v2TagFlags};
+use crate::matroska::MatroskaTag;
 use crate::mp4::Ilst;
-use crate::ogg::tag::{create_vorbis_comments_ref, VorbisCommentsRef};
+use crate::ogg::tag::{create_vorbis_comments_ref, tag_chapter_comments, VorbisCommentsRef};
+use crate::xmp::XmpTag;
 use ape::tag::ApeTagRef;
 use iff::aiff::tag::AiffTextChunksRef;
 use iff::wav::tag::RIFFInfoListRef;
@@ -34,15 +37,35 @@ where
 		FileType::Aac => aac::write::write_to(file, tag, write_options),
 		FileType::Aiff => iff::aiff::write::write_to(file, tag, write_options),
 		FileType::Ape => ape::write::write_to(file, tag, write_options),
+		FileType::Dsdiff => dsd::dsdiff::write::write_to(file, tag, write_options),
+		FileType::Dsf => dsd::dsf::write::write_to(file, tag, write_options),
 		FileType::Flac => flac::write::write_to(file, tag, write_options),
-		FileType::Opus | FileType::Speex | FileType::Vorbis => {
+		FileType::Opus | FileType::Speex | FileType::Vorbis | FileType::OggFlac => {
 			crate::ogg::write::write_to(file, tag, file_type, write_options)
 		},
+		FileType::Matroska => crate::matroska::tag::write::write_to(
+			file,
+			&Into::<MatroskaTag>::into(tag.clone()),
+			write_options,
+		),
+		FileType::Asf => crate::asf::tag::write::write_to(
+			file,
+			&Into::<AsfTag>::into(tag.clone()),
+			write_options,
+		),
 		FileType::Mpc => musepack::write::write_to(file, tag, write_options),
 		FileType::Mpeg => mpeg::write::write_to(file, tag, write_options),
+		FileType::Mp4 if tag.tag_type() == TagType::Xmp => {
+			crate::xmp::write::write_to(file, &Into::<XmpTag>::into(tag.clone()), write_options)
+		},
+		FileType::Mp4 if tag.tag_type() == TagType::Id3v2 => Id3v2TagRef {
+			flags: Id3v2TagFlags::default(),
+			frames: v2::tag::tag_frames(tag).peekable(),
+		}
+		.write_to(file, write_options),
 		FileType::Mp4 => crate::mp4::ilst::write::write_to(
 			file,
-			&mut Into::<Ilst>::into(tag.clone()).as_ref(),
+			&mut Into::<Ilst>::into(tag.clone()).as_ref(write_options),
 			write_options,
 		),
 		FileType::Wav => iff::wav::write::write_to(file, tag, write_options),
@@ -70,10 +93,14 @@ pub(crate) fn dump_tag<W: Write>(
 		}
 		.dump_to(writer, write_options),
 		TagType::Mp4Ilst => Into::<Ilst>::into(tag.clone())
-			.as_ref()
+			.as_ref(write_options)
 			.dump_to(writer, write_options),
+		TagType::Matroska => Into::<MatroskaTag>::into(tag.clone()).dump_to(writer, write_options),
+		TagType::Asf => Into::<AsfTag>::into(tag.clone()).dump_to(writer, write_options),
+		TagType::Xmp => Into::<XmpTag>::into(tag.clone()).dump_to(writer, write_options),
 		TagType::VorbisComments => {
-			let (vendor, items, pictures) = create_vorbis_comments_ref(tag);
+			let chapter_comments = tag_chapter_comments(tag);
+			let (vendor, items, pictures) = create_vorbis_comments_ref(tag, &chapter_comments);
 
 			VorbisCommentsRef {
 				vendor: Cow::from(vendor),
@@ -1,11 +1,13 @@
 //! Various traits for reading and writing to file-like objects
 
 use crate::error::{LoftyError, Result};
+use crate::macros::try_vec;
 use crate::util::math::F80;
 
 use std::collections::VecDeque;
 use std::fs::File;
-use std::io::{Cursor, Read, Seek, Write};
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
 
 // TODO: https://github.com/rust-lang/rust/issues/59359
 pub(crate) trait SeekStreamLen: Seek {
@@ -246,6 +248,102 @@ where
 	}
 }
 
+const COPY_BUFFER_SIZE: u64 = 64 * 1024;
+
+/// The length a file of `len` bytes will have after [`splice_file`]
+pub(crate) fn spliced_len(len: u64, edits: &[(Range<u64>, Vec<u8>)]) -> u64 {
+	edits.iter().fold(len, |new_len, (range, content)| {
+		let removed = range.end.min(len).saturating_sub(range.start);
+		new_len - removed + content.len() as u64
+	})
+}
+
+/// Replace multiple ranges of a file in place, returning the new length of the file
+///
+/// Unlike reading the file into memory and splicing it, this only ever holds a small buffer,
+/// making it suitable for files of any size. The ranges must not overlap.
+pub(crate) fn splice_file<F>(file: &mut F, mut edits: Vec<(Range<u64>, Vec<u8>)>) -> Result<u64>
+where
+	F: FileLike,
+	LoftyError: From<<F as Truncate>::Error>,
+	LoftyError: From<<F as Length>::Error>,
+{
+	let file_len = file.len()?;
+
+	edits.sort_by_key(|(range, _)| range.start);
+
+	// The untouched sections of the file, and where they need to end up
+	let mut moves = Vec::with_capacity(edits.len() + 1);
+	let mut writes = Vec::with_capacity(edits.len());
+
+	let mut src = 0;
+	let mut dst = 0;
+	for (range, content) in edits {
+		debug_assert!(range.start >= src, "overlapping edits");
+
+		let start = range.start.min(file_len);
+		if start > src {
+			moves.push((src..start, dst));
+			dst += start - src;
+		}
+
+		let content_len = content.len() as u64;
+		writes.push((dst, content));
+		dst += content_len;
+
+		src = range.end.clamp(start, file_len);
+	}
+
+	if file_len > src {
+		moves.push((src..file_len, dst));
+		dst += file_len - src;
+	}
+
+	let mut buf = try_vec![0; COPY_BUFFER_SIZE.min(file_len) as usize];
+
+	// Sections moving towards the start of the file are handled first, front to back, followed by
+	// the sections moving towards the end, back to front. This way, nothing gets overwritten
+	// before it is moved.
+	for (range, dst) in moves.iter().filter(|(range, dst)| *dst < range.start) {
+		let len = range.end - range.start;
+
+		let mut copied = 0;
+		while copied < len {
+			let chunk_len = (len - copied).min(COPY_BUFFER_SIZE) as usize;
+
+			file.seek(SeekFrom::Start(range.start + copied))?;
+			file.read_exact(&mut buf[..chunk_len])?;
+			file.seek(SeekFrom::Start(dst + copied))?;
+			file.write_all(&buf[..chunk_len])?;
+
+			copied += chunk_len as u64;
+		}
+	}
+
+	for (range, dst) in moves.iter().rev().filter(|(range, dst)| *dst > range.start) {
+		let mut remaining = range.end - range.start;
+		while remaining > 0 {
+			let chunk_len = remaining.min(COPY_BUFFER_SIZE) as usize;
+			remaining -= chunk_len as u64;
+
+			file.seek(SeekFrom::Start(range.start + remaining))?;
+			file.read_exact(&mut buf[..chunk_len])?;
+			file.seek(SeekFrom::Start(dst + remaining))?;
+			file.write_all(&buf[..chunk_len])?;
+		}
+	}
+
+	for (pos, content) in writes {
+		file.seek(SeekFrom::Start(pos))?;
+		file.write_all(&content)?;
+	}
+
+	file.truncate(dst)?;
+	file.seek(SeekFrom::Start(dst))?;
+
+	Ok(dst)
+}
+
 #[cfg(test)]
 mod tests {
 	use crate::config::{ParseOptions, WriteOptions};
@@ -368,4 +466,30 @@ mod tests {
 		let current_file_contents = f.buf;
 		assert_eq!(current_file_contents, test_asset_contents());
 	}
+
+	#[test_log::test]
+	fn splice_file() {
+		// Large enough to need multiple copies for each section
+		let original = (0..200_000u32).map(|i| i as u8).collect::<Vec<u8>>();
+
+		let edits = vec![
+			(1000..1000, vec![1; 70_000]),
+			(5000..80_000, Vec::new()),
+			(90_000..90_010, vec![2; 3]),
+			(150_000..150_000, vec![3; 100_000]),
+			(199_990..200_000, vec![4; 5]),
+		];
+
+		let mut expected = original.clone();
+		for (range, content) in edits.iter().rev() {
+			expected.splice(range.start as usize..range.end as usize, content.clone());
+		}
+
+		let mut file = Cursor::new(original);
+		assert_eq!(super::spliced_len(200_000, &edits), expected.len() as u64);
+
+		let len = super::splice_file(&mut file, edits).unwrap();
+		assert_eq!(len, expected.len() as u64);
+		assert_eq!(file.into_inner(), expected);
+	}
 }
@@ -0,0 +1,837 @@
+//! XMP specific items
+//!
+//! ## Storage
+//!
+//! XMP packets have no container of their own, and are instead stored wherever the host format
+//! allows:
+//!
+//! | File Format   | Location                                           |
+//! |---------------|----------------------------------------------------|
+//! | MP3, AAC      | An ID3v2 `PRIV` frame, owned by `XMP`              |
+//! | MP4           | A top-level `uuid` atom (`BE7ACFCB-97A9-42E8-...`) |
+//! | WAV, AIFF     | A `_PMX` chunk                                     |
+//! | FLAC          | An `APPLICATION` block, with the ID `XMP `         |
+//! | Ogg formats   | An `XMP` Vorbis comment                            |
+//!
+//! ## Supported values
+//!
+//! Only the properties of the top-level `rdf:Description`s are read. Simple values and arrays of
+//! simple values are parsed, anything else (structures, qualified values, etc.) is kept as
+//! [`XmpValue::Xml`], and written back as-is.
+
+pub(crate) mod read;
+pub(crate) mod write;
+mod xml;
+
+use crate::config::WriteOptions;
+use crate::error::LoftyError;
+use crate::id3::v2::util::pairs::{format_number_pair, set_number};
+use crate::tag::{
+	try_parse_year, Accessor, ItemKey, ItemValue, MergeTag, SplitTag, Tag, TagExt, TagItem, TagType,
+};
+use crate::util::flag_item;
+use crate::util::io::{FileLike, Length, Truncate};
+
+use std::borrow::Cow;
+use std::io::Write;
+use std::ops::Deref;
+
+use lofty_attr::tag;
+
+pub(crate) const RDF_NAMESPACE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+pub(crate) const X_NAMESPACE: &str = "adobe:ns:meta/";
+pub(crate) const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// The owner of the ID3v2 `PRIV` frame holding the packet
+pub(crate) const ID3V2_PRIV_OWNER: &str = "XMP";
+/// The ID of the WAV and AIFF chunks holding the packet
+pub(crate) const IFF_CHUNK_ID: [u8; 4] = *b"_PMX";
+/// The ID of the FLAC APPLICATION block holding the packet
+pub(crate) const FLAC_APPLICATION_ID: [u8; 4] = *b"XMP ";
+/// The UUID of the MP4 `uuid` atom holding the packet
+pub(crate) const MP4_UUID: [u8; 16] = [
+	0xBE, 0x7A, 0xCF, 0xCB, 0x97, 0xA9, 0x42, 0xE8, 0x9C, 0x71, 0x99, 0x94, 0x91, 0xE3, 0xAF, 0xAC,
+];
+/// The Vorbis comment holding the packet
+pub(crate) const VORBIS_COMMENT_KEY: &str = "XMP";
+
+/// The namespaces that are always available, along with their standard prefixes
+pub(crate) const KNOWN_NAMESPACES: &[(&str, &str)] = &[
+	("dc", "http://purl.org/dc/elements/1.1/"),
+	("xmp", "http://ns.adobe.com/xap/1.0/"),
+	("xmpDM", "http://ns.adobe.com/xmp/1.0/DynamicMedia/"),
+	("xmpRights", "http://ns.adobe.com/xap/1.0/rights/"),
+	("xmpMM", "http://ns.adobe.com/xap/1.0/mm/"),
+	("photoshop", "http://ns.adobe.com/photoshop/1.0/"),
+];
+
+/// The language of the default item in a language alternative
+pub const DEFAULT_LANGUAGE: &str = "x-default";
+
+const TITLE_NAME: &str = "dc:title";
+const TRACK_NUMBER_NAME: &str = "xmpDM:trackNumber";
+const DISC_NUMBER_NAME: &str = "xmpDM:discNumber";
+const RELEASE_DATE_NAME: &str = "xmpDM:releaseDate";
+const COMPILATION_NAME: &str = "xmpDM:partOfCompilation";
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum PropertyKind {
+	Text,
+	Seq,
+	Bag,
+	Alt,
+}
+
+// Properties that are defined as arrays, anything not listed is simple text
+const ARRAY_PROPERTIES: &[(&str, PropertyKind)] = &[
+	("dc:contributor", PropertyKind::Bag),
+	("dc:creator", PropertyKind::Seq),
+	("dc:date", PropertyKind::Seq),
+	("dc:description", PropertyKind::Alt),
+	("dc:language", PropertyKind::Bag),
+	("dc:publisher", PropertyKind::Bag),
+	("dc:rights", PropertyKind::Alt),
+	("dc:subject", PropertyKind::Bag),
+	("dc:title", PropertyKind::Alt),
+	("dc:type", PropertyKind::Bag),
+	("xmpRights:UsageTerms", PropertyKind::Alt),
+];
+
+fn property_kind(name: &str) -> PropertyKind {
+	ARRAY_PROPERTIES
+		.iter()
+		.find(|(n, _)| *n == name)
+		.map_or(PropertyKind::Text, |(_, kind)| *kind)
+}
+
+/// The value of an XMP property
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[non_exhaustive]
+pub enum XmpValue {
+	/// A simple text value
+	Text(String),
+	/// An ordered array (`rdf:Seq`)
+	Seq(Vec<String>),
+	/// An unordered array (`rdf:Bag`)
+	Bag(Vec<String>),
+	/// A language alternative (`rdf:Alt`), as pairs of language and text
+	///
+	/// The default item uses the language [`DEFAULT_LANGUAGE`].
+	Alt(Vec<(String, String)>),
+	/// Any other value, as the raw XML of the property element
+	///
+	/// This is written back as-is, so it must be a complete element with the same name as the
+	/// property (Ex. `<xmpMM:History>...</xmpMM:History>`).
+	Xml(String),
+}
+
+impl XmpValue {
+	/// Returns the value as text
+	///
+	/// For arrays, this is the first item. For language alternatives, this is the default item,
+	/// falling back to the first.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::xmp::XmpValue;
+	///
+	/// let value = XmpValue::Alt(vec![
+	/// 	(String::from("de"), String::from("Foo Titel")),
+	/// 	(String::from("x-default"), String::from("Foo title")),
+	/// ]);
+	///
+	/// assert_eq!(value.text(), Some("Foo title"));
+	/// ```
+	pub fn text(&self) -> Option<&str> {
+		match self {
+			XmpValue::Text(text) => Some(text),
+			XmpValue::Seq(values) | XmpValue::Bag(values) => values.first().map(String::as_str),
+			XmpValue::Alt(alternatives) => alternatives
+				.iter()
+				.find(|(lang, _)| lang == DEFAULT_LANGUAGE)
+				.or_else(|| alternatives.first())
+				.map(|(_, text)| text.as_str()),
+			XmpValue::Xml(_) => None,
+		}
+	}
+}
+
+macro_rules! impl_accessor {
+	($($name:ident => $key:literal;)+) => {
+		paste::paste! {
+			$(
+				fn $name(&self) -> Option<Cow<'_, str>> {
+					self.get_text($key).map(Cow::Borrowed)
+				}
+
+				fn [<set_ $name>](&mut self, value: String) {
+					self.insert(String::from($key), XmpValue::Text(value))
+				}
+
+				fn [<remove_ $name>](&mut self) {
+					let _ = self.remove($key);
+				}
+			)+
+		}
+	}
+}
+
+/// An XMP packet
+///
+/// ## Property names
+///
+/// Properties are named by their namespace prefix and local name (Ex. `dc:title`). The common
+/// namespaces (`dc`, `xmp`, `xmpDM`, `xmpRights`, `xmpMM`, and `photoshop`) always use their
+/// standard prefixes, regardless of the prefixes used in the file. Raw XML values are kept as-is
+/// though, so any other prefix they use for these namespaces is registered as well.
+///
+/// Any other namespace must be registered with [`XmpTag::register_namespace`] before its
+/// properties can be written. Namespaces declared in the file are registered when reading.
+///
+/// ## Conversions
+///
+/// ### To `Tag`
+///
+/// Simple values are converted to [`TagItem`]s, with unknown names stored as
+/// [`ItemKey::Unknown`]. Arrays are only converted if their name maps to an [`ItemKey`], with
+/// one item per array entry. Only the default item of a language alternative is converted.
+///
+/// `xmpDM:discNumber` is split into [`ItemKey::DiscNumber`] and [`ItemKey::DiscTotal`].
+///
+/// ### From `Tag`
+///
+/// Items are stored as the type of value their property is defined as (Ex. `dc:creator` is an
+/// `rdf:Seq`). [`ItemKey::Unknown`] items are only kept if their name has a registered prefix.
+#[derive(Default, PartialEq, Eq, Debug, Clone)]
+#[tag(
+	description = "An XMP packet",
+	supported_formats(Aac, Aiff, Flac, Mp4, Mpeg, OggFlac, Opus, Speex, Vorbis, Wav)
+)]
+pub struct XmpTag {
+	/// Namespaces other than the known ones, as pairs of prefix and URI
+	pub(crate) namespaces: Vec<(String, String)>,
+	pub(crate) properties: Vec<(String, XmpValue)>,
+}
+
+impl XmpTag {
+	/// Create a new empty `XmpTag`
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::tag::TagExt;
+	/// use lofty::xmp::XmpTag;
+	///
+	/// let xmp_tag = XmpTag::new();
+	/// assert!(xmp_tag.is_empty());
+	/// ```
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Get the value of a property
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::tag::Accessor;
+	/// use lofty::xmp::{XmpTag, XmpValue};
+	///
+	/// let mut tag = XmpTag::new();
+	/// tag.set_artist(String::from("Foo artist"));
+	///
+	/// assert_eq!(
+	/// 	tag.get("xmpDM:artist"),
+	/// 	Some(&XmpValue::Text(String::from("Foo artist")))
+	/// );
+	/// ```
+	pub fn get(&self, name: &str) -> Option<&XmpValue> {
+		self.properties
+			.iter()
+			.find(|(n, _)| n == name)
+			.map(|(_, value)| value)
+	}
+
+	/// Get the value of a property as text
+	///
+	/// See [`XmpValue::text`]
+	pub fn get_text(&self, name: &str) -> Option<&str> {
+		self.get(name).and_then(XmpValue::text)
+	}
+
+	fn get_mut(&mut self, name: &str) -> Option<&mut XmpValue> {
+		self.properties
+			.iter_mut()
+			.find(|(n, _)| n == name)
+			.map(|(_, value)| value)
+	}
+
+	/// Insert a property, replacing any existing value
+	///
+	/// NOTE: If the property's prefix isn't known or registered, it will not be written.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::xmp::{XmpTag, XmpValue};
+	///
+	/// let mut tag = XmpTag::new();
+	/// tag.insert(
+	/// 	String::from("dc:subject"),
+	/// 	XmpValue::Bag(vec![String::from("Foo"), String::from("Bar")]),
+	/// );
+	///
+	/// assert_eq!(tag.get_text("dc:subject"), Some("Foo"));
+	/// ```
+	pub fn insert(&mut self, name: String, value: XmpValue) {
+		match self.get_mut(&name) {
+			Some(existing) => *existing = value,
+			None => self.properties.push((name, value)),
+		}
+	}
+
+	/// Remove a property, returning its value
+	pub fn remove(&mut self, name: &str) -> Option<XmpValue> {
+		let pos = self.properties.iter().position(|(n, _)| n == name)?;
+		Some(self.properties.remove(pos).1)
+	}
+
+	/// Returns an iterator over the property names and their values
+	pub fn properties(&self) -> impl Iterator<Item = (&str, &XmpValue)> {
+		self.properties
+			.iter()
+			.map(|(name, value)| (name.as_str(), value))
+	}
+
+	/// Returns an iterator over the registered namespaces, as pairs of prefix and URI
+	///
+	/// This does not include the known namespaces.
+	pub fn namespaces(&self) -> impl Iterator<Item = (&str, &str)> {
+		self.namespaces
+			.iter()
+			.map(|(prefix, uri)| (prefix.as_str(), uri.as_str()))
+	}
+
+	/// Register a namespace, allowing properties with its prefix to be written
+	///
+	/// This will do nothing if `prefix` is already in use.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::xmp::{XmpTag, XmpValue};
+	///
+	/// let mut tag = XmpTag::new();
+	/// tag.register_namespace(
+	/// 	String::from("exif"),
+	/// 	String::from("http://ns.adobe.com/exif/1.0/"),
+	/// );
+	/// tag.insert(
+	/// 	String::from("exif:UserComment"),
+	/// 	XmpValue::Text(String::from("Foo")),
+	/// );
+	///
+	/// assert_eq!(tag.namespaces().count(), 1);
+	/// ```
+	pub fn register_namespace(&mut self, prefix: String, uri: String) {
+		if self.namespace_uri(&prefix).is_some() {
+			return;
+		}
+
+		self.namespaces.push((prefix, uri));
+	}
+
+	pub(crate) fn namespace_uri(&self, prefix: &str) -> Option<&str> {
+		KNOWN_NAMESPACES
+			.iter()
+			.map(|(p, uri)| (*p, *uri))
+			.chain(self.namespaces())
+			.find(|(p, _)| *p == prefix)
+			.map(|(_, uri)| uri)
+	}
+
+	// Sets the default item of a language alternative, keeping any other languages
+	fn set_default_text(&mut self, name: &str, text: String) {
+		if let Some(XmpValue::Alt(alternatives)) = self.get_mut(name) {
+			match alternatives
+				.iter_mut()
+				.find(|(lang, _)| lang == DEFAULT_LANGUAGE)
+			{
+				Some((_, existing)) => *existing = text,
+				None => alternatives.insert(0, (String::from(DEFAULT_LANGUAGE), text)),
+			}
+
+			return;
+		}
+
+		self.insert(
+			name.to_owned(),
+			XmpValue::Alt(vec![(String::from(DEFAULT_LANGUAGE), text)]),
+		);
+	}
+
+	// Adds `text` to a property, according to its `PropertyKind`
+	fn push_text(&mut self, name: &str, text: String) {
+		let kind = property_kind(name);
+		match kind {
+			PropertyKind::Seq | PropertyKind::Bag => {
+				if let Some(XmpValue::Seq(values) | XmpValue::Bag(values)) = self.get_mut(name) {
+					values.push(text);
+					return;
+				}
+
+				let value = if kind == PropertyKind::Seq {
+					XmpValue::Seq(vec![text])
+				} else {
+					XmpValue::Bag(vec![text])
+				};
+
+				self.insert(name.to_owned(), value);
+			},
+			PropertyKind::Alt => self.set_default_text(name, text),
+			PropertyKind::Text => self.insert(name.to_owned(), XmpValue::Text(text)),
+		}
+	}
+
+	fn split_num_pair(&self, name: &str) -> (Option<u32>, Option<u32>) {
+		let Some(text) = self.get_text(name) else {
+			return (None, None);
+		};
+
+		let mut split = text.split('/').map(|n| n.trim().parse::<u32>().ok());
+		(split.next().flatten(), split.next().flatten())
+	}
+
+	fn insert_number_pair(&mut self, name: &'static str, number: Option<u32>, total: Option<u32>) {
+		if let Some(value) = format_number_pair(number, total) {
+			self.insert(String::from(name), XmpValue::Text(value));
+		} else {
+			log::warn!("{name} is not set. number: {number:?}, total: {total:?}");
+		}
+	}
+}
+
+impl Accessor for XmpTag {
+	impl_accessor!(
+		artist  => "xmpDM:artist";
+		album   => "xmpDM:album";
+		genre   => "xmpDM:genre";
+		comment => "xmpDM:logComment";
+	);
+
+	fn title(&self) -> Option<Cow<'_, str>> {
+		self.get_text(TITLE_NAME).map(Cow::Borrowed)
+	}
+
+	fn set_title(&mut self, value: String) {
+		self.set_default_text(TITLE_NAME, value);
+	}
+
+	fn remove_title(&mut self) {
+		let _ = self.remove(TITLE_NAME);
+	}
+
+	fn track(&self) -> Option<u32> {
+		self.get_text(TRACK_NUMBER_NAME)?.trim().parse().ok()
+	}
+
+	fn set_track(&mut self, value: u32) {
+		self.insert(
+			String::from(TRACK_NUMBER_NAME),
+			XmpValue::Text(value.to_string()),
+		);
+	}
+
+	fn remove_track(&mut self) {
+		let _ = self.remove(TRACK_NUMBER_NAME);
+	}
+
+	fn disk(&self) -> Option<u32> {
+		self.split_num_pair(DISC_NUMBER_NAME).0
+	}
+
+	fn set_disk(&mut self, value: u32) {
+		self.insert_number_pair(DISC_NUMBER_NAME, Some(value), self.disk_total());
+	}
+
+	fn remove_disk(&mut self) {
+		let _ = self.remove(DISC_NUMBER_NAME);
+	}
+
+	fn disk_total(&self) -> Option<u32> {
+		self.split_num_pair(DISC_NUMBER_NAME).1
+	}
+
+	fn set_disk_total(&mut self, value: u32) {
+		self.insert_number_pair(DISC_NUMBER_NAME, self.disk(), Some(value));
+	}
+
+	fn remove_disk_total(&mut self) {
+		let existing_disk_number = self.disk();
+		let _ = self.remove(DISC_NUMBER_NAME);
+
+		if let Some(disk) = existing_disk_number {
+			self.insert_number_pair(DISC_NUMBER_NAME, Some(disk), None);
+		}
+	}
+
+	fn year(&self) -> Option<u32> {
+		self.get_text(RELEASE_DATE_NAME).and_then(try_parse_year)
+	}
+
+	fn set_year(&mut self, value: u32) {
+		self.insert(
+			String::from(RELEASE_DATE_NAME),
+			XmpValue::Text(value.to_string()),
+		);
+	}
+
+	fn remove_year(&mut self) {
+		let _ = self.remove(RELEASE_DATE_NAME);
+	}
+}
+
+impl TagExt for XmpTag {
+	type Err = LoftyError;
+	type RefKey<'a> = &'a str;
+
+	#[inline]
+	fn tag_type(&self) -> TagType {
+		TagType::Xmp
+	}
+
+	fn len(&self) -> usize {
+		self.properties.len()
+	}
+
+	fn contains<'a>(&'a self, key: Self::RefKey<'a>) -> bool {
+		self.get(key).is_some()
+	}
+
+	fn is_empty(&self) -> bool {
+		self.properties.is_empty()
+	}
+
+	/// Writes the tag to a file
+	///
+	/// An empty tag will remove the XMP packet from the file.
+	///
+	/// # Errors
+	///
+	/// * Attempting to write the tag to a format that does not support it
+	/// * The file's host tag or container is invalid
+	/// * [`std::io::Error`]
+	fn save_to<F>(
+		&self,
+		file: &mut F,
+		write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err>
+	where
+		F: FileLike,
+		LoftyError: From<<F as Truncate>::Error>,
+		LoftyError: From<<F as Length>::Error>,
+	{
+		write::write_to(file, self, write_options)
+	}
+
+	/// Dumps the tag to a writer
+	///
+	/// This will write the XMP packet, without any host tag or container.
+	///
+	/// # Errors
+	///
+	/// * [`std::io::Error`]
+	fn dump_to<W: Write>(
+		&self,
+		writer: &mut W,
+		_write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err> {
+		writer.write_all(write::create_packet(self).as_bytes())?;
+		Ok(())
+	}
+
+	fn clear(&mut self) {
+		self.properties.clear();
+	}
+}
+
+/// The properties of an [`XmpTag`] that couldn't be converted to a [`Tag`]
+///
+/// See [`SplitTag`]
+#[derive(Debug, Clone, Default)]
+pub struct SplitTagRemainder(XmpTag);
+
+impl From<SplitTagRemainder> for XmpTag {
+	fn from(from: SplitTagRemainder) -> Self {
+		from.0
+	}
+}
+
+impl Deref for SplitTagRemainder {
+	type Target = XmpTag;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl SplitTag for XmpTag {
+	type Remainder = SplitTagRemainder;
+
+	fn split_tag(mut self) -> (Self::Remainder, Tag) {
+		let mut tag = Tag::new(TagType::Xmp);
+
+		for (name, value) in std::mem::take(&mut self.properties) {
+			let item_key = ItemKey::from_key(TagType::Xmp, &name);
+
+			match (item_key, value) {
+				(ItemKey::DiscNumber | ItemKey::DiscTotal, XmpValue::Text(value)) => {
+					let mut split = value.splitn(2, '/');
+					if let Some(number) = split.next() {
+						tag.items.push(TagItem::new(
+							ItemKey::DiscNumber,
+							ItemValue::Text(number.trim().to_string()),
+						));
+					}
+
+					if let Some(total) = split.next() {
+						tag.items.push(TagItem::new(
+							ItemKey::DiscTotal,
+							ItemValue::Text(total.trim().to_string()),
+						));
+					}
+				},
+				// XMP booleans are "True" or "False"
+				(ItemKey::FlagCompilation, XmpValue::Text(value)) => {
+					let flag = if value.trim().eq_ignore_ascii_case("true") {
+						String::from("1")
+					} else if value.trim().eq_ignore_ascii_case("false") {
+						String::from("0")
+					} else {
+						value
+					};
+
+					tag.items.push(TagItem::new(
+						ItemKey::FlagCompilation,
+						ItemValue::Text(flag),
+					));
+				},
+				// Arrays can't be converted back to their original type without a known name
+				(
+					ItemKey::Unknown(_),
+					value @ (XmpValue::Seq(_) | XmpValue::Bag(_) | XmpValue::Alt(_)),
+				)
+				| (_, value @ XmpValue::Xml(_)) => self.properties.push((name, value)),
+				(item_key, XmpValue::Text(value)) => {
+					tag.items
+						.push(TagItem::new(item_key, ItemValue::Text(value)));
+				},
+				(item_key, XmpValue::Seq(values) | XmpValue::Bag(values)) => {
+					for value in values {
+						tag.items
+							.push(TagItem::new(item_key.clone(), ItemValue::Text(value)));
+					}
+				},
+				(item_key, value @ XmpValue::Alt(_)) => {
+					if let Some(text) = value.text() {
+						tag.items
+							.push(TagItem::new(item_key, ItemValue::Text(text.to_owned())));
+					}
+				},
+			}
+		}
+
+		(SplitTagRemainder(self), tag)
+	}
+}
+
+impl MergeTag for SplitTagRemainder {
+	type Merged = XmpTag;
+
+	fn merge_tag(self, tag: Tag) -> Self::Merged {
+		let Self(mut merged) = self;
+
+		for item in tag.items {
+			match item.key() {
+				ItemKey::TrackNumber => set_number(&item, |number| merged.set_track(number)),
+				ItemKey::DiscNumber => set_number(&item, |number| merged.set_disk(number)),
+				ItemKey::DiscTotal => set_number(&item, |number| merged.set_disk_total(number)),
+				ItemKey::FlagCompilation => {
+					if let Some(flag) = item.value().text().and_then(flag_item) {
+						let value = if flag { "True" } else { "False" };
+						merged.insert(
+							String::from(COMPILATION_NAME),
+							XmpValue::Text(String::from(value)),
+						);
+					}
+				},
+				item_key => {
+					let Some(name) = item_key.map_key(TagType::Xmp, true) else {
+						continue;
+					};
+
+					// Without a namespace, there's no way to write the property
+					let Some((prefix, _)) = name.split_once(':') else {
+						continue;
+					};
+
+					if merged.namespace_uri(prefix).is_none() {
+						continue;
+					}
+
+					let name = name.to_string();
+					let (ItemValue::Text(text) | ItemValue::Locator(text)) = item.item_value else {
+						continue;
+					};
+
+					merged.push_text(&name, text);
+				},
+			}
+		}
+
+		merged
+	}
+}
+
+impl From<XmpTag> for Tag {
+	fn from(input: XmpTag) -> Self {
+		input.split_tag().1
+	}
+}
+
+impl From<Tag> for XmpTag {
+	fn from(input: Tag) -> Self {
+		SplitTagRemainder::default().merge_tag(input)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::prelude::*;
+	use crate::tag::{ItemKey, Tag, TagType};
+	use crate::xmp::{XmpTag, XmpValue};
+
+	fn text(value: &str) -> XmpValue {
+		XmpValue::Text(String::from(value))
+	}
+
+	#[test_log::test]
+	fn xmp_to_tag() {
+		let mut xmp_tag = XmpTag::new();
+		xmp_tag.insert(
+			String::from("dc:title"),
+			XmpValue::Alt(vec![
+				(String::from("de"), String::from("Foo Titel")),
+				(String::from("x-default"), String::from("Foo title")),
+			]),
+		);
+		xmp_tag.insert(
+			String::from("dc:creator"),
+			XmpValue::Seq(vec![String::from("Bar"), String::from("Baz")]),
+		);
+		xmp_tag.insert(String::from("xmpDM:album"), text("Qux album"));
+		xmp_tag.insert(String::from("xmpDM:discNumber"), text("2/3"));
+		xmp_tag.insert(String::from("xmpDM:partOfCompilation"), text("True"));
+		xmp_tag.insert(String::from("xmpDM:scene"), text("Foo scene"));
+		xmp_tag.insert(
+			String::from("dc:subject"),
+			XmpValue::Bag(vec![String::from("Foo")]),
+		);
+
+		let (remainder, tag) = xmp_tag.split_tag();
+
+		assert_eq!(tag.title().as_deref(), Some("Foo title"));
+		assert_eq!(
+			tag.get_strings(&ItemKey::TrackArtists).collect::<Vec<_>>(),
+			["Bar", "Baz"]
+		);
+		assert_eq!(tag.album().as_deref(), Some("Qux album"));
+		assert_eq!(tag.disk(), Some(2));
+		assert_eq!(tag.disk_total(), Some(3));
+		assert_eq!(tag.get_string(&ItemKey::FlagCompilation), Some("1"));
+		assert_eq!(
+			tag.get_string(&ItemKey::Unknown(String::from("xmpDM:scene"))),
+			Some("Foo scene")
+		);
+
+		// Unknown arrays are left alone
+		assert_eq!(remainder.properties().count(), 1);
+		assert!(remainder.get("dc:subject").is_some());
+	}
+
+	#[test_log::test]
+	fn tag_to_xmp() {
+		let mut tag = Tag::new(TagType::Xmp);
+		tag.set_title(String::from("Foo title"));
+		tag.push(TagItem::new(
+			ItemKey::TrackArtists,
+			ItemValue::Text(String::from("Bar")),
+		));
+		tag.push(TagItem::new(
+			ItemKey::TrackArtists,
+			ItemValue::Text(String::from("Baz")),
+		));
+		tag.set_track(5);
+		tag.set_disk(1);
+		tag.set_disk_total(2);
+		tag.insert_text(ItemKey::FlagCompilation, String::from("1"));
+
+		let xmp_tag: XmpTag = tag.into();
+
+		assert_eq!(
+			xmp_tag.get("dc:title"),
+			Some(&XmpValue::Alt(vec![(
+				String::from("x-default"),
+				String::from("Foo title")
+			)]))
+		);
+		assert_eq!(
+			xmp_tag.get("dc:creator"),
+			Some(&XmpValue::Seq(vec![
+				String::from("Bar"),
+				String::from("Baz")
+			]))
+		);
+		assert_eq!(xmp_tag.get_text("xmpDM:trackNumber"), Some("5"));
+		assert_eq!(xmp_tag.get_text("xmpDM:discNumber"), Some("1/2"));
+		assert_eq!(xmp_tag.get_text("xmpDM:partOfCompilation"), Some("True"));
+	}
+
+	#[test_log::test]
+	fn unknown_prefix() {
+		let mut tag = Tag::new(TagType::Xmp);
+		tag.push_unchecked(TagItem::new(
+			ItemKey::Unknown(String::from("xmpDM:scene")),
+			ItemValue::Text(String::from("Foo scene")),
+		));
+		tag.push_unchecked(TagItem::new(
+			ItemKey::Unknown(String::from("foo:bar")),
+			ItemValue::Text(String::from("Baz")),
+		));
+
+		let xmp_tag: XmpTag = tag.into();
+
+		assert_eq!(xmp_tag.get_text("xmpDM:scene"), Some("Foo scene"));
+		assert!(xmp_tag.get("foo:bar").is_none());
+	}
+
+	#[test_log::test]
+	fn set_title_keeps_languages() {
+		let mut xmp_tag = XmpTag::new();
+		xmp_tag.insert(
+			String::from("dc:title"),
+			XmpValue::Alt(vec![(String::from("de"), String::from("Foo Titel"))]),
+		);
+
+		xmp_tag.set_title(String::from("Foo title"));
+
+		assert_eq!(
+			xmp_tag.get("dc:title"),
+			Some(&XmpValue::Alt(vec![
+				(String::from("x-default"), String::from("Foo title")),
+				(String::from("de"), String::from("Foo Titel")),
+			]))
+		);
+	}
+}
@@ -0,0 +1,418 @@
+use super::xml::{self, Element, Node};
+use super::{
+	XmpTag, XmpValue, DEFAULT_LANGUAGE, ID3V2_PRIV_OWNER, KNOWN_NAMESPACES, RDF_NAMESPACE,
+	VORBIS_COMMENT_KEY, XML_NAMESPACE, X_NAMESPACE,
+};
+use crate::config::ParsingMode;
+use crate::error::Result;
+use crate::id3::v2::{Frame, Id3v2Tag};
+use crate::macros::parse_mode_choice;
+use crate::ogg::VorbisComments;
+use crate::util::text::utf8_decode_str;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+
+/// Parses an XMP packet
+///
+/// This may also be a bare `x:xmpmeta` or `rdf:RDF` element, without the `xpacket` wrapper.
+pub(crate) fn parse_xmp(packet: &[u8]) -> Result<XmpTag> {
+	let packet = packet.strip_prefix(UTF8_BOM).unwrap_or(packet);
+	let document = utf8_decode_str(packet)?;
+
+	let root = xml::parse(document)?;
+
+	let mut tag = XmpTag::new();
+	let mut scopes = Scopes::default();
+	read_element(document, &root, &mut scopes, &mut tag, false);
+
+	Ok(tag)
+}
+
+/// Parses an XMP packet, discarding it if it is invalid and `parse_mode` allows it
+pub(crate) fn read_xmp(packet: &[u8], parse_mode: ParsingMode) -> Result<Option<XmpTag>> {
+	match parse_xmp(packet) {
+		Ok(tag) => Ok(Some(tag)),
+		Err(e) => {
+			parse_mode_choice!(
+				parse_mode,
+				STRICT: return Err(e),
+				DEFAULT: log::warn!("Unable to read XMP packet, discarding: {e}")
+			);
+
+			Ok(None)
+		},
+	}
+}
+
+/// Reads the packet stored in an ID3v2 `PRIV` frame, if there is one
+pub(crate) fn read_from_id3v2(tag: &Id3v2Tag, parse_mode: ParsingMode) -> Result<Option<XmpTag>> {
+	for frame in tag {
+		if let Frame::Private(private) = frame {
+			if private.owner == ID3V2_PRIV_OWNER {
+				return read_xmp(&private.private_data, parse_mode);
+			}
+		}
+	}
+
+	Ok(None)
+}
+
+/// Reads the packet stored in an `XMP` comment, if there is one
+pub(crate) fn read_from_vorbis_comments(
+	tag: &VorbisComments,
+	parse_mode: ParsingMode,
+) -> Result<Option<XmpTag>> {
+	match tag.get(VORBIS_COMMENT_KEY) {
+		Some(packet) => read_xmp(packet.as_bytes(), parse_mode),
+		None => Ok(None),
+	}
+}
+
+// The namespace declarations currently in scope, as pairs of prefix and URI
+#[derive(Default)]
+struct Scopes(Vec<(String, String)>);
+
+impl Scopes {
+	// Adds the declarations of `element`, returning the previous length to restore later
+	fn declare(&mut self, element: &Element) -> usize {
+		let len = self.0.len();
+		for (name, value) in &element.attributes {
+			let prefix = match name.strip_prefix("xmlns") {
+				Some("") => "",
+				Some(prefixed) => match prefixed.strip_prefix(':') {
+					Some(prefix) => prefix,
+					None => continue,
+				},
+				None => continue,
+			};
+
+			self.0.push((prefix.to_owned(), value.clone()));
+		}
+
+		len
+	}
+
+	fn restore(&mut self, len: usize) {
+		self.0.truncate(len);
+	}
+
+	fn resolve(&self, prefix: &str) -> Option<&str> {
+		if prefix == "xml" {
+			return Some(XML_NAMESPACE);
+		}
+
+		self.0
+			.iter()
+			.rev()
+			.find(|(p, _)| p == prefix)
+			.map(|(_, uri)| uri.as_str())
+	}
+
+	// Resolves a qualified name to its namespace URI and local name
+	fn resolve_name<'a>(&self, name: &'a str) -> Option<(&str, &'a str)> {
+		let (prefix, local) = name.split_once(':').unwrap_or(("", name));
+		self.resolve(prefix).map(|uri| (uri, local))
+	}
+
+	fn is(&self, element: &Element, namespace: &str, local_name: &str) -> bool {
+		self.resolve_name(&element.name) == Some((namespace, local_name))
+	}
+}
+
+fn read_element(
+	document: &str,
+	element: &Element,
+	scopes: &mut Scopes,
+	tag: &mut XmpTag,
+	in_rdf: bool,
+) {
+	let scope_len = scopes.declare(element);
+
+	if in_rdf && scopes.is(element, RDF_NAMESPACE, "Description") {
+		read_description(document, element, scopes, tag);
+	} else {
+		let is_rdf = scopes.is(element, RDF_NAMESPACE, "RDF");
+		for child in element.child_elements() {
+			read_element(document, child, scopes, tag, is_rdf);
+		}
+	}
+
+	scopes.restore(scope_len);
+}
+
+fn read_description(document: &str, description: &Element, scopes: &mut Scopes, tag: &mut XmpTag) {
+	// Any namespace in scope may be used by the raw XML of a property
+	for (prefix, uri) in &scopes.0 {
+		register_namespace(tag, prefix, uri);
+	}
+
+	// Simple properties can be stored as attributes
+	for (name, value) in &description.attributes {
+		if name == "xmlns" || name.starts_with("xmlns:") {
+			continue;
+		}
+
+		let Some(name) = property_name(scopes, name) else {
+			continue;
+		};
+
+		if tag.get(&name).is_none() {
+			tag.properties.push((name, XmpValue::Text(value.clone())));
+		}
+	}
+
+	for property in description.child_elements() {
+		let scope_len = scopes.declare(property);
+
+		if let Some(name) = property_name(scopes, &property.name) {
+			if tag.get(&name).is_none() {
+				let value = read_value(document, property, scopes);
+				if let XmpValue::Xml(xml) = &value {
+					register_renamed_namespaces(tag, scopes, xml);
+				}
+
+				tag.properties.push((name, value));
+			}
+		}
+
+		scopes.restore(scope_len);
+	}
+}
+
+fn register_namespace(tag: &mut XmpTag, prefix: &str, uri: &str) {
+	if prefix.is_empty() || [RDF_NAMESPACE, X_NAMESPACE, XML_NAMESPACE].contains(&uri) {
+		return;
+	}
+
+	// Known namespaces are always declared with their standard prefixes
+	if KNOWN_NAMESPACES.iter().any(|(_, u)| *u == uri) {
+		return;
+	}
+
+	match tag.namespace_uri(prefix) {
+		Some(existing) if existing != uri => {
+			log::warn!("XMP: The prefix \"{prefix}\" is already in use, ignoring namespace {uri}");
+		},
+		Some(_) => {},
+		None => tag.namespaces.push((prefix.to_owned(), uri.to_owned())),
+	}
+}
+
+// Known namespaces are only declared with their standard prefixes, but raw XML keeps the prefixes
+// of the source packet, so those need to be declared as well
+fn register_renamed_namespaces(tag: &mut XmpTag, scopes: &Scopes, xml: &str) {
+	for (prefix, uri) in &scopes.0 {
+		let is_renamed = KNOWN_NAMESPACES
+			.iter()
+			.any(|(p, u)| *u == uri.as_str() && *p != prefix.as_str());
+
+		if is_renamed && xml.contains(&format!("{prefix}:")) && tag.namespace_uri(prefix).is_none()
+		{
+			tag.namespaces.push((prefix.clone(), uri.clone()));
+		}
+	}
+}
+
+// Resolves the name of a property, using the standard prefix for known namespaces
+fn property_name(scopes: &Scopes, name: &str) -> Option<String> {
+	let Some((uri, local_name)) = scopes.resolve_name(name) else {
+		log::warn!("XMP: Unable to resolve the namespace of \"{name}\", skipping");
+		return None;
+	};
+
+	if uri == RDF_NAMESPACE || uri == XML_NAMESPACE {
+		return None;
+	}
+
+	if let Some((prefix, _)) = KNOWN_NAMESPACES.iter().find(|(_, u)| *u == uri) {
+		return Some(format!("{prefix}:{local_name}"));
+	}
+
+	match name.split_once(':') {
+		Some((prefix, _)) => Some(format!("{prefix}:{local_name}")),
+		None => {
+			log::warn!("XMP: Property \"{name}\" has no prefix, skipping");
+			None
+		},
+	}
+}
+
+fn read_value(document: &str, property: &Element, scopes: &Scopes) -> XmpValue {
+	// Qualifiers, `rdf:resource`, `rdf:parseType`, etc.
+	let has_attributes = property
+		.attributes
+		.iter()
+		.any(|(name, _)| !is_ignored_attribute(name));
+
+	if !has_attributes {
+		let mut children = property.child_elements();
+		match (children.next(), children.next()) {
+			(None, _) => return XmpValue::Text(property.text()),
+			(Some(array), None) => {
+				if let Some(value) = read_array(array, scopes) {
+					return value;
+				}
+			},
+			_ => {},
+		}
+	}
+
+	XmpValue::Xml(document[property.span.clone()].to_owned())
+}
+
+// Reads an array of simple values, returning `None` if it has anything more complex
+fn read_array(array: &Element, scopes: &Scopes) -> Option<XmpValue> {
+	if array
+		.attributes
+		.iter()
+		.any(|(name, _)| !is_ignored_attribute(name))
+	{
+		return None;
+	}
+
+	let mut items = Vec::new();
+	for child in &array.children {
+		let item = match child {
+			Node::Element(item) => item,
+			Node::Text(text) if text.trim().is_empty() => continue,
+			Node::Text(_) => return None,
+		};
+
+		if !scopes.is(item, RDF_NAMESPACE, "li") || item.child_elements().next().is_some() {
+			return None;
+		}
+
+		let mut lang = None;
+		for (name, value) in &item.attributes {
+			match name.as_str() {
+				"xml:lang" => lang = Some(value.as_str()),
+				name if is_ignored_attribute(name) => {},
+				_ => return None,
+			}
+		}
+
+		items.push((lang.unwrap_or(DEFAULT_LANGUAGE), item.text()));
+	}
+
+	let texts = items.iter().map(|(_, text)| text.clone());
+	match scopes.resolve_name(&array.name)? {
+		(RDF_NAMESPACE, "Seq") => Some(XmpValue::Seq(texts.collect())),
+		(RDF_NAMESPACE, "Bag") => Some(XmpValue::Bag(texts.collect())),
+		(RDF_NAMESPACE, "Alt") => Some(XmpValue::Alt(
+			items
+				.iter()
+				.map(|(lang, text)| ((*lang).to_owned(), text.clone()))
+				.collect(),
+		)),
+		_ => None,
+	}
+}
+
+fn is_ignored_attribute(name: &str) -> bool {
+	name == "xml:lang" || name == "xmlns" || name.starts_with("xmlns:")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::parse_xmp;
+	use crate::xmp::write::create_packet;
+	use crate::xmp::XmpValue;
+
+	// Trimmed down from an Adobe Audition export
+	const AUDITION_PACKET: &str = r#"<?xpacket begin="" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/" x:xmptk="Adobe XMP Core 7.1-c000">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description rdf:about=""
+    xmlns:xmp="http://ns.adobe.com/xap/1.0/"
+    xmlns:xmpDM="http://ns.adobe.com/xmp/1.0/DynamicMedia/"
+    xmlns:xmpMM="http://ns.adobe.com/xap/1.0/mm/"
+    xmlns:stEvt="http://ns.adobe.com/xap/1.0/sType/ResourceEvent#"
+    xmlns:purl="http://purl.org/dc/elements/1.1/"
+   xmp:CreatorTool="Adobe Audition 2023.0 (Windows)"
+   xmpDM:album="Foo album"
+   xmpDM:artist="Bar artist"
+   xmpDM:trackNumber="3">
+   <xmpMM:History>
+    <rdf:Seq>
+     <rdf:li rdf:parseType="Resource">
+      <stEvt:action>saved</stEvt:action>
+     </rdf:li>
+    </rdf:Seq>
+   </xmpMM:History>
+   <purl:title>
+    <rdf:Alt>
+     <rdf:li xml:lang="x-default">Foo &amp; bar</rdf:li>
+    </rdf:Alt>
+   </purl:title>
+   <purl:creator>
+    <rdf:Seq>
+     <rdf:li>Foo</rdf:li>
+     <rdf:li>Bar</rdf:li>
+    </rdf:Seq>
+   </purl:creator>
+   <purl:relation>
+    <rdf:Bag>
+     <rdf:li rdf:resource="https://example.com/foo"/>
+    </rdf:Bag>
+   </purl:relation>
+   <xmpDM:genre>Rock</xmpDM:genre>
+  </rdf:Description>
+ </rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>"#;
+
+	#[test_log::test]
+	fn parse_audition_packet() {
+		let tag = parse_xmp(AUDITION_PACKET.as_bytes()).unwrap();
+
+		assert_eq!(
+			tag.get_text("xmp:CreatorTool"),
+			Some("Adobe Audition 2023.0 (Windows)")
+		);
+		assert_eq!(tag.get_text("xmpDM:album"), Some("Foo album"));
+		assert_eq!(tag.get_text("xmpDM:trackNumber"), Some("3"));
+		assert_eq!(tag.get_text("xmpDM:genre"), Some("Rock"));
+
+		// Known namespaces always use their standard prefixes
+		assert_eq!(
+			tag.get("dc:title"),
+			Some(&XmpValue::Alt(vec![(
+				String::from("x-default"),
+				String::from("Foo & bar")
+			)]))
+		);
+		assert_eq!(
+			tag.get("dc:creator"),
+			Some(&XmpValue::Seq(vec![
+				String::from("Foo"),
+				String::from("Bar")
+			]))
+		);
+
+		// Structures are kept as-is
+		let Some(XmpValue::Xml(history)) = tag.get("xmpMM:History") else {
+			panic!("Expected raw XML for xmpMM:History");
+		};
+		assert!(history.starts_with("<xmpMM:History>"));
+		assert!(history.ends_with("</xmpMM:History>"));
+
+		// Along with the namespaces they need
+		assert!(tag.namespaces().any(|(prefix, _)| prefix == "stEvt"));
+
+		// Raw XML keeps the source prefix of a known namespace, so it has to stay declared
+		let Some(XmpValue::Xml(relation)) = tag.get("dc:relation") else {
+			panic!("Expected raw XML for dc:relation");
+		};
+		assert!(relation.starts_with("<purl:relation>"));
+		assert!(tag.namespaces().any(|(prefix, _)| prefix == "purl"));
+
+		let read = parse_xmp(create_packet(&tag).as_bytes()).unwrap();
+		assert_eq!(read.get("dc:relation"), tag.get("dc:relation"));
+		assert_eq!(read.get("dc:title"), tag.get("dc:title"));
+	}
+
+	#[test_log::test]
+	fn invalid_packet() {
+		assert!(parse_xmp(b"<x:xmpmeta><rdf:RDF></x:xmpmeta>").is_err());
+	}
+}
@@ -0,0 +1,465 @@
+use super::xml::escape;
+use super::{
+	XmpTag, XmpValue, FLAC_APPLICATION_ID, ID3V2_PRIV_OWNER, IFF_CHUNK_ID, KNOWN_NAMESPACES,
+	RDF_NAMESPACE, VORBIS_COMMENT_KEY, X_NAMESPACE,
+};
+use crate::config::{ParseOptions, WriteOptions};
+use crate::error::{LoftyError, Result};
+use crate::file::{AudioFile, FileType};
+use crate::id3::v2::util::synchsafe::{SynchsafeInteger, UnsynchronizedStream};
+use crate::id3::v2::{Frame, Id3v2Tag, Id3v2Version, PrivateFrame};
+use crate::id3::{find_id3v2, FindId3v2Config, ID3FindResults};
+use crate::iff::chunk::ChunkUpdate;
+use crate::macros::{err, try_vec};
+use crate::ogg::{OggFlacFile, OpusFile, SpeexFile, VorbisFile};
+use crate::probe::Probe;
+use crate::tag::TagExt;
+use crate::util::io::{splice_file, FileLike, Length, Truncate};
+
+use std::io::Read;
+
+use byteorder::{BigEndian, ByteOrder, WriteBytesExt};
+
+// The packet ID defined by the XMP specification
+const PACKET_ID: &str = "W5M0MpCehiHzreSzNTczkc9d";
+
+// The size of an ID3v2 tag header and footer, as well as an ID3v2.3/4 frame header
+const ID3V2_HEADER_LEN: usize = 10;
+
+pub(crate) fn write_to<F>(file: &mut F, tag: &XmpTag, write_options: WriteOptions) -> Result<()>
+where
+	F: FileLike,
+	LoftyError: From<<F as Truncate>::Error>,
+	LoftyError: From<<F as Length>::Error>,
+{
+	let probe = Probe::new(file).guess_file_type()?;
+	let file_type = probe.file_type();
+
+	let file = probe.into_inner();
+
+	let Some(file_type) = file_type else {
+		err!(UnknownFormat);
+	};
+
+	if !XmpTag::SUPPORTED_FORMATS.contains(&file_type) {
+		err!(UnsupportedTag);
+	}
+
+	// An empty tag implies the packet should be removed
+	let packet = if tag.is_empty() {
+		None
+	} else {
+		Some(create_packet(tag))
+	};
+
+	match file_type {
+		FileType::Mpeg | FileType::Aac => write_to_id3v2(file, packet, write_options),
+		FileType::Wav => crate::iff::wav::container::write_chunks(
+			file,
+			&[ChunkUpdate::new(
+				IFF_CHUNK_ID,
+				packet.map(String::into_bytes),
+			)],
+		),
+		FileType::Aiff => crate::iff::aiff::chunks::write_chunks(
+			file,
+			&[ChunkUpdate::new(
+				IFF_CHUNK_ID,
+				packet.map(String::into_bytes),
+			)],
+		),
+		FileType::Flac => crate::flac::write::write_application_block(
+			file,
+			FLAC_APPLICATION_ID,
+			packet.as_ref().map(String::as_bytes),
+			write_options,
+		),
+		FileType::Mp4 => crate::mp4::ilst::write::write_xmp_to(
+			file,
+			packet.as_ref().map(String::as_bytes),
+			write_options,
+		),
+		_ => write_to_vorbis_comments(file, file_type, packet, write_options),
+	}
+}
+
+// Replaces the `PRIV` frame holding the packet, keeping the rest of the ID3v2 tag intact
+//
+// The other frames are copied as-is, rather than parsed and rewritten, so the tag keeps its version
+// and any frames that can't be parsed.
+fn write_to_id3v2<F>(
+	file: &mut F,
+	packet: Option<String>,
+	write_options: WriteOptions,
+) -> Result<()>
+where
+	F: FileLike,
+	LoftyError: From<<F as Truncate>::Error>,
+	LoftyError: From<<F as Length>::Error>,
+{
+	file.rewind()?;
+	let ID3FindResults(Some(header), _) = find_id3v2(file, FindId3v2Config::NO_READ_TAG)? else {
+		// There's no tag to keep, so one is created just for the packet
+		let Some(packet) = packet else {
+			return Ok(());
+		};
+
+		let mut id3v2 = Id3v2Tag::new();
+		id3v2.insert(Frame::Private(PrivateFrame::new(
+			String::from(ID3V2_PRIV_OWNER),
+			packet.into_bytes(),
+		)));
+
+		file.rewind()?;
+		return id3v2.save_to(file, write_options);
+	};
+
+	if header.version == Id3v2Version::V2 {
+		// There's no `PRIV` frame in ID3v2.2, so there's nothing to remove either
+		if packet.is_none() {
+			return Ok(());
+		}
+
+		log::warn!("XMP: Unable to write a packet to an ID3v2.2 tag");
+		err!(UnsupportedTag);
+	}
+
+	let is_id3v24 = header.version == Id3v2Version::V4;
+
+	file.rewind()?;
+	let mut tag = try_vec![0; ID3V2_HEADER_LEN + header.size as usize];
+	file.read_exact(&mut tag)?;
+
+	let footer_len = if header.flags.footer {
+		ID3V2_HEADER_LEN
+	} else {
+		0
+	};
+	let tag_len = (tag.len() + footer_len) as u64;
+
+	let mut tag_flags = tag[5];
+
+	// The extended header may hold a CRC or the padding size of the old frames, so it's dropped
+	let mut frames_start = ID3V2_HEADER_LEN;
+	if tag_flags & 0x40 == 0x40 {
+		let Some(size) = tag.get(frames_start..frames_start + 4) else {
+			err!(SizeMismatch);
+		};
+
+		let size = BigEndian::read_u32(size);
+		frames_start += if is_id3v24 {
+			size.unsynch() as usize
+		} else {
+			// The ID3v2.3 size doesn't include itself
+			size as usize + 4
+		};
+
+		tag_flags &= !0x40;
+	}
+
+	let Some(raw_frames) = tag.get(frames_start..) else {
+		err!(SizeMismatch);
+	};
+
+	// In ID3v2.3, the frame headers are unsynchronised as well, so it has to be undone to read them.
+	// ID3v2.4 only unsynchronises the frame contents, which are left alone.
+	let mut frames = raw_frames.to_vec();
+	if header.flags.unsynchronisation && !is_id3v24 {
+		frames.clear();
+		UnsynchronizedStream::new(raw_frames).read_to_end(&mut frames)?;
+
+		tag_flags &= !0x80;
+	}
+
+	let mut content = Vec::with_capacity(frames.len());
+	let mut remaining = &frames[..];
+	while remaining.len() >= ID3V2_HEADER_LEN && remaining[0] != 0 {
+		let mut size = BigEndian::read_u32(&remaining[4..8]);
+		if is_id3v24 {
+			size = size.unsynch();
+		}
+
+		let Some(frame) = remaining.get(..ID3V2_HEADER_LEN + size as usize) else {
+			log::warn!("XMP: Found a frame extending past the end of the ID3v2 tag, discarding");
+			break;
+		};
+
+		if !is_xmp_frame(frame, is_id3v24) {
+			content.extend_from_slice(frame);
+		}
+
+		remaining = &remaining[frame.len()..];
+	}
+
+	if let Some(packet) = packet {
+		let frame_content_len = ID3V2_PRIV_OWNER.len() + 1 + packet.len();
+		let Ok(mut size) = u32::try_from(frame_content_len) else {
+			err!(TooMuchData);
+		};
+
+		if is_id3v24 {
+			size = size.synch()?;
+		}
+
+		content.extend_from_slice(b"PRIV");
+		content.write_u32::<BigEndian>(size)?;
+		content.extend_from_slice(&[0, 0]);
+		content.extend_from_slice(ID3V2_PRIV_OWNER.as_bytes());
+		content.push(0);
+		content.extend_from_slice(packet.as_bytes());
+	}
+
+	// Without any frames left, the tag is removed entirely
+	if content.is_empty() {
+		splice_file(file, vec![(0..tag_len, Vec::new())])?;
+		return Ok(());
+	}
+
+	// A tag with a footer can't have padding. Otherwise, the old space is reused if possible, so
+	// the audio doesn't have to move.
+	if footer_len == 0 {
+		let padding = if content.len() <= header.size as usize {
+			header.size as usize - content.len()
+		} else {
+			write_options.preferred_padding.unwrap_or(0) as usize
+		};
+
+		content.resize(content.len() + padding, 0);
+	}
+
+	let Ok(size) = u32::try_from(content.len()) else {
+		err!(TooMuchData);
+	};
+
+	let mut new_tag = Vec::with_capacity(ID3V2_HEADER_LEN + content.len() + footer_len);
+
+	// The identifier and version are kept
+	new_tag.extend_from_slice(&tag[..5]);
+	new_tag.push(tag_flags);
+	new_tag.write_u32::<BigEndian>(size.synch()?)?;
+	new_tag.extend(content);
+
+	if footer_len > 0 {
+		// The footer is the same as the header, but with the identifier reversed
+		let footer = [&b"3DI"[..], &new_tag[3..ID3V2_HEADER_LEN]].concat();
+		new_tag.extend(footer);
+	}
+
+	splice_file(file, vec![(0..tag_len, new_tag)])?;
+	Ok(())
+}
+
+// Whether an ID3v2.3 or ID3v2.4 frame is a `PRIV` frame holding an XMP packet
+fn is_xmp_frame(frame: &[u8], is_id3v24: bool) -> bool {
+	if &frame[..4] != b"PRIV" {
+		return false;
+	}
+
+	let format_flags = frame[9];
+
+	// Compressed and encrypted frames can't be checked, and the extra header fields are skipped
+	let extra_len = if is_id3v24 {
+		if format_flags & 0x0C != 0 {
+			return false;
+		}
+
+		// Grouping identity (1) and data length indicator (4)
+		usize::from(format_flags & 0x40 != 0) + 4 * usize::from(format_flags & 0x01 != 0)
+	} else {
+		if format_flags & 0xC0 != 0 {
+			return false;
+		}
+
+		// Group identity (1)
+		usize::from(format_flags & 0x20 != 0)
+	};
+
+	let content = frame[ID3V2_HEADER_LEN..]
+		.get(extra_len..)
+		.unwrap_or_default();
+	content
+		.strip_prefix(ID3V2_PRIV_OWNER.as_bytes())
+		.is_some_and(|rest| rest.first() == Some(&0))
+}
+
+// Replaces the `XMP` comment, keeping the rest of the comments intact
+fn write_to_vorbis_comments<F>(
+	file: &mut F,
+	file_type: FileType,
+	packet: Option<String>,
+	write_options: WriteOptions,
+) -> Result<()>
+where
+	F: FileLike,
+	LoftyError: From<<F as Truncate>::Error>,
+	LoftyError: From<<F as Length>::Error>,
+{
+	let parse_options = ParseOptions::new().read_properties(false);
+	let mut vorbis_comments = match file_type {
+		FileType::Opus => OpusFile::read_from(file, parse_options)?.vorbis_comments_tag,
+		FileType::Speex => SpeexFile::read_from(file, parse_options)?.vorbis_comments_tag,
+		FileType::Vorbis => VorbisFile::read_from(file, parse_options)?.vorbis_comments_tag,
+		FileType::OggFlac => OggFlacFile::read_from(file, parse_options)?.vorbis_comments_tag,
+		_ => err!(UnsupportedTag),
+	};
+
+	let _ = vorbis_comments.remove(VORBIS_COMMENT_KEY);
+	if let Some(packet) = packet {
+		vorbis_comments.insert(String::from(VORBIS_COMMENT_KEY), packet);
+	}
+
+	file.rewind()?;
+	vorbis_comments.save_to(file, write_options)
+}
+
+/// Creates an XMP packet, holding a single `rdf:Description`
+///
+/// Properties with an unknown prefix are skipped.
+pub(crate) fn create_packet(tag: &XmpTag) -> String {
+	let mut namespaces = Vec::new();
+	let mut properties = String::new();
+
+	for (name, value) in &tag.properties {
+		let Some((prefix, uri)) = name
+			.split_once(':')
+			.and_then(|(prefix, _)| Some((prefix, tag.namespace_uri(prefix)?)))
+		else {
+			log::warn!("XMP: Property \"{name}\" has an unknown prefix, skipping");
+			continue;
+		};
+
+		if !namespaces.iter().any(|(p, _)| *p == prefix) {
+			namespaces.push((prefix, uri));
+		}
+
+		write_property(&mut properties, name, value);
+	}
+
+	// Raw XML values may use any of the namespaces
+	for (prefix, uri) in KNOWN_NAMESPACES
+		.iter()
+		.map(|(prefix, uri)| (*prefix, *uri))
+		.chain(tag.namespaces())
+	{
+		let is_used = tag.properties.iter().any(
+			|(_, value)| matches!(value, XmpValue::Xml(xml) if xml.contains(&format!("{prefix}:"))),
+		);
+
+		if is_used && !namespaces.iter().any(|(p, _)| *p == prefix) {
+			namespaces.push((prefix, uri));
+		}
+	}
+
+	let mut packet = String::new();
+	packet.push_str(&format!(
+		"<?xpacket begin=\"\u{FEFF}\" id=\"{PACKET_ID}\"?>\n"
+	));
+	packet.push_str(&format!("<x:xmpmeta xmlns:x=\"{X_NAMESPACE}\">\n"));
+	packet.push_str(&format!(" <rdf:RDF xmlns:rdf=\"{RDF_NAMESPACE}\">\n"));
+	packet.push_str("  <rdf:Description rdf:about=\"\"");
+	for (prefix, uri) in namespaces {
+		packet.push_str(&format!("\n    xmlns:{prefix}=\"{}\"", escape(uri)));
+	}
+	packet.push_str(">\n");
+	packet.push_str(&properties);
+	packet.push_str("  </rdf:Description>\n");
+	packet.push_str(" </rdf:RDF>\n");
+	packet.push_str("</x:xmpmeta>\n");
+	packet.push_str("<?xpacket end=\"w\"?>");
+
+	packet
+}
+
+fn write_property(out: &mut String, name: &str, value: &XmpValue) {
+	let (array_type, items) = match value {
+		XmpValue::Text(text) => {
+			out.push_str(&format!("   <{name}>{}</{name}>\n", escape(text)));
+			return;
+		},
+		XmpValue::Xml(xml) => {
+			out.push_str(&format!("   {xml}\n"));
+			return;
+		},
+		XmpValue::Seq(values) => (
+			"rdf:Seq",
+			values.iter().map(|v| (None, v)).collect::<Vec<_>>(),
+		),
+		XmpValue::Bag(values) => (
+			"rdf:Bag",
+			values.iter().map(|v| (None, v)).collect::<Vec<_>>(),
+		),
+		XmpValue::Alt(alternatives) => (
+			"rdf:Alt",
+			alternatives
+				.iter()
+				.map(|(lang, text)| (Some(lang), text))
+				.collect::<Vec<_>>(),
+		),
+	};
+
+	out.push_str(&format!("   <{name}>\n    <{array_type}>\n"));
+	for (lang, text) in items {
+		match lang {
+			Some(lang) => out.push_str(&format!(
+				"     <rdf:li xml:lang=\"{}\">{}</rdf:li>\n",
+				escape(lang),
+				escape(text)
+			)),
+			None => out.push_str(&format!("     <rdf:li>{}</rdf:li>\n", escape(text))),
+		}
+	}
+	out.push_str(&format!("    </{array_type}>\n   </{name}>\n"));
+}
+
+#[cfg(test)]
+mod tests {
+	use super::create_packet;
+	use crate::xmp::read::parse_xmp;
+	use crate::xmp::{XmpTag, XmpValue};
+
+	#[test_log::test]
+	fn round_trip() {
+		let mut tag = XmpTag::new();
+		tag.insert(
+			String::from("dc:title"),
+			XmpValue::Alt(vec![
+				(String::from("x-default"), String::from("Foo <title>")),
+				(String::from("de"), String::from("Foo Titel")),
+			]),
+		);
+		tag.insert(
+			String::from("dc:creator"),
+			XmpValue::Seq(vec![String::from("Bar"), String::from("Baz")]),
+		);
+		tag.insert(
+			String::from("dc:subject"),
+			XmpValue::Bag(vec![String::from("Qux")]),
+		);
+		tag.insert(
+			String::from("xmpDM:album"),
+			XmpValue::Text(String::from("Foo & album")),
+		);
+
+		tag.register_namespace(
+			String::from("stEvt"),
+			String::from("http://ns.adobe.com/xap/1.0/sType/ResourceEvent#"),
+		);
+		tag.insert(
+			String::from("xmpMM:History"),
+			XmpValue::Xml(String::from(
+				"<xmpMM:History><rdf:Seq><rdf:li \
+				 rdf:parseType=\"Resource\"><stEvt:action>saved</stEvt:action></rdf:li></rdf:\
+				 Seq></xmpMM:History>",
+			)),
+		);
+
+		// Not written, there's no namespace for this prefix
+		tag.insert(String::from("foo:bar"), XmpValue::Text(String::from("Baz")));
+
+		let packet = create_packet(&tag);
+		let read = parse_xmp(packet.as_bytes()).unwrap();
+
+		tag.remove("foo:bar");
+		assert_eq!(read, tag);
+	}
+}
@@ -0,0 +1,334 @@
+//! A minimal XML parser, just capable enough to read XMP packets
+//!
+//! Processing instructions, comments, and DTDs are skipped, and only the predefined and numeric
+//! character references are supported.
+
+use crate::error::Result;
+use crate::macros::decode_err;
+
+use std::ops::Range;
+
+// Anything nested deeper than this is almost certainly not XMP
+const MAX_DEPTH: usize = 128;
+
+#[derive(Debug)]
+pub(super) struct Element {
+	/// The qualified name of the element (Ex. `rdf:Description`)
+	pub(super) name: String,
+	pub(super) attributes: Vec<(String, String)>,
+	pub(super) children: Vec<Node>,
+	/// The position of the element within the document, including its start and end tags
+	pub(super) span: Range<usize>,
+}
+
+impl Element {
+	pub(super) fn attribute(&self, name: &str) -> Option<&str> {
+		self.attributes
+			.iter()
+			.find(|(n, _)| n == name)
+			.map(|(_, value)| value.as_str())
+	}
+
+	pub(super) fn child_elements(&self) -> impl Iterator<Item = &Element> {
+		self.children.iter().filter_map(|child| match child {
+			Node::Element(element) => Some(element),
+			Node::Text(_) => None,
+		})
+	}
+
+	/// The concatenation of all of the element's direct text children
+	pub(super) fn text(&self) -> String {
+		let mut text = String::new();
+		for child in &self.children {
+			if let Node::Text(t) = child {
+				text.push_str(t);
+			}
+		}
+
+		text
+	}
+}
+
+#[derive(Debug)]
+pub(super) enum Node {
+	Element(Element),
+	Text(String),
+}
+
+/// Parses `document`, returning its root element
+pub(super) fn parse(document: &str) -> Result<Element> {
+	let mut parser = Parser { document, pos: 0 };
+
+	parser.skip_misc()?;
+	if !parser.rest().starts_with('<') {
+		decode_err!(@BAIL "XMP: Expected a root element");
+	}
+
+	parser.parse_element(0)
+}
+
+struct Parser<'a> {
+	document: &'a str,
+	pos: usize,
+}
+
+impl<'a> Parser<'a> {
+	fn rest(&self) -> &'a str {
+		&self.document[self.pos..]
+	}
+
+	fn skip_whitespace(&mut self) {
+		let rest = self.rest();
+		self.pos += rest.len() - rest.trim_start().len();
+	}
+
+	// Moves past the next occurrence of `terminator`
+	fn skip_past(&mut self, terminator: &str) -> Result<&'a str> {
+		let rest = self.rest();
+		let Some(end) = rest.find(terminator) else {
+			decode_err!(@BAIL "XMP: Unexpected end of document");
+		};
+
+		self.pos += end + terminator.len();
+		Ok(&rest[..end])
+	}
+
+	// Skips any whitespace, processing instructions, comments, and DTDs
+	fn skip_misc(&mut self) -> Result<()> {
+		loop {
+			self.skip_whitespace();
+
+			let rest = self.rest();
+			if rest.starts_with("<?") {
+				self.skip_past("?>")?;
+			} else if rest.starts_with("<!--") {
+				self.skip_past("-->")?;
+			} else if rest.starts_with("<!") {
+				self.skip_past(">")?;
+			} else {
+				return Ok(());
+			}
+		}
+	}
+
+	fn parse_name(&mut self) -> Result<&'a str> {
+		let rest = self.rest();
+		let end = rest
+			.find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '='))
+			.unwrap_or(rest.len());
+
+		if end == 0 {
+			decode_err!(@BAIL "XMP: Expected a name");
+		}
+
+		self.pos += end;
+		Ok(&rest[..end])
+	}
+
+	// NOTE: This expects the parser to be at the element's `<`
+	fn parse_element(&mut self, depth: usize) -> Result<Element> {
+		if depth > MAX_DEPTH {
+			decode_err!(@BAIL "XMP: Maximum element depth exceeded");
+		}
+
+		let start = self.pos;
+		self.pos += 1;
+
+		let name = self.parse_name()?.to_owned();
+
+		let mut attributes = Vec::new();
+		loop {
+			self.skip_whitespace();
+
+			let rest = self.rest();
+			if rest.starts_with("/>") {
+				self.pos += 2;
+				return Ok(Element {
+					name,
+					attributes,
+					children: Vec::new(),
+					span: start..self.pos,
+				});
+			}
+
+			if rest.starts_with('>') {
+				self.pos += 1;
+				break;
+			}
+
+			let attribute_name = self.parse_name()?.to_owned();
+
+			self.skip_whitespace();
+			if !self.rest().starts_with('=') {
+				decode_err!(@BAIL "XMP: Expected `=` after attribute name");
+			}
+			self.pos += 1;
+			self.skip_whitespace();
+
+			let quote = match self.rest().chars().next() {
+				Some(quote @ ('"' | '\'')) => quote,
+				_ => decode_err!(@BAIL "XMP: Expected a quoted attribute value"),
+			};
+			self.pos += 1;
+
+			let value = self.skip_past(if quote == '"' { "\"" } else { "'" })?;
+			attributes.push((attribute_name, unescape(value)));
+		}
+
+		let mut children = Vec::new();
+		loop {
+			let rest = self.rest();
+			if rest.is_empty() {
+				decode_err!(@BAIL "XMP: Unexpected end of document");
+			}
+
+			if rest.starts_with("</") {
+				self.pos += 2;
+				let end_name = self.parse_name()?;
+				if end_name != name {
+					decode_err!(@BAIL "XMP: Mismatched end tag");
+				}
+
+				self.skip_whitespace();
+				if !self.rest().starts_with('>') {
+					decode_err!(@BAIL "XMP: Expected `>` after end tag name");
+				}
+				self.pos += 1;
+
+				break;
+			}
+
+			if rest.starts_with("<![CDATA[") {
+				self.pos += "<![CDATA[".len();
+				let text = self.skip_past("]]>")?;
+				children.push(Node::Text(text.to_owned()));
+			} else if rest.starts_with("<!--") {
+				self.skip_past("-->")?;
+			} else if rest.starts_with("<?") {
+				self.skip_past("?>")?;
+			} else if rest.starts_with('<') {
+				children.push(Node::Element(self.parse_element(depth + 1)?));
+			} else {
+				let end = rest.find('<').unwrap_or(rest.len());
+				self.pos += end;
+				children.push(Node::Text(unescape(&rest[..end])));
+			}
+		}
+
+		Ok(Element {
+			name,
+			attributes,
+			children,
+			span: start..self.pos,
+		})
+	}
+}
+
+// Replaces any character references, unknown references are left as-is
+fn unescape(text: &str) -> String {
+	let mut unescaped = String::with_capacity(text.len());
+
+	let mut rest = text;
+	while let Some(start) = rest.find('&') {
+		unescaped.push_str(&rest[..start]);
+		rest = &rest[start..];
+
+		let Some(end) = rest.find(';') else {
+			break;
+		};
+
+		let reference = &rest[1..end];
+		let c = match reference {
+			"lt" => Some('<'),
+			"gt" => Some('>'),
+			"amp" => Some('&'),
+			"quot" => Some('"'),
+			"apos" => Some('\''),
+			_ => reference
+				.strip_prefix("#x")
+				.map(|hex| u32::from_str_radix(hex, 16))
+				.or_else(|| reference.strip_prefix('#').map(str::parse))
+				.and_then(Result::ok)
+				.and_then(char::from_u32),
+		};
+
+		match c {
+			Some(c) => {
+				unescaped.push(c);
+				rest = &rest[end + 1..];
+			},
+			None => {
+				unescaped.push('&');
+				rest = &rest[1..];
+			},
+		}
+	}
+
+	unescaped.push_str(rest);
+	unescaped
+}
+
+pub(super) fn escape(text: &str) -> String {
+	let mut escaped = String::with_capacity(text.len());
+	for c in text.chars() {
+		match c {
+			'<' => escaped.push_str("&lt;"),
+			'>' => escaped.push_str("&gt;"),
+			'&' => escaped.push_str("&amp;"),
+			'"' => escaped.push_str("&quot;"),
+			_ => escaped.push(c),
+		}
+	}
+
+	escaped
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{escape, parse, unescape, Node};
+
+	#[test_log::test]
+	fn parse_document() {
+		let document = r#"<?xml version="1.0"?>
+<!-- A comment -->
+<root a="1" b='&lt;2&gt;'>
+	<child>Foo &amp; bar</child>
+	<empty />
+	<![CDATA[<raw>]]>
+</root>"#;
+
+		let root = parse(document).unwrap();
+		assert_eq!(root.name, "root");
+		assert_eq!(root.attribute("a"), Some("1"));
+		assert_eq!(root.attribute("b"), Some("<2>"));
+
+		let children = root.child_elements().collect::<Vec<_>>();
+		assert_eq!(children.len(), 2);
+		assert_eq!(children[0].text(), "Foo & bar");
+		assert_eq!(
+			&document[children[0].span.clone()],
+			"<child>Foo &amp; bar</child>"
+		);
+		assert_eq!(children[1].name, "empty");
+
+		assert!(root
+			.children
+			.iter()
+			.any(|child| matches!(child, Node::Text(text) if text == "<raw>")));
+	}
+
+	#[test_log::test]
+	fn mismatched_end_tag() {
+		assert!(parse("<a><b></a></b>").is_err());
+		assert!(parse("<a><b></b>").is_err());
+	}
+
+	#[test_log::test]
+	fn character_references() {
+		assert_eq!(
+			unescape("&#65;&#x42;&unknown; & more"),
+			"AB&unknown; & more"
+		);
+		assert_eq!(escape(r#"<"a" & 'b'>"#), "&lt;&quot;a&quot; &amp; 'b'&gt;");
+	}
+}
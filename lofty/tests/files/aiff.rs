@@ -1,11 +1,12 @@
 use crate::{set_artist, temp_file, verify_artist};
-use lofty::config::ParseOptions;
+use lofty::config::{ParseOptions, WriteOptions};
 use lofty::file::FileType;
+use lofty::iff::aiff::{AiffFile, AiffLoopPlayMode, ApplicationChunk, Marker};
 use lofty::prelude::*;
 use lofty::probe::Probe;
 use lofty::tag::TagType;
 
-use std::io::Seek;
+use std::io::{Read, Seek};
 
 #[test_log::test]
 fn read() {
@@ -80,3 +81,108 @@ fn read_no_properties() {
 fn read_no_tags() {
 	crate::no_tag_test!("tests/files/assets/minimal/full_test.aiff");
 }
+
+#[test_log::test]
+fn read_markers() {
+	let mut file = temp_file!("tests/files/assets/minimal/aiff_markers.aiff");
+	let aiff = AiffFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	let markers = aiff.markers();
+	assert_eq!(markers.len(), 2);
+	assert_eq!(markers[0].name, "Foo start");
+	assert_eq!(markers[1].name, "Bar end");
+	assert_eq!(markers[1].position, 2048);
+
+	let instrument = aiff.instrument().unwrap();
+	assert_eq!(instrument.base_note, 60);
+	assert_eq!(instrument.sustain_loop.play_mode, AiffLoopPlayMode::Forward);
+	assert_eq!(
+		aiff.marker(instrument.sustain_loop.begin_marker)
+			.unwrap()
+			.name,
+		"Foo start"
+	);
+	assert_eq!(
+		aiff.marker(instrument.sustain_loop.end_marker)
+			.unwrap()
+			.name,
+		"Bar end"
+	);
+
+	let application_chunks = aiff.application_chunks();
+	assert_eq!(application_chunks.len(), 1);
+	assert_eq!(&application_chunks[0].signature, b"pdos");
+	assert_eq!(application_chunks[0].data, b"Baz data");
+
+	// The comment is linked to the first marker
+	let comments = aiff.text_chunks().unwrap().comments.as_ref().unwrap();
+	assert_eq!(comments[0].text, "Qux comment");
+	assert_eq!(aiff.comment_marker(&comments[0]).unwrap().name, "Foo start");
+}
+
+#[test_log::test]
+fn write_markers() {
+	let mut file = temp_file!("tests/files/assets/minimal/aiff_markers.aiff");
+	let mut aiff = AiffFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	aiff.markers_mut()[1].position = 4096;
+	aiff.markers_mut()
+		.push(Marker::new(3, 1024, String::from("Quux release")));
+
+	let instrument = aiff.instrument_mut().unwrap();
+	instrument.release_loop.play_mode = AiffLoopPlayMode::ForwardBackward;
+	instrument.release_loop.begin_marker = 3;
+	instrument.release_loop.end_marker = 2;
+
+	aiff.application_chunks_mut()
+		.push(ApplicationChunk::new(*b"stoc", b"Corge data".to_vec()));
+
+	file.rewind().unwrap();
+	aiff.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	file.rewind().unwrap();
+	let aiff = AiffFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	let markers = aiff.markers();
+	assert_eq!(markers.len(), 3);
+	assert_eq!(markers[1].position, 4096);
+	assert_eq!(markers[2].name, "Quux release");
+
+	let release_loop = aiff.instrument().unwrap().release_loop;
+	assert_eq!(release_loop.play_mode, AiffLoopPlayMode::ForwardBackward);
+	assert_eq!(
+		aiff.marker(release_loop.begin_marker).unwrap().name,
+		"Quux release"
+	);
+
+	let application_chunks = aiff.application_chunks();
+	assert_eq!(application_chunks.len(), 2);
+	assert_eq!(&application_chunks[1].signature, b"stoc");
+
+	// The comment should still be linked
+	let comments = aiff.text_chunks().unwrap().comments.as_ref().unwrap();
+	assert_eq!(aiff.comment_marker(&comments[0]).unwrap().name, "Foo start");
+}
+
+#[test_log::test]
+fn remove_markers() {
+	let mut file = temp_file!("tests/files/assets/minimal/aiff_markers.aiff");
+	let mut aiff = AiffFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	assert_eq!(aiff.remove_application_chunks().len(), 1);
+
+	file.rewind().unwrap();
+	aiff.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	file.rewind().unwrap();
+	let mut contents = Vec::new();
+	file.read_to_end(&mut contents).unwrap();
+
+	assert!(!contents.windows(4).any(|w| w == b"APPL"));
+
+	file.rewind().unwrap();
+	let aiff = AiffFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert!(aiff.application_chunks().is_empty());
+	assert_eq!(aiff.markers().len(), 2);
+	assert!(aiff.instrument().is_some());
+}
@@ -0,0 +1,104 @@
+use crate::{set_artist, temp_file, verify_artist};
+use lofty::config::ParseOptions;
+use lofty::dsd::dsdiff::DsdiffFile;
+use lofty::file::FileType;
+use lofty::prelude::*;
+use lofty::probe::Probe;
+use lofty::tag::TagType;
+
+use std::io::Seek;
+
+#[test_log::test]
+fn read() {
+	let file = Probe::open("tests/files/assets/minimal/full_test.dff")
+		.unwrap()
+		.options(ParseOptions::new().read_properties(false))
+		.read()
+		.unwrap();
+
+	assert_eq!(file.file_type(), FileType::Dsdiff);
+
+	crate::verify_artist!(file, primary_tag, "Foo artist", 1);
+}
+
+#[test_log::test]
+fn write() {
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.dff");
+
+	let mut tagged_file = Probe::new(&mut file)
+		.options(ParseOptions::new().read_properties(false))
+		.guess_file_type()
+		.unwrap()
+		.read()
+		.unwrap();
+
+	assert_eq!(tagged_file.file_type(), FileType::Dsdiff);
+
+	crate::set_artist!(tagged_file, primary_tag_mut, "Foo artist", 1 => file, "Bar artist");
+
+	// Now reread the file
+	file.rewind().unwrap();
+	let mut tagged_file = Probe::new(&mut file)
+		.options(ParseOptions::new().read_properties(false))
+		.guess_file_type()
+		.unwrap()
+		.read()
+		.unwrap();
+
+	crate::set_artist!(tagged_file, primary_tag_mut, "Bar artist", 1 => file, "Foo artist");
+}
+
+#[test_log::test]
+fn read_edited_master_info() {
+	let mut file = std::fs::File::open("tests/files/assets/minimal/full_test.dff").unwrap();
+	let dsdiff_file = DsdiffFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	let info = dsdiff_file.edited_master_info().unwrap();
+	assert_eq!(info.artist.as_deref(), Some("Foo artist"));
+	assert_eq!(info.title.as_deref(), Some("Foo title"));
+	assert!(info.id.is_none());
+}
+
+#[test_log::test]
+fn write_keeps_edited_master_info() {
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.dff");
+
+	let mut dsdiff_file = DsdiffFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	dsdiff_file
+		.id3v2_mut()
+		.unwrap()
+		.set_title(String::from("Bar title"));
+
+	file.rewind().unwrap();
+	dsdiff_file
+		.save_to(&mut file, lofty::config::WriteOptions::default())
+		.unwrap();
+
+	file.rewind().unwrap();
+	let dsdiff_file = DsdiffFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	assert_eq!(
+		dsdiff_file.id3v2().unwrap().title().as_deref(),
+		Some("Bar title")
+	);
+	assert_eq!(
+		dsdiff_file.edited_master_info().unwrap().title.as_deref(),
+		Some("Foo title")
+	);
+	assert_eq!(dsdiff_file.properties().duration().as_millis(), 100);
+}
+
+#[test_log::test]
+fn remove() {
+	crate::remove_tag!("tests/files/assets/minimal/full_test.dff", TagType::Id3v2);
+}
+
+#[test_log::test]
+fn read_no_properties() {
+	crate::no_properties_test!("tests/files/assets/minimal/full_test.dff");
+}
+
+#[test_log::test]
+fn read_no_tags() {
+	crate::no_tag_test!("tests/files/assets/minimal/full_test.dff");
+}
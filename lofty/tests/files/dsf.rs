@@ -0,0 +1,79 @@
+use crate::{set_artist, temp_file, verify_artist};
+use lofty::config::ParseOptions;
+use lofty::file::FileType;
+use lofty::prelude::*;
+use lofty::probe::Probe;
+use lofty::tag::TagType;
+
+use std::io::{Read, Seek};
+
+#[test_log::test]
+fn read() {
+	let file = Probe::open("tests/files/assets/minimal/full_test.dsf")
+		.unwrap()
+		.options(ParseOptions::new().read_properties(false))
+		.read()
+		.unwrap();
+
+	assert_eq!(file.file_type(), FileType::Dsf);
+
+	crate::verify_artist!(file, primary_tag, "Foo artist", 1);
+}
+
+#[test_log::test]
+fn write() {
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.dsf");
+
+	let mut tagged_file = Probe::new(&mut file)
+		.options(ParseOptions::new().read_properties(false))
+		.guess_file_type()
+		.unwrap()
+		.read()
+		.unwrap();
+
+	assert_eq!(tagged_file.file_type(), FileType::Dsf);
+
+	crate::set_artist!(tagged_file, primary_tag_mut, "Foo artist", 1 => file, "Bar artist");
+
+	// Now reread the file
+	file.rewind().unwrap();
+	let mut tagged_file = Probe::new(&mut file)
+		.options(ParseOptions::new().read_properties(false))
+		.guess_file_type()
+		.unwrap()
+		.read()
+		.unwrap();
+
+	crate::set_artist!(tagged_file, primary_tag_mut, "Bar artist", 1 => file, "Foo artist");
+}
+
+#[test_log::test]
+fn remove() {
+	crate::remove_tag!("tests/files/assets/minimal/full_test.dsf", TagType::Id3v2);
+}
+
+#[test_log::test]
+fn remove_clears_metadata_pointer() {
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.dsf");
+	TagType::Id3v2.remove_from(&mut file).unwrap();
+
+	let mut contents = Vec::new();
+	file.rewind().unwrap();
+	file.read_to_end(&mut contents).unwrap();
+
+	// The "DSD " chunk stores the total file size, followed by the offset of the tag
+	let file_size = u64::from_le_bytes(contents[12..20].try_into().unwrap());
+	let metadata_offset = u64::from_le_bytes(contents[20..28].try_into().unwrap());
+	assert_eq!(file_size, contents.len() as u64);
+	assert_eq!(metadata_offset, 0);
+}
+
+#[test_log::test]
+fn read_no_properties() {
+	crate::no_properties_test!("tests/files/assets/minimal/full_test.dsf");
+}
+
+#[test_log::test]
+fn read_no_tags() {
+	crate::no_tag_test!("tests/files/assets/minimal/full_test.dsf");
+}
@@ -1,10 +1,12 @@
 use crate::temp_file;
 
 use std::fs::File;
-use std::io::Seek;
+use std::io::{Read, Seek, SeekFrom, Write};
 
 use lofty::config::{ParseOptions, ParsingMode, WriteOptions};
-use lofty::flac::FlacFile;
+use lofty::flac::{
+	BlockType, CueSheet, CueSheetIndex, CueSheetTrack, FlacFile, MetadataBlock, MetadataBlocks,
+};
 use lofty::ogg::VorbisComments;
 use lofty::prelude::*;
 
@@ -64,3 +66,238 @@ fn retain_vendor_string() {
 	// The vendor string should be retained
 	assert_eq!(f.vorbis_comments().unwrap().vendor(), "Lavf58.76.100");
 }
+
+#[test_log::test]
+fn read_metadata_blocks() {
+	let mut file = File::open("tests/files/assets/flac_metadata_blocks.flac").unwrap();
+
+	// The iterator includes every block
+	let block_types = MetadataBlocks::new(&mut file)
+		.unwrap()
+		.map(|block| block.unwrap().block_type())
+		.collect::<Vec<_>>();
+	assert_eq!(
+		block_types,
+		[
+			BlockType::StreamInfo,
+			BlockType::SeekTable,
+			BlockType::Application,
+			BlockType::VorbisComment,
+			BlockType::Unknown(10),
+			BlockType::Padding,
+		]
+	);
+
+	// `FlacFile` only includes the blocks it doesn't handle itself
+	file.rewind().unwrap();
+	let f = FlacFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	let blocks = f.metadata_blocks();
+	assert_eq!(blocks.len(), 3);
+	assert_eq!(blocks[0].block_type(), BlockType::SeekTable);
+	assert_eq!(blocks[1].application_id(), Some(*b"riff"));
+	assert!(blocks[1].application_data().unwrap().starts_with(b"RIFF"));
+	assert_eq!(blocks[2].block_type(), BlockType::Unknown(10));
+	assert_eq!(blocks[2].content(), b"Foo");
+}
+
+#[test_log::test]
+fn write_metadata_blocks() {
+	let mut file = temp_file!("tests/files/assets/flac_metadata_blocks.flac");
+	let mut f = FlacFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	// Move the APPLICATION block to the end, and replace the unknown block
+	let blocks = f.metadata_blocks_mut();
+	let application = blocks.remove(1);
+	blocks.retain(|block| block.block_type() != BlockType::Unknown(10));
+	blocks.push(MetadataBlock::application(*b"aiff", b"FORM").unwrap());
+	blocks.push(application);
+
+	f.vorbis_comments_mut()
+		.unwrap()
+		.set_artist(String::from("Foo artist"));
+
+	file.rewind().unwrap();
+	f.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	file.rewind().unwrap();
+	let block_types = MetadataBlocks::new(&mut file)
+		.unwrap()
+		.map(|block| block.unwrap().block_type())
+		.collect::<Vec<_>>();
+	assert_eq!(
+		block_types,
+		[
+			BlockType::StreamInfo,
+			BlockType::SeekTable,
+			BlockType::Application,
+			BlockType::Application,
+			BlockType::VorbisComment,
+			BlockType::Padding,
+		]
+	);
+
+	file.rewind().unwrap();
+	let f = FlacFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	let blocks = f.metadata_blocks();
+	assert_eq!(blocks[1].application_id(), Some(*b"aiff"));
+	assert_eq!(blocks[2].application_id(), Some(*b"riff"));
+
+	assert_eq!(
+		f.vorbis_comments().unwrap().artist().as_deref(),
+		Some("Foo artist")
+	);
+	assert!(f.properties().duration().as_millis() > 0);
+}
+
+#[test_log::test]
+fn write_cue_sheet() {
+	let mut file = temp_file!("tests/files/assets/flac_metadata_blocks.flac");
+	let mut f = FlacFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	assert!(f.cue_sheet().unwrap().is_none());
+
+	let cue_sheet = CueSheet {
+		media_catalog_number: String::from("1234567890123"),
+		lead_in: 88200,
+		is_cd: true,
+		tracks: vec![
+			CueSheetTrack {
+				offset: 0,
+				number: 1,
+				isrc: String::from("USFOO2400001"),
+				indices: vec![CueSheetIndex {
+					offset: 0,
+					number: 1,
+				}],
+				..CueSheetTrack::default()
+			},
+			CueSheetTrack {
+				offset: 588 * 10,
+				number: 170,
+				..CueSheetTrack::default()
+			},
+		],
+	};
+
+	f.set_cue_sheet(&cue_sheet).unwrap();
+
+	file.rewind().unwrap();
+	f.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	file.rewind().unwrap();
+	let mut f = FlacFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	assert_eq!(f.metadata_blocks().len(), 4);
+	assert_eq!(f.cue_sheet().unwrap(), Some(cue_sheet));
+
+	// The `CUESHEET` comment is only used when there's no CUESHEET block
+	f.remove_cue_sheet();
+	f.vorbis_comments_mut().unwrap().insert(
+		String::from("CUESHEET"),
+		String::from("FILE \"Foo.wav\" WAVE\n  TRACK 01 AUDIO\n    INDEX 01 00:01:00\n"),
+	);
+
+	let cue_sheet = f.cue_sheet().unwrap().unwrap();
+	assert_eq!(cue_sheet.tracks.len(), 1);
+	assert_eq!(
+		cue_sheet.tracks[0].offset,
+		u64::from(f.properties().sample_rate())
+	);
+}
+
+fn padding_size(file: &mut File) -> Option<usize> {
+	file.rewind().unwrap();
+
+	let last_block = MetadataBlocks::new(file)
+		.unwrap()
+		.map(Result::unwrap)
+		.last()
+		.unwrap();
+
+	(last_block.block_type() == BlockType::Padding).then_some(last_block.content().len())
+}
+
+#[test_log::test]
+fn write_with_padding() {
+	let mut file = temp_file!("tests/files/assets/flac_metadata_blocks.flac");
+	let original_len = file.metadata().unwrap().len();
+	assert_eq!(padding_size(&mut file), Some(16));
+
+	// The new comment doesn't fit in the existing padding, so the file is rewritten with the preferred padding
+	file.rewind().unwrap();
+	let mut f = FlacFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	f.vorbis_comments_mut()
+		.unwrap()
+		.set_comment(String::from("A comment that is much larger than 16 bytes"));
+
+	file.rewind().unwrap();
+	f.save_to(&mut file, WriteOptions::new().preferred_padding(2048))
+		.unwrap();
+
+	assert_eq!(padding_size(&mut file), Some(2048));
+
+	// Now the changes fit in the padding, so the file size should stay the same
+	let len = file.metadata().unwrap().len();
+	assert!(len > original_len);
+
+	for title in ["Bar title", "A much longer bar title", "Baz"] {
+		file.rewind().unwrap();
+		let mut f = FlacFile::read_from(&mut file, ParseOptions::new()).unwrap();
+		f.vorbis_comments_mut()
+			.unwrap()
+			.set_title(String::from(title));
+
+		file.rewind().unwrap();
+		f.save_to(&mut file, WriteOptions::default()).unwrap();
+
+		assert_eq!(file.metadata().unwrap().len(), len);
+
+		file.rewind().unwrap();
+		let f = FlacFile::read_from(&mut file, ParseOptions::new()).unwrap();
+		assert_eq!(f.vorbis_comments().unwrap().title().as_deref(), Some(title));
+		assert_eq!(f.metadata_blocks().len(), 3);
+	}
+
+	// No padding at all
+	file.rewind().unwrap();
+	let f = FlacFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	file.rewind().unwrap();
+	f.save_to(&mut file, WriteOptions::new().preferred_padding(0))
+		.unwrap();
+
+	assert_eq!(padding_size(&mut file), None);
+	assert!(file.metadata().unwrap().len() < len);
+
+	file.rewind().unwrap();
+	let f = FlacFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert_eq!(f.vorbis_comments().unwrap().title().as_deref(), Some("Baz"));
+}
+
+#[test_log::test]
+fn verify_frames() {
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.flac");
+
+	let verification = lofty::flac::verify_frames(&mut file).unwrap();
+	assert!(verification.is_valid());
+	assert_eq!(verification.frame_count(), 15);
+	assert_eq!(verification.sample_count(), 68546);
+	assert_eq!(verification.expected_sample_count(), 68546);
+
+	// Corrupt the second frame
+	let audio_start = 8263;
+	file.seek(SeekFrom::Start(audio_start + 2000)).unwrap();
+
+	let mut byte = [0; 1];
+	file.read_exact(&mut byte).unwrap();
+	file.seek(SeekFrom::Current(-1)).unwrap();
+	file.write_all(&[!byte[0]]).unwrap();
+
+	file.rewind().unwrap();
+	let verification = lofty::flac::verify_frames(&mut file).unwrap();
+	assert!(!verification.is_valid());
+	assert_eq!(verification.frame_count(), 15);
+	assert_eq!(verification.corrupt_frames(), &[9046]);
+}
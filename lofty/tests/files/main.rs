@@ -3,7 +3,11 @@
 mod aac;
 mod aiff;
 mod ape;
+mod asf;
+mod dsdiff;
+mod dsf;
 mod flac;
+mod matroska;
 mod mp4;
 mod mpc;
 mod mpeg;
@@ -11,4 +15,5 @@ mod ogg;
 pub(crate) mod util;
 mod wav;
 mod wavpack;
+mod xmp;
 mod zero_sized;
@@ -1,11 +1,17 @@
 use crate::{set_artist, temp_file, verify_artist};
-use lofty::config::ParseOptions;
+use lofty::config::{MoovPosition, ParseOptions, WriteOptions};
 use lofty::file::FileType;
+use lofty::id3::v2::{Frame, FrameId};
+use lofty::mp4::constants::keys;
+use lofty::mp4::{AssetLocation, AssetTextType, AtomData, Mp4Codec, Mp4File};
 use lofty::prelude::*;
 use lofty::probe::Probe;
-use lofty::tag::TagType;
+use lofty::tag::items::Chapter;
+use lofty::tag::{ItemKey, Tag, TagType};
 
-use std::io::Seek;
+use std::borrow::Cow;
+use std::io::{Read, Seek};
+use std::time::Duration;
 
 #[test_log::test]
 fn read() {
@@ -68,3 +74,645 @@ fn read_no_properties() {
 fn read_no_tags() {
 	crate::no_tag_test!("tests/files/assets/minimal/m4a_codec_aac.m4a");
 }
+
+#[test_log::test]
+fn read_multiple_tracks() {
+	let mut file = temp_file!("tests/files/assets/mp4_multiple_tracks.mp4");
+	let mut mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	let tracks = mp4_file.tracks();
+	assert_eq!(tracks.len(), 3);
+
+	assert_eq!(tracks[0].id(), 1);
+	assert_eq!(&tracks[0].handler_type(), b"vide");
+	assert_eq!(tracks[0].codec(), Mp4Codec::Unknown);
+	assert_eq!(tracks[0].language(), Some(*b"und"));
+	assert!(tracks[0].properties().is_none());
+
+	assert_eq!(tracks[1].id(), 2);
+	assert_eq!(&tracks[1].handler_type(), b"soun");
+	assert_eq!(tracks[1].codec(), Mp4Codec::Opus);
+	assert_eq!(tracks[1].language(), Some(*b"eng"));
+	assert!(tracks[1].is_enabled());
+
+	assert_eq!(tracks[2].id(), 3);
+	assert_eq!(tracks[2].language(), Some(*b"fra"));
+	assert!(!tracks[2].is_enabled());
+
+	for track in tracks {
+		assert_eq!(track.duration(), Duration::from_millis(1440));
+	}
+
+	// The first audio track is described by default
+	assert_eq!(mp4_file.properties().channels(), 2);
+
+	assert!(mp4_file.select_audio_track(3));
+	assert_eq!(mp4_file.properties().channels(), 1);
+
+	// Not an audio track
+	assert!(!mp4_file.select_audio_track(1));
+	assert_eq!(mp4_file.properties().channels(), 1);
+}
+
+// (fourcc, start, length) of each top-level atom
+fn top_level_atoms(file: &mut std::fs::File) -> Vec<([u8; 4], u64, u64)> {
+	let mut contents = Vec::new();
+	file.rewind().unwrap();
+	file.read_to_end(&mut contents).unwrap();
+
+	let mut atoms = Vec::new();
+
+	let mut pos = 0;
+	while pos < contents.len() {
+		let len = u32::from_be_bytes(contents[pos..pos + 4].try_into().unwrap()) as usize;
+		let fourcc = contents[pos + 4..pos + 8].try_into().unwrap();
+
+		atoms.push((fourcc, pos as u64, len as u64));
+		pos += len;
+	}
+
+	atoms
+}
+
+// The audio data pointed to by the first chunk offset, which has to survive the `moov` being moved
+fn first_chunk(file: &mut std::fs::File) -> Vec<u8> {
+	let mut contents = Vec::new();
+	file.rewind().unwrap();
+	file.read_to_end(&mut contents).unwrap();
+
+	let stco = contents
+		.windows(4)
+		.position(|window| window == b"stco")
+		.unwrap();
+
+	// Identifier (4) + version/flags (4) + entry count (4)
+	let entry = stco + 12;
+	let offset = u32::from_be_bytes(contents[entry..entry + 4].try_into().unwrap()) as usize;
+
+	contents[offset..offset + 64].to_vec()
+}
+
+fn position_of(atoms: &[([u8; 4], u64, u64)], fourcc: &[u8; 4]) -> usize {
+	atoms
+		.iter()
+		.position(|(ident, ..)| ident == fourcc)
+		.unwrap()
+}
+
+#[test_log::test]
+fn write_moov_position() {
+	let mut file = temp_file!("tests/files/assets/minimal/m4a_codec_aac.m4a");
+	let original_chunk = first_chunk(&mut file);
+
+	file.rewind().unwrap();
+	let mut mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+	let original_duration = mp4_file.properties().duration();
+	let original_audio_bitrate = mp4_file.properties().audio_bitrate();
+
+	// Move `moov` to the start of the file
+	mp4_file
+		.ilst_mut()
+		.unwrap()
+		.set_artist(String::from("Bar artist"));
+
+	let write_options = WriteOptions::new().moov_position(MoovPosition::Start);
+
+	file.rewind().unwrap();
+	mp4_file.save_to(&mut file, write_options).unwrap();
+
+	let atoms = top_level_atoms(&mut file);
+	let moov = position_of(&atoms, b"moov");
+	let mdat = position_of(&atoms, b"mdat");
+	assert!(moov < mdat);
+
+	// The preferred padding is placed after `moov`
+	assert_eq!(&atoms[moov + 1].0, b"free");
+	assert_eq!(
+		atoms[moov + 1].2,
+		u64::from(WriteOptions::DEFAULT_PREFERRED_PADDING)
+	);
+
+	assert_eq!(first_chunk(&mut file), original_chunk);
+
+	file.rewind().unwrap();
+	let mut mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert_eq!(
+		mp4_file.ilst().unwrap().artist().as_deref(),
+		Some("Bar artist")
+	);
+	assert_eq!(mp4_file.properties().duration(), original_duration);
+	assert_eq!(
+		mp4_file.properties().audio_bitrate(),
+		original_audio_bitrate
+	);
+
+	// Growing the tag should make use of the padding, leaving the media in place
+	let media_start = atoms[mdat].1;
+	mp4_file
+		.ilst_mut()
+		.unwrap()
+		.set_artist("Baz artist ".repeat(10));
+
+	file.rewind().unwrap();
+	mp4_file.save_to(&mut file, write_options).unwrap();
+
+	let atoms = top_level_atoms(&mut file);
+	let mdat = position_of(&atoms, b"mdat");
+	assert_eq!(atoms[mdat].1, media_start);
+	assert_eq!(first_chunk(&mut file), original_chunk);
+
+	// And back to the end
+	let write_options = WriteOptions::new().moov_position(MoovPosition::End);
+
+	file.rewind().unwrap();
+	mp4_file.save_to(&mut file, write_options).unwrap();
+
+	let atoms = top_level_atoms(&mut file);
+	assert_eq!(&atoms.last().unwrap().0, b"moov");
+	assert_eq!(first_chunk(&mut file), original_chunk);
+
+	file.rewind().unwrap();
+	let mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert_eq!(
+		mp4_file.ilst().unwrap().artist().as_deref(),
+		Some("Baz artist ".repeat(10).as_str())
+	);
+	assert_eq!(mp4_file.properties().duration(), original_duration);
+	assert_eq!(
+		mp4_file.properties().audio_bitrate(),
+		original_audio_bitrate
+	);
+}
+
+#[test_log::test]
+fn write_moov_position_padding() {
+	// The layout is `ftyp`, `mdat`, `moov`, `mdat`, with the chapter text in the final `mdat`
+	let mut file = temp_file!("tests/files/assets/mp4_moov_between_mdat.mp4");
+	let original_chunk = first_chunk(&mut file);
+
+	file.rewind().unwrap();
+	let mut mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+	let original_chapters = mp4_file.chapters();
+	assert_eq!(original_chapters.len(), 2);
+
+	mp4_file
+		.ilst_mut()
+		.unwrap()
+		.set_artist(String::from("Bar artist"));
+
+	let write_options = WriteOptions::new()
+		.moov_position(MoovPosition::Start)
+		.preferred_padding(1024);
+
+	file.rewind().unwrap();
+	mp4_file.save_to(&mut file, write_options).unwrap();
+
+	let atoms = top_level_atoms(&mut file);
+	let moov = position_of(&atoms, b"moov");
+	assert_eq!(&atoms[moov + 1].0, b"free");
+	assert_eq!(atoms[moov + 1].2, 1024);
+	assert_eq!(&atoms[moov + 2].0, b"mdat");
+
+	// Both the media before and after the old `moov` position has to be found again
+	assert_eq!(first_chunk(&mut file), original_chunk);
+
+	file.rewind().unwrap();
+	let mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert_eq!(mp4_file.chapters(), original_chapters);
+}
+
+#[test_log::test]
+fn write_moov_position_fragmented() {
+	// The audio pointed to by the base data offset of the first `tfhd` atom
+	fn first_fragment(file: &mut std::fs::File) -> Vec<u8> {
+		let mut contents = Vec::new();
+		file.rewind().unwrap();
+		file.read_to_end(&mut contents).unwrap();
+
+		let tfhd = contents
+			.windows(4)
+			.position(|window| window == b"tfhd")
+			.unwrap();
+
+		// Identifier (4) + version/flags (4) + track ID (4)
+		let entry = tfhd + 12;
+		let offset = u64::from_be_bytes(contents[entry..entry + 8].try_into().unwrap()) as usize;
+
+		contents[offset..offset + 64].to_vec()
+	}
+
+	// The layout is `ftyp`, `moov`, `moof`, `mdat`
+	let mut file = temp_file!("tests/files/assets/mp4_fragmented.m4a");
+	let original_atoms = top_level_atoms(&mut file);
+	let original_fragment = first_fragment(&mut file);
+
+	file.rewind().unwrap();
+	let mut mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+	let original_duration = mp4_file.properties().duration();
+
+	mp4_file
+		.ilst_mut()
+		.unwrap()
+		.set_artist(String::from("Bar artist"));
+
+	// `moov` has to stay before the `moof` atoms, so the layout is left alone
+	let write_options = WriteOptions::new().moov_position(MoovPosition::End);
+
+	file.rewind().unwrap();
+	mp4_file.save_to(&mut file, write_options).unwrap();
+
+	let atoms = top_level_atoms(&mut file);
+	assert_eq!(
+		atoms.iter().map(|(ident, ..)| *ident).collect::<Vec<_>>(),
+		original_atoms
+			.iter()
+			.map(|(ident, ..)| *ident)
+			.collect::<Vec<_>>()
+	);
+
+	// The fragments are still found after `moov` grows
+	assert_eq!(first_fragment(&mut file), original_fragment);
+
+	file.rewind().unwrap();
+	let mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert_eq!(
+		mp4_file.ilst().unwrap().artist().as_deref(),
+		Some("Bar artist")
+	);
+	assert_eq!(mp4_file.properties().duration(), original_duration);
+}
+
+#[test_log::test]
+fn keyed_metadata() {
+	let mut file = temp_file!("tests/files/assets/mp4_keyed_metadata.mp4");
+	let original_chunk = first_chunk(&mut file);
+
+	file.rewind().unwrap();
+	let mut mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	let keyed_metadata = mp4_file.keyed_metadata().unwrap();
+	assert_eq!(keyed_metadata.len(), 4);
+	assert_eq!(
+		keyed_metadata.location(),
+		Some("+37.3349-122.0090+010.000/")
+	);
+	assert_eq!(
+		keyed_metadata.get(keys::MAKE),
+		Some(&AtomData::UTF8(String::from("Apple")))
+	);
+
+	// Keys with an `ItemKey` equivalent are mapped into the `Tag`
+	let tag = Tag::from(mp4_file.ilst().unwrap().clone());
+	assert_eq!(tag.title().as_deref(), Some("Foo title"));
+	assert_eq!(
+		tag.get_string(&ItemKey::RecordingDate),
+		Some("2024-01-01T12:00:00+0000")
+	);
+
+	mp4_file
+		.keyed_metadata_mut()
+		.insert(keys::TITLE, AtomData::UTF8(String::from("Bar title")));
+	mp4_file.keyed_metadata_mut().remove(keys::MAKE);
+
+	file.rewind().unwrap();
+	mp4_file
+		.save_to(&mut file, WriteOptions::default())
+		.unwrap();
+	assert_eq!(first_chunk(&mut file), original_chunk);
+
+	file.rewind().unwrap();
+	let mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	let keyed_metadata = mp4_file.keyed_metadata().unwrap();
+	assert_eq!(keyed_metadata.len(), 3);
+	assert_eq!(
+		keyed_metadata.get(keys::TITLE),
+		Some(&AtomData::UTF8(String::from("Bar title")))
+	);
+	assert!(keyed_metadata.get(keys::MAKE).is_none());
+
+	// Changes made through a `Tag` should update the existing keys
+	let mut tag = Tag::from(mp4_file.ilst().unwrap().clone());
+	tag.set_title(String::from("Baz title"));
+
+	file.rewind().unwrap();
+	tag.save_to(&mut file, WriteOptions::default()).unwrap();
+	assert_eq!(first_chunk(&mut file), original_chunk);
+
+	file.rewind().unwrap();
+	let mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	// The title is only written back to the keyed metadata, not to the `ilst` atom
+	let ilst = mp4_file.ilst().unwrap();
+	assert!(ilst.title().is_none());
+	assert_eq!(
+		ilst.keyed_metadata().get(keys::TITLE),
+		Some(&AtomData::UTF8(String::from("Baz title")))
+	);
+	assert_eq!(
+		ilst.keyed_metadata().location(),
+		Some("+37.3349-122.0090+010.000/")
+	);
+}
+
+#[test_log::test]
+fn assets_3gpp() {
+	let mut file = temp_file!("tests/files/assets/mp4_3gpp_assets.mp4");
+	let original_chunk = first_chunk(&mut file);
+
+	file.rewind().unwrap();
+	let mut mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	let assets = mp4_file.assets().unwrap();
+	let title = assets.text(AssetTextType::Title).unwrap();
+	assert_eq!(title.language, *b"eng");
+	assert_eq!(title.text, "Foo title");
+
+	// Stored as UTF-16
+	let description = assets.text(AssetTextType::Description).unwrap();
+	assert_eq!(description.language, *b"fra");
+	assert_eq!(description.text, "Foo description");
+
+	assert_eq!(assets.year(), Some(2024));
+
+	let album = assets.album().unwrap();
+	assert_eq!(album.title, "Foo album");
+	assert_eq!(album.track_number, Some(3));
+
+	let location = AssetLocation {
+		language: *b"eng",
+		name: String::from("Foo location"),
+		role: 0,
+		longitude: -122.25,
+		latitude: 37.5,
+		altitude: 10.0,
+		astronomical_body: String::from("earth"),
+		additional_notes: String::new(),
+	};
+	assert_eq!(assets.location(), Some(&location));
+
+	// Assets with an `ItemKey` equivalent are mapped into the `Tag`
+	let tag = Tag::from(mp4_file.ilst().unwrap().clone());
+	assert_eq!(tag.title().as_deref(), Some("Foo title"));
+	assert_eq!(tag.artist().as_deref(), Some("Bar artist"));
+	assert_eq!(tag.album().as_deref(), Some("Foo album"));
+	assert_eq!(tag.track(), Some(3));
+	assert_eq!(tag.get_string(&ItemKey::RecordingDate), Some("2024"));
+
+	mp4_file.assets_mut().remove_text(AssetTextType::Performer);
+	mp4_file.assets_mut().set_year(2025);
+
+	file.rewind().unwrap();
+	mp4_file
+		.save_to(&mut file, WriteOptions::default())
+		.unwrap();
+	assert_eq!(first_chunk(&mut file), original_chunk);
+
+	file.rewind().unwrap();
+	let mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	let assets = mp4_file.assets().unwrap();
+	assert!(assets.text(AssetTextType::Performer).is_none());
+	assert_eq!(assets.year(), Some(2025));
+	assert_eq!(
+		assets.text(AssetTextType::Description).unwrap().text,
+		"Foo description"
+	);
+	assert_eq!(assets.location(), Some(&location));
+
+	// Changes made through a `Tag` should update the existing assets
+	let mut tag = Tag::from(mp4_file.ilst().unwrap().clone());
+	tag.set_title(String::from("Bar title"));
+	tag.remove_album();
+
+	file.rewind().unwrap();
+	tag.save_to(&mut file, WriteOptions::default()).unwrap();
+	assert_eq!(first_chunk(&mut file), original_chunk);
+
+	file.rewind().unwrap();
+	let mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	// The title is only written back to the assets, not to the `ilst` atom
+	let ilst = mp4_file.ilst().unwrap();
+	assert!(ilst.title().is_none());
+
+	let title = ilst.assets().text(AssetTextType::Title).unwrap();
+	assert_eq!(title.language, *b"eng");
+	assert_eq!(title.text, "Bar title");
+	assert!(ilst.assets().album().is_none());
+	assert_eq!(ilst.assets().year(), Some(2025));
+}
+
+#[test_log::test]
+fn quicktime_text() {
+	fn count_atoms(file: &mut std::fs::File, fourcc: &[u8; 4]) -> usize {
+		let mut contents = Vec::new();
+		file.rewind().unwrap();
+		file.read_to_end(&mut contents).unwrap();
+
+		contents
+			.windows(4)
+			.filter(|window| window == fourcc)
+			.count()
+	}
+
+	let mut file = temp_file!("tests/files/assets/mp4_quicktime_text.mp4");
+	let original_chunk = first_chunk(&mut file);
+
+	file.rewind().unwrap();
+	let mut mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	// The text atoms are only used for items that the `ilst` atom doesn't have
+	let ilst = mp4_file.ilst().unwrap();
+	assert_eq!(ilst.title().as_deref(), Some("Café title"));
+	assert_eq!(ilst.artist().as_deref(), Some("Foo artist"));
+	assert_eq!(ilst.year(), Some(2005));
+	assert_eq!(ilst.comment().as_deref(), Some("Foo comment"));
+
+	// By default, the text atoms are left alone, and aren't copied into the `ilst` atom
+	mp4_file
+		.ilst_mut()
+		.unwrap()
+		.set_artist(String::from("Baz artist"));
+
+	file.rewind().unwrap();
+	mp4_file
+		.save_to(&mut file, WriteOptions::default())
+		.unwrap();
+	assert_eq!(first_chunk(&mut file), original_chunk);
+	assert_eq!(count_atoms(&mut file, b"\xa9nam"), 1);
+	assert_eq!(count_atoms(&mut file, b"\xa9day"), 1);
+
+	file.rewind().unwrap();
+	let mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert_eq!(
+		mp4_file.ilst().unwrap().artist().as_deref(),
+		Some("Baz artist")
+	);
+
+	// Same for a round trip through a `Tag`
+	let tag = Tag::from(mp4_file.ilst().unwrap().clone());
+	assert_eq!(tag.title().as_deref(), Some("Café title"));
+
+	file.rewind().unwrap();
+	tag.save_to(&mut file, WriteOptions::default()).unwrap();
+	assert_eq!(count_atoms(&mut file, b"\xa9nam"), 1);
+	assert_eq!(count_atoms(&mut file, b"\xa9day"), 1);
+
+	file.rewind().unwrap();
+	let mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	// Now move them into the `ilst` atom
+	file.rewind().unwrap();
+	mp4_file
+		.save_to(
+			&mut file,
+			WriteOptions::default().migrate_quicktime_text(true),
+		)
+		.unwrap();
+	assert_eq!(first_chunk(&mut file), original_chunk);
+	assert_eq!(count_atoms(&mut file, b"\xa9nam"), 1);
+	assert_eq!(count_atoms(&mut file, b"\xa9day"), 1);
+
+	file.rewind().unwrap();
+	let mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	let ilst = mp4_file.ilst().unwrap();
+	assert_eq!(ilst.title().as_deref(), Some("Café title"));
+	assert_eq!(ilst.artist().as_deref(), Some("Baz artist"));
+	assert_eq!(ilst.year(), Some(2005));
+	assert_eq!(ilst.comment().as_deref(), Some("Foo comment"));
+}
+
+#[test_log::test]
+fn quicktime_chapter_track() {
+	fn chapter(start: u64, title: &str) -> Chapter {
+		let mut chapter = Chapter::new(Duration::from_millis(start));
+		chapter.title = Some(String::from(title));
+		chapter
+	}
+
+	let mut file = temp_file!("tests/files/assets/mp4_chapter_track.mp4");
+	let original_chunk = first_chunk(&mut file);
+
+	file.rewind().unwrap();
+	let mut mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert_eq!(
+		mp4_file.chapters(),
+		vec![chapter(0, "Intro"), chapter(720, "Verse")]
+	);
+
+	let new_chapters = vec![
+		chapter(0, "Intro"),
+		chapter(500, "Verse"),
+		chapter(1000, "Outro"),
+	];
+	mp4_file.set_chapters(new_chapters.clone());
+
+	file.rewind().unwrap();
+	mp4_file
+		.save_to(&mut file, WriteOptions::default())
+		.unwrap();
+	assert_eq!(first_chunk(&mut file), original_chunk);
+
+	let mut contents = Vec::new();
+	file.rewind().unwrap();
+	file.read_to_end(&mut contents).unwrap();
+
+	// The chapters are written back to the track, not to a new `chpl` atom
+	assert!(!contents.windows(4).any(|window| window == b"chpl"));
+
+	file.rewind().unwrap();
+	let mut mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert_eq!(mp4_file.chapters(), new_chapters);
+
+	// Saving the same chapters again shouldn't touch the track
+	file.rewind().unwrap();
+	mp4_file
+		.save_to(&mut file, WriteOptions::default())
+		.unwrap();
+	assert_eq!(file.metadata().unwrap().len(), contents.len() as u64);
+
+	// Changing them again replaces the previous samples, rather than leaving them behind
+	let mut renamed_chapters = new_chapters.clone();
+	renamed_chapters[0].title = Some(String::from("Opening"));
+
+	for chapters in [renamed_chapters, new_chapters] {
+		mp4_file.set_chapters(chapters.clone());
+
+		file.rewind().unwrap();
+		mp4_file
+			.save_to(&mut file, WriteOptions::default())
+			.unwrap();
+
+		file.rewind().unwrap();
+		mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+		assert_eq!(mp4_file.chapters(), chapters);
+	}
+
+	assert_eq!(file.metadata().unwrap().len(), contents.len() as u64);
+	assert_eq!(first_chunk(&mut file), original_chunk);
+
+	mp4_file.remove_chapters();
+
+	file.rewind().unwrap();
+	mp4_file
+		.save_to(&mut file, WriteOptions::default())
+		.unwrap();
+
+	file.rewind().unwrap();
+	let mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert!(mp4_file.chapters().is_empty());
+	assert_eq!(first_chunk(&mut file), original_chunk);
+}
+
+#[test_log::test]
+fn id3v2_in_id32() {
+	let mut file = temp_file!("tests/files/assets/mp4_id32.mp4");
+	let original_chunk = first_chunk(&mut file);
+
+	file.rewind().unwrap();
+	let mut mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert!(mp4_file.ilst().is_none());
+
+	let id3v2 = mp4_file.id3v2().unwrap();
+	assert_eq!(id3v2.title().as_deref(), Some("Foo title"));
+	assert_eq!(id3v2.get_user_text("FOO"), Some("Bar value"));
+
+	let Some(Frame::Private(private)) = id3v2.get(&FrameId::Valid(Cow::Borrowed("PRIV"))) else {
+		panic!("Expected a PRIV frame");
+	};
+	assert_eq!(
+		private.owner,
+		"com.apple.streaming.transportStreamTimestamp"
+	);
+	assert_eq!(private.private_data, 900_000_u64.to_be_bytes());
+
+	mp4_file
+		.id3v2_mut()
+		.unwrap()
+		.set_title(String::from("Bar title"));
+
+	file.rewind().unwrap();
+	mp4_file
+		.save_to(&mut file, WriteOptions::default())
+		.unwrap();
+	assert_eq!(first_chunk(&mut file), original_chunk);
+
+	file.rewind().unwrap();
+	let mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	let id3v2 = mp4_file.id3v2().unwrap();
+	assert_eq!(id3v2.title().as_deref(), Some("Bar title"));
+	assert_eq!(id3v2.get_user_text("FOO"), Some("Bar value"));
+	assert!(id3v2.get(&FrameId::Valid(Cow::Borrowed("PRIV"))).is_some());
+
+	// Removing the tag shouldn't touch anything else
+	file.rewind().unwrap();
+	TagType::Id3v2.remove_from(&mut file).unwrap();
+	assert_eq!(first_chunk(&mut file), original_chunk);
+
+	file.rewind().unwrap();
+	let mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert!(mp4_file.id3v2().is_none());
+}
@@ -7,7 +7,7 @@ use lofty::tag::TagType;
 
 use std::io::Seek;
 
-// The tests for OGG Opus/Vorbis/Speex are nearly identical
+// The tests for OGG Opus/Vorbis/Speex/FLAC are nearly identical
 // We have the vendor string and a title stored in the tag
 
 #[test_log::test]
@@ -83,10 +83,36 @@ fn speex_remove() {
 	)
 }
 
+#[test_log::test]
+fn ogg_flac_read() {
+	read(
+		"tests/files/assets/minimal/full_test.oga",
+		FileType::OggFlac,
+	)
+}
+
+#[test_log::test]
+fn ogg_flac_write() {
+	write(
+		"tests/files/assets/minimal/full_test.oga",
+		FileType::OggFlac,
+	)
+}
+
+#[test_log::test]
+fn ogg_flac_remove() {
+	remove(
+		"tests/files/assets/minimal/full_test.oga",
+		TagType::VorbisComments,
+	)
+}
+
 fn read(path: &str, file_type: FileType) {
 	let file = Probe::open(path)
 		.unwrap()
 		.options(ParseOptions::new().read_properties(false))
+		.guess_file_type()
+		.unwrap()
 		.read()
 		.unwrap();
 
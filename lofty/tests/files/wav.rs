@@ -1,16 +1,48 @@
 use crate::{set_artist, temp_file, verify_artist};
-use lofty::config::ParseOptions;
+use lofty::config::{ParseOptions, WriteOptions};
 use lofty::file::FileType;
+use lofty::iff::wav::{CuePoint, CueRegion, RiffInfoList, SampleLoop, SampleLoopType, WavFile};
 use lofty::prelude::*;
 use lofty::probe::Probe;
 use lofty::tag::TagType;
 
-use std::io::Seek;
+use std::io::{Read, Seek};
+
+// Here we have WAV files with both an ID3v2 chunk and a RIFF INFO chunk
+// The RF64 and Wave64 files contain the same chunks as the RIFF file
 
 #[test_log::test]
 fn read() {
-	// Here we have a WAV file with both an ID3v2 chunk and a RIFF INFO chunk
-	let file = Probe::open("tests/files/assets/minimal/wav_format_pcm.wav")
+	read_file("tests/files/assets/minimal/wav_format_pcm.wav");
+}
+
+#[test_log::test]
+fn read_rf64() {
+	read_file("tests/files/assets/minimal/wav_format_rf64.wav");
+}
+
+#[test_log::test]
+fn read_wave64() {
+	read_file("tests/files/assets/minimal/wav_format_w64.w64");
+}
+
+#[test_log::test]
+fn write() {
+	write_file("tests/files/assets/minimal/wav_format_pcm.wav");
+}
+
+#[test_log::test]
+fn write_rf64() {
+	write_file("tests/files/assets/minimal/wav_format_rf64.wav");
+}
+
+#[test_log::test]
+fn write_wave64() {
+	write_file("tests/files/assets/minimal/wav_format_w64.w64");
+}
+
+fn read_file(path: &str) {
+	let file = Probe::open(path)
 		.unwrap()
 		.options(ParseOptions::new().read_properties(false))
 		.read()
@@ -25,9 +57,8 @@ fn read() {
 	crate::verify_artist!(file, tag, TagType::RiffInfo, "Bar artist", 1);
 }
 
-#[test_log::test]
-fn write() {
-	let mut file = temp_file!("tests/files/assets/minimal/wav_format_pcm.wav");
+fn write_file(path: &str) {
+	let mut file = temp_file!(path);
 
 	let mut tagged_file = Probe::new(&mut file)
 		.options(ParseOptions::new().read_properties(false))
@@ -58,6 +89,192 @@ fn write() {
 	crate::set_artist!(tagged_file, tag_mut, TagType::RiffInfo, "Baz artist", 1 => file, "Bar artist");
 }
 
+#[test_log::test]
+fn read_broadcast_wave() {
+	let mut file = temp_file!("tests/files/assets/minimal/wav_bext.wav");
+	let wav = WavFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	let bext = wav.broadcast_extension().unwrap();
+	assert_eq!(bext.description, "Foo description");
+	assert_eq!(bext.originator, "Bar originator");
+	assert_eq!(bext.originator_reference, "BAZREF0001");
+	assert_eq!(bext.origination_date, "2024:01:01");
+	assert_eq!(bext.origination_time, "12:34:56");
+	assert_eq!(bext.time_reference, 48000);
+	assert_eq!(bext.version, 2);
+	assert_eq!(bext.umid[..4], [6, 10, 43, 52]);
+	assert_eq!(bext.loudness_value, Some(-2300));
+	assert_eq!(bext.loudness_range, Some(500));
+	assert_eq!(bext.max_true_peak_level, Some(-100));
+	assert_eq!(bext.max_momentary_loudness, Some(-1800));
+	assert_eq!(bext.max_short_term_loudness, Some(-2000));
+	assert_eq!(bext.coding_history, "A=PCM,F=48000,W=16,M=stereo\r\n");
+
+	assert!(wav
+		.ixml()
+		.unwrap()
+		.contains("<PROJECT>Foo project</PROJECT>"));
+	assert!(wav.axml().unwrap().contains("<title>Foo title</title>"));
+}
+
+#[test_log::test]
+fn write_broadcast_wave() {
+	let mut file = temp_file!("tests/files/assets/minimal/wav_bext.wav");
+	let mut wav = WavFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	wav.broadcast_extension_mut().unwrap().description = String::from("Bar description");
+	wav.set_ixml(String::from(
+		"<BWFXML><IXML_VERSION>2.10</IXML_VERSION><PROJECT>Bar \
+		 project</PROJECT><SCENE>1</SCENE></BWFXML>",
+	));
+
+	file.rewind().unwrap();
+	wav.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	file.rewind().unwrap();
+	let wav = WavFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	assert_eq!(
+		wav.broadcast_extension().unwrap().description,
+		"Bar description"
+	);
+	assert!(wav
+		.ixml()
+		.unwrap()
+		.contains("<PROJECT>Bar project</PROJECT>"));
+	assert!(wav.axml().unwrap().contains("<title>Foo title</title>"));
+
+	// The chunks should stay where they were
+	file.rewind().unwrap();
+	let mut contents = Vec::new();
+	file.read_to_end(&mut contents).unwrap();
+
+	let position = |fourcc: &[u8]| contents.windows(4).position(|w| w == fourcc).unwrap();
+	assert!(position(b"bext") < position(b"data"));
+	assert!(position(b"data") < position(b"iXML"));
+	assert!(position(b"iXML") < position(b"axml"));
+}
+
+#[test_log::test]
+fn remove_broadcast_wave() {
+	let mut file = temp_file!("tests/files/assets/minimal/wav_bext.wav");
+	let mut wav = WavFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	assert!(wav.remove_broadcast_extension().is_some());
+	assert!(wav.remove_ixml().is_some());
+
+	file.rewind().unwrap();
+	wav.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	file.rewind().unwrap();
+	let wav = WavFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	assert!(wav.broadcast_extension().is_none());
+	assert!(wav.ixml().is_none());
+	assert!(wav.axml().unwrap().contains("<title>Foo title</title>"));
+
+	file.rewind().unwrap();
+	let mut contents = Vec::new();
+	file.read_to_end(&mut contents).unwrap();
+
+	assert!(!contents.windows(4).any(|w| w == b"bext"));
+	assert!(!contents.windows(4).any(|w| w == b"iXML"));
+}
+
+#[test_log::test]
+fn read_markers() {
+	let mut file = temp_file!("tests/files/assets/minimal/wav_markers.wav");
+	let wav = WavFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	let cue_points = wav.cue_points();
+	assert_eq!(cue_points.len(), 2);
+
+	assert_eq!(cue_points[0].id, 1);
+	assert_eq!(cue_points[0].sample_offset, 100);
+	assert_eq!(cue_points[0].label.as_deref(), Some("Foo marker"));
+	assert_eq!(cue_points[0].note.as_deref(), Some("Bar note"));
+	assert!(cue_points[0].region.is_none());
+
+	assert_eq!(cue_points[1].id, 2);
+	assert_eq!(cue_points[1].sample_offset, 2400);
+	let region = cue_points[1].region.as_ref().unwrap();
+	assert_eq!(region.sample_length, 1200);
+	assert_eq!(region.text.as_deref(), Some("Baz region"));
+
+	let sampler_info = wav.sampler_info().unwrap();
+	assert_eq!(sampler_info.midi_unity_note, 60);
+	assert_eq!(sampler_info.loops.len(), 1);
+	assert_eq!(sampler_info.loops[0].loop_type, SampleLoopType::Forward);
+	assert_eq!(sampler_info.loops[0].start, 1024);
+	assert_eq!(sampler_info.loops[0].end, 2047);
+
+	assert_eq!(wav.riff_info().unwrap().get("IART"), Some("Bar artist"));
+}
+
+#[test_log::test]
+fn write_markers() {
+	let mut file = temp_file!("tests/files/assets/minimal/wav_markers.wav");
+	let mut wav = WavFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	let mut marker = CuePoint::new(3, 4000);
+	marker.label = Some(String::from("Qux marker"));
+	marker.region = Some(CueRegion::new(500));
+	wav.cue_points_mut().push(marker);
+	wav.cue_points_mut()[0].label = Some(String::from("Foo marker 2"));
+
+	let sampler_info = wav.sampler_info_mut().unwrap();
+	sampler_info.loops[0].end = 4095;
+	sampler_info.loops.push(SampleLoop::new(3, 4000, 4499));
+
+	let mut riff_info = RiffInfoList::default();
+	riff_info.insert(String::from("IART"), String::from("Baz artist"));
+	wav.set_riff_info(riff_info);
+
+	file.rewind().unwrap();
+	wav.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	file.rewind().unwrap();
+	let wav = WavFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	let cue_points = wav.cue_points();
+	assert_eq!(cue_points.len(), 3);
+	assert_eq!(cue_points[0].label.as_deref(), Some("Foo marker 2"));
+	assert_eq!(cue_points[1].region.as_ref().unwrap().sample_length, 1200);
+	assert_eq!(cue_points[2].label.as_deref(), Some("Qux marker"));
+	assert_eq!(cue_points[2].region.as_ref().unwrap().sample_length, 500);
+
+	let loops = &wav.sampler_info().unwrap().loops;
+	assert_eq!(loops.len(), 2);
+	assert_eq!(loops[0].end, 4095);
+	assert_eq!(loops[1].cue_point_id, 3);
+
+	assert_eq!(wav.riff_info().unwrap().get("IART"), Some("Baz artist"));
+}
+
+#[test_log::test]
+fn remove_markers() {
+	let mut file = temp_file!("tests/files/assets/minimal/wav_markers.wav");
+	let mut wav = WavFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	assert_eq!(wav.remove_cue_points().len(), 2);
+
+	file.rewind().unwrap();
+	wav.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	file.rewind().unwrap();
+	let mut contents = Vec::new();
+	file.read_to_end(&mut contents).unwrap();
+
+	assert!(!contents.windows(4).any(|w| w == b"cue " || w == b"adtl"));
+
+	// Everything else should be untouched
+	file.rewind().unwrap();
+	let wav = WavFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert!(wav.cue_points().is_empty());
+	assert_eq!(wav.sampler_info().unwrap().loops.len(), 1);
+	assert_eq!(wav.riff_info().unwrap().get("IART"), Some("Bar artist"));
+}
+
 #[test_log::test]
 fn remove_id3v2() {
 	crate::remove_tag!(
@@ -95,3 +312,28 @@ fn read_no_properties() {
 fn read_no_tags() {
 	crate::no_tag_test!("tests/files/assets/minimal/wav_format_pcm.wav");
 }
+
+#[test_log::test]
+fn oversized_wave64_chunk() {
+	let mut contents = std::fs::read("tests/files/assets/minimal/wav_format_w64.w64").unwrap();
+
+	// Only keep the `fmt ` and `data` chunks, followed by a chunk claiming to be larger than
+	// `i64::MAX`, which can't be skipped over
+	contents.truncate(19304);
+	contents.extend([0x11; 16]);
+	contents.extend((u64::MAX - 7).to_le_bytes());
+
+	let mut file = tempfile::tempfile().unwrap();
+	std::io::Write::write_all(&mut file, &contents).unwrap();
+
+	file.rewind().unwrap();
+	assert!(WavFile::read_from(&mut file, ParseOptions::new()).is_err());
+
+	let mut riff_info = RiffInfoList::default();
+	riff_info.insert(String::from("IART"), String::from("Foo artist"));
+
+	file.rewind().unwrap();
+	assert!(riff_info
+		.save_to(&mut file, WriteOptions::default())
+		.is_err());
+}
@@ -0,0 +1,131 @@
+use crate::temp_file;
+use lofty::config::{ParseOptions, WriteOptions};
+use lofty::id3::v2::{Id3v2Tag, Id3v2Version};
+use lofty::mpeg::MpegFile;
+use lofty::prelude::*;
+use lofty::probe::Probe;
+use lofty::tag::TagType;
+use lofty::xmp::XmpTag;
+
+use std::fs::File;
+use std::io::Seek;
+
+fn read_xmp(file: &mut File) -> Option<lofty::tag::Tag> {
+	file.rewind().unwrap();
+	Probe::new(file)
+		.guess_file_type()
+		.unwrap()
+		.read()
+		.unwrap()
+		.tag(TagType::Xmp)
+		.cloned()
+}
+
+fn write_read_remove(path: &str) {
+	let mut file = temp_file!(path);
+	assert!(read_xmp(&mut file).is_none());
+
+	let mut xmp = XmpTag::new();
+	xmp.set_title(String::from("Foo title"));
+	xmp.set_artist(String::from("Bar artist"));
+	xmp.set_track(1);
+
+	file.rewind().unwrap();
+	xmp.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	let tag = read_xmp(&mut file).unwrap();
+	assert_eq!(tag.title().as_deref(), Some("Foo title"));
+	assert_eq!(tag.artist().as_deref(), Some("Bar artist"));
+	assert_eq!(tag.track(), Some(1));
+
+	// Writing the packet again should replace it
+	xmp.set_title(String::from("Baz title"));
+
+	file.rewind().unwrap();
+	xmp.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	let tag = read_xmp(&mut file).unwrap();
+	assert_eq!(tag.title().as_deref(), Some("Baz title"));
+
+	file.rewind().unwrap();
+	TagType::Xmp.remove_from(&mut file).unwrap();
+	assert!(read_xmp(&mut file).is_none());
+}
+
+#[test_log::test]
+fn aiff() {
+	write_read_remove("tests/files/assets/minimal/full_test.aiff");
+}
+
+#[test_log::test]
+fn flac() {
+	write_read_remove("tests/files/assets/minimal/full_test.flac");
+}
+
+#[test_log::test]
+fn mp4() {
+	write_read_remove("tests/files/assets/minimal/mp4_codec_opus.mp4");
+}
+
+#[test_log::test]
+fn mpeg() {
+	write_read_remove("tests/files/assets/minimal/full_test.mp3");
+}
+
+#[test_log::test]
+fn mpeg_id3v23() {
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.mp3");
+
+	let mut id3v2 = Id3v2Tag::new();
+	id3v2.set_title(String::from("Foo title"));
+
+	let mut write_options = WriteOptions::default();
+	write_options.use_id3v23(true);
+
+	file.rewind().unwrap();
+	id3v2.save_to(&mut file, write_options).unwrap();
+
+	let mut xmp = XmpTag::new();
+	xmp.set_artist(String::from("Bar artist"));
+
+	file.rewind().unwrap();
+	xmp.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	// Only the packet is added, the rest of the ID3v2.3 tag stays as it was
+	file.rewind().unwrap();
+	let mpeg_file = MpegFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	let id3v2 = mpeg_file.id3v2().unwrap();
+	assert_eq!(id3v2.original_version(), Id3v2Version::V3);
+	assert_eq!(id3v2.title().as_deref(), Some("Foo title"));
+
+	let tag = read_xmp(&mut file).unwrap();
+	assert_eq!(tag.artist().as_deref(), Some("Bar artist"));
+
+	file.rewind().unwrap();
+	TagType::Xmp.remove_from(&mut file).unwrap();
+	assert!(read_xmp(&mut file).is_none());
+
+	file.rewind().unwrap();
+	let mpeg_file = MpegFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	let id3v2 = mpeg_file.id3v2().unwrap();
+	assert_eq!(id3v2.original_version(), Id3v2Version::V3);
+	assert_eq!(id3v2.title().as_deref(), Some("Foo title"));
+}
+
+#[test_log::test]
+fn vorbis() {
+	write_read_remove("tests/files/assets/minimal/full_test.ogg");
+}
+
+#[test_log::test]
+fn wav() {
+	write_read_remove("tests/files/assets/minimal/wav_format_pcm.wav");
+}
+
+#[test_log::test]
+fn unsupported() {
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.ape");
+	assert!(XmpTag::new()
+		.save_to(&mut file, WriteOptions::default())
+		.is_err());
+}
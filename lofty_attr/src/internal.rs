@@ -9,9 +9,9 @@ use quote::quote;
 pub(crate) fn opt_internal_file_type(
 	struct_name: String,
 ) -> Option<(proc_macro2::TokenStream, bool)> {
-	const LOFTY_FILE_TYPES: [&str; 12] = [
-		"Aac", "Aiff", "Ape", "Flac", "Mpeg", "Mp4", "Mpc", "Opus", "Vorbis", "Speex", "Wav",
-		"WavPack",
+	const LOFTY_FILE_TYPES: [&str; 17] = [
+		"Aac", "Aiff", "Ape", "Asf", "Dsdiff", "Dsf", "Flac", "Matroska", "Mpeg", "Mp4", "Mpc",
+		"OggFlac", "Opus", "Vorbis", "Speex", "Wav", "WavPack",
 	];
 
 	const ID3V2_STRIPPABLE: [&str; 2] = ["Flac", "Ape"];
@@ -87,6 +87,14 @@ pub(crate) fn init_write_lookup(
 		.write_to(file, write_options)
 	});
 
+	insert!(map, Xmp, {
+		lofty::xmp::write::write_to(
+			file,
+			&Into::<lofty::xmp::XmpTag>::into(tag.clone()),
+			write_options,
+		)
+	});
+
 	map
 }
 